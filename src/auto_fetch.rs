@@ -0,0 +1,112 @@
+use crate::events::Waker;
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often the fetching thread checks whether it should stop.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The outcome of a background fetch.
+#[derive(Clone, Debug)]
+pub(crate) struct FetchStatus {
+    pub at: Instant,
+    /// The first line git printed to stderr, if the fetch failed
+    pub error: Option<String>,
+}
+
+impl FetchStatus {
+    /// Shown in the bottom right corner of the screen.
+    pub(crate) fn describe(&self) -> String {
+        let secs = self.at.elapsed().as_secs();
+        let ago = match secs {
+            0..60 => "just now".to_string(),
+            60..3600 => format!("{}m ago", secs / 60),
+            _ => format!("{}h ago", secs / 3600),
+        };
+
+        match &self.error {
+            Some(error) => format!("Fetch failed {}: {}", ago, error),
+            None => format!("Fetched {}", ago),
+        }
+    }
+}
+
+/// Runs `git fetch --all` right away and then every `interval`, on a background thread.
+/// Nothing is prompted for, a fetch needing credentials fails instead. `waker` is woken on each result.
+pub(crate) struct AutoFetch {
+    results: Receiver<FetchStatus>,
+    stop: Arc<AtomicBool>,
+}
+
+impl AutoFetch {
+    pub(crate) fn spawn(workdir: PathBuf, interval: Duration, waker: Waker) -> Self {
+        let (sender, results) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+
+        thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                if sender.send(fetch(&workdir)).is_err() {
+                    return;
+                }
+                waker.wake();
+
+                let next = Instant::now() + interval;
+                while Instant::now() < next {
+                    if stopped.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    thread::sleep(STOP_CHECK_INTERVAL.min(interval));
+                }
+            }
+        });
+
+        Self { results, stop }
+    }
+
+    /// The latest fetch that finished since the last call.
+    pub(crate) fn poll(&self) -> Option<FetchStatus> {
+        self.results.try_iter().last()
+    }
+}
+
+impl Drop for AutoFetch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn fetch(workdir: &Path) -> FetchStatus {
+    let output = Command::new("git")
+        .args(["fetch", "--all", "--quiet"])
+        .current_dir(workdir)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output();
+
+    let error = match output {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .map(|line| line.trim().trim_start_matches("fatal: "))
+                .unwrap_or("git fetch failed")
+                .to_string(),
+        ),
+        Err(err) => Some(err.to_string()),
+    };
+
+    FetchStatus {
+        at: Instant::now(),
+        error,
+    }
+}
@@ -1,5 +1,8 @@
 use std::collections::BTreeMap;
 
+use itertools::Itertools;
+use serde::Serialize;
+
 use crate::{
     key_parser,
     menu::{Menu, PendingMenu},
@@ -99,3 +102,63 @@ impl Binding {
         }
     }
 }
+
+/// Checks that all keys parse, that toggled arguments exist in their menu,
+/// and that no two bindings of a menu shadow each other.
+/// Returns an error message for every problem found.
+pub(crate) fn validate(bindings: &BTreeMap<Menu, BTreeMap<Op, Vec<String>>>) -> Vec<String> {
+    let mut errors = vec![];
+
+    for (menu, ops) in bindings {
+        let args = PendingMenu::init(*menu).args;
+        let mut parsed = vec![];
+
+        for (op, binds) in ops {
+            if let Op::ToggleArg(arg) = op {
+                if !args.contains_key(arg.as_str()) {
+                    errors.push(format!(
+                        "Unknown op or argument '{}' in {}",
+                        arg,
+                        config_name(menu)
+                    ));
+                    continue;
+                }
+            }
+
+            for raw in binds {
+                match key_parser::parse_keys(raw) {
+                    Ok(("", keys)) if !keys.is_empty() => parsed.push((raw, op, keys)),
+                    _ => errors.push(format!(
+                        "Invalid keys '{}' for {}.{}",
+                        raw,
+                        config_name(menu),
+                        config_name(op)
+                    )),
+                }
+            }
+        }
+
+        for ((raw_a, op_a, keys_a), (raw_b, op_b, keys_b)) in parsed.iter().tuple_combinations() {
+            if keys_a.starts_with(keys_b) || keys_b.starts_with(keys_a) {
+                errors.push(format!(
+                    "Keys '{}' for {menu}.{} conflict with '{}' for {menu}.{}",
+                    raw_a,
+                    config_name(*op_a),
+                    raw_b,
+                    config_name(*op_b),
+                    menu = config_name(menu),
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// The name of a menu or op as written in the config file
+fn config_name<T: Serialize>(value: &T) -> String {
+    match toml::Value::try_from(value) {
+        Ok(toml::Value::String(name)) => name,
+        _ => "?".into(),
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{events::Waker, github::Checks, items::TargetData, Res};
+use git2::{Oid, Repository};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Statuses older than this are fetched again the next time they're shown.
+const MAX_AGE: Duration = Duration::from_secs(60);
+
+/// Requests made side by side, fetching the statuses of a screenful of commits.
+const WORKERS: usize = 4;
+
+type FetchFn = Arc<dyn Fn(Oid) -> Res<Checks> + Send + Sync>;
+
+#[derive(Default)]
+struct Cache {
+    checks: HashMap<Oid, (Instant, Checks)>,
+    in_flight: HashSet<Oid>,
+}
+
+/// The CI status of commits, fetched on background threads as they're shown and kept around for a while.
+/// `waker` is woken as statuses come in.
+pub(crate) struct CiStatuses {
+    cache: Arc<Mutex<Cache>>,
+    requests: Sender<Oid>,
+    updated: Arc<AtomicBool>,
+}
+
+impl CiStatuses {
+    pub(crate) fn spawn(
+        waker: Waker,
+        fetch: impl Fn(Oid) -> Res<Checks> + Send + Sync + 'static,
+    ) -> Self {
+        let fetch: FetchFn = Arc::new(fetch);
+        let cache = Arc::new(Mutex::new(Cache::default()));
+        let updated = Arc::new(AtomicBool::new(false));
+        let (requests, received) = mpsc::channel();
+        let received = Arc::new(Mutex::new(received));
+
+        for _ in 0..WORKERS {
+            let (fetch, cache, updated, received, waker) = (
+                Arc::clone(&fetch),
+                Arc::clone(&cache),
+                Arc::clone(&updated),
+                Arc::clone(&received),
+                waker.clone(),
+            );
+
+            thread::spawn(move || {
+                while let Some(oid) = next_request(&received) {
+                    // Failed requests are remembered as no status, and tried again later
+                    let checks = fetch(oid).unwrap_or_default();
+
+                    let mut cache = cache.lock().unwrap();
+                    cache.in_flight.remove(&oid);
+                    cache.checks.insert(oid, (Instant::now(), checks));
+                    updated.store(true, Ordering::Relaxed);
+                    drop(cache);
+                    waker.wake();
+                }
+            });
+        }
+
+        Self {
+            cache,
+            requests,
+            updated,
+        }
+    }
+
+    /// What's known of the status of `oid`, fetching it if it's missing or getting old.
+    pub(crate) fn get(&self, oid: Oid) -> Option<Checks> {
+        let mut cache = self.cache.lock().unwrap();
+        let cached = cache.checks.get(&oid).cloned();
+
+        let stale = cached
+            .as_ref()
+            .is_none_or(|(fetched, _)| fetched.elapsed() > MAX_AGE);
+        if stale && cache.in_flight.insert(oid) {
+            let _ = self.requests.send(oid);
+        }
+
+        cached.map(|(_, checks)| checks)
+    }
+
+    /// Whether any statuses were fetched since the last call, which calls for a redraw.
+    pub(crate) fn take_updated(&self) -> bool {
+        self.updated.swap(false, Ordering::Relaxed)
+    }
+}
+
+fn next_request(received: &Mutex<Receiver<Oid>>) -> Option<Oid> {
+    received.lock().unwrap().recv().ok()
+}
+
+/// The commit a commit, branch or tag item points to.
+pub(crate) fn target_commit(repo: &Repository, target: Option<&TargetData>) -> Option<Oid> {
+    let rev = match target? {
+        TargetData::Branch(branch) => branch,
+        target => target.commitish()?,
+    };
+
+    Some(repo.revparse_single(rev).ok()?.peel_to_commit().ok()?.id())
+}
@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Default, Debug, Parser)]
 #[command(name = "gitu")]
@@ -15,8 +16,19 @@ pub struct Args {
     #[clap(short, long, verbatim_doc_comment)]
     pub keys: Option<String>,
 
-    /// Print one frame and exit. Useful for debugging.
-    #[clap(long, action)]
+    /// Open the repository at a path, rather than the one of the current directory.
+    #[clap(short = 'C', long = "dir", global = true)]
+    pub dir: Option<PathBuf>,
+
+    /// Use a theme instead of the one set in the config (`general.theme`).
+    ///     Built-in themes are: default, light, gruvbox.
+    ///     Others are loaded from a `themes/<NAME>.toml` file next to the config file.
+    #[clap(long, verbatim_doc_comment)]
+    pub theme: Option<String>,
+
+    /// Print one frame to stdout with ANSI colors and exit (eg: `gitu log --print | less -R`).
+    ///     Keys sent with `--keys` are handled first, which helps to show a bug.
+    #[clap(long, action, global = true, verbatim_doc_comment)]
     pub print: bool,
 
     /// Enable logging to 'gitu.log'
@@ -30,5 +42,41 @@ pub struct Args {
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
+    /// Show a commit, or any other object.
     Show { reference: String },
+    /// Open the status screen.
+    Status {
+        /// Print the status as JSON and exit, without opening gitu.
+        #[clap(long, action)]
+        porcelain: bool,
+    },
+    /// Open the log of HEAD, or of a rev.
+    Log {
+        /// Print the commits as JSON and exit, without opening gitu.
+        #[clap(long, action)]
+        json: bool,
+        /// Limit the number of commits.
+        #[clap(short = 'n', long)]
+        max_count: Option<usize>,
+        rev: Option<String>,
+    },
+    /// Open the diff of a range (eg: `main..feature`), or of the unstaged changes.
+    Diff {
+        /// Print the diff as JSON and exit, without opening gitu.
+        #[clap(long, action)]
+        json: bool,
+        range: Option<String>,
+    },
+}
+
+impl Args {
+    /// Whether to print structured output instead of running gitu interactively.
+    pub fn is_porcelain(&self) -> bool {
+        matches!(
+            self.command,
+            Some(Commands::Status { porcelain: true })
+                | Some(Commands::Log { json: true, .. })
+                | Some(Commands::Diff { json: true, .. })
+        )
+    }
 }
@@ -23,6 +23,8 @@ impl CmdLog {
         let value = Arc::new(RwLock::new(CmdLogEntry::Cmd {
             args: command_args(cmd),
             out: None,
+            progress: String::new(),
+            exit_code: None,
         }));
 
         self.entries.push(Arc::clone(&value));
@@ -33,10 +35,13 @@ impl CmdLog {
         &mut self,
         cmd: &Command,
         out: Cow<'static, str>,
+        exit_code: Option<i32>,
     ) -> Arc<RwLock<CmdLogEntry>> {
         let value = Arc::new(RwLock::new(CmdLogEntry::Cmd {
             args: command_args(cmd),
             out: Some(out),
+            progress: String::new(),
+            exit_code,
         }));
 
         self.entries.push(Arc::clone(&value));
@@ -47,6 +52,11 @@ impl CmdLog {
         self.entries.push(Arc::new(RwLock::new(entry)));
     }
 
+    /// Shares an entry with another log, it'll show updates to it as well.
+    pub fn push_shared(&mut self, entry: &Arc<RwLock<CmdLogEntry>>) {
+        self.entries.push(Arc::clone(entry));
+    }
+
     pub fn clear(&mut self) {
         self.entries.clear();
     }
@@ -77,18 +87,24 @@ pub(crate) fn format_log_entry<'a>(
     log: &Arc<RwLock<CmdLogEntry>>,
 ) -> Vec<Line<'a>> {
     match &*log.read().unwrap() {
-        CmdLogEntry::Cmd { args, out } => [Line::styled(
+        CmdLogEntry::Cmd {
+            args,
+            out,
+            progress,
+            ..
+        } => [Line::styled(
             format!("{}{}", if out.is_some() { "$ " } else { "Running: " }, args),
             &config.style.command,
         )]
         .into_iter()
-        .chain(out.iter().flat_map(|out| {
+        .chain({
+            let out = out.as_deref().unwrap_or(progress);
             if out.is_empty() {
                 vec![]
             } else {
-                Text::raw(out.to_string()).lines
+                Text::raw(collapse_progress(out)).lines
             }
-        }))
+        })
         .collect::<Vec<_>>(),
         CmdLogEntry::Error(err) => {
             vec![Line::styled(
@@ -105,10 +121,24 @@ pub(crate) fn format_log_entry<'a>(
     }
 }
 
+/// Git redraws progress meters in place with carriage returns, only their latest state is shown.
+fn collapse_progress(out: &str) -> String {
+    out.lines()
+        .map(|line| {
+            line.rsplit('\r')
+                .find(|part| !part.is_empty())
+                .unwrap_or_default()
+        })
+        .join("\n")
+}
+
 pub(crate) enum CmdLogEntry {
     Cmd {
         args: Cow<'static, str>,
         out: Option<Cow<'static, str>>,
+        /// Output so far of a command that's still running
+        progress: String,
+        exit_code: Option<i32>,
     },
     Error(String),
     Info(String),
@@ -1,41 +1,223 @@
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 
-use crate::{menu::Menu, ops::Op, Res};
+use crate::{bindings, menu::Menu, ops::Op, Res};
 use etcetera::{choose_base_strategy, BaseStrategy};
 use figment::{
     providers::{Format, Toml},
-    Figment,
+    Figment, Provider,
 };
 use ratatui::style::{Color, Modifier, Style};
 use serde::Deserialize;
 
 const DEFAULT_CONFIG: &str = include_str!("default_config.toml");
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
 pub(crate) struct Config {
     pub general: GeneralConfig,
     pub style: StyleConfig,
     pub bindings: BTreeMap<Menu, BTreeMap<Op, Vec<String>>>,
+    /// Changed from the diff options menu while running, not read from the config file.
+    /// Shared by clones, such as the one the status screen refreshes with in the background.
+    #[serde(skip)]
+    pub diff_settings: Arc<RwLock<DiffSettings>>,
+    /// Files whose diffs are shown though larger than `general.large_diff_lines`.
+    #[serde(skip)]
+    pub shown_large_diffs: Arc<RwLock<HashSet<PathBuf>>>,
+}
+
+/// How diffs are shown on the status, show and diff screens.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DiffSettings {
+    pub ignore_whitespace: bool,
+    pub ignore_blank_lines: bool,
+    /// The Myers algorithm is used otherwise
+    pub patience: bool,
+    /// Lines of context around each hunk, `None` leaves it at git's 3
+    pub context_lines: Option<u32>,
+    /// Shows what hunks removed to the left of what they added in its place
+    pub side_by_side: bool,
+}
+
+impl Default for DiffSettings {
+    fn default() -> Self {
+        Self {
+            ignore_whitespace: false,
+            ignore_blank_lines: false,
+            patience: true,
+            context_lines: None,
+            side_by_side: false,
+        }
+    }
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
 pub struct GeneralConfig {
+    pub theme: String,
     pub always_show_help: BoolConfigEntry,
     pub confirm_quit: BoolConfigEntry,
+    pub confirm_discard: BoolConfigEntry,
+    pub confirm_clean: BoolConfigEntry,
+    pub confirm_reset_hard: BoolConfigEntry,
+    pub confirm_force_delete_branch: BoolConfigEntry,
+    pub confirm_drop_commit: BoolConfigEntry,
+    pub confirm_checkout_file: BoolConfigEntry,
+    pub confirm_remove_remote: BoolConfigEntry,
+    pub builtin_commit_editor: CommitEditorConfig,
+    pub diff_line_numbers: BoolConfigEntry,
+    pub diff_side_by_side: BoolConfigEntry,
+    pub large_diff_lines: usize,
+    pub image_preview: ImagePreviewConfig,
     pub collapsed_sections: Vec<String>,
+    pub section_level: usize,
+    pub recent_commits_limit: usize,
+    pub log_page_size: usize,
+    pub auto_refresh: AutoRefreshConfig,
+    pub poll_interval: PollIntervalConfig,
+    pub scrollbar: BoolConfigEntry,
+    pub auto_fetch: AutoFetchConfig,
+    pub difftool: DifftoolConfig,
+    pub forge: ForgeConfig,
+    pub clipboard: ClipboardConfig,
+    pub repositories: RepositoriesConfig,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
 pub struct BoolConfigEntry {
     #[serde(default)]
     pub enabled: bool,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct ImagePreviewConfig {
+    /// Only takes effect in terminals supporting the kitty graphics protocol
+    #[serde(default)]
+    pub enabled: bool,
+    /// The most rows an image takes up
+    #[serde(default)]
+    pub rows: u16,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct AutoRefreshConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Milliseconds between looking for changes while files keep changing, or between polls without a file watcher
+    #[serde(default)]
+    pub interval: u64,
+}
+
+/// Milliseconds to wait for keys before looking after background work again.
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct PollIntervalConfig {
+    /// While a command runs or a screen refreshes, otherwise gitu waits until there's something to do
+    #[serde(default)]
+    pub busy: u64,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct AutoFetchConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between fetches
+    #[serde(default)]
+    pub interval: u64,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct DifftoolConfig {
+    /// Passed as `--tool`, `--gui` is used if empty
+    #[serde(default)]
+    pub tool: String,
+    #[serde(default)]
+    pub dir_diff: bool,
+    /// Shows diffs as `core.pager`, git's own pager is used if empty
+    #[serde(default)]
+    pub pager: String,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct ForgeConfig {
+    /// Opens URLs, the platform's default if empty
+    #[serde(default)]
+    pub browser: String,
+    /// Which of `templates` a remote's host uses
+    #[serde(default)]
+    pub hosts: BTreeMap<String, String>,
+    #[serde(default)]
+    pub templates: BTreeMap<String, ForgeTemplates>,
+    /// Authenticates GitHub API requests, which are made with the `gh` CLI if empty
+    #[serde(default)]
+    pub github_token: String,
+    /// Shows the CI status of commits and branches on GitHub
+    #[serde(default)]
+    pub ci_status: bool,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct ForgeTemplates {
+    pub commit: String,
+    pub branch: String,
+    pub file: String,
+    /// Appended to `file` when there's a line to go to
+    pub line: String,
+    #[serde(default)]
+    pub pull_request: String,
+    /// Where a pull request of `{branch}` is created
+    #[serde(default)]
+    pub new_pull_request: String,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct RepositoriesConfig {
+    /// Directories the repository switcher lists the repositories of, `~` being the home directory
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// How many levels of directories below the roots are searched
+    #[serde(default)]
+    pub depth: usize,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct ClipboardConfig {
+    #[serde(default)]
+    pub backend: ClipboardBackend,
+    /// Run by the `command` backend with the copied text on stdin
+    #[serde(default)]
+    pub command: Vec<String>,
+}
+
+/// Where copied text goes.
+#[derive(Clone, Copy, Default, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardBackend {
+    /// The system clipboard, or OSC 52 over SSH and where there's none
+    #[default]
+    Auto,
+    System,
+    /// The terminal's clipboard, set with an escape sequence
+    Osc52,
+    Command,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct CommitEditorConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Initial message of new commits, `commit.template` is used if empty
+    #[serde(default)]
+    pub template: String,
+}
+
+#[derive(Clone, Default, Debug, Deserialize)]
 pub struct StyleConfig {
     pub section_header: StyleConfigEntry,
     pub file_header: StyleConfigEntry,
     pub hunk_header: StyleConfigEntry,
+    pub line_number: StyleConfigEntry,
 
     #[serde(default)]
     pub diff_highlight: DiffHighlightConfig,
@@ -47,18 +229,22 @@ pub struct StyleConfig {
     pub selection_line: StyleConfigEntry,
     pub selection_bar: SymbolStyleConfigEntry,
     pub selection_area: StyleConfigEntry,
+    pub search_match: StyleConfigEntry,
 
     pub hash: StyleConfigEntry,
     pub branch: StyleConfigEntry,
     pub remote: StyleConfigEntry,
     pub tag: StyleConfigEntry,
+    pub signature_good: StyleConfigEntry,
+    pub signature_bad: StyleConfigEntry,
+    pub signature_untrusted: StyleConfigEntry,
 
     pub command: StyleConfigEntry,
     pub active_arg: StyleConfigEntry,
     pub hotkey: StyleConfigEntry,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
 pub struct DiffHighlightConfig {
     #[serde(default)]
     pub tag_old: StyleConfigEntry,
@@ -74,10 +260,13 @@ pub struct DiffHighlightConfig {
     pub changed_new: StyleConfigEntry,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
 pub struct SyntaxHighlightConfig {
     #[serde(default)]
     pub enabled: bool,
+    /// Files larger than this many bytes aren't highlighted, 0 highlights any size
+    #[serde(default)]
+    pub max_file_size: usize,
     #[serde(default)]
     pub attribute: StyleConfigEntry,
     #[serde(default)]
@@ -125,7 +314,7 @@ pub struct SyntaxHighlightConfig {
     pub variable_parameter: StyleConfigEntry,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
 pub struct StyleConfigEntry {
     #[serde(default)]
     fg: Option<Color>,
@@ -135,7 +324,7 @@ pub struct StyleConfigEntry {
     mods: Option<Modifier>,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Clone, Default, Debug, Deserialize)]
 pub struct SymbolStyleConfigEntry {
     #[serde(default)]
     pub symbol: char,
@@ -171,7 +360,16 @@ impl From<&SymbolStyleConfigEntry> for Style {
     }
 }
 
-pub(crate) fn init_config() -> Res<Config> {
+const THEMES: [(&str, &str); 3] = [
+    ("default", ""),
+    ("light", include_str!("themes/light.toml")),
+    ("gruvbox", include_str!("themes/gruvbox.toml")),
+];
+
+/// Loads the user's config file on top of the defaults and the selected theme.
+/// Problems with it don't prevent startup, they're returned as error messages
+/// and the affected parts of the config fall back to the defaults.
+pub(crate) fn init_config(theme: Option<&str>) -> Res<(Config, Vec<String>)> {
     let config_path = config_path();
 
     if config_path.exists() {
@@ -180,12 +378,73 @@ pub(crate) fn init_config() -> Res<Config> {
         log::info!("No config file at {:?}", config_path);
     }
 
-    let config = Figment::new()
+    load_config(Toml::file(config_path), theme)
+}
+
+fn load_config(user_config: impl Provider, theme: Option<&str>) -> Res<(Config, Vec<String>)> {
+    let defaults = Figment::new().merge(Toml::string(DEFAULT_CONFIG));
+    let user_config = Figment::from(user_config);
+    let mut errors = vec![];
+
+    let theme_name = match theme {
+        Some(name) => name.to_string(),
+        None => defaults
+            .clone()
+            .merge(user_config.clone())
+            .extract_inner("general.theme")
+            .unwrap_or_else(|_| "default".into()),
+    };
+
+    let theme = load_theme(&theme_name).unwrap_or_else(|err| {
+        errors.push(format!("{}, using the default theme", err));
+        Figment::new()
+    });
+
+    let mut config: Config = match defaults.clone().merge(theme).merge(user_config).extract() {
+        Ok(config) => config,
+        Err(err) => {
+            errors.push(format!("Couldn't load config, using defaults: {}", err));
+            return Ok((defaults.extract()?, errors));
+        }
+    };
+
+    config.diff_settings = Arc::new(RwLock::new(DiffSettings {
+        side_by_side: config.general.diff_side_by_side.enabled,
+        ..DiffSettings::default()
+    }));
+
+    let binding_errors = bindings::validate(&config.bindings);
+    if !binding_errors.is_empty() {
+        config.bindings = defaults.extract::<Config>()?.bindings;
+        errors.extend(binding_errors);
+        errors.push("Invalid bindings in config, using defaults".into());
+    }
+
+    Ok((config, errors))
+}
+
+/// The bindings of the default config, before the user's are applied.
+pub(crate) fn default_bindings() -> Res<BTreeMap<Menu, BTreeMap<Op, Vec<String>>>> {
+    Ok(Figment::new()
         .merge(Toml::string(DEFAULT_CONFIG))
-        .merge(Toml::file(config_path))
-        .extract()?;
+        .extract_inner("bindings")?)
+}
 
-    Ok(config)
+/// A built-in theme, or one from the `themes` directory next to the config file.
+fn load_theme(name: &str) -> Res<Figment> {
+    if let Some((_, theme)) = THEMES.iter().find(|(theme_name, _)| *theme_name == name) {
+        return Ok(Figment::from(Toml::string(theme)));
+    }
+
+    let path = config_path()
+        .with_file_name("themes")
+        .join(format!("{}.toml", name));
+    if path.exists() {
+        log::info!("Loading theme file at {:?}", path);
+        Ok(Figment::from(Toml::file(path)))
+    } else {
+        Err(format!("Unknown theme '{}'", name).into())
+    }
 }
 
 pub fn config_path() -> PathBuf {
@@ -213,7 +472,8 @@ mod tests {
     };
     use ratatui::style::Color;
 
-    use super::{Config, DEFAULT_CONFIG};
+    use super::{load_config, Config, DEFAULT_CONFIG, THEMES};
+    use crate::{bindings, menu::Menu, ops::Op};
 
     #[test]
     fn config_merges() {
@@ -231,4 +491,217 @@ mod tests {
         assert_eq!(config.style.hunk_header.bg, Some(Color::LightGreen));
         assert_eq!(config.style.hunk_header.fg, Some(Color::Blue));
     }
+
+    #[test]
+    fn default_bindings_are_valid() {
+        let config: Config = Figment::new()
+            .merge(Toml::string(DEFAULT_CONFIG))
+            .extract()
+            .unwrap();
+
+        assert_eq!(bindings::validate(&config.bindings), Vec::<String>::new());
+    }
+
+    #[test]
+    fn custom_bindings() {
+        let (config, errors) = load_config(
+            Toml::string(
+                r#"
+            [bindings]
+            root.refresh = ["<ctrl+r>", "gr"]
+            "#,
+            ),
+            None,
+        )
+        .unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            config.bindings[&Menu::Root][&Op::Refresh],
+            vec!["<ctrl+r>", "gr"]
+        );
+    }
+
+    #[test]
+    fn diff_side_by_side_sets_initial_diff_settings() {
+        let (config, errors) = load_config(
+            Toml::string(
+                r#"
+            [general]
+            diff_side_by_side.enabled = true
+            "#,
+            ),
+            None,
+        )
+        .unwrap();
+
+        assert!(errors.is_empty());
+        assert!(config.diff_settings.read().unwrap().side_by_side);
+    }
+
+    #[test]
+    fn poll_interval_overrides_default() {
+        let (config, errors) = load_config(
+            Toml::string(
+                r#"
+            [general]
+            poll_interval.busy = 50
+            "#,
+            ),
+            None,
+        )
+        .unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(config.general.poll_interval.busy, 50);
+    }
+
+    #[test]
+    fn invalid_keys_fall_back_to_defaults() {
+        let (config, errors) = load_config(
+            Toml::string(
+                r#"
+            [bindings]
+            root.refresh = ["<ctrl+"]
+            "#,
+            ),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            errors,
+            vec![
+                "Invalid keys '<ctrl+' for root.refresh",
+                "Invalid bindings in config, using defaults"
+            ]
+        );
+        assert_eq!(config.bindings[&Menu::Root][&Op::Refresh], vec!["g"]);
+    }
+
+    #[test]
+    fn unknown_op() {
+        let (_config, errors) = load_config(
+            Toml::string(
+                r#"
+            [bindings]
+            root.refersh = ["g"]
+            "#,
+            ),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(errors[0], "Unknown op or argument 'refersh' in root");
+    }
+
+    #[test]
+    fn conflicting_keys() {
+        let (config, errors) = load_config(
+            Toml::string(
+                r#"
+            [bindings]
+            root.refresh = ["ss"]
+            "#,
+            ),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            errors[0],
+            "Keys 's' for root.stage conflict with 'ss' for root.refresh"
+        );
+        assert_eq!(config.bindings[&Menu::Root][&Op::Refresh], vec!["g"]);
+    }
+
+    #[test]
+    fn invalid_config_falls_back_to_defaults() {
+        let (config, errors) = load_config(
+            Toml::string(
+                r#"
+            [style]
+            hunk_header.bg = "not a color"
+            "#,
+            ),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Couldn't load config, using defaults"));
+        assert_eq!(config.style.hunk_header.fg, Some(Color::Blue));
+    }
+
+    #[test]
+    fn theme_from_config() {
+        let (config, errors) = load_config(
+            Toml::string(
+                r#"
+                [general]
+                theme = "gruvbox"
+                "#,
+            ),
+            None,
+        )
+        .unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            config.style.hunk_header.fg,
+            Some(Color::Rgb(0x83, 0xa5, 0x98))
+        );
+    }
+
+    #[test]
+    fn theme_from_arg_overrides_config() {
+        let (config, _errors) = load_config(
+            Toml::string(
+                r#"
+                [general]
+                theme = "gruvbox"
+                "#,
+            ),
+            Some("light"),
+        )
+        .unwrap();
+
+        assert_eq!(config.style.hunk_header.fg, Some(Color::Indexed(25)));
+    }
+
+    #[test]
+    fn style_overrides_theme() {
+        let (config, _errors) = load_config(
+            Toml::string(
+                r#"
+                [style]
+                hunk_header.fg = "light green"
+                "#,
+            ),
+            Some("light"),
+        )
+        .unwrap();
+
+        assert_eq!(config.style.hunk_header.fg, Some(Color::LightGreen));
+        assert_eq!(config.style.file_header.fg, Some(Color::Indexed(90)));
+    }
+
+    #[test]
+    fn unknown_theme() {
+        let (config, errors) = load_config(Toml::string(""), Some("nonexistent")).unwrap();
+
+        assert_eq!(
+            errors,
+            vec!["Unknown theme 'nonexistent', using the default theme"]
+        );
+        assert_eq!(config.style.hunk_header.fg, Some(Color::Blue));
+    }
+
+    #[test]
+    fn builtin_themes_load() {
+        for (name, _) in THEMES {
+            let (_config, errors) = load_config(Toml::string(""), Some(name)).unwrap();
+            assert!(errors.is_empty(), "{}: {:?}", name, errors);
+        }
+    }
 }
@@ -0,0 +1,241 @@
+use crate::Res;
+use crossterm::event::{self, Event};
+use std::{
+    io, iter,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+// Everything the main loop looks after comes to it through a single channel: keys and other terminal
+// events, read on a thread of their own, and word from background work, e.g. that a command finished
+// or the file watcher saw a change. An idle gitu sleeps until there's something, rather than polling.
+
+/// At most this many events are handled before drawing again, so that a flood of them still shows.
+const MAX_BATCHED_EVENTS: usize = 64;
+
+/// How long the input thread waits for an event before looking whether it's to pause, which is as
+/// long as `Input::pause` may take.
+const PAUSE_POLL: Duration = Duration::from_millis(10);
+
+enum Wake {
+    Term(io::Result<Event>),
+    /// Background work has something to look at
+    Background,
+}
+
+/// Wakes the main loop from another thread. The default one wakes nothing, for when there's no
+/// loop waiting, like in tests.
+#[derive(Clone, Default)]
+pub(crate) struct Waker(Option<Sender<Wake>>);
+
+impl Waker {
+    pub(crate) fn wake(&self) {
+        if let Some(sender) = &self.0 {
+            // Nothing is waiting anymore once gitu quits
+            let _ = sender.send(Wake::Background);
+        }
+    }
+}
+
+pub(crate) struct Events {
+    receiver: Receiver<Wake>,
+    waker: Waker,
+    input: Input,
+}
+
+impl Events {
+    /// Starts reading terminal events on a thread of their own.
+    pub(crate) fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let shared = Arc::new((Mutex::new(InputState::Reading), Condvar::new()));
+
+        let input_sender = sender.clone();
+        let input_shared = Arc::clone(&shared);
+        thread::spawn(move || read_input(input_sender, input_shared));
+
+        Self {
+            receiver,
+            waker: Waker(Some(sender)),
+            input: Input(Some(shared)),
+        }
+    }
+
+    pub(crate) fn waker(&self) -> Waker {
+        self.waker.clone()
+    }
+
+    pub(crate) fn input(&self) -> Input {
+        self.input.clone()
+    }
+
+    /// Blocks until there's something to look after, or for at most `timeout`, e.g. to turn a spinner.
+    /// The terminal events that came along are taken too, so they're all handled before drawing once.
+    /// E.g. when `j` is held down.
+    pub(crate) fn wait(&self, timeout: Option<Duration>) -> Res<Vec<Event>> {
+        let first = match timeout {
+            None => Some(self.receiver.recv()?),
+            Some(timeout) => match self.receiver.recv_timeout(timeout) {
+                Ok(wake) => Some(wake),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(err) => return Err(err.into()),
+            },
+        };
+
+        let mut events = vec![];
+        for wake in first
+            .into_iter()
+            .chain(iter::from_fn(|| self.receiver.try_recv().ok()))
+        {
+            if let Wake::Term(event) = wake {
+                events.push(event?);
+            }
+
+            if events.len() >= MAX_BATCHED_EVENTS {
+                break;
+            }
+        }
+
+        Ok(coalesce_resizes(events))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum InputState {
+    Reading,
+    Pausing,
+    Paused,
+}
+
+type SharedInputState = Arc<(Mutex<InputState>, Condvar)>;
+
+/// Hands terminal input over while paused, e.g. to an editor, which would otherwise miss keys
+/// that gitu read. The default one pauses nothing, for when nothing reads input, like in tests.
+#[derive(Clone, Default)]
+pub(crate) struct Input(Option<SharedInputState>);
+
+impl Input {
+    /// Stops reading terminal events until the returned guard is dropped.
+    pub(crate) fn pause(&self) -> PausedInput {
+        if let Some(shared) = &self.0 {
+            let (state, changed) = &**shared;
+            let mut state = state.lock().unwrap();
+            *state = InputState::Pausing;
+            changed.notify_all();
+
+            while *state != InputState::Paused {
+                state = changed.wait(state).unwrap();
+            }
+        }
+
+        PausedInput(self.0.clone())
+    }
+}
+
+/// Reads terminal events again once dropped.
+pub(crate) struct PausedInput(Option<SharedInputState>);
+
+impl Drop for PausedInput {
+    fn drop(&mut self) {
+        if let Some(shared) = &self.0 {
+            let (state, changed) = &**shared;
+            *state.lock().unwrap() = InputState::Reading;
+            changed.notify_all();
+        }
+    }
+}
+
+fn read_input(sender: Sender<Wake>, shared: SharedInputState) {
+    let (state, changed) = &*shared;
+
+    loop {
+        {
+            let mut state = state.lock().unwrap();
+            while *state != InputState::Reading {
+                if *state == InputState::Pausing {
+                    *state = InputState::Paused;
+                    changed.notify_all();
+                }
+                state = changed.wait(state).unwrap();
+            }
+        }
+
+        let event = match read_event() {
+            Some(event) => event,
+            None => continue,
+        };
+
+        let failed = event.is_err();
+        if sender.send(Wake::Term(event)).is_err() || failed {
+            return;
+        }
+    }
+}
+
+/// Waits a little for an event, rather than blocking on a read that nothing could interrupt when
+/// input is paused. Nothing is read from the terminal while the thread checks whether it's paused.
+fn read_event() -> Option<io::Result<Event>> {
+    match event::poll(PAUSE_POLL) {
+        Ok(true) => Some(event::read()),
+        Ok(false) => None,
+        Err(err) => Some(Err(err)),
+    }
+}
+
+/// Only the last of several resizes matters.
+fn coalesce_resizes(events: Vec<Event>) -> Vec<Event> {
+    let last_resize = events
+        .iter()
+        .rposition(|event| matches!(event, Event::Resize(..)));
+
+    events
+        .into_iter()
+        .enumerate()
+        .filter(|(i, event)| !matches!(event, Event::Resize(..)) || Some(*i) == last_resize)
+        .map(|(_, event)| event)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    #[test]
+    fn coalesce_resizes_keeps_keys_and_last_resize() {
+        let key = Event::Key(KeyEvent::from(KeyCode::Char('j')));
+        let events = vec![
+            Event::Resize(10, 10),
+            key.clone(),
+            Event::Resize(20, 20),
+            key.clone(),
+            Event::Resize(30, 30),
+        ];
+
+        assert_eq!(
+            coalesce_resizes(events),
+            [key.clone(), key, Event::Resize(30, 30)]
+        );
+    }
+
+    #[test]
+    fn wait_wakes_on_background_work() {
+        let (sender, receiver) = mpsc::channel();
+        let events = Events {
+            receiver,
+            waker: Waker(Some(sender)),
+            input: Input::default(),
+        };
+
+        let waker = events.waker();
+        thread::spawn(move || waker.wake());
+        assert!(events.wait(None).unwrap().is_empty());
+        assert!(events
+            .wait(Some(Duration::from_millis(1)))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -2,4 +2,6 @@
 pub(crate) struct Commit {
     pub hash: String,
     pub details: String,
+    /// Like `git describe --tags`, if there's a tag to describe it by
+    pub describe: Option<String>,
 }
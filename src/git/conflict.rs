@@ -0,0 +1,260 @@
+/// A region between conflict markers in a work tree file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Conflict {
+    /// Line of the `<<<<<<<` marker, starting at 1
+    pub line: usize,
+    pub ours_label: String,
+    pub ours: Vec<String>,
+    /// Only present with `merge.conflictStyle = diff3`
+    pub base: Option<Vec<String>>,
+    pub theirs: Vec<String>,
+    pub theirs_label: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Side {
+    Ours,
+    Theirs,
+    Both,
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Conflict {
+        line: usize,
+        ours_marker: &'a str,
+        ours: Vec<&'a str>,
+        base: Option<(&'a str, Vec<&'a str>)>,
+        separator: &'a str,
+        theirs: Vec<&'a str>,
+        theirs_marker: &'a str,
+    },
+}
+
+pub(crate) fn parse(content: &str) -> Vec<Conflict> {
+    segments(content)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Text(_) => None,
+            Segment::Conflict {
+                line,
+                ours_marker,
+                ours,
+                base,
+                theirs,
+                theirs_marker,
+                ..
+            } => Some(Conflict {
+                line,
+                ours_label: marker_label(ours_marker, "<<<<<<<"),
+                ours: trim_lines(&ours),
+                base: base.map(|(_, base)| trim_lines(&base)),
+                theirs: trim_lines(&theirs),
+                theirs_label: marker_label(theirs_marker, ">>>>>>>"),
+            }),
+        })
+        .collect()
+}
+
+/// Replaces the conflict at `index`, or all of them, with the chosen side.
+pub(crate) fn resolve(content: &str, index: Option<usize>, side: Side) -> String {
+    let mut conflict_i = 0;
+    let mut out = String::with_capacity(content.len());
+
+    for segment in segments(content) {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Conflict {
+                ours_marker,
+                ours,
+                base,
+                separator,
+                theirs,
+                theirs_marker,
+                ..
+            } => {
+                if index.is_none_or(|index| index == conflict_i) {
+                    if side != Side::Theirs {
+                        out.extend(ours);
+                    }
+                    if side != Side::Ours {
+                        out.extend(theirs);
+                    }
+                } else {
+                    out.push_str(ours_marker);
+                    out.extend(ours);
+                    if let Some((base_marker, base)) = base {
+                        out.push_str(base_marker);
+                        out.extend(base);
+                    }
+                    out.push_str(separator);
+                    out.extend(theirs);
+                    out.push_str(theirs_marker);
+                }
+                conflict_i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Splits `content` into text and conflicts, any unterminated conflict is kept as text.
+fn segments(content: &str) -> Vec<Segment<'_>> {
+    let lines = content.split_inclusive('\n').collect::<Vec<_>>();
+    let mut segments = vec![];
+    let (mut text_start, mut offset, mut i) = (0, 0, 0);
+
+    while i < lines.len() {
+        let conflict = is_marker(lines[i], "<<<<<<<")
+            .then(|| parse_conflict(&lines, i))
+            .flatten();
+
+        let Some((segment, end)) = conflict else {
+            offset += lines[i].len();
+            i += 1;
+            continue;
+        };
+
+        if text_start < offset {
+            segments.push(Segment::Text(&content[text_start..offset]));
+        }
+        segments.push(segment);
+
+        offset += lines[i..end].iter().map(|line| line.len()).sum::<usize>();
+        text_start = offset;
+        i = end;
+    }
+
+    if text_start < content.len() {
+        segments.push(Segment::Text(&content[text_start..]));
+    }
+
+    segments
+}
+
+/// Parses the conflict whose `<<<<<<<` marker is at `start`, returning it and the line after it.
+fn parse_conflict<'a>(lines: &[&'a str], start: usize) -> Option<(Segment<'a>, usize)> {
+    let mut ours = vec![];
+    let mut base: Option<(&str, Vec<&str>)> = None;
+    let mut separator = None;
+    let mut theirs = vec![];
+
+    for (i, &line) in lines.iter().enumerate().skip(start + 1) {
+        if let Some(separator) = separator {
+            if is_marker(line, ">>>>>>>") {
+                let segment = Segment::Conflict {
+                    line: start + 1,
+                    ours_marker: lines[start],
+                    ours,
+                    base,
+                    separator,
+                    theirs,
+                    theirs_marker: line,
+                };
+                return Some((segment, i + 1));
+            }
+            theirs.push(line);
+        } else if line.trim_end_matches(['\r', '\n']) == "=======" {
+            separator = Some(line);
+        } else if is_marker(line, "|||||||") && base.is_none() {
+            base = Some((line, vec![]));
+        } else if let Some((_, base)) = &mut base {
+            base.push(line);
+        } else {
+            ours.push(line);
+        }
+    }
+
+    None
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\r', '\n']))
+}
+
+fn marker_label(line: &str, marker: &str) -> String {
+    line[marker.len()..].trim().to_string()
+}
+
+fn trim_lines(lines: &[&str]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| line.trim_end_matches(['\r', '\n']).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "\
+start
+<<<<<<< HEAD
+hi
+=======
+hey
+>>>>>>> other-branch
+middle
+<<<<<<< HEAD
+one
+||||||| base
+zero
+=======
+two
+>>>>>>> other-branch
+end
+";
+
+    #[test]
+    fn parse_conflicts() {
+        let conflicts = parse(CONTENT);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(
+            conflicts[0],
+            Conflict {
+                line: 2,
+                ours_label: "HEAD".into(),
+                ours: vec!["hi".into()],
+                base: None,
+                theirs: vec!["hey".into()],
+                theirs_label: "other-branch".into(),
+            }
+        );
+        assert_eq!(conflicts[1].line, 8);
+        assert_eq!(conflicts[1].base, Some(vec!["zero".into()]));
+    }
+
+    #[test]
+    fn resolve_all() {
+        assert_eq!(
+            resolve(CONTENT, None, Side::Ours),
+            "start\nhi\nmiddle\none\nend\n"
+        );
+        assert_eq!(
+            resolve(CONTENT, None, Side::Theirs),
+            "start\nhey\nmiddle\ntwo\nend\n"
+        );
+        assert_eq!(
+            resolve(CONTENT, None, Side::Both),
+            "start\nhi\nhey\nmiddle\none\ntwo\nend\n"
+        );
+    }
+
+    #[test]
+    fn resolve_one() {
+        let resolved = resolve(CONTENT, Some(1), Side::Theirs);
+        assert!(
+            resolved.starts_with("start\n<<<<<<< HEAD\nhi\n=======\nhey\n>>>>>>> other-branch\n")
+        );
+        assert!(resolved.ends_with("middle\ntwo\nend\n"));
+    }
+
+    #[test]
+    fn unterminated_conflict() {
+        let content = "a\n<<<<<<< HEAD\nb\n";
+        assert!(parse(content).is_empty());
+        assert_eq!(resolve(content, None, Side::Ours), content);
+    }
+}
@@ -1,22 +1,24 @@
 use crate::{
     config::Config,
+    image::Image,
     syntax_highlight::{self},
     Res,
 };
-use git2::Repository;
+use git2::{FileMode, Repository};
 use itertools::Itertools;
 use ratatui::{
     style::Style,
     text::{Line, Span, Text},
 };
-use similar::{Algorithm, DiffOp, DiffTag, DiffableStr, TextDiff};
+use similar::{udiff::UnifiedDiffHunk, Algorithm, DiffOp, DiffTag, DiffableStr, TextDiff};
 use std::{
+    borrow::Cow,
     fs,
     iter::{self},
     ops::Range,
-    path::PathBuf,
-    rc::Rc,
+    path::{Path, PathBuf},
     str,
+    sync::Arc,
 };
 
 #[derive(Debug, Clone)]
@@ -29,8 +31,34 @@ pub(crate) struct Delta {
     pub file_header: String,
     pub old_file: PathBuf,
     pub new_file: PathBuf,
-    pub hunks: Vec<Rc<Hunk>>,
+    pub hunks: Vec<Arc<Hunk>>,
     pub status: git2::Delta,
+    /// The line count of a file too large to be diffed, see `general.large_diff_lines`.
+    /// It has no hunks until shown anyway.
+    pub large: Option<usize>,
+    /// The old and new size in bytes of a file git considers binary, which has no hunks.
+    pub binary: Option<(usize, usize)>,
+    /// The new version of a changed PNG image, or the old one if it was deleted, see `general.image_preview`.
+    pub image: Option<Arc<Image>>,
+    pub old_mode: FileMode,
+    pub new_mode: FileMode,
+    /// Kept out of the hunks, so that it's staged on its own.
+    pub mode_change: Option<Arc<ModeChange>>,
+}
+
+/// A change of a file's mode alone, like it being made executable.
+#[derive(Debug)]
+pub(crate) struct ModeChange {
+    pub file: PathBuf,
+    pub old_mode: FileMode,
+    pub new_mode: FileMode,
+    patch: String,
+}
+
+impl ModeChange {
+    pub(crate) fn format_patch(&self) -> Vec<u8> {
+        self.patch.clone().into_bytes()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +68,9 @@ pub(crate) struct Hunk {
     pub new_start: u32,
     pub header: String,
     pub content: Text<'static>,
+    /// The exact bytes of each line of `content`, when the file wasn't valid UTF-8 and
+    /// `content` had to be decoded lossily. Patches are made of these so that they still apply.
+    pub raw_lines: Option<Vec<Vec<u8>>>,
 }
 
 #[derive(Debug)]
@@ -49,45 +80,86 @@ pub(crate) enum PatchMode {
 }
 
 impl Hunk {
-    pub(crate) fn format_patch(&self) -> String {
-        format!("{}{}\n{}\n", &self.file_header, self.header, self.content)
+    pub(crate) fn format_patch(&self) -> Vec<u8> {
+        self.patch_with_lines(self.patch_lines())
     }
 
-    pub(crate) fn format_line_patch(&self, line_range: Range<usize>, mode: PatchMode) -> String {
-        let modified_content = self
-            .content
-            .lines
-            .iter()
-            .enumerate()
-            .filter_map(|(i, line)| {
-                let add = match mode {
-                    PatchMode::Normal => '+',
-                    PatchMode::Reverse => '-',
-                };
-
-                let remove = match mode {
-                    PatchMode::Normal => '-',
-                    PatchMode::Reverse => '+',
-                };
-
-                let patch_line = format!("{line}");
+    pub(crate) fn format_line_patch(&self, line_range: Range<usize>, mode: PatchMode) -> Vec<u8> {
+        let (add, remove) = match mode {
+            PatchMode::Normal => (b'+', b'-'),
+            PatchMode::Reverse => (b'-', b'+'),
+        };
 
+        let modified_lines = self
+            .patch_lines()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, patch_line)| {
                 if line_range.contains(&i) {
                     Some(patch_line)
-                } else if patch_line.starts_with(add) {
+                } else if patch_line.first() == Some(&add) {
                     None
-                } else if let Some(stripped) = patch_line.strip_prefix(remove) {
-                    Some(format!(" {}", stripped))
+                } else if patch_line.first() == Some(&remove) {
+                    Some(Cow::Owned([b" ", &patch_line[1..]].concat()))
                 } else {
                     Some(patch_line)
                 }
             })
-            .join("\n");
+            .collect();
 
-        format!(
-            "{}{}\n{}\n",
-            &self.file_header, self.header, modified_content
-        )
+        self.patch_with_lines(modified_lines)
+    }
+
+    /// The lines of the content as they are in the file.
+    fn patch_lines(&self) -> Vec<Cow<'_, [u8]>> {
+        match &self.raw_lines {
+            Some(raw_lines) => raw_lines
+                .iter()
+                .map(|line| Cow::Borrowed(&line[..]))
+                .collect(),
+            None => self
+                .content
+                .lines
+                .iter()
+                .map(|line| Cow::Owned(line.to_string().into_bytes()))
+                .collect(),
+        }
+    }
+
+    fn patch_with_lines(&self, lines: Vec<Cow<'_, [u8]>>) -> Vec<u8> {
+        let mut patch = format!("{}{}\n", &self.file_header, self.header).into_bytes();
+        patch.extend(lines.join(&b'\n'));
+        patch.push(b'\n');
+        patch
+    }
+
+    /// The old and new line number of each line of the content, on the sides it's on.
+    pub(crate) fn line_numbers(&self) -> Vec<(Option<u32>, Option<u32>)> {
+        let old_start = self
+            .header
+            .strip_prefix("@@ -")
+            .and_then(|rest| rest.split([' ', ',']).next())
+            .and_then(|start| start.parse().ok())
+            .unwrap_or(0);
+
+        let (mut old, mut new) = (old_start, self.new_start);
+        self.content
+            .lines
+            .iter()
+            .map(|line| {
+                let start = line.spans.first().map_or("", |span| span.content.as_ref());
+                let (on_old, on_new) = match start.chars().next() {
+                    Some('-') => (true, false),
+                    Some('+') => (false, true),
+                    _ => (true, true),
+                };
+
+                let numbers = (on_old.then_some(old), on_new.then_some(new));
+                old += on_old as u32;
+                new += on_new as u32;
+                numbers
+            })
+            .collect()
     }
 
     pub(crate) fn first_diff_line(&self) -> u32 {
@@ -127,14 +199,22 @@ pub(crate) fn convert_diff(
                     new_file: path(&diffdelta.new_file()),
                     hunks: vec![],
                     status: diffdelta.status(),
+                    large: None,
+                    binary: None,
+                    image: None,
+                    old_mode: diffdelta.old_file().mode(),
+                    new_mode: diffdelta.new_file().mode(),
+                    mode_change: None,
                 };
+                delta.mode_change = mode_change(&delta);
 
-                if let Ok(hunks) = diff_files(repo, diffdelta, workdir, config, &delta) {
-                    delta.hunks = hunks;
+                // A file is replaced by another kind of file as a whole, the two have no hunks
+                if delta.status != git2::Delta::Typechange {
+                    let _ = diff_files(repo, diffdelta, workdir, config, &mut delta);
                 }
 
                 deltas.push(delta);
-            } else {
+            } else if line.origin_value() == git2::DiffLineType::FileHeader {
                 let delta = deltas.last_mut().unwrap();
                 delta.file_header.push_str(line_content);
             }
@@ -146,40 +226,193 @@ pub(crate) fn convert_diff(
     Ok(Diff { deltas })
 }
 
+fn is_mode_line(line: &str) -> bool {
+    line.starts_with("old mode ") || line.starts_with("new mode ")
+}
+
+fn mode_change(delta: &Delta) -> Option<Arc<ModeChange>> {
+    // Changing the type of a file changes its mode too, that's part of the change as a whole
+    if delta.status == git2::Delta::Typechange {
+        return None;
+    }
+
+    let mut lines = delta.file_header.lines();
+    let diff_line = lines.next()?;
+    let mode_lines = lines.filter(|line| is_mode_line(line)).collect::<Vec<_>>();
+    if mode_lines.is_empty() {
+        return None;
+    }
+
+    Some(Arc::new(ModeChange {
+        file: delta.new_file.clone(),
+        old_mode: delta.old_mode,
+        new_mode: delta.new_mode,
+        patch: format!("{}\n{}\n", diff_line, mode_lines.join("\n")),
+    }))
+}
+
+/// The header of a file's hunks, without its change of mode.
+fn hunk_file_header(file_header: &str) -> String {
+    file_header
+        .split_inclusive('\n')
+        .filter(|line| !is_mode_line(line))
+        .collect()
+}
+
 fn diff_files(
     repo: &Repository,
     diffdelta: git2::DiffDelta<'_>,
     workdir: bool,
     config: &Config,
-    delta: &Delta,
-) -> Res<Vec<Rc<Hunk>>> {
-    let old_content = read_blob(repo, &diffdelta.old_file())?.replace("\r\n", "\n");
-    let new_content = if workdir {
+    delta: &mut Delta,
+) -> Res<()> {
+    let old_bytes = read_blob(repo, &diffdelta.old_file())?;
+    let new_bytes = if workdir {
         read_workdir(repo, &diffdelta.new_file())?
     } else {
         read_blob(repo, &diffdelta.new_file())?
+    };
+
+    if diffdelta.flags().is_binary() {
+        delta.binary = Some((old_bytes.len(), new_bytes.len()));
+        if config.general.image_preview.enabled {
+            let shown = if new_bytes.is_empty() {
+                old_bytes
+            } else {
+                new_bytes
+            };
+            delta.image = Image::from_png(shown).map(Arc::new);
+        }
+        return Ok(());
+    }
+
+    let old_content = Content::decode(old_bytes);
+    let new_content = Content::decode(new_bytes);
+
+    let lines = old_content.text.lines().count() + new_content.text.lines().count();
+    let limit = config.general.large_diff_lines;
+    if limit > 0 && lines > limit && !is_shown_anyway(config, &delta.new_file) {
+        delta.large = Some(lines);
+        return Ok(());
     }
-    .replace("\r\n", "\n");
 
-    diff_content(config, delta, &old_content, &new_content)
+    delta.hunks = diff_content(config, delta, &old_content, &new_content)?;
+    Ok(())
+}
+
+/// A file's content as text, with `\r\n` line endings as `\n`.
+struct Content {
+    text: String,
+    /// The exact bytes of each line, when `text` is a lossy decoding of them
+    raw_lines: Option<Vec<Vec<u8>>>,
+}
+
+impl Content {
+    fn decode(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => Self {
+                text: text.replace("\r\n", "\n"),
+                raw_lines: None,
+            },
+            Err(err) => {
+                let bytes = replace_crlf(err.as_bytes());
+                // Lines are split like `tokenize_lines` does, and decoding a line never adds
+                // or removes line breaks, so the lines of `text` line up with `raw_lines`.
+                let raw_lines = split_lines(&bytes);
+                Self {
+                    text: raw_lines
+                        .iter()
+                        .map(|line| String::from_utf8_lossy(line))
+                        .collect(),
+                    raw_lines: Some(raw_lines.into_iter().map(<[u8]>::to_vec).collect()),
+                }
+            }
+        }
+    }
+
+    fn raw_line<'a>(&'a self, lines: &[&'a str], i: usize) -> &'a [u8] {
+        match &self.raw_lines {
+            Some(raw_lines) => &raw_lines[i],
+            None => lines[i].as_bytes(),
+        }
+    }
+}
+
+fn replace_crlf(bytes: &[u8]) -> Vec<u8> {
+    let mut replaced = Vec::with_capacity(bytes.len());
+    let mut rest = bytes;
+    while let Some(i) = rest.windows(2).position(|pair| pair == b"\r\n") {
+        replaced.extend(&rest[..i]);
+        replaced.push(b'\n');
+        rest = &rest[i + 2..];
+    }
+    replaced.extend(rest);
+    replaced
+}
+
+/// Splits after each `\r\n`, `\r` or `\n`, keeping them.
+fn split_lines(bytes: &[u8]) -> Vec<&[u8]> {
+    let mut lines = vec![];
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let end = match (bytes[i], bytes.get(i + 1)) {
+            (b'\r', Some(b'\n')) => i + 2,
+            (b'\r' | b'\n', _) => i + 1,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        lines.push(&bytes[start..end]);
+        start = end;
+        i = end;
+    }
+    if start < bytes.len() {
+        lines.push(&bytes[start..]);
+    }
+    lines
+}
+
+fn is_shown_anyway(config: &Config, file: &Path) -> bool {
+    config
+        .shown_large_diffs
+        .read()
+        .expect("Shown large diffs lock poisoned")
+        .contains(file)
 }
 
 fn diff_content(
     config: &Config,
     delta: &Delta,
-    old_content: &str,
-    new_content: &str,
-) -> Res<Vec<Rc<Hunk>>> {
+    old: &Content,
+    new: &Content,
+) -> Res<Vec<Arc<Hunk>>> {
     let style = &config.style;
+    let (old_content, new_content) = (old.text.as_str(), new.text.as_str());
     let old_lines = old_content.tokenize_lines();
     let new_lines = new_content.tokenize_lines();
 
     let old_line_indices = byte_ranges(&old_lines);
     let new_line_indices = byte_ranges(&new_lines);
 
+    let settings = config
+        .diff_settings
+        .read()
+        .expect("Diff settings lock poisoned")
+        .clone();
+
+    let old_keys = comparison_keys(&old_lines, settings.ignore_whitespace);
+    let new_keys = comparison_keys(&new_lines, settings.ignore_whitespace);
+    let old_keys = old_keys.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+    let new_keys = new_keys.iter().map(AsRef::as_ref).collect::<Vec<_>>();
     let text_diff = TextDiff::configure()
-        .algorithm(Algorithm::Patience)
-        .diff_slices(&old_lines, &new_lines);
+        .algorithm(if settings.patience {
+            Algorithm::Patience
+        } else {
+            Algorithm::Myers
+        })
+        .diff_slices(&old_keys, &new_keys);
 
     let mut old_syntax_highlights = if config.style.syntax_highlight.enabled {
         syntax_highlight::highlight(config, &delta.old_file, old_content)
@@ -197,9 +430,16 @@ fn diff_content(
     .into_iter()
     .peekable();
 
-    Ok(text_diff
-        .unified_diff()
+    let mut unified_diff = text_diff.unified_diff();
+    if let Some(lines) = settings.context_lines {
+        unified_diff.context_radius(lines as usize);
+    }
+
+    Ok(unified_diff
         .iter_hunks()
+        .filter(|hunk| {
+            !settings.ignore_blank_lines || !only_blank_lines_changed(hunk, &old_lines, &new_lines)
+        })
         .map(|hunk| {
             let mut lines = vec![];
 
@@ -302,17 +542,84 @@ fn diff_content(
                 .parse()
                 .unwrap();
 
-            Rc::new(Hunk {
-                file_header: delta.file_header.clone(),
+            let raw_lines = (old.raw_lines.is_some() || new.raw_lines.is_some())
+                .then(|| raw_hunk_lines(&hunk, (old, &old_lines), (new, &new_lines)));
+
+            Arc::new(Hunk {
+                file_header: hunk_file_header(&delta.file_header),
                 new_file: delta.new_file.clone(),
                 new_start,
                 header: format!("{}", hunk.header()),
                 content: formatted_hunk,
+                raw_lines,
             })
         })
         .collect::<Vec<_>>())
 }
 
+/// The lines of a hunk as `create_lines` makes them, but of the exact bytes of the files.
+fn raw_hunk_lines(
+    hunk: &UnifiedDiffHunk<str>,
+    (old, old_lines): (&Content, &[&str]),
+    (new, new_lines): (&Content, &[&str]),
+) -> Vec<Vec<u8>> {
+    let mut raw_lines = vec![];
+    let mut push = |prefix: u8, line: &[u8]| {
+        let end = line.len()
+            - line
+                .iter()
+                .rev()
+                .take_while(|&&b| b == b'\r' || b == b'\n')
+                .count();
+        raw_lines.push([&[prefix], &line[..end]].concat());
+        if !line.ends_with(b"\n") {
+            raw_lines.push(b"\\ No newline at end of file".to_vec());
+        }
+    };
+
+    for op in hunk.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        let old_prefix = if tag == DiffTag::Equal { b' ' } else { b'-' };
+        for i in old_range {
+            push(old_prefix, old.raw_line(old_lines, i));
+        }
+        if tag != DiffTag::Equal {
+            for i in new_range {
+                push(b'+', new.raw_line(new_lines, i));
+            }
+        }
+    }
+
+    raw_lines
+}
+
+/// With whitespace ignored, lines are compared with all of it taken out.
+fn comparison_keys<'a>(lines: &[&'a str], ignore_whitespace: bool) -> Vec<Cow<'a, str>> {
+    lines
+        .iter()
+        .map(|&line| match ignore_whitespace {
+            true => Cow::Owned(line.chars().filter(|c| !c.is_whitespace()).collect()),
+            false => Cow::Borrowed(line),
+        })
+        .collect()
+}
+
+fn only_blank_lines_changed(
+    hunk: &UnifiedDiffHunk<str>,
+    old_lines: &[&str],
+    new_lines: &[&str],
+) -> bool {
+    hunk.ops()
+        .iter()
+        .filter(|op| op.tag() != DiffTag::Equal)
+        .flat_map(|op| {
+            old_lines[op.old_range()]
+                .iter()
+                .chain(&new_lines[op.new_range()])
+        })
+        .all(|line| line.trim().is_empty())
+}
+
 fn map_from_token_to_byte_range(
     word_range: &Range<usize>,
     old_lines_range: &Range<usize>,
@@ -471,18 +778,24 @@ fn next_merged_style_range(
     }
 }
 
-fn read_workdir(repo: &Repository, new_file: &git2::DiffFile<'_>) -> Res<String> {
-    Ok(fs::read_to_string(
-        repo.workdir()
-            .expect("No workdir")
-            .join(new_file.path().unwrap()),
-    )?)
+/// Like git, the content of a symlink is where it points to.
+fn read_workdir(repo: &Repository, new_file: &git2::DiffFile<'_>) -> Res<Vec<u8>> {
+    let path = repo
+        .workdir()
+        .expect("No workdir")
+        .join(new_file.path().unwrap());
+
+    Ok(match new_file.mode() {
+        FileMode::Link => fs::read_link(path)?.into_os_string().into_encoded_bytes(),
+        _ => fs::read(path)?,
+    })
 }
 
-fn read_blob(repo: &Repository, file: &git2::DiffFile<'_>) -> Res<String> {
-    let blob = repo.find_blob(file.id());
-    blob.map(|blob| Ok(String::from_utf8(blob.content().to_vec())?))
-        .unwrap_or(Ok("".to_string()))
+fn read_blob(repo: &Repository, file: &git2::DiffFile<'_>) -> Res<Vec<u8>> {
+    Ok(repo
+        .find_blob(file.id())
+        .map(|blob| blob.content().to_vec())
+        .unwrap_or_default())
 }
 
 fn path(file: &git2::DiffFile) -> PathBuf {
@@ -496,23 +809,56 @@ mod tests {
 
     #[test]
     fn changed_line() {
-        let hunks = diff_content("old line\n", "new line\n");
-        insta::assert_snapshot!(hunks[0].format_patch());
+        let hunks = diff_content(b"old line\n", b"new line\n");
+        insta::assert_snapshot!(String::from_utf8(hunks[0].format_patch()).unwrap());
     }
 
     #[test]
     fn changed_line_no_newline() {
-        let hunks = diff_content("old line", "new line");
-        insta::assert_snapshot!(hunks[0].format_patch());
+        let hunks = diff_content(b"old line", b"new line");
+        insta::assert_snapshot!(String::from_utf8(hunks[0].format_patch()).unwrap());
     }
 
     #[test]
     fn multiple_changed_lines() {
-        let hunks = diff_content("one\ntwo\nthree\n", "three\ntwo\none\n");
-        insta::assert_snapshot!(hunks[0].format_patch());
+        let hunks = diff_content(b"one\ntwo\nthree\n", b"three\ntwo\none\n");
+        insta::assert_snapshot!(String::from_utf8(hunks[0].format_patch()).unwrap());
+    }
+
+    #[test]
+    fn line_numbers() {
+        let hunks = diff_content(b"one\ntwo\nthree\n", b"one\n2\nthree\nfour\n");
+        assert_eq!(
+            hunks[0].line_numbers(),
+            [
+                (Some(1), Some(1)),
+                (Some(2), None),
+                (None, Some(2)),
+                (Some(3), Some(3)),
+                (None, Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_utf8_lines() {
+        let hunks = diff_content(b"caf\xe9\r\nsame\n", b"caf\xe9 au lait\r\nsame\n");
+        assert_eq!(
+            hunks[0].content.lines[0].to_string(),
+            "-caf\u{fffd}",
+            "Shown lossily"
+        );
+        assert_eq!(
+            hunks[0].format_patch(),
+            b"header\n@@ -1,2 +1,2 @@\n-caf\xe9\n+caf\xe9 au lait\n same\n",
+        );
+        assert_eq!(
+            hunks[0].format_line_patch(1..2, super::PatchMode::Normal),
+            b"header\n@@ -1,2 +1,2 @@\n caf\xe9\n+caf\xe9 au lait\n same\n",
+        );
     }
 
-    fn diff_content(old_content: &str, new_content: &str) -> Vec<std::rc::Rc<super::Hunk>> {
+    fn diff_content(old_content: &[u8], new_content: &[u8]) -> Vec<std::sync::Arc<super::Hunk>> {
         super::diff_content(
             &config::init_test_config().unwrap(),
             &Delta {
@@ -521,9 +867,15 @@ mod tests {
                 old_file: "old_file".into(),
                 hunks: vec![],
                 status: git2::Delta::Modified,
+                large: None,
+                binary: None,
+                image: None,
+                old_mode: git2::FileMode::Blob,
+                new_mode: git2::FileMode::Blob,
+                mode_change: None,
             },
-            old_content,
-            new_content,
+            &super::Content::decode(old_content.to_vec()),
+            &super::Content::decode(new_content.to_vec()),
         )
         .unwrap()
     }
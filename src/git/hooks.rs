@@ -0,0 +1,116 @@
+use git2::Repository;
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The hooks that may stop a git subcommand, which `--no-verify` skips.
+fn skippable_hooks(subcommand: &str) -> &'static [&'static str] {
+    match subcommand {
+        "commit" => &["pre-commit", "commit-msg"],
+        "merge" => &["pre-merge-commit", "commit-msg"],
+        "push" => &["pre-push"],
+        _ => &[],
+    }
+}
+
+/// The hooks set up in the repository that `cmd` would run, unless they're skipped with `--no-verify`.
+pub(crate) fn run_by(repo: &Repository, cmd: &Command) -> Vec<&'static str> {
+    if cmd.get_program() != "git" {
+        return vec![];
+    }
+
+    let args = cmd
+        .get_args()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>();
+
+    let Some(subcommand) = subcommand(&args) else {
+        return vec![];
+    };
+
+    if args.iter().any(|arg| arg == "--no-verify") {
+        return vec![];
+    }
+
+    let dir = hooks_dir(repo);
+    skippable_hooks(subcommand)
+        .iter()
+        .copied()
+        .filter(|hook| is_executable(&dir.join(hook)))
+        .collect()
+}
+
+/// The first argument that isn't an option, or the value of one like `-c <name>=<value>`.
+fn subcommand<'a>(args: &'a [Cow<'a, str>]) -> Option<&'a str> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "-c" | "-C" => {
+                args.next();
+            }
+            arg if arg.starts_with('-') => (),
+            arg => return Some(arg),
+        }
+    }
+    None
+}
+
+/// Names the hooks a failed command ran, as they're likely what stopped it.
+pub(crate) fn describe_failure(hooks: &[&str]) -> Option<String> {
+    match hooks {
+        [] => None,
+        [hook] => Some(format!("{} hook, skip with --no-verify", hook)),
+        _ => Some(format!("{} hooks, skip with --no-verify", hooks.join("/"))),
+    }
+}
+
+/// A relative `core.hooksPath` is relative to the work tree, like git treats it.
+fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo
+        .config()
+        .and_then(|config| config.get_path("core.hooksPath"))
+        .ok();
+
+    match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.workdir().unwrap_or(repo.path()).join(path),
+        // A worktree's git dir is `.git/worktrees/<name>`, hooks are shared with the main one
+        None if repo.is_worktree() => repo.path().join("../../hooks"),
+        None => repo.path().join("hooks"),
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subcommand_of(args: &[&'static str]) -> Option<String> {
+        let args = args.iter().map(|&arg| Cow::from(arg)).collect::<Vec<_>>();
+        subcommand(&args).map(String::from)
+    }
+
+    #[test]
+    fn subcommand_skips_option_values() {
+        assert_eq!(subcommand_of(&["commit", "-m", "msg"]).unwrap(), "commit");
+        assert_eq!(subcommand_of(&["-c", "a.b=c", "commit"]).unwrap(), "commit");
+        assert_eq!(
+            subcommand_of(&["-C", "dir", "--no-pager", "push"]).unwrap(),
+            "push"
+        );
+        assert_eq!(subcommand_of(&["-c"]), None);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{git2_opts, Res};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use git2::{Commit, DiffFindOptions, Oid, Repository};
+use regex::Regex;
+use std::path::Path;
+
+/// Narrows down the commits of a log, like the equivalent `git log` options.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LogFilter {
+    /// `--grep`
+    pub grep: Option<Regex>,
+    /// `--author`, matched against "Name <email>"
+    pub author: Option<Regex>,
+    /// `--since` / `--until`, in seconds since the epoch of the commit time
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// `-S`, commits changing the number of occurrences of the string
+    pub pickaxe: Option<String>,
+    /// `-- <path>...`, commits touching any of these
+    pub paths: Vec<String>,
+    /// `--follow`, keeps following a single path through renames
+    pub follow: bool,
+    /// The filter as given in the log menu, to describe it with
+    pub args: Vec<String>,
+}
+
+impl LogFilter {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.grep.is_none()
+            && self.author.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.pickaxe.is_none()
+            && self.paths.is_empty()
+    }
+
+    /// Commits are expected newest first, as a rename updates the path being followed.
+    pub(crate) fn matches(&mut self, repo: &Repository, commit: &Commit) -> Res<bool> {
+        if let Some(grep) = &self.grep {
+            if !grep.is_match(commit.message().unwrap_or("")) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(author) = &self.author {
+            let signature = commit.author();
+            let author_line = format!(
+                "{} <{}>",
+                signature.name().unwrap_or(""),
+                signature.email().unwrap_or("")
+            );
+
+            if !author.is_match(&author_line) {
+                return Ok(false);
+            }
+        }
+
+        let time = commit.time().seconds();
+        if self.since.is_some_and(|since| time < since)
+            || self.until.is_some_and(|until| time > until)
+        {
+            return Ok(false);
+        }
+
+        if self.pickaxe.is_none() && self.paths.is_empty() {
+            return Ok(true);
+        }
+
+        self.matches_changes(repo, commit)
+    }
+
+    /// Follows the paths from HEAD back to `oid`, to where they're named as of that commit.
+    pub(crate) fn follow_to(&mut self, repo: &Repository, oid: Oid) -> Res<()> {
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_head()?;
+
+        for next in revwalk {
+            let next = next?;
+            if next == oid {
+                break;
+            }
+
+            self.matches_changes(repo, &repo.find_commit(next)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compares against the first parent, as `git log` does without `-m`.
+    fn matches_changes(&mut self, repo: &Repository, commit: &Commit) -> Res<bool> {
+        let mut opts = git2_opts::diff(repo)?;
+        for path in &self.paths {
+            opts.pathspec(path);
+        }
+
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff =
+            repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut opts))?;
+
+        if self.follow && self.paths.len() == 1 {
+            let added = diff
+                .deltas()
+                .any(|delta| delta.status() == git2::Delta::Added);
+
+            if added {
+                self.follow_rename(repo, parent_tree.as_ref(), commit)?;
+            }
+        }
+
+        let Some(pickaxe) = &self.pickaxe else {
+            return Ok(diff.deltas().len() > 0);
+        };
+
+        for delta in diff.deltas() {
+            let old = count_in_blob(repo, delta.old_file().id(), pickaxe)?;
+            let new = count_in_blob(repo, delta.new_file().id(), pickaxe)?;
+            if old != new {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// A path that's added may have been renamed from another, which older commits are filtered by instead.
+    fn follow_rename(
+        &mut self,
+        repo: &Repository,
+        parent_tree: Option<&git2::Tree>,
+        commit: &Commit,
+    ) -> Res<()> {
+        let mut diff = repo.diff_tree_to_tree(
+            parent_tree,
+            Some(&commit.tree()?),
+            Some(&mut git2_opts::diff(repo)?),
+        )?;
+        diff.find_similar(Some(&mut DiffFindOptions::new().renames(true)))?;
+
+        let renamed_from = diff.deltas().find_map(|delta| {
+            let renamed = delta.status() == git2::Delta::Renamed
+                && delta.new_file().path() == Some(Path::new(&self.paths[0]));
+
+            renamed
+                .then(|| delta.old_file().path())
+                .flatten()
+                .map(|path| path.to_string_lossy().to_string())
+        });
+
+        if let Some(path) = renamed_from {
+            self.paths[0] = path;
+        }
+
+        Ok(())
+    }
+}
+
+fn count_in_blob(repo: &Repository, id: Oid, needle: &str) -> Res<usize> {
+    if id.is_zero() || needle.is_empty() {
+        return Ok(0);
+    }
+
+    let blob = repo.find_blob(id)?;
+    let (mut haystack, needle) = (blob.content(), needle.as_bytes());
+    let mut count = 0;
+
+    while let Some(i) = haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+    {
+        count += 1;
+        haystack = &haystack[i + needle.len()..];
+    }
+
+    Ok(count)
+}
+
+/// Parses dates such as "2024-01-31", "2024-01-31 12:00" or "2 weeks ago"
+/// into seconds since the epoch. Relative dates are counted back from `now`.
+pub(crate) fn parse_date(input: &str, now: i64) -> Res<i64> {
+    let input = input.trim();
+
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M") {
+        return local_timestamp(date_time);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return local_timestamp(date.and_hms_opt(0, 0, 0).expect("Midnight is valid"));
+    }
+
+    let relative = input.strip_suffix("ago").unwrap_or(input).replace('.', " ");
+    if let [amount, unit] = relative.split_whitespace().collect::<Vec<_>>()[..] {
+        let amount = amount.parse::<i64>()?;
+        let unit_seconds = match unit.trim_end_matches('s') {
+            "second" => 1,
+            "minute" => 60,
+            "hour" => 60 * 60,
+            "day" => 24 * 60 * 60,
+            "week" => 7 * 24 * 60 * 60,
+            "month" => 30 * 24 * 60 * 60,
+            "year" => 365 * 24 * 60 * 60,
+            _ => return Err(format!("Unknown unit '{}'", unit).into()),
+        };
+
+        return Ok(now - amount * unit_seconds);
+    }
+
+    Err(format!("Couldn't parse date '{}'", input).into())
+}
+
+fn local_timestamp(date_time: NaiveDateTime) -> Res<i64> {
+    Local
+        .from_local_datetime(&date_time)
+        .earliest()
+        .map(|date_time| date_time.timestamp())
+        .ok_or_else(|| "Date doesn't exist in the local time zone".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn relative_dates() {
+        assert_eq!(
+            parse_date("2 weeks ago", NOW).unwrap(),
+            NOW - 14 * 24 * 60 * 60
+        );
+        assert_eq!(parse_date("1.day", NOW).unwrap(), NOW - 24 * 60 * 60);
+        assert_eq!(parse_date("3 hours", NOW).unwrap(), NOW - 3 * 60 * 60);
+    }
+
+    #[test]
+    fn absolute_dates() {
+        let midnight = parse_date("2024-01-31", NOW).unwrap();
+        let noon = parse_date("2024-01-31 12:00", NOW).unwrap();
+        assert_eq!(noon - midnight, 12 * 60 * 60);
+    }
+
+    #[test]
+    fn invalid_dates() {
+        assert!(parse_date("yesterday-ish", NOW).is_err());
+        assert!(parse_date("2 fortnights ago", NOW).is_err());
+    }
+}
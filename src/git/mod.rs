@@ -1,41 +1,51 @@
-use git2::{DiffFindOptions, Repository};
+use git2::{
+    DescribeFormatOptions, DescribeOptions, DiffFindOptions, ObjectType, Oid, Repository,
+    SubmoduleIgnore, SubmoduleStatus as SubmoduleStatusFlags,
+};
 use itertools::Itertools;
 
-use self::{commit::Commit, diff::Diff, merge_status::MergeStatus, rebase_status::RebaseStatus};
+use self::{
+    commit::Commit, conflict::Conflict, diff::Diff, merge_status::MergeStatus,
+    rebase_status::RebaseStatus,
+};
 use crate::{config::Config, git2_opts, Res};
 use std::{
     fs,
-    path::Path,
-    process::Command,
-    str::{self},
+    path::{Path, PathBuf},
 };
 
 pub(crate) mod commit;
+pub(crate) mod conflict;
 pub(crate) mod diff;
+pub(crate) mod hooks;
+pub(crate) mod log_filter;
 pub(crate) mod merge_status;
 pub(crate) mod rebase_status;
 pub(crate) mod remote;
+pub(crate) mod signature;
+pub(crate) mod trash;
 
-// TODO Use only plumbing commands
+// Everything shown on screen is read through libgit2 here. Commands that change the repo are run
+// with the `git` executable instead (see `ops`), so that hooks, editors and credentials work as usual.
 
 pub(crate) fn rebase_status(repo: &Repository) -> Res<Option<RebaseStatus>> {
-    let dir = repo.workdir().expect("No workdir");
-    let mut rebase_onto_file = dir.to_path_buf();
-    rebase_onto_file.push(".git/rebase-merge/onto");
-
-    let mut rebase_head_name_file = dir.to_path_buf();
-    rebase_head_name_file.push(".git/rebase-merge/head-name");
+    let rebase_onto_file = repo.path().join("rebase-merge/onto");
+    let rebase_head_name_file = repo.path().join("rebase-merge/head-name");
 
     match fs::read_to_string(&rebase_onto_file) {
         Ok(content) => {
             let onto_hash = content.trim().to_string();
             Ok(Some(RebaseStatus {
-                onto: branch_name(dir, &onto_hash)?.unwrap_or_else(|| onto_hash[..7].to_string()),
+                onto: branch_name(repo, &onto_hash)?.unwrap_or_else(|| onto_hash[..7].to_string()),
                 head_name: fs::read_to_string(rebase_head_name_file)?
                     .trim()
                     .strip_prefix("refs/heads/")
                     .unwrap()
                     .to_string(),
+                // Only written when stopping for an `edit`, not for conflicts
+                edit: fs::read_to_string(repo.path().join("rebase-merge/amend"))
+                    .ok()
+                    .map(|oid| oid.trim().to_string()),
                 // TODO include log of 'done' items
             }))
         }
@@ -51,15 +61,13 @@ pub(crate) fn rebase_status(repo: &Repository) -> Res<Option<RebaseStatus>> {
 }
 
 pub(crate) fn merge_status(repo: &Repository) -> Res<Option<MergeStatus>> {
-    let dir = repo.workdir().expect("No workdir");
-    let mut merge_head_file = dir.to_path_buf();
-    merge_head_file.push(".git/MERGE_HEAD");
+    let merge_head_file = repo.path().join("MERGE_HEAD");
 
     match fs::read_to_string(&merge_head_file) {
         Ok(content) => {
             let head = content.trim().to_string();
             Ok(Some(MergeStatus {
-                head: branch_name(dir, &head)?.unwrap_or(head[..7].to_string()),
+                head: branch_name(repo, &head)?.unwrap_or(head[..7].to_string()),
             }))
         }
         Err(err) => {
@@ -78,15 +86,13 @@ pub(crate) struct RevertStatus {
 }
 
 pub(crate) fn revert_status(repo: &Repository) -> Res<Option<RevertStatus>> {
-    let dir = repo.workdir().expect("No workdir");
-    let mut revert_head_file = dir.to_path_buf();
-    revert_head_file.push(".git/REVERT_HEAD");
+    let revert_head_file = repo.path().join("REVERT_HEAD");
 
     match fs::read_to_string(&revert_head_file) {
         Ok(content) => {
             let head = content.trim().to_string();
             Ok(Some(RevertStatus {
-                head: branch_name(dir, &head)?.unwrap_or(head[..7].to_string()),
+                head: branch_name(repo, &head)?.unwrap_or(head[..7].to_string()),
             }))
         }
         Err(err) => {
@@ -100,23 +106,231 @@ pub(crate) fn revert_status(repo: &Repository) -> Res<Option<RevertStatus>> {
     }
 }
 
-// TODO replace with libgit2
-fn branch_name(dir: &Path, hash: &str) -> Res<Option<String>> {
-    let out = Command::new("git")
-        .args(["for-each-ref", "--format", "%(objectname) %(refname:short)"])
-        .current_dir(dir)
-        .output()?
-        .stdout;
+pub(crate) struct CherryPickStatus {
+    pub head: String,
+}
 
-    Ok(str::from_utf8(&out)?
-        .lines()
-        .find(|line| line.starts_with(hash))
-        .map(|line| line.split(' ').nth(1).unwrap().to_string()))
+pub(crate) fn cherry_pick_status(repo: &Repository) -> Res<Option<CherryPickStatus>> {
+    let cherry_pick_head_file = repo.path().join("CHERRY_PICK_HEAD");
+
+    match fs::read_to_string(&cherry_pick_head_file) {
+        Ok(content) => {
+            let head = content.trim().to_string();
+            Ok(Some(CherryPickStatus {
+                head: head.get(..7).unwrap_or(&head).to_string(),
+            }))
+        }
+        Err(err) => {
+            log::warn!(
+                "Couldn't read {}, due to {}",
+                cherry_pick_head_file.to_string_lossy(),
+                err
+            );
+            Ok(None)
+        }
+    }
 }
 
+pub(crate) struct BisectStatus {
+    /// Revisions left to test and roughly how many steps that takes,
+    /// known once there's both a good and a bad revision.
+    pub remaining: Option<(usize, usize)>,
+}
+
+/// Cheaper than `bisect_status`, for when the revisions left to test don't matter.
+pub(crate) fn is_bisecting(repo: &Repository) -> bool {
+    repo.path().join("BISECT_START").exists()
+}
+
+pub(crate) fn bisect_status(repo: &Repository) -> Res<Option<BisectStatus>> {
+    if !is_bisecting(repo) {
+        return Ok(None);
+    }
+
+    let Ok(bad) = repo.find_reference("refs/bisect/bad") else {
+        return Ok(Some(BisectStatus { remaining: None }));
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(bad.peel_to_commit()?.id())?;
+
+    let mut has_good = false;
+    for good in repo.references_glob("refs/bisect/good-*")? {
+        revwalk.hide(good?.peel_to_commit()?.id())?;
+        has_good = true;
+    }
+
+    if !has_good {
+        return Ok(Some(BisectStatus { remaining: None }));
+    }
+
+    let all = revwalk.count();
+    Ok(Some(BisectStatus {
+        remaining: Some((all.saturating_sub(1), estimate_bisect_steps(all))),
+    }))
+}
+
+pub(crate) struct AmStatus {
+    /// The patch being applied, counting from 1
+    pub current: usize,
+    pub total: usize,
+}
+
+/// Patches being applied with `git am`, which shares its state directory with the old `rebase --apply`.
+pub(crate) fn am_status(repo: &Repository) -> Res<Option<AmStatus>> {
+    let dir = repo.path().join("rebase-apply");
+    if !dir.join("applying").exists() {
+        return Ok(None);
+    }
+
+    let read_number =
+        |name: &str| -> Res<usize> { Ok(fs::read_to_string(dir.join(name))?.trim().parse()?) };
+
+    Ok(Some(AmStatus {
+        current: read_number("next")?,
+        total: read_number("last")?,
+    }))
+}
+
+/// The changes `rev` made, as a patch `git apply` takes. Merges are diffed against their first parent.
+pub(crate) fn commit_patch(repo: &Repository, rev: &str) -> Res<Vec<u8>> {
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+    let mut patch = vec![];
+    diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin() as u8);
+        }
+        patch.extend_from_slice(line.content());
+        true
+    })?;
+
+    Ok(patch)
+}
+
+/// Same estimate as `git bisect` makes
+fn estimate_bisect_steps(all: usize) -> usize {
+    if all < 3 {
+        return 0;
+    }
+
+    let n = all.ilog2() as usize;
+    let e = 1 << n;
+    let x = all - e;
+    if e < 3 * x {
+        n
+    } else {
+        n - 1
+    }
+}
+
+pub(crate) struct SubmoduleStatus {
+    pub path: PathBuf,
+    pub checked_out: bool,
+    /// Has changes to tracked files, or untracked files
+    pub dirty: bool,
+    /// Commits checked out in the submodule relative to the one recorded in HEAD
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+pub(crate) fn submodule_statuses(repo: &Repository) -> Res<Vec<SubmoduleStatus>> {
+    repo.submodules()?
+        .iter()
+        .map(|submodule| {
+            let name = submodule
+                .name()
+                .ok_or("Submodule name is not valid UTF-8")?;
+            let status = repo.submodule_status(name, SubmoduleIgnore::None)?;
+
+            let (ahead, behind) = match (
+                submodule.open(),
+                submodule.head_id(),
+                submodule.workdir_id(),
+            ) {
+                (Ok(sub_repo), Some(recorded), Some(checked_out)) if recorded != checked_out => {
+                    sub_repo
+                        .graph_ahead_behind(checked_out, recorded)
+                        .unwrap_or((0, 0))
+                }
+                _ => (0, 0),
+            };
+
+            Ok(SubmoduleStatus {
+                path: submodule.path().to_path_buf(),
+                checked_out: !status.contains(SubmoduleStatusFlags::WD_UNINITIALIZED),
+                dirty: status.intersects(
+                    SubmoduleStatusFlags::WD_INDEX_MODIFIED
+                        | SubmoduleStatusFlags::WD_WD_MODIFIED
+                        | SubmoduleStatusFlags::WD_UNTRACKED,
+                ),
+                ahead,
+                behind,
+            })
+        })
+        .collect()
+}
+
+/// The first ref pointing at `hash`, by name, like `git for-each-ref` would list them.
+fn branch_name(repo: &Repository, hash: &str) -> Res<Option<String>> {
+    let Ok(oid) = Oid::from_str(hash) else {
+        return Ok(None);
+    };
+
+    let mut names = repo
+        .references()?
+        .filter_map(Result::ok)
+        .filter(|reference| reference.target() == Some(oid))
+        .filter_map(|reference| {
+            Some((
+                reference.name()?.to_string(),
+                reference.shorthand()?.to_string(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    names.sort();
+    Ok(names.into_iter().next().map(|(_, shorthand)| shorthand))
+}
+
+/// The conflicts left in a work tree file, none if it can't be read as text.
+pub(crate) fn conflicts(repo: &Repository, path: &Path) -> Vec<Conflict> {
+    let Some(workdir) = repo.workdir() else {
+        return vec![];
+    };
+
+    fs::read_to_string(workdir.join(path))
+        .map(|content| conflict::parse(&content))
+        .unwrap_or_default()
+}
+
+/// Files moved in the work tree show up as renamed, rather than as deleted and untracked.
 pub(crate) fn diff_unstaged(config: &Config, repo: &Repository) -> Res<Diff> {
     let diff = repo.diff_index_to_workdir(None, Some(&mut git2_opts::diff(repo)?))?;
-    diff::convert_diff(config, repo, diff, true)
+    let has_deleted = diff
+        .deltas()
+        .any(|delta| delta.status() == git2::Delta::Deleted);
+
+    if !has_deleted {
+        return diff::convert_diff(config, repo, diff, true);
+    }
+
+    let mut opts = git2_opts::diff(repo)?;
+    opts.include_untracked(true).recurse_untracked_dirs(true);
+    let mut diff = repo.diff_index_to_workdir(None, Some(&mut opts))?;
+    diff.find_similar(Some(
+        DiffFindOptions::new().renames(true).for_untracked(true),
+    ))?;
+
+    let mut diff = diff::convert_diff(config, repo, diff, true)?;
+    diff.deltas
+        .retain(|delta| delta.status != git2::Delta::Untracked);
+    Ok(diff)
 }
 
 pub(crate) fn diff_staged(config: &Config, repo: &Repository) -> Res<Diff> {
@@ -132,7 +346,13 @@ pub(crate) fn diff_staged(config: &Config, repo: &Repository) -> Res<Diff> {
     diff::convert_diff(config, repo, diff, false)
 }
 
-pub(crate) fn show(config: &Config, repo: &Repository, reference: &str) -> Res<Diff> {
+/// With `paths`, only the changes to these are shown, including renames to or from them.
+pub(crate) fn show(
+    config: &Config,
+    repo: &Repository,
+    reference: &str,
+    paths: &[PathBuf],
+) -> Res<Diff> {
     let object = &repo.revparse_single(reference)?;
 
     let commit = object.peel_to_commit()?;
@@ -142,13 +362,87 @@ pub(crate) fn show(config: &Config, repo: &Repository, reference: &str) -> Res<D
         .next()
         .and_then(|parent| parent.tree().ok());
 
-    let diff = repo.diff_tree_to_tree(
+    let mut diff = repo.diff_tree_to_tree(
         parent_tree.as_ref(),
         Some(&tree),
         Some(&mut git2_opts::diff(repo)?),
     )?;
 
-    diff::convert_diff(config, repo, diff, false)
+    if paths.is_empty() {
+        return diff::convert_diff(config, repo, diff, false);
+    }
+
+    diff.find_similar(Some(&mut DiffFindOptions::new().renames(true)))?;
+    let mut diff = diff::convert_diff(config, repo, diff, false)?;
+    diff.deltas.retain(|delta| {
+        paths
+            .iter()
+            .any(|path| delta.old_file.starts_with(path) || delta.new_file.starts_with(path))
+    });
+
+    Ok(diff)
+}
+
+/// What `git clean` would remove: untracked files, and with `directories` (`-d`) untracked
+/// directories. With `ignored` (`-x`), ignored ones as well.
+pub(crate) fn clean_preview(
+    repo: &Repository,
+    directories: bool,
+    ignored: bool,
+) -> Res<Vec<PathBuf>> {
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .include_ignored(ignored)
+        .recurse_ignored_dirs(false);
+
+    Ok(repo
+        .statuses(Some(&mut opts))?
+        .iter()
+        .filter(|entry| entry.status().is_wt_new() || entry.status().is_ignored())
+        .filter(|entry| directories || !entry.path_bytes().ends_with(b"/"))
+        .map(|entry| path_from_bytes(entry.path_bytes()))
+        .collect())
+}
+
+/// Like `git diff <range> [-- <path>...]`: a single rev is compared to the work tree,
+/// `a..b` compares the two and `a...b` compares `b` to where it branched off from `a`.
+pub(crate) fn diff_range(
+    config: &Config,
+    repo: &Repository,
+    range: &str,
+    paths: &[PathBuf],
+) -> Res<Diff> {
+    let revspec = repo.revparse(range)?;
+    let opts = &mut git2_opts::diff(repo)?;
+    for path in paths {
+        opts.pathspec(path);
+    }
+    let from = revspec.from().ok_or("Range has no start")?;
+
+    let (mut diff, workdir) = match revspec.to() {
+        None => (
+            repo.diff_tree_to_workdir_with_index(Some(&from.peel_to_tree()?), Some(opts))?,
+            true,
+        ),
+        Some(to) => {
+            let from_tree = if revspec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+                let base =
+                    repo.merge_base(from.peel_to_commit()?.id(), to.peel_to_commit()?.id())?;
+                repo.find_commit(base)?.tree()?
+            } else {
+                from.peel_to_tree()?
+            };
+
+            (
+                repo.diff_tree_to_tree(Some(&from_tree), Some(&to.peel_to_tree()?), Some(opts))?,
+                false,
+            )
+        }
+    };
+
+    diff.find_similar(Some(&mut DiffFindOptions::new().renames(true)))?;
+    diff::convert_diff(config, repo, diff, workdir)
 }
 
 pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
@@ -177,19 +471,84 @@ pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
         &offset,
     );
 
+    let signature = signature::verify(repo, &[commit.id()])
+        .ok()
+        .and_then(|mut signatures| signatures.remove(&commit.id()))
+        .map(|signature| match signature.signer.as_str() {
+            "" => format!("\nSignature: {}", signature.status.describe()),
+            signer => format!(
+                "\nSignature: {}, by {}",
+                signature.status.describe(),
+                signer
+            ),
+        })
+        .unwrap_or_default();
+
     let details = format!(
-        "Author: {}\nDate:   {}\n\n{}",
+        "Author: {}\nDate:   {}{}\n\n{}",
         [name, &email].join(" "),
         time.to_rfc2822(),
+        signature,
         message
     );
 
     Ok(Commit {
         hash: commit.id().to_string(),
         details,
+        describe: describe(repo, reference),
     })
 }
 
+/// The nearest tag the commit comes after, like `git describe --tags`: such as `v1.2-3-gabc1234`.
+pub(crate) fn describe(repo: &Repository, rev: &str) -> Option<String> {
+    let object = repo
+        .revparse_single(rev)
+        .ok()?
+        .peel(ObjectType::Commit)
+        .ok()?;
+    let describe = object
+        .describe(DescribeOptions::new().describe_tags())
+        .ok()?;
+    describe.format(Some(&DescribeFormatOptions::new())).ok()
+}
+
+/// The tags whose history has the commit in it, like `git tag --contains`, oldest first.
+/// The first one is where it was released.
+pub(crate) fn tags_containing(repo: &Repository, rev: &str) -> Res<Vec<String>> {
+    let oid = repo.revparse_single(rev)?.peel_to_commit()?.id();
+
+    let mut tags = repo
+        .tag_names(None)?
+        .iter()
+        .flatten()
+        .filter_map(|name| {
+            let target = repo
+                .revparse_single(&format!("refs/tags/{}", name))
+                .ok()?
+                .peel_to_commit()
+                .ok()?;
+            let contains = target.id() == oid || repo.graph_descendant_of(target.id(), oid).ok()?;
+            contains.then(|| (target.time().seconds(), name.to_string()))
+        })
+        .collect::<Vec<_>>();
+
+    tags.sort();
+    Ok(tags.into_iter().map(|(_, name)| name).collect())
+}
+
+/// A path as git has it, which on unix needn't be valid UTF-8.
+pub(crate) fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+        PathBuf::from(OsStr::from_bytes(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
 pub(crate) fn get_head(repo: &git2::Repository) -> Res<String> {
     let head = repo.head()?;
     if head.is_branch() {
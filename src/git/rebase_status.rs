@@ -1,4 +1,6 @@
 pub(crate) struct RebaseStatus {
     pub onto: String,
     pub head_name: String,
+    /// The commit an `edit` in the todo stopped at, to amend or split
+    pub edit: Option<String>,
 }
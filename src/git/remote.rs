@@ -2,7 +2,7 @@ use git2::{Branch, Remote, Repository};
 
 use crate::Res;
 
-pub(crate) fn get_upstream(repo: &Repository) -> Res<Option<Branch>> {
+pub(crate) fn get_upstream(repo: &Repository) -> Res<Option<Branch<'_>>> {
     let r = if repo.head()?.is_branch() {
         Branch::wrap(repo.head()?)
     } else {
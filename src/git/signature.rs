@@ -0,0 +1,82 @@
+use crate::Res;
+use git2::{Oid, Repository};
+use std::{collections::HashMap, process::Command};
+
+/// The verdict of `git log --format=%G?` on a signed commit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SignatureStatus {
+    Good,
+    Bad,
+    /// Good, but made with a key that isn't trusted, has expired or was revoked
+    Untrusted,
+    /// The key to check it with is missing
+    Unverifiable,
+}
+
+impl SignatureStatus {
+    fn parse(code: &str) -> Option<Self> {
+        match code {
+            "G" => Some(Self::Good),
+            "B" => Some(Self::Bad),
+            "U" | "X" | "Y" | "R" => Some(Self::Untrusted),
+            "E" => Some(Self::Unverifiable),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn describe(&self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Bad => "bad",
+            Self::Untrusted => "untrusted",
+            Self::Unverifiable => "unverifiable",
+        }
+    }
+}
+
+/// A verified signature, along with who made it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Signature {
+    pub status: SignatureStatus,
+    pub signer: String,
+}
+
+/// Verifies the signatures of the signed commits among `ids`.
+/// libgit2 can't verify them, so git is run once for all of them, letting it use gpg or ssh as configured.
+pub(crate) fn verify(repo: &Repository, ids: &[Oid]) -> Res<HashMap<Oid, Signature>> {
+    let signed = ids
+        .iter()
+        .filter(|id| repo.extract_signature(id, None).is_ok())
+        .map(Oid::to_string)
+        .collect::<Vec<_>>();
+
+    if signed.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let output = Command::new("git")
+        .arg("--git-dir")
+        .arg(repo.path())
+        .args(["log", "--no-walk=unsorted", "--format=%H %G? %GS"])
+        .args(&signed)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Couldn't verify signatures: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            let id = Oid::from_str(parts.next()?).ok()?;
+            let status = SignatureStatus::parse(parts.next()?)?;
+            let signer = parts.next().unwrap_or("").to_string();
+            Some((id, Signature { status, signer }))
+        })
+        .collect())
+}
@@ -0,0 +1,128 @@
+use crate::Res;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// How many discards are kept around to be undone.
+const LIMIT: usize = 20;
+
+/// The work tree files as they were before a discard.
+#[derive(Default, Serialize, Deserialize)]
+struct Manifest {
+    files: Vec<TrashedFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrashedFile {
+    path: PathBuf,
+    /// Files that didn't exist are removed again on restore, e.g. after discarding a deletion
+    existed: bool,
+}
+
+fn trash_dir(repo: &Repository) -> PathBuf {
+    repo.path().join("gitu").join("trash")
+}
+
+/// Entries, oldest first.
+fn entries(repo: &Repository) -> Vec<PathBuf> {
+    let Ok(dir) = fs::read_dir(trash_dir(repo)) else {
+        return vec![];
+    };
+
+    let mut entries = dir
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.join("manifest.toml").is_file())
+        .collect::<Vec<_>>();
+
+    entries.sort();
+    entries
+}
+
+pub(crate) fn is_empty(repo: &Repository) -> bool {
+    entries(repo).is_empty()
+}
+
+/// Copies the work tree `paths` (relative to the work tree, directories included) into the trash,
+/// before they're discarded.
+pub(crate) fn save(repo: &Repository, paths: &[PathBuf]) -> Res<()> {
+    let workdir = repo.workdir().ok_or("No workdir")?;
+    let mut files = vec![];
+    for path in paths {
+        collect_files(workdir, path, &mut files)?;
+    }
+
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let entries = entries(repo);
+    let next = entries
+        .last()
+        .and_then(|entry| entry.file_name()?.to_str()?.parse::<u64>().ok())
+        .map_or(0, |last| last + 1);
+
+    let entry = trash_dir(repo).join(format!("{:010}", next));
+    fs::create_dir_all(&entry)?;
+
+    let mut manifest = Manifest::default();
+    for (i, path) in files.into_iter().enumerate() {
+        let existed = workdir.join(&path).is_file();
+        if existed {
+            fs::copy(workdir.join(&path), entry.join(i.to_string()))?;
+        }
+
+        manifest.files.push(TrashedFile { path, existed });
+    }
+
+    fs::write(entry.join("manifest.toml"), toml::to_string(&manifest)?)?;
+
+    for old in entries.iter().rev().skip(LIMIT - 1) {
+        fs::remove_dir_all(old)?;
+    }
+
+    Ok(())
+}
+
+fn collect_files(workdir: &Path, path: &Path, files: &mut Vec<PathBuf>) -> Res<()> {
+    let full_path = workdir.join(path);
+    if !full_path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(full_path)? {
+        collect_files(workdir, &path.join(entry?.file_name()), files)?;
+    }
+
+    Ok(())
+}
+
+/// Puts back the files of the most recent discard, returning them.
+pub(crate) fn restore_last(repo: &Repository) -> Res<Vec<PathBuf>> {
+    let workdir = repo.workdir().ok_or("No workdir")?;
+    let Some(entry) = entries(repo).pop() else {
+        return Err("Nothing to undo".into());
+    };
+
+    let manifest: Manifest = toml::from_str(&fs::read_to_string(entry.join("manifest.toml"))?)?;
+
+    for (i, file) in manifest.files.iter().enumerate() {
+        let target = workdir.join(&file.path);
+        if file.existed {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            fs::copy(entry.join(i.to_string()), &target)?;
+        } else if target.is_file() {
+            fs::remove_file(&target)?;
+        }
+    }
+
+    fs::remove_dir_all(entry)?;
+    Ok(manifest.files.into_iter().map(|file| file.path).collect())
+}
@@ -10,12 +10,15 @@ pub(crate) fn status(repo: &Repository) -> Res<StatusOptions> {
             .ok()
             .unwrap_or(true),
     );
+    // Like the unstaged diff, counting files moved in the work tree as renamed instead of untracked
+    opts.renames_index_to_workdir(true);
 
     Ok(opts)
 }
 
 pub(crate) fn diff(_repo: &Repository) -> Res<DiffOptions> {
     let mut diff_options = DiffOptions::new();
-    diff_options.patience(true);
+    // Like git, a file replaced by a symlink is a single change rather than a deletion and an addition
+    diff_options.patience(true).include_typechange(true);
     Ok(diff_options)
 }
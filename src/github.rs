@@ -0,0 +1,341 @@
+//! Pull requests and their CI status, read from GitHub's REST API. Requests go through the `gh` CLI,
+//! which takes care of logging in, or through `curl` if `general.forge.github_token` is set.
+
+use crate::{
+    config::{Config, StyleConfig, StyleConfigEntry},
+    ops::forge,
+    Res,
+};
+use git2::Repository;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+    thread,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CiStatus {
+    Success,
+    Failure,
+    Pending,
+}
+
+impl CiStatus {
+    /// Colored like the signatures of commits.
+    pub(crate) fn glyph<'a>(&self, style: &'a StyleConfig) -> (&'static str, &'a StyleConfigEntry) {
+        match self {
+            CiStatus::Success => ("✓", &style.signature_good),
+            CiStatus::Failure => ("✗", &style.signature_bad),
+            CiStatus::Pending => ("●", &style.signature_untrusted),
+        }
+    }
+}
+
+/// The check runs of a commit, summed up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Checks {
+    /// `None` if there are no check runs
+    pub status: Option<CiStatus>,
+    /// The page of the first check run that failed
+    pub failed_url: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub author: String,
+    pub head_ref: String,
+    pub draft: bool,
+    pub ci: Option<CiStatus>,
+}
+
+/// A repository on GitHub, or a GitHub Enterprise server, that a remote points to.
+#[derive(Clone, Debug)]
+pub(crate) struct GitHub {
+    pub remote: String,
+    host: String,
+    path: String,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct ApiPullRequest {
+    number: u64,
+    title: String,
+    user: ApiUser,
+    head: ApiHead,
+    #[serde(default)]
+    draft: bool,
+}
+
+#[derive(Deserialize)]
+struct ApiUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct ApiHead {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct ApiRepository {
+    default_branch: String,
+}
+
+#[derive(Deserialize)]
+struct ApiCreatedPullRequest {
+    html_url: String,
+}
+
+#[derive(Deserialize)]
+struct ApiCheckRuns {
+    check_runs: Vec<ApiCheckRun>,
+}
+
+#[derive(Deserialize)]
+struct ApiCheckRun {
+    status: String,
+    conclusion: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
+}
+
+impl GitHub {
+    /// The forge remote of `repo`, if its host uses the "github" templates in `general.forge.hosts`.
+    pub(crate) fn from_repo(repo: &Repository, config: &Config) -> Res<Self> {
+        let remote = forge::forge_remote(repo)?;
+        let (host, path) = repo
+            .find_remote(&remote)?
+            .url()
+            .and_then(forge::parse_remote_url)
+            .ok_or_else(|| format!("Couldn't make out the url of remote {}", remote))?;
+
+        let forge = &config.general.forge;
+        if forge.hosts.get(&host).map(String::as_str) != Some("github") {
+            return Err(format!("Remote {} isn't on GitHub ({})", remote, host).into());
+        }
+
+        Ok(Self {
+            remote,
+            host,
+            path,
+            token: forge.github_token.clone(),
+        })
+    }
+
+    /// Open pull requests, newest first, along with the CI status of their last commit.
+    pub(crate) fn pull_requests(&self) -> Res<Vec<PullRequest>> {
+        let pulls: Vec<ApiPullRequest> = self.get(&format!("repos/{}/pulls", self.path))?;
+
+        // One request per pull request, run side by side
+        let statuses = thread::scope(|scope| {
+            let handles = pulls
+                .iter()
+                .map(|pull| scope.spawn(|| self.checks(&pull.head.sha).ok()?.status))
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().ok().flatten())
+                .collect::<Vec<_>>()
+        });
+
+        Ok(pulls
+            .into_iter()
+            .zip(statuses)
+            .map(|(pull, ci)| PullRequest {
+                number: pull.number,
+                title: pull.title,
+                author: pull.user.login,
+                head_ref: pull.head.ref_name,
+                draft: pull.draft,
+                ci,
+            })
+            .collect())
+    }
+
+    pub(crate) fn checks(&self, sha: &str) -> Res<Checks> {
+        let checks: ApiCheckRuns =
+            self.get(&format!("repos/{}/commits/{}/check-runs", self.path, sha))?;
+
+        Ok(Checks {
+            status: summarize_checks(&checks.check_runs),
+            failed_url: checks
+                .check_runs
+                .iter()
+                .filter(|check| is_failure(check))
+                .find_map(|check| check.html_url.clone()),
+        })
+    }
+
+    /// Creates a pull request of the `head` branch into the repository's default branch, returning its URL.
+    pub(crate) fn create_pull_request(&self, head: &str, title: &str, body: &str) -> Res<String> {
+        let repository: ApiRepository = self.get(&format!("repos/{}", self.path))?;
+        let created: ApiCreatedPullRequest = self.request(
+            "POST",
+            &format!("repos/{}/pulls", self.path),
+            Some(serde_json::json!({
+                "title": title,
+                "head": head,
+                "base": repository.default_branch,
+                "body": body,
+            })),
+        )?;
+
+        Ok(created.html_url)
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Res<T> {
+        self.request("GET", path, None)
+    }
+
+    fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Res<T> {
+        let body = body.map(|body| body.to_string());
+
+        let output = if self.token.is_empty() {
+            let mut cmd = Command::new("gh");
+            cmd.args(["api", "--hostname", &self.host, "--method", method, path]);
+            if body.is_some() {
+                cmd.args(["--input", "-"]);
+            }
+
+            let mut child = cmd
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(body.unwrap_or_default().as_bytes())?;
+            child.wait_with_output()?
+        } else {
+            let mut cmd = Command::new("curl");
+            cmd.args(["--silent", "--show-error", "--fail", "--location"])
+                .args(["--request", method])
+                .args([
+                    "--header",
+                    "@-",
+                    "--header",
+                    "Accept: application/vnd.github+json",
+                ])
+                .arg(format!("{}/{}", self.api_url(), path));
+            if let Some(body) = body {
+                cmd.arg("--data-binary").arg(body);
+            }
+
+            // The token is passed on stdin to keep it out of the process list
+            let mut child = cmd
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+
+            write!(
+                child.stdin.take().unwrap(),
+                "Authorization: Bearer {}",
+                self.token
+            )?;
+            child.wait_with_output()?
+        };
+
+        if !output.status.success() {
+            return Err(format!(
+                "GitHub request failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+
+    fn api_url(&self) -> String {
+        if self.host == "github.com" {
+            "https://api.github.com".into()
+        } else {
+            format!("https://{}/api/v3", self.host)
+        }
+    }
+}
+
+fn summarize_checks(checks: &[ApiCheckRun]) -> Option<CiStatus> {
+    if checks.is_empty() {
+        return None;
+    }
+
+    if checks.iter().any(is_failure) {
+        Some(CiStatus::Failure)
+    } else if checks.iter().any(|check| check.status != "completed") {
+        Some(CiStatus::Pending)
+    } else {
+        Some(CiStatus::Success)
+    }
+}
+
+fn is_failure(check: &ApiCheckRun) -> bool {
+    matches!(
+        check.conclusion.as_deref(),
+        Some("failure" | "timed_out" | "cancelled" | "action_required")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pull_requests() {
+        let json = r#"[{
+            "number": 12,
+            "title": "Fix the thing",
+            "user": { "login": "octocat" },
+            "head": { "ref": "fix-thing", "sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e" },
+            "draft": true
+        }]"#;
+
+        let pulls: Vec<ApiPullRequest> = serde_json::from_str(json).unwrap();
+        assert_eq!(pulls[0].number, 12);
+        assert_eq!(pulls[0].user.login, "octocat");
+        assert_eq!(pulls[0].head.ref_name, "fix-thing");
+        assert!(pulls[0].draft);
+    }
+
+    #[test]
+    fn summarize_check_runs() {
+        let run = |status: &str, conclusion: Option<&str>| ApiCheckRun {
+            status: status.into(),
+            conclusion: conclusion.map(String::from),
+            html_url: None,
+        };
+
+        assert_eq!(summarize_checks(&[]), None);
+        assert_eq!(
+            summarize_checks(&[run("completed", Some("success")), run("in_progress", None)]),
+            Some(CiStatus::Pending)
+        );
+        assert_eq!(
+            summarize_checks(&[run("completed", Some("failure")), run("queued", None)]),
+            Some(CiStatus::Failure)
+        );
+        assert_eq!(
+            summarize_checks(&[
+                run("completed", Some("success")),
+                run("completed", Some("skipped"))
+            ]),
+            Some(CiStatus::Success)
+        );
+    }
+}
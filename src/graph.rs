@@ -0,0 +1,171 @@
+use git2::Oid;
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+};
+
+const COLORS: [Color; 6] = [
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Red,
+];
+
+/// Draws the branch lines of a log, like `git log --graph` does, fitting each commit on a single row.
+/// Commits are expected in topological order, each with its parents.
+#[derive(Default)]
+pub(crate) struct Graph {
+    /// The commit each lane is headed to next
+    lanes: Vec<Option<Oid>>,
+}
+
+impl Graph {
+    pub(crate) fn row(&mut self, oid: Oid, parents: &[Oid]) -> Vec<Span<'static>> {
+        let lane = self
+            .lanes
+            .iter()
+            .position(|next| *next == Some(oid))
+            .unwrap_or_else(|| self.free_lane());
+        self.lanes[lane] = Some(oid);
+
+        let above = self.lanes.clone();
+        let ends = (0..above.len())
+            .filter(|&i| i != lane && above[i] == Some(oid))
+            .collect::<Vec<_>>();
+
+        self.lanes[lane] = parents.first().copied();
+        let (mut starts, mut joins) = (vec![], vec![]);
+        for &parent in parents.iter().skip(1) {
+            match self.lanes.iter().position(|next| *next == Some(parent)) {
+                Some(i) => joins.push(i),
+                None => {
+                    let i = self.free_lane_except(&ends);
+                    self.lanes[i] = Some(parent);
+                    starts.push(i);
+                }
+            }
+        }
+
+        for &i in &ends {
+            self.lanes[i] = None;
+        }
+
+        let width = above.len().max(self.lanes.len());
+        let mut cells = (0..width * 2)
+            .map(|cell| match above.get(cell / 2) {
+                Some(Some(_)) if cell % 2 == 0 => ('│', cell / 2),
+                _ => (' ', cell / 2),
+            })
+            .collect::<Vec<_>>();
+
+        let edges = ends
+            .iter()
+            .map(|&i| (i, if i > lane { '╯' } else { '╰' }))
+            .chain(
+                starts
+                    .iter()
+                    .map(|&i| (i, if i > lane { '╮' } else { '╭' })),
+            )
+            .chain(joins.iter().map(|&i| (i, if i > lane { '┤' } else { '├' })));
+
+        for (i, symbol) in edges {
+            let (from, to) = (lane.min(i) * 2 + 1, lane.max(i) * 2);
+            for cell in &mut cells[from..to] {
+                *cell = match cell.0 {
+                    '│' | '┼' => ('┼', cell.1),
+                    _ => ('─', i),
+                };
+            }
+            cells[i * 2] = (symbol, i);
+        }
+        cells[lane * 2] = ('●', lane);
+
+        while self.lanes.last() == Some(&None) {
+            self.lanes.pop();
+        }
+
+        cells
+            .into_iter()
+            .map(|(symbol, color)| {
+                Span::styled(
+                    symbol.to_string(),
+                    Style::new().fg(COLORS[color % COLORS.len()]),
+                )
+            })
+            .collect()
+    }
+
+    fn free_lane(&mut self) -> usize {
+        self.free_lane_except(&[])
+    }
+
+    fn free_lane_except(&mut self, taken: &[usize]) -> usize {
+        match (0..self.lanes.len()).find(|i| self.lanes[*i].is_none() && !taken.contains(i)) {
+            Some(i) => i,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn draw(graph: &mut Graph, commit: u8, parents: &[u8]) -> String {
+        let parents = parents.iter().map(|&n| oid(n)).collect::<Vec<_>>();
+        graph
+            .row(oid(commit), &parents)
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn linear() {
+        let mut graph = Graph::default();
+        assert_eq!(draw(&mut graph, 3, &[2]), "●");
+        assert_eq!(draw(&mut graph, 2, &[1]), "●");
+        assert_eq!(draw(&mut graph, 1, &[]), "●");
+    }
+
+    #[test]
+    fn merge() {
+        // 4 merges 3 (on a branch off 1) into 2
+        let mut graph = Graph::default();
+        assert_eq!(draw(&mut graph, 4, &[2, 3]), "●─╮");
+        assert_eq!(draw(&mut graph, 3, &[1]), "│ ●");
+        assert_eq!(draw(&mut graph, 2, &[1]), "● │");
+        assert_eq!(draw(&mut graph, 1, &[]), "●─╯");
+    }
+
+    #[test]
+    fn branches_side_by_side() {
+        let mut graph = Graph::default();
+        assert_eq!(draw(&mut graph, 5, &[3]), "●");
+        assert_eq!(draw(&mut graph, 4, &[2]), "│ ●");
+        assert_eq!(draw(&mut graph, 3, &[1]), "● │");
+        assert_eq!(draw(&mut graph, 2, &[1]), "│ ●");
+        assert_eq!(draw(&mut graph, 1, &[]), "●─╯");
+    }
+
+    #[test]
+    fn merge_into_existing_lane() {
+        // 6 merges 5, which 7 is already headed to
+        let mut graph = Graph::default();
+        assert_eq!(draw(&mut graph, 7, &[5]), "●");
+        assert_eq!(draw(&mut graph, 6, &[4, 5]), "├─●");
+        assert_eq!(draw(&mut graph, 4, &[5]), "│ ●");
+        assert_eq!(draw(&mut graph, 5, &[]), "●─╯");
+    }
+}
@@ -0,0 +1,170 @@
+//! Images shown below the diff of a changed image file, in terminals supporting the kitty graphics protocol.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// The size of a cell in pixels when the terminal doesn't tell, which also sizes the rows
+/// kept free for an image before it's known where it's drawn.
+pub(crate) const DEFAULT_CELL_SIZE: (u16, u16) = (8, 16);
+
+/// A PNG image, which terminals decode themselves.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Image {
+    /// Tells the terminal which image it already has, the same for the same content
+    pub(crate) id: u32,
+    pub(crate) png: Vec<u8>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl fmt::Debug for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Image")
+            .field("id", &self.id)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+impl Image {
+    /// The image, if `bytes` are a PNG. Its size is read from the header, the rest is left to the terminal.
+    pub(crate) fn from_png(bytes: Vec<u8>) -> Option<Self> {
+        if !bytes.starts_with(PNG_SIGNATURE) || bytes.get(12..16) != Some(b"IHDR") {
+            return None;
+        }
+
+        let read_u32 =
+            |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+        let (width, height) = (read_u32(16)?, read_u32(20)?);
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+
+        Some(Self {
+            // Ids start at 1
+            id: (hasher.finish() as u32).max(1),
+            png: bytes,
+            width,
+            height,
+        })
+    }
+
+    /// The columns and rows the image takes up with cells of `cell_size` pixels, shrunk to fit
+    /// within `max_cols` and `max_rows`. Small images aren't scaled up.
+    pub(crate) fn fit(&self, cell_size: (u16, u16), max_cols: u16, max_rows: u16) -> (u16, u16) {
+        let (cell_width, cell_height) = (cell_size.0.max(1) as u32, cell_size.1.max(1) as u32);
+        let cols = self.width.div_ceil(cell_width) as f64;
+        let rows = self.height.div_ceil(cell_height) as f64;
+
+        let scale = (max_cols as f64 / cols)
+            .min(max_rows as f64 / rows)
+            .min(1.0);
+
+        (
+            ((cols * scale) as u16).max(1),
+            ((rows * scale) as u16).max(1),
+        )
+    }
+
+    /// How many rows to keep free for the image, at most `max_rows`.
+    pub(crate) fn rows(&self, max_rows: u16) -> u16 {
+        self.fit(DEFAULT_CELL_SIZE, u16::MAX, max_rows).1
+    }
+}
+
+/// Whether the terminal gitu runs in shows images sent with the kitty graphics protocol, going by
+/// the environment it set. Inside tmux or screen they wouldn't get through.
+pub(crate) fn kitty_graphics_supported(var: impl Fn(&str) -> Option<String>) -> bool {
+    if var("TMUX").is_some() || var("TERM").is_some_and(|term| term.starts_with("screen")) {
+        return false;
+    }
+
+    var("KITTY_WINDOW_ID").is_some()
+        || var("TERM").is_some_and(|term| term.contains("kitty") || term.contains("ghostty"))
+        || var("TERM_PROGRAM").is_some_and(|program| {
+            ["WezTerm", "ghostty"]
+                .iter()
+                .any(|supported| program.eq_ignore_ascii_case(supported))
+        })
+}
+
+/// The start of a PNG of the given size, enough to be taken for one.
+#[cfg(test)]
+pub(crate) fn test_png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = PNG_SIGNATURE.to_vec();
+    bytes.extend(13u32.to_be_bytes());
+    bytes.extend(b"IHDR");
+    bytes.extend(width.to_be_bytes());
+    bytes.extend(height.to_be_bytes());
+    bytes.extend([8, 6, 0, 0, 0]);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_png_reads_size() {
+        let image = Image::from_png(test_png(640, 480)).unwrap();
+        assert_eq!((image.width, image.height), (640, 480));
+        assert_eq!(image.id, Image::from_png(test_png(640, 480)).unwrap().id);
+        assert_ne!(image.id, Image::from_png(test_png(480, 640)).unwrap().id);
+    }
+
+    #[test]
+    fn from_png_rejects_other_files() {
+        assert!(Image::from_png(vec![0; 100]).is_none());
+        assert!(Image::from_png(b"GIF89a".to_vec()).is_none());
+        assert!(Image::from_png(test_png(640, 480)[..20].to_vec()).is_none());
+        assert!(Image::from_png(test_png(0, 480)).is_none());
+    }
+
+    #[test]
+    fn fit_shrinks_large_images_only() {
+        let image = Image::from_png(test_png(800, 320)).unwrap();
+        assert_eq!(image.fit((8, 16), 200, 40), (100, 20));
+        assert_eq!(image.fit((8, 16), 200, 10), (50, 10));
+        assert_eq!(image.fit((8, 16), 25, 40), (25, 5));
+        assert_eq!(image.rows(12), 12);
+
+        let icon = Image::from_png(test_png(16, 16)).unwrap();
+        assert_eq!(icon.fit((8, 16), 200, 40), (2, 1));
+        assert_eq!(icon.rows(12), 1);
+    }
+
+    #[test]
+    fn kitty_graphics_supported_by_environment() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert!(kitty_graphics_supported(env(&[("KITTY_WINDOW_ID", "1")])));
+        assert!(kitty_graphics_supported(env(&[("TERM", "xterm-kitty")])));
+        assert!(kitty_graphics_supported(env(&[(
+            "TERM_PROGRAM",
+            "WezTerm"
+        )])));
+        assert!(!kitty_graphics_supported(env(&[(
+            "TERM",
+            "xterm-256color"
+        )])));
+        assert!(!kitty_graphics_supported(env(&[
+            ("TERM", "xterm-kitty"),
+            ("TMUX", "/tmp/tmux-1000/default,1,0")
+        ])));
+    }
+}
@@ -2,23 +2,39 @@ use crate::config::Config;
 use crate::git::diff::Delta;
 use crate::git::diff::Diff;
 use crate::git::diff::Hunk;
+use crate::git::diff::ModeChange;
+use crate::git::log_filter::LogFilter;
+use crate::git::signature::{self, SignatureStatus};
+use crate::graph::Graph;
+use crate::image::Image;
+use crate::screen::rebase_todo::RebaseTodo;
 use crate::Res;
 use git2::Commit;
+use git2::FileMode;
 use git2::Oid;
 use git2::Repository;
+use itertools::EitherOrBoth;
+use itertools::Itertools;
 use ratatui::style::Style;
 use ratatui::text::Line;
 use ratatui::text::Span;
-use regex::Regex;
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::iter;
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::Arc;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Default, Clone, Debug)]
 pub(crate) struct Item {
     pub(crate) id: Cow<'static, str>,
     pub(crate) display: Line<'static>,
+    /// The right column of a side-by-side diff, to the right of `display`
+    pub(crate) right_display: Option<Line<'static>>,
+    /// Drawn over this line and the blank ones after it, by the terminal
+    pub(crate) image: Option<Arc<Image>>,
     pub(crate) section: bool,
     pub(crate) default_collapsed: bool,
     pub(crate) depth: usize,
@@ -31,27 +47,168 @@ pub(crate) enum TargetData {
     AllStaged,
     AllUnstaged,
     AllUntracked(Vec<PathBuf>),
+    /// A file as of a revision, in the tree of it
+    Blob {
+        rev: String,
+        path: PathBuf,
+    },
+    /// A line of a file as of a revision, numbered from 1
+    BlobLine {
+        rev: String,
+        path: PathBuf,
+        line: u32,
+    },
     Branch(String),
     Commit(String),
+    Conflict {
+        file: PathBuf,
+        index: usize,
+    },
     Delta(Delta),
+    /// A row of a diffstat, by the file whose changes it counts
+    DiffStat(PathBuf),
     File(PathBuf),
-    Hunk(Rc<Hunk>),
-    HunkLine(Rc<Hunk>, usize),
-    Stash { commit: String, id: usize },
+    Hunk(Arc<Hunk>),
+    HunkLine(Arc<Hunk>, usize),
+    /// Stands in for the hunks of a file too large to be diffed, showing them anyway
+    LargeDiff(PathBuf),
+    /// A change of a file's mode, staged apart from its hunks
+    ModeChange(Arc<ModeChange>),
+    /// An open pull request, by its number
+    #[cfg(feature = "forge")]
+    PullRequest(u64),
+    RebaseTodo(Rc<RefCell<RebaseTodo>>, usize),
+    Remote(String),
+    /// A repository of the repository switcher, by its work tree or git dir
+    Repository(PathBuf),
+    /// A delta that moves a file, which ops treat as both of its paths
+    Rename {
+        old_file: PathBuf,
+        new_file: PathBuf,
+    },
+    Stash {
+        commit: String,
+        id: usize,
+    },
+    Submodule(PathBuf),
+    Tag(String),
 }
 
+impl TargetData {
+    /// The commit or tag of an item, as a revision git resolves to a commit.
+    pub(crate) fn commitish(&self) -> Option<&str> {
+        match self {
+            TargetData::Commit(rev) | TargetData::Tag(rev) => Some(rev),
+            _ => None,
+        }
+    }
+}
+
+/// The ids of the items start with `section`, so that a file in two sections, like staged and unstaged, is
+/// folded in each on its own.
 pub(crate) fn create_diff_items<'a>(
     config: Rc<Config>,
+    section: &'static str,
     diff: &'a Diff,
     depth: &'a usize,
     default_collapsed: bool,
 ) -> impl Iterator<Item = Item> + 'a {
     diff.deltas.iter().flat_map(move |delta| {
-        let target_data = TargetData::Delta(delta.clone());
+        let target_data = match delta.status {
+            git2::Delta::Renamed => TargetData::Rename {
+                old_file: delta.old_file.clone(),
+                new_file: delta.new_file.clone(),
+            },
+            _ => TargetData::Delta(delta.clone()),
+        };
         let config = Rc::clone(&config);
+        let large = delta.large.map(|lines| Item {
+            id: format!(
+                "{}_large_diff_{}",
+                section,
+                delta.new_file.to_string_lossy()
+            )
+            .into(),
+            display: Line::styled(
+                format!("Large diff of {} lines hidden, show it anyway", lines),
+                &config.style.hunk_header,
+            ),
+            depth: *depth + 1,
+            target_data: Some(TargetData::LargeDiff(delta.new_file.clone())),
+            ..Default::default()
+        });
+        let mode_change = delta.mode_change.as_ref().map(|mode_change| Item {
+            id: format!(
+                "{}_mode_change_{}",
+                section,
+                delta.new_file.to_string_lossy()
+            )
+            .into(),
+            display: Line::styled(
+                format!(
+                    "Mode changed {:o} → {:o}",
+                    i32::from(mode_change.old_mode),
+                    i32::from(mode_change.new_mode)
+                ),
+                &config.style.hunk_header,
+            ),
+            depth: *depth + 1,
+            target_data: Some(TargetData::ModeChange(Arc::clone(mode_change))),
+            ..Default::default()
+        });
+        let type_change = (delta.status == git2::Delta::Typechange).then(|| Item {
+            id: format!(
+                "{}_type_change_{}",
+                section,
+                delta.new_file.to_string_lossy()
+            )
+            .into(),
+            display: Line::styled(
+                format!(
+                    "Type changed from {} to {}",
+                    file_type(delta.old_mode),
+                    file_type(delta.new_mode)
+                ),
+                &config.style.hunk_header,
+            ),
+            depth: *depth + 1,
+            target_data: Some(TargetData::Delta(delta.clone())),
+            ..Default::default()
+        });
+        let binary = delta.binary.map(|(old_size, new_size)| Item {
+            id: format!("{}_binary_{}", section, delta.new_file.to_string_lossy()).into(),
+            display: Line::styled(
+                format!(
+                    "Binary file changed ({} → {})",
+                    format_size(old_size),
+                    format_size(new_size)
+                ),
+                &config.style.hunk_header,
+            ),
+            depth: *depth + 1,
+            unselectable: true,
+            ..Default::default()
+        });
+        let max_image_rows = config.general.image_preview.rows;
+        let image = delta.image.iter().flat_map(move |image| {
+            (0..image.rows(max_image_rows)).map(move |row| Item {
+                id: format!(
+                    "{}_image_{}_{}",
+                    section,
+                    delta.new_file.to_string_lossy(),
+                    row
+                )
+                .into(),
+                depth: *depth + 1,
+                unselectable: true,
+                image: (row == 0).then(|| Arc::clone(image)),
+                ..Default::default()
+            })
+        });
 
         iter::once(Item {
-            id: delta.file_header.to_string().into(),
+            // Unlike the header, the path stays the same as the file's changes are staged
+            id: format!("{}_file_{}", section, delta.new_file.to_string_lossy()).into(),
             display: Line::styled(
                 format!(
                     "{:8}   {}",
@@ -73,71 +230,324 @@ pub(crate) fn create_diff_items<'a>(
             target_data: Some(target_data),
             ..Default::default()
         })
-        .chain(
-            delta
+        .chain(mode_change)
+        .chain(type_change)
+        .chain(large)
+        .chain(binary)
+        .chain(image)
+        .chain(delta.hunks.iter().cloned().flat_map(move |hunk| {
+            let is_symlink = delta.old_mode == FileMode::Link && delta.new_mode == FileMode::Link;
+            match is_symlink {
+                true => vec![symlink_change_item(&config, hunk, *depth + 1)],
+                false => create_hunk_items(Rc::clone(&config), hunk, *depth + 1).collect(),
+            }
+        }))
+    })
+}
+
+fn file_type(mode: FileMode) -> &'static str {
+    match mode {
+        FileMode::Link => "symlink",
+        FileMode::Commit => "submodule",
+        FileMode::Tree => "directory",
+        FileMode::Blob | FileMode::BlobGroupWritable | FileMode::BlobExecutable => "file",
+        FileMode::Unreadable => "nothing",
+    }
+}
+
+/// The hunk of a symlink that points elsewhere, told as where from and to.
+fn symlink_change_item(config: &Config, hunk: Arc<Hunk>, depth: usize) -> Item {
+    let target = |prefix: char| {
+        hunk.content
+            .lines
+            .iter()
+            .map(ToString::to_string)
+            .find_map(|line| line.strip_prefix(prefix).map(String::from))
+            .unwrap_or_default()
+    };
+
+    Item {
+        id: String::from_utf8_lossy(&hunk.format_patch())
+            .into_owned()
+            .into(),
+        display: Line::styled(
+            format!("Symlink changed from {} to {}", target('-'), target('+')),
+            &config.style.hunk_header,
+        ),
+        depth,
+        target_data: Some(TargetData::Hunk(hunk)),
+        ..Default::default()
+    }
+}
+
+const DIFFSTAT_BAR_WIDTH: usize = 40;
+
+/// A summary of the diff like `git diff --stat`, with a row for each file.
+pub(crate) fn create_diffstat_items(config: &Config, diff: &Diff, depth: usize) -> Vec<Item> {
+    let style = &config.style;
+    let stats = diff
+        .deltas
+        .iter()
+        .map(|delta| {
+            let (insertions, deletions) = delta
                 .hunks
                 .iter()
-                .cloned()
-                .flat_map(move |hunk| create_hunk_items(Rc::clone(&config), hunk, *depth + 1)),
-        )
+                .flat_map(|hunk| hunk.content.lines.iter())
+                .fold((0, 0), |(insertions, deletions), line| {
+                    match line
+                        .spans
+                        .first()
+                        .and_then(|span| span.content.chars().next())
+                    {
+                        Some('+') => (insertions + 1, deletions),
+                        Some('-') => (insertions, deletions + 1),
+                        _ => (insertions, deletions),
+                    }
+                });
+
+            let name = match delta.status {
+                git2::Delta::Renamed => format!(
+                    "{} => {}",
+                    delta.old_file.to_string_lossy(),
+                    delta.new_file.to_string_lossy()
+                ),
+                _ => delta.new_file.to_string_lossy().to_string(),
+            };
+
+            (delta, name, insertions, deletions)
+        })
+        .collect::<Vec<_>>();
+
+    let name_width = stats.iter().map(|(_, name, ..)| name.width()).max();
+    let max_changes = stats.iter().map(|(_, _, i, d)| i + d).max().unwrap_or(0);
+    let has_binary = stats.iter().any(|(delta, ..)| delta.binary.is_some());
+    let count_width = max_changes
+        .to_string()
+        .len()
+        .max(if has_binary { 3 } else { 0 });
+    let (insertions, deletions) = stats
+        .iter()
+        .fold((0, 0), |(i, d), (_, _, insertions, deletions)| {
+            (i + insertions, d + deletions)
+        });
+
+    let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+    let mut summary = format!("{} changed", plural(stats.len(), "file"));
+    if insertions > 0 {
+        summary.push_str(&format!(", {}(+)", plural(insertions, "insertion")));
+    }
+    if deletions > 0 {
+        summary.push_str(&format!(", {}(-)", plural(deletions, "deletion")));
+    }
+
+    // Bars are scaled down to fit, like git does, though each change shows at least one mark
+    let bar = |n: usize| match max_changes > DIFFSTAT_BAR_WIDTH {
+        true if n > 0 => (n * DIFFSTAT_BAR_WIDTH / max_changes).max(1),
+        _ => n,
+    };
+
+    iter::once(Item {
+        id: "diffstat".into(),
+        display: Line::styled(summary, &style.section_header),
+        section: true,
+        depth,
+        ..Default::default()
     })
+    .chain(
+        stats
+            .into_iter()
+            .map(|(delta, name, insertions, deletions)| Item {
+                id: format!("diffstat_{}", delta.new_file.to_string_lossy()).into(),
+                display: Line::from(vec![
+                    Span::raw(format!(
+                        "{} | {:>count_width$} ",
+                        pad_to_width(&name, name_width.unwrap_or(0)),
+                        match (delta.large, delta.binary) {
+                            (Some(_), _) => "?".to_string(),
+                            (_, Some(_)) => "Bin".to_string(),
+                            _ => (insertions + deletions).to_string(),
+                        },
+                    )),
+                    match delta.binary {
+                        Some((old_size, new_size)) => Span::raw(format!(
+                            "{} → {}",
+                            format_size(old_size),
+                            format_size(new_size)
+                        )),
+                        None => {
+                            Span::styled("+".repeat(bar(insertions)), &style.diff_highlight.tag_new)
+                        }
+                    },
+                    Span::styled("-".repeat(bar(deletions)), &style.diff_highlight.tag_old),
+                ]),
+                depth: depth + 1,
+                target_data: Some(TargetData::DiffStat(delta.new_file.clone())),
+                ..Default::default()
+            }),
+    )
+    .collect()
 }
 
 fn create_hunk_items(
     config: Rc<Config>,
-    hunk: Rc<Hunk>,
+    hunk: Arc<Hunk>,
     depth: usize,
 ) -> impl Iterator<Item = Item> {
-    let target_data = TargetData::Hunk(Rc::clone(&hunk));
+    let target_data = TargetData::Hunk(Arc::clone(&hunk));
 
     iter::once(Item {
-        id: hunk.format_patch().into(),
+        id: String::from_utf8_lossy(&hunk.format_patch())
+            .into_owned()
+            .into(),
         display: Line::styled(hunk.header.clone(), &config.style.hunk_header),
         section: true,
         depth,
         target_data: Some(target_data),
         ..Default::default()
     })
-    .chain(format_diff_hunk_items(depth + 1, hunk))
+    .chain(format_diff_hunk_items(&config, depth + 1, hunk))
 }
 
-fn format_diff_hunk_items(depth: usize, hunk: Rc<Hunk>) -> Vec<Item> {
+fn format_diff_hunk_items(config: &Config, depth: usize, hunk: Arc<Hunk>) -> Vec<Item> {
+    let line_numbers = hunk.line_numbers();
+    let width = line_numbers
+        .iter()
+        .flat_map(|&(old, new)| old.max(new))
+        .max()
+        .map_or(1, |max| max.to_string().len());
+
+    let side_by_side = config
+        .diff_settings
+        .read()
+        .expect("Diff settings lock poisoned")
+        .side_by_side;
+    if side_by_side {
+        return side_by_side_hunk_items(config, depth, hunk, &line_numbers, width);
+    }
+
     hunk.content
         .lines
         .iter()
         .enumerate()
         .map(|(i, line)| Item {
-            display: replace_tabs_with_spaces(line.clone()),
+            display: match config.general.diff_line_numbers.enabled {
+                true => with_line_numbers(config, line_numbers[i], width, line),
+                false => replace_tabs_with_spaces(line.clone()),
+            },
             unselectable: line
                 .spans
                 .first()
                 .is_some_and(|s| s.content.starts_with(' ')),
             depth,
-            target_data: Some(TargetData::HunkLine(Rc::clone(&hunk), i)),
+            target_data: Some(TargetData::HunkLine(Arc::clone(&hunk), i)),
             ..Default::default()
         })
         .collect()
 }
 
+/// Each row pairs a line on the old side with one on the new side, context lines with themselves
+/// and the lines a change removed with those it added in their place. Lines are only staged
+/// one by one in the unified view, so the rows stand for the hunk as a whole.
+fn side_by_side_hunk_items(
+    config: &Config,
+    depth: usize,
+    hunk: Arc<Hunk>,
+    line_numbers: &[(Option<u32>, Option<u32>)],
+    width: usize,
+) -> Vec<Item> {
+    let lines = &hunk.content.lines;
+    let origin = |i: usize| {
+        lines[i]
+            .spans
+            .first()
+            .and_then(|span| span.content.chars().next())
+    };
+
+    let side = |i: usize, number: Option<u32>| {
+        let mut line = replace_tabs_with_spaces(lines[i].clone());
+        if config.general.diff_line_numbers.enabled {
+            let number = number.map_or(String::new(), |n| n.to_string());
+            line.spans.insert(
+                0,
+                Span::styled(format!("{:>width$} ", number), &config.style.line_number),
+            );
+        }
+        line
+    };
+
+    let mut rows = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        if !matches!(origin(i), Some('-' | '+')) {
+            let (old, new) = line_numbers[i];
+            rows.push((Some(side(i, old)), Some(side(i, new))));
+            i += 1;
+            continue;
+        }
+
+        let removed_end = (i..lines.len())
+            .find(|&j| origin(j) != Some('-'))
+            .unwrap_or(lines.len());
+        let added_end = (removed_end..lines.len())
+            .find(|&j| origin(j) != Some('+'))
+            .unwrap_or(lines.len());
+
+        let removed = (i..removed_end).map(|j| side(j, line_numbers[j].0));
+        let added = (removed_end..added_end).map(|j| side(j, line_numbers[j].1));
+        rows.extend(removed.zip_longest(added).map(EitherOrBoth::left_and_right));
+        i = added_end;
+    }
+
+    rows.into_iter()
+        .map(|(old, new)| Item {
+            display: old.unwrap_or_default(),
+            right_display: Some(new.unwrap_or_default()),
+            unselectable: true,
+            depth,
+            target_data: Some(TargetData::Hunk(Arc::clone(&hunk))),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn with_line_numbers<'a>(
+    config: &Config,
+    (old, new): (Option<u32>, Option<u32>),
+    width: usize,
+    line: &Line<'a>,
+) -> Line<'a> {
+    let number = |number: Option<u32>| number.map_or(String::new(), |n| n.to_string());
+    let gutter = Span::styled(
+        format!("{:>width$} {:>width$} ", number(old), number(new)),
+        &config.style.line_number,
+    );
+
+    let mut line = replace_tabs_with_spaces(line.clone());
+    line.spans.insert(0, gutter);
+    line
+}
+
 fn replace_tabs_with_spaces(line: Line<'_>) -> Line<'_> {
     let spans = line
         .spans
         .iter()
-        .cloned()
         .map(|span| Span::styled(span.content.replace('\t', "    "), span.style))
         .collect::<Vec<_>>();
 
     Line { spans, ..line }
 }
 
-pub(crate) fn stash_list(config: &Config, repo: &Repository, limit: usize) -> Res<Vec<Item>> {
+/// The stashes as shown, by their commits, apart from their items so they can be loaded off the main thread.
+pub(crate) type StashLines = Vec<(String, Line<'static>)>;
+
+pub(crate) fn stash_lines(config: &Config, repo: &Repository, limit: usize) -> Res<StashLines> {
     let style = &config.style;
 
     Ok(repo
         .reflog("refs/stash")?
         .iter()
         .enumerate()
-        .map(|(i, stash)| -> Res<Item> {
+        .map(|(i, stash)| {
             let spans = itertools::intersperse(
                 iter::once(Span::styled(format!("stash@{i}"), &style.hash)).chain([stash
                     .message()
@@ -148,122 +558,268 @@ pub(crate) fn stash_list(config: &Config, repo: &Repository, limit: usize) -> Re
             )
             .collect::<Vec<_>>();
 
-            Ok(Item {
-                id: stash.id_new().to_string().into(),
-                display: Line::from(spans),
-                depth: 1,
-                target_data: Some(TargetData::Stash {
-                    commit: stash.id_new().to_string(),
-                    id: i,
-                }),
-                ..Default::default()
-            })
-        })
-        .map(|result| match result {
-            Ok(item) => item,
-            Err(err) => Item {
-                id: err.to_string().into(),
-                display: err.to_string().into(),
-                ..Default::default()
-            },
+            (stash.id_new().to_string(), Line::from(spans))
         })
         .take(limit)
-        .collect::<Vec<_>>())
+        .collect())
+}
+
+pub(crate) fn stash_items(stashes: StashLines) -> Vec<Item> {
+    stashes
+        .into_iter()
+        .enumerate()
+        .map(|(i, (commit, display))| Item {
+            id: commit.clone().into(),
+            display,
+            depth: 1,
+            target_data: Some(TargetData::Stash { commit, id: i }),
+            ..Default::default()
+        })
+        .collect()
 }
 
-pub(crate) fn log(
+/// How the commits of a log are shown.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct LogOptions {
+    /// Draws the commit graph, commits are sorted topologically for it
+    pub graph: bool,
+    /// Colors the hashes of signed commits by whether their signature checks out, like `--show-signature`
+    pub signatures: bool,
+}
+
+/// The commits of a log as shown, or why one couldn't be, apart from their items
+/// so they can be loaded off the main thread.
+pub(crate) type LogLines = Vec<Result<(Oid, Line<'static>), String>>;
+
+/// Without `rev`, the commits from HEAD, or `None` if there is no HEAD to start from.
+pub(crate) fn log_lines(
     config: &Config,
     repo: &Repository,
     limit: usize,
     rev: Option<Oid>,
-    msg_regex: Option<Regex>,
-) -> Res<Vec<Item>> {
-    let style = &config.style;
+    filter: &LogFilter,
+    options: LogOptions,
+) -> Res<Option<LogLines>> {
+    let Some(mut revwalk) = log_revwalk(repo, rev, options)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(
+        CommitLines::new(config, repo, filter, options)?.next_page(
+            config,
+            repo,
+            &mut revwalk,
+            limit,
+        )?,
+    ))
+}
+
+/// Walks the commits of a log from `rev`, or HEAD without it. `None` if there is no HEAD to start from.
+pub(crate) fn log_revwalk(
+    repo: &Repository,
+    rev: Option<Oid>,
+    options: LogOptions,
+) -> Res<Option<git2::Revwalk<'_>>> {
     let mut revwalk = repo.revwalk()?;
+    if options.graph {
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    }
+
     if let Some(r) = rev {
         revwalk.push(r)?;
     } else if revwalk.push_head().is_err() {
-        return Ok(vec![]);
+        return Ok(None);
     }
 
-    let references = repo
-        .references()?
-        .filter_map(Result::ok)
-        .filter_map(
-            |reference| match (reference.peel_to_commit(), reference.shorthand()) {
-                (Ok(target), Some(name)) => {
-                    if name.ends_with("/HEAD") || name.starts_with("prefetch/remotes/") {
-                        return None;
-                    }
+    Ok(Some(revwalk))
+}
 
-                    let style: Style = if reference.is_remote() {
-                        &style.remote
-                    } else if reference.is_tag() {
-                        &style.tag
-                    } else {
-                        &style.branch
-                    }
-                    .into();
+pub(crate) fn log_items(lines: Option<LogLines>) -> Vec<Item> {
+    match lines {
+        None => vec![],
+        Some(lines) if lines.is_empty() => vec![Item {
+            display: Line::raw("No commits found"),
+            ..Default::default()
+        }],
+        Some(lines) => commit_items(lines),
+    }
+}
 
-                    Some((target, Span::styled(name.to_string(), style)))
-                }
-                _ => None,
-            },
-        )
-        .collect::<Vec<(Commit, Span)>>();
+/// Commits reachable from `rev` but not from `hidden`, like `git log hidden..rev`.
+pub(crate) fn log_range_lines(
+    config: &Config,
+    repo: &Repository,
+    limit: usize,
+    rev: Oid,
+    hidden: Oid,
+) -> Res<LogLines> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(rev)?;
+    revwalk.hide(hidden)?;
+    commit_lines(
+        config,
+        repo,
+        revwalk,
+        limit,
+        &LogFilter::default(),
+        LogOptions::default(),
+    )
+}
 
-    let items: Vec<Item> = revwalk
-        .map(|oid_result| -> Res<Option<Item>> {
-            let oid = oid_result?;
-            let commit = repo.find_commit(oid)?;
-            let short_id = commit.as_object().short_id()?.as_str().unwrap().to_string();
+fn commit_lines(
+    config: &Config,
+    repo: &Repository,
+    mut revwalk: git2::Revwalk,
+    limit: usize,
+    filter: &LogFilter,
+    options: LogOptions,
+) -> Res<LogLines> {
+    CommitLines::new(config, repo, filter, options)?.next_page(config, repo, &mut revwalk, limit)
+}
 
-            let spans = itertools::intersperse(
-                iter::once(Span::styled(short_id, &style.hash))
-                    .chain(
-                        references
-                            .iter()
-                            .filter(|(commit, _)| commit.id() == oid)
-                            .map(|(_, name)| name.clone()),
-                    )
-                    .chain([commit.summary().unwrap_or("").to_string().into()]),
-                Span::raw(" "),
+/// Shows the commits of a revwalk a page at a time, keeping the filter and graph
+/// going from one page to the next.
+pub(crate) struct CommitLines {
+    references: Vec<(Oid, Span<'static>)>,
+    filter: LogFilter,
+    graph: Option<Graph>,
+    signatures: bool,
+}
+
+impl CommitLines {
+    pub(crate) fn new(
+        config: &Config,
+        repo: &Repository,
+        filter: &LogFilter,
+        options: LogOptions,
+    ) -> Res<Self> {
+        let style = &config.style;
+        let references = repo
+            .references()?
+            .filter_map(Result::ok)
+            .filter_map(
+                |reference| match (reference.peel_to_commit(), reference.shorthand()) {
+                    (Ok(target), Some(name)) => {
+                        if name.ends_with("/HEAD") || name.starts_with("prefetch/remotes/") {
+                            return None;
+                        }
+
+                        let style: Style = if reference.is_remote() {
+                            &style.remote
+                        } else if reference.is_tag() {
+                            &style.tag
+                        } else {
+                            &style.branch
+                        }
+                        .into();
+
+                        Some((target.id(), Span::styled(name.to_string(), style)))
+                    }
+                    _ => None,
+                },
             )
+            .collect();
+
+        Ok(Self {
+            references,
+            filter: filter.clone(),
+            graph: options.graph.then(Graph::default),
+            signatures: options.signatures,
+        })
+    }
+
+    /// The next `limit` commits of `revwalk` that pass the filter.
+    pub(crate) fn next_page(
+        &mut self,
+        config: &Config,
+        repo: &Repository,
+        revwalk: &mut git2::Revwalk,
+        limit: usize,
+    ) -> Res<LogLines> {
+        let style = &config.style;
+        let filter = &mut self.filter;
+
+        let commits = revwalk
+            .map(|oid_result| -> Res<Option<Commit>> {
+                let commit = repo.find_commit(oid_result?)?;
+                Ok(filter.matches(repo, &commit)?.then_some(commit))
+            })
+            .filter_map(Result::transpose)
+            .take(limit)
             .collect::<Vec<_>>();
 
-            if let Some(re) = &msg_regex {
-                if !re.is_match(commit.message().unwrap_or("")) {
-                    return Ok(None);
-                }
-            }
+        let signatures = if self.signatures {
+            let ids = commits
+                .iter()
+                .filter_map(|commit| Some(commit.as_ref().ok()?.id()))
+                .collect::<Vec<_>>();
+
+            signature::verify(repo, &ids)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(commits
+            .into_iter()
+            .map(|commit_result| -> Res<(Oid, Line<'static>)> {
+                let commit = commit_result?;
+                let oid = commit.id();
+
+                let short_id = commit.as_object().short_id()?.as_str().unwrap().to_string();
+                let graph_spans = match &mut self.graph {
+                    Some(graph) => graph.row(oid, &commit.parent_ids().collect::<Vec<_>>()),
+                    None => vec![],
+                };
+
+                let hash_style = match signatures.get(&oid).map(|signature| signature.status) {
+                    Some(SignatureStatus::Good) => &style.signature_good,
+                    Some(SignatureStatus::Bad) => &style.signature_bad,
+                    Some(SignatureStatus::Untrusted | SignatureStatus::Unverifiable) => {
+                        &style.signature_untrusted
+                    }
+                    None => &style.hash,
+                };
+
+                let spans = graph_spans
+                    .into_iter()
+                    .chain(itertools::intersperse(
+                        iter::once(Span::styled(short_id, hash_style))
+                            .chain(
+                                self.references
+                                    .iter()
+                                    .filter(|(target, _)| *target == oid)
+                                    .map(|(_, name)| name.clone()),
+                            )
+                            .chain([commit.summary().unwrap_or("").to_string().into()]),
+                        Span::raw(" "),
+                    ))
+                    .collect::<Vec<_>>();
+
+                Ok((oid, Line::from(spans)))
+            })
+            .map(|result| result.map_err(|err| err.to_string()))
+            .collect())
+    }
+}
 
-            Ok(Some(Item {
+pub(crate) fn commit_items(lines: LogLines) -> Vec<Item> {
+    lines
+        .into_iter()
+        .map(|line| match line {
+            Ok((oid, display)) => Item {
                 id: oid.to_string().into(),
-                display: Line::from(spans),
+                display,
                 depth: 1,
                 target_data: Some(TargetData::Commit(oid.to_string())),
                 ..Default::default()
-            }))
-        })
-        .filter_map(|result| match result {
-            Ok(item) => item,
-            Err(err) => Some(Item {
-                id: err.to_string().into(),
-                display: err.to_string().into(),
+            },
+            Err(err) => Item {
+                id: err.clone().into(),
+                display: err.into(),
                 ..Default::default()
-            }),
+            },
         })
-        .take(limit)
-        .collect();
-
-    if items.is_empty() {
-        Ok(vec![Item {
-            display: Line::raw("No commits found"),
-            ..Default::default()
-        }])
-    } else {
-        Ok(items)
-    }
+        .collect()
 }
 
 pub(crate) fn blank_line() -> Item {
@@ -274,3 +830,25 @@ pub(crate) fn blank_line() -> Item {
         ..Default::default()
     }
 }
+
+/// A size in bytes as the largest unit it's at least one of, like `1.5 KiB`.
+pub(crate) fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Pads `text` with spaces to take up `width` columns. Unlike `format!("{:width$}")`,
+/// this counts wide characters as two columns and combining ones as none.
+pub(crate) fn pad_to_width(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
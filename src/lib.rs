@@ -1,13 +1,23 @@
+mod auto_fetch;
 mod bindings;
+#[cfg(feature = "forge")]
+mod ci;
 pub mod cli;
 mod cmd_log;
 pub mod config;
+mod events;
 mod git;
 mod git2_opts;
+#[cfg(feature = "forge")]
+mod github;
+mod graph;
+mod image;
 mod items;
 mod key_parser;
 mod menu;
 mod ops;
+mod picker;
+mod porcelain;
 mod prompt;
 mod screen;
 pub mod state;
@@ -16,13 +26,29 @@ pub mod term;
 #[cfg(test)]
 mod tests;
 mod ui;
+mod watcher;
+mod welcome;
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventState, KeyModifiers};
-use git2::Repository;
+use git2::{Repository, RepositoryOpenFlags};
 use items::Item;
 use ops::Action;
-use std::{error::Error, path::PathBuf, process::Command, rc::Rc, time::Duration};
-use term::Term;
+use ratatui::{backend::TestBackend, Terminal};
+use std::{
+    env,
+    error::Error,
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use term::{Term, TermBackend};
 
 //                                An overview of Gitu's ui and terminology:
 //
@@ -64,34 +90,49 @@ use term::Term;
 pub type Res<T> = Result<T, Box<dyn Error>>;
 
 pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
-    log::debug!("Finding git dir");
-    let dir = PathBuf::from(
-        String::from_utf8(
-            Command::new("git")
-                .args(["rev-parse", "--show-toplevel"])
-                .output()?
-                .stdout,
-        )?
-        .trim_end(),
-    );
-
-    log::debug!("Opening repo");
-    let repo = open_repo_from_env()?;
-    repo.set_workdir(&dir, false)?;
-
     log::debug!("Initializing config");
-    let config = config::init_config()?;
+    let (mut config, config_errors) = config::init_config(args.theme.as_deref())?;
+    // Printed output has no room for images
+    config.general.image_preview.enabled &=
+        !args.print && image::kitty_graphics_supported(|var| env::var(var).ok());
+    let config = Rc::new(config);
+    let events = (!args.print).then(events::Events::spawn);
+
+    // Rather than just the error, offer to make a repository to go on with
+    let repo = match (open_repo(args.dir.as_deref()), &events) {
+        (Err(err), Some(events)) if err.is::<NotFound>() => {
+            let dir = match &args.dir {
+                Some(dir) => dir.clone(),
+                None => env::current_dir()?,
+            };
+            match welcome::run(Rc::clone(&config), dir, term, events)? {
+                Some(path) => open_repo(Some(&path))?,
+                None => return Ok(()),
+            }
+        }
+        (result, _) => result?,
+    };
 
     log::debug!("Creating initial state");
-    let mut state = state::State::create(Rc::new(repo), term.size()?, args, Rc::new(config), true)?;
+    let mut state = state::State::create(Rc::new(repo), term.size()?, args, config, !args.print)?;
+    if let Some(events) = &events {
+        state.waker = events.waker();
+        state.input = events.input();
+    }
 
-    log::debug!("Initial update");
-    state.update(term, &[Event::FocusGained])?;
+    for error in config_errors {
+        log::warn!("{}", error);
+        state.display_error(error);
+    }
 
-    if args.print {
-        return Ok(());
+    #[cfg(feature = "forge")]
+    if !args.print {
+        spawn_ci_statuses(&mut state);
     }
 
+    log::debug!("Initial update");
+    state.update(term, &[Event::FocusGained])?;
+
     if let Some(keys_string) = &args.keys {
         let ("", keys) = key_parser::parse_keys(keys_string).expect("Couldn't parse keys") else {
             panic!("Couldn't parse keys");
@@ -99,12 +140,81 @@ pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
         handle_initial_send_keys(&keys, &mut state, term)?;
     }
 
+    // Only printing, there's nothing to wait for
+    let Some(events) = events else {
+        return Ok(());
+    };
+
+    state.recent_repos_file = screen::repositories::recent_file().ok();
+    state.remember_repo();
+
+    let mut watched_repo = state.repo.path().to_path_buf();
+    let mut watcher = spawn_watcher(&state);
+    let mut fetcher = spawn_fetcher(&state);
+
+    // Gitu may be stopped by other means than `Op::Suspend`, the shell would have reset the terminal
+    let resumed = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    {
+        let mut signals = signal_hook::iterator::Signals::new([signal_hook::consts::SIGCONT])?;
+        let (resumed, waker) = (Arc::clone(&resumed), events.waker());
+        std::thread::spawn(move || {
+            for _ in signals.forever() {
+                resumed.store(true, Ordering::Relaxed);
+                waker.wake();
+            }
+        });
+    }
+
     while !state.quit {
-        let events = if event::poll(Duration::from_millis(100))? {
-            vec![event::read()?]
-        } else {
-            vec![]
-        };
+        // Switched to another repository, or into a submodule
+        if state.repo.path() != watched_repo {
+            watched_repo = state.repo.path().to_path_buf();
+            watcher = spawn_watcher(&state);
+            fetcher = spawn_fetcher(&state);
+            #[cfg(feature = "forge")]
+            spawn_ci_statuses(&mut state);
+        }
+
+        let mut events = events.wait(poll_timeout(&state.config, state.is_busy()))?;
+
+        if resumed.swap(false, Ordering::Relaxed) {
+            term::resume(term)?;
+            let size = term.size()?;
+            events.push(Event::Resize(size.width, size.height));
+        }
+
+        // Gitu's own commands refresh the screen once they're done
+        state.files_watched = watcher.is_some();
+        let changed = watcher.as_ref().is_some_and(watcher::Watcher::take_changed);
+        if changed {
+            state.repo_changes += 1;
+        }
+        let fetched = fetcher.as_ref().and_then(auto_fetch::AutoFetch::poll);
+        let was_fetched = fetched.is_some();
+        if let Some(fetched) = fetched {
+            state.last_fetch = Some(fetched);
+            state.repo_changes += 1;
+        }
+
+        // Refreshed once for both
+        if changed || was_fetched {
+            if state.pending_cmd.is_none() {
+                if let Err(err) = state.screen_mut().update() {
+                    state.display_error(err.to_string());
+                }
+            }
+            events.push(Event::FocusGained);
+        }
+
+        #[cfg(feature = "forge")]
+        if state
+            .ci_statuses
+            .as_ref()
+            .is_some_and(ci::CiStatuses::take_updated)
+        {
+            events.push(Event::FocusGained);
+        }
 
         state.update(term, &events)?;
     }
@@ -112,16 +222,166 @@ pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
     Ok(())
 }
 
-fn open_repo_from_env() -> Res<Repository> {
-    match Repository::open_from_env() {
-        Ok(repo) => Ok(repo),
-        Err(err) if err.code() == git2::ErrorCode::NotFound => {
-            Err("No .git found in the current directory".into())
+/// How long to wait for events before drawing again, which is only needed to turn the spinner while busy.
+pub(crate) fn poll_timeout(config: &config::Config, busy: bool) -> Option<Duration> {
+    busy.then(|| Duration::from_millis(config.general.poll_interval.busy.max(1)))
+}
+
+fn spawn_watcher(state: &state::State) -> Option<watcher::Watcher> {
+    state.config.general.auto_refresh.enabled.then(|| {
+        watcher::Watcher::spawn(
+            state.repo.path().into(),
+            state.repo.workdir().map(Into::into),
+            Duration::from_millis(state.config.general.auto_refresh.interval.max(1)),
+            state.waker.clone(),
+        )
+    })
+}
+
+fn spawn_fetcher(state: &state::State) -> Option<auto_fetch::AutoFetch> {
+    let fetch_config = &state.config.general.auto_fetch;
+    fetch_config.enabled.then(|| {
+        auto_fetch::AutoFetch::spawn(
+            state.repo.workdir().unwrap_or(state.repo.path()).into(),
+            Duration::from_secs(fetch_config.interval.max(1)),
+            state.waker.clone(),
+        )
+    })
+}
+
+#[cfg(feature = "forge")]
+fn spawn_ci_statuses(state: &mut state::State) {
+    state.ci_statuses = None;
+    if !state.config.general.forge.ci_status {
+        return;
+    }
+
+    if let Ok(github) = github::GitHub::from_repo(&state.repo, &state.config) {
+        state.ci_statuses = Some(ci::CiStatuses::spawn(state.waker.clone(), move |oid| {
+            github.checks(&oid.to_string())
+        }));
+    }
+}
+
+/// Draws a single frame for `gitu --print`, after the keys of `--keys` if any, and prints it to stdout.
+/// The frame is as large as the terminal, or 80x24 if there's none.
+pub fn print(args: &cli::Args) -> Res<()> {
+    let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+    let mut term = Terminal::new(TermBackend::Test(TestBackend::new(width, height)))?;
+    run(args, &mut term)?;
+
+    let TermBackend::Test(backend) = term.backend() else {
+        unreachable!();
+    };
+    print_stdout(&term::to_ansi(backend.buffer()))
+}
+
+/// Prints the status, log or diff as JSON for `gitu status --porcelain`, `gitu log --json` and `gitu diff --json`.
+pub fn print_porcelain(args: &cli::Args) -> Res<()> {
+    let repo = open_repo(args.dir.as_deref())?;
+    let (config, config_errors) = config::init_config(args.theme.as_deref())?;
+    for error in config_errors {
+        log::warn!("{}", error);
+    }
+
+    let command = args.command.as_ref().ok_or("No command")?;
+    print_stdout(&format!("{}\n", porcelain::json(&config, &repo, command)?))
+}
+
+/// Output piped into `head` and the like can be closed before all of it is written, which isn't an error.
+fn print_stdout(text: &str) -> Res<()> {
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(text.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Opens the repository that `dir`, or else the current directory, is in.
+/// Like git, `GIT_DIR`, `GIT_WORK_TREE` and `GIT_CEILING_DIRECTORIES` are taken into account.
+fn open_repo(dir: Option<&Path>) -> Res<Repository> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => env::current_dir()?,
+    };
+
+    log::debug!("Opening repo");
+    let repo = open_repo_from_env(&dir)?;
+
+    // Fails in bare repositories, unless given a work tree like with `GIT_WORK_TREE`
+    log::debug!("Finding git dir");
+    let toplevel = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(&dir)
+        .output()?;
+    if toplevel.status.success() {
+        repo.set_workdir(
+            &PathBuf::from(String::from_utf8(toplevel.stdout)?.trim_end()),
+            false,
+        )?;
+    }
+
+    Ok(repo)
+}
+
+fn open_repo_from_env(dir: &Path) -> Res<Repository> {
+    // libgit2 only looks at `GIT_DIR` when it isn't given a directory to search from
+    let result = match env::var_os("GIT_DIR") {
+        Some(_) => Repository::open_from_env(),
+        None => {
+            let ceiling_dirs = env::var_os("GIT_CEILING_DIRECTORIES").unwrap_or_default();
+            let ceiling_dirs =
+                env::split_paths(&ceiling_dirs).filter(|dir| !dir.as_os_str().is_empty());
+            Repository::open_ext(dir, RepositoryOpenFlags::empty(), ceiling_dirs)
         }
+    };
+
+    match result {
+        Ok(repo) => Ok(repo),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => Err(Box::new(NotFound::in_dir(dir))),
         Err(err) => Err(Box::new(err)),
     }
 }
 
+/// No repository was found where gitu was started.
+#[derive(Debug)]
+struct NotFound(String);
+
+impl NotFound {
+    /// Points to the repositories right below `dir`, as gitu is often started a directory too high.
+    fn in_dir(dir: &Path) -> Self {
+        let mut repos = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().join(".git").exists())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        repos.sort();
+
+        let found = format!("No .git found in {}", dir.display());
+        NotFound(match &repos[..] {
+            [] => format!("{}, open a repository with `gitu -C <path>`", found),
+            _ => format!(
+                "{}, open one of its repositories with `gitu -C <path>`: {}",
+                found,
+                repos.join(", ")
+            ),
+        })
+    }
+}
+
+impl fmt::Display for NotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for NotFound {}
+
 fn handle_initial_send_keys(
     keys: &[(KeyModifiers, KeyCode)],
     state: &mut state::State,
@@ -140,6 +400,7 @@ fn handle_initial_send_keys(
         .collect::<Vec<_>>();
 
     if !initial_events.is_empty() {
+        state.screen_mut().await_refresh()?;
         state.update(term, &initial_events)?;
     }
 
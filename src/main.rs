@@ -18,16 +18,24 @@ pub fn main() -> Res<()> {
         simple_logging::log_to_file("gitu.log", LevelFilter::Trace)?;
     }
 
+    if args.is_porcelain() {
+        return gitu::print_porcelain(&args);
+    }
+
     panic::set_hook(Box::new(|panic_info| {
-        term::cleanup_alternate_screen();
-        term::cleanup_raw_mode();
+        cleanup_term();
 
         eprintln!("{}", panic_info);
         eprintln!("trace: \n{}", Backtrace::force_capture());
     }));
 
+    #[cfg(unix)]
+    if !args.print {
+        exit_on_signal()?;
+    }
+
     if args.print {
-        setup_term_and_run(&args)?;
+        gitu::print(&args)?;
     } else {
         term::alternate_screen(|| term::raw_mode(|| setup_term_and_run(&args)))?
     }
@@ -35,6 +43,30 @@ pub fn main() -> Res<()> {
     Ok(())
 }
 
+/// Puts the terminal back the way it was before gitu started, so that the shell is usable again.
+fn cleanup_term() {
+    term::cleanup_alternate_screen();
+    term::cleanup_raw_mode();
+    term::cleanup_cursor();
+}
+
+/// Being killed would otherwise leave the terminal in raw mode, without a cursor.
+#[cfg(unix)]
+fn exit_on_signal() -> Res<()> {
+    use signal_hook::{consts::*, iterator::Signals};
+    use std::{process, thread};
+
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGHUP, SIGQUIT])?;
+    thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            cleanup_term();
+            process::exit(128 + signal);
+        }
+    });
+
+    Ok(())
+}
+
 fn setup_term_and_run(args: &Args) -> Res<()> {
     log::debug!("Initializing terminal backend");
     let mut terminal = Terminal::new(term::backend())?;
@@ -5,6 +5,7 @@ use std::ffi::OsString;
 use serde::{Deserialize, Serialize};
 
 use crate::ops;
+use crate::Res;
 
 pub(crate) mod arg;
 
@@ -13,28 +14,64 @@ pub(crate) mod arg;
 pub(crate) enum Menu {
     #[serde(rename = "root")]
     Root,
+    #[serde(rename = "bisect_menu")]
+    Bisect,
     #[serde(rename = "branch_menu")]
     Branch,
+    #[serde(rename = "cherry_pick_menu")]
+    CherryPick,
+    #[serde(rename = "clean_menu")]
+    Clean,
+    #[serde(rename = "clean_preview_menu")]
+    CleanPreview,
     #[serde(rename = "commit_menu")]
     Commit,
+    #[serde(rename = "commit_msg_menu")]
+    CommitMsg,
+    #[serde(rename = "copy_menu")]
+    Copy,
+    #[serde(rename = "diff_menu")]
+    Diff,
+    #[serde(rename = "diff_options_menu")]
+    DiffOptions,
     #[serde(rename = "fetch_menu")]
     Fetch,
+    #[serde(rename = "forge_menu")]
+    Forge,
     #[serde(rename = "help_menu")]
     Help,
+    #[serde(rename = "ignore_menu")]
+    Ignore,
     #[serde(rename = "log_menu")]
     Log,
+    #[serde(rename = "merge_menu")]
+    Merge,
+    #[serde(rename = "patch_menu")]
+    Patch,
     #[serde(rename = "pull_menu")]
     Pull,
     #[serde(rename = "push_menu")]
     Push,
     #[serde(rename = "rebase_menu")]
     Rebase,
+    #[serde(rename = "rebase_todo_menu")]
+    RebaseTodo,
+    #[serde(rename = "remote_menu")]
+    Remote,
     #[serde(rename = "reset_menu")]
     Reset,
+    #[serde(rename = "resolve_menu")]
+    Resolve,
     #[serde(rename = "revert_menu")]
     Revert,
+    #[serde(rename = "shell_menu")]
+    Shell,
     #[serde(rename = "stash_menu")]
     Stash,
+    #[serde(rename = "submodule_menu")]
+    Submodule,
+    #[serde(rename = "tag_menu")]
+    Tag,
 }
 
 pub(crate) struct PendingMenu {
@@ -50,17 +87,35 @@ impl PendingMenu {
             is_hidden: false,
             args: match menu {
                 Menu::Root => vec![],
-                Menu::Branch => ops::checkout::init_args(),
+                Menu::Bisect => vec![],
+                Menu::Branch => ops::branch::init_args(),
+                Menu::CherryPick => ops::cherry_pick::init_args(),
+                Menu::Clean => ops::clean::init_args(),
+                Menu::CleanPreview => vec![],
                 Menu::Commit => ops::commit::init_args(),
+                Menu::CommitMsg => vec![],
+                Menu::Copy => vec![],
+                Menu::Diff => vec![],
+                Menu::DiffOptions => ops::diff_options::init_args(),
                 Menu::Fetch => ops::fetch::init_args(),
+                Menu::Forge => vec![],
                 Menu::Help => vec![],
+                Menu::Ignore => vec![],
                 Menu::Log => ops::log::init_args(),
+                Menu::Merge => ops::merge::init_args(),
+                Menu::Patch => ops::patch::init_args(),
                 Menu::Pull => ops::pull::init_args(),
                 Menu::Push => ops::push::init_args(),
                 Menu::Rebase => ops::rebase::init_args(),
+                Menu::RebaseTodo => vec![],
+                Menu::Remote => ops::remote::init_args(),
                 Menu::Reset => ops::reset::init_args(),
+                Menu::Resolve => vec![],
                 Menu::Revert => ops::revert::init_args(),
+                Menu::Shell => vec![],
                 Menu::Stash => ops::stash::init_args(),
+                Menu::Submodule => ops::submodule::init_args(),
+                Menu::Tag => ops::tag::init_args(),
             }
             .into_iter()
             .map(|arg| (Cow::from(arg.arg), arg))
@@ -68,6 +123,41 @@ impl PendingMenu {
         }
     }
 
+    /// Unsets the flag that negates `name`, so `--edit` and `--no-edit` are never both passed.
+    pub fn unset_opposite(&mut self, name: &str) {
+        let opposite = match name.strip_prefix("--no-") {
+            Some(rest) => format!("--{}", rest),
+            None => match name.strip_prefix("--") {
+                Some(rest) => format!("--no-{}", rest),
+                None => return,
+            },
+        };
+
+        if let Some(arg) = self.args.get_mut(opposite.as_str()) {
+            arg.unset();
+        }
+    }
+
+    /// The active args and their values, to reopen the menu with later.
+    pub fn active_args(&self) -> Vec<(&'static str, Option<String>)> {
+        self.args
+            .values()
+            .filter(|arg| arg.is_active())
+            .map(|arg| (arg.arg, arg.value_as_string()))
+            .collect()
+    }
+
+    pub fn restore_args(&mut self, active_args: &[(&'static str, Option<String>)]) -> Res<()> {
+        for arg in self.args.values_mut() {
+            match active_args.iter().find(|(name, _)| *name == arg.arg) {
+                Some((_, value)) => arg.set(value.as_deref().unwrap_or(""))?,
+                None => arg.unset(),
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn args(&self) -> Vec<OsString> {
         self.args
             .iter()
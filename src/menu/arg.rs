@@ -1,11 +1,15 @@
-use crate::Res;
+use crate::{git::log_filter, picker::Candidate, state::State, Res};
 use regex::Regex;
 
+type CandidatesFn = fn(&State) -> Res<Vec<Candidate>>;
+
 #[derive(Debug)]
 pub(crate) struct Arg {
     pub arg: &'static str,
     pub display: &'static str,
     value: Box<dyn ArgValue>,
+    /// Suggested values, picked from while the value is prompted for
+    pub candidates: Option<CandidatesFn>,
 }
 
 impl Arg {
@@ -14,6 +18,7 @@ impl Arg {
             arg,
             display,
             value: Box::new(ArgBool { value: default }),
+            candidates: None,
         }
     }
 
@@ -34,9 +39,15 @@ impl Arg {
                 default,
                 parser,
             }),
+            candidates: None,
         }
     }
 
+    pub fn with_candidates(mut self, candidates: CandidatesFn) -> Self {
+        self.candidates = Some(candidates);
+        self
+    }
+
     pub fn is_active(&self) -> bool {
         self.value.is_set()
     }
@@ -70,6 +81,8 @@ impl Arg {
 
     pub fn get_cli_token(&self) -> String {
         match self.value_as_string() {
+            // Paths after `--` are separate words
+            Some(value) if self.arg == "--" => format!("-- {}", value),
             Some(value) => format!("{}={}", self.arg, value),
             None => self.arg.to_string(),
         }
@@ -176,6 +189,16 @@ pub fn any_regex(s: &str) -> Res<Regex> {
     Ok(Regex::try_from(s)?)
 }
 
+pub fn any_string(s: &str) -> Res<String> {
+    Ok(s.to_string())
+}
+
+/// Keeps the date as written, so that relative ones stay relative.
+pub fn date(s: &str) -> Res<String> {
+    log_filter::parse_date(s, chrono::Local::now().timestamp())?;
+    Ok(s.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::menu::arg::{self, Arg};
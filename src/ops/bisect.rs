@@ -0,0 +1,135 @@
+use std::{process::Command, rc::Rc};
+
+use crate::{git, items::TargetData, state::State, term::Term, Res};
+
+use super::{create_prompt_with_default, set_rev_prompt, Action, OpTrait};
+
+pub(crate) struct BisectStart;
+impl OpTrait for BisectStart {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Start bisect with bad revision",
+            bisect_start_prompt,
+            |_| Some("HEAD".into()),
+            true,
+        ))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        !is_bisecting(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Start".into()
+    }
+}
+
+fn bisect_start_prompt(state: &mut State, _term: &mut Term, bad: &str) -> Res<()> {
+    let bad = bad.to_string();
+    set_rev_prompt(
+        state,
+        "Good revision",
+        Box::new(move |state, term, good| bisect_start(state, term, &bad, good)),
+        true,
+    )
+}
+
+fn bisect_start(state: &mut State, term: &mut Term, bad: &str, good: &str) -> Res<()> {
+    if good.is_empty() {
+        state.close_menu();
+        return Err("Good revision required".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["bisect", "start", bad, good]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct BisectGood;
+impl OpTrait for BisectGood {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(bisect_mark("good", target))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_bisecting(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Good".into()
+    }
+}
+
+pub(crate) struct BisectBad;
+impl OpTrait for BisectBad {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(bisect_mark("bad", target))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_bisecting(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Bad".into()
+    }
+}
+
+pub(crate) struct BisectSkip;
+impl OpTrait for BisectSkip {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(bisect_mark("skip", target))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_bisecting(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Skip".into()
+    }
+}
+
+/// Marks the commit at point, or the current commit if there's none selected.
+fn bisect_mark(mark: &'static str, target: Option<&TargetData>) -> Action {
+    let rev = match target {
+        Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => Some(r.clone()),
+        _ => None,
+    };
+
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["bisect", mark]);
+        cmd.args(rev.as_ref());
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
+
+pub(crate) struct BisectReset;
+impl OpTrait for BisectReset {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["bisect", "reset"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_bisecting(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Reset".into()
+    }
+}
+
+fn is_bisecting(state: &State) -> bool {
+    git::is_bisecting(&state.repo)
+}
@@ -0,0 +1,56 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term, Res};
+use git2::BlameOptions;
+use std::{path::Path, rc::Rc};
+
+/// Shows the commit that last changed the line at point, like `git blame -L <line>,<line> <rev> -- <path>`.
+pub(crate) struct BlameLine;
+impl OpTrait for BlameLine {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::BlobLine { rev, path, line }) = target else {
+            return None;
+        };
+
+        let (rev, path, line) = (rev.clone(), path.clone(), *line);
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let commit = blame_line(state, &rev, &path, line)?;
+
+            state.close_menu();
+            state.screens.push(screen::show::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+                state.enable_async_cmds,
+                commit,
+                vec![path.clone()],
+            )?);
+            Ok(())
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Blame line".into()
+    }
+}
+
+fn blame_line(state: &State, rev: &str, path: &Path, line: u32) -> Res<String> {
+    let newest = state.repo.revparse_single(rev)?.peel_to_commit()?.id();
+    let line = line as usize;
+
+    let blame = state.repo.blame_file(
+        path,
+        Some(
+            BlameOptions::new()
+                .newest_commit(newest)
+                .min_line(line)
+                .max_line(line),
+        ),
+    )?;
+
+    let hunk = blame.get_line(line).ok_or("Line not found in blame")?;
+    Ok(hunk.final_commit_id().to_string())
+}
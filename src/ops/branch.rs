@@ -0,0 +1,239 @@
+use super::{
+    create_confirm_prompt, create_picker_prompt, create_rev_prompt, selected_branch, set_prompt,
+    Action, OpTrait,
+};
+use crate::{
+    items::TargetData, menu::arg::Arg, picker, prompt::PromptData, state::State, term::Term, Res,
+};
+use std::{process::Command, rc::Rc};
+use tui_prompts::State as _;
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![]
+}
+
+pub(crate) struct Checkout;
+impl OpTrait for Checkout {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt("Checkout", checkout, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Checkout branch/revision".into()
+    }
+}
+
+fn checkout(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["checkout"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(rev);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)?;
+    Ok(())
+}
+
+/// Replaces a file in the work tree with how it was at a revision, like `git checkout <rev> -- <path>`.
+pub(crate) struct CheckoutFile;
+impl OpTrait for CheckoutFile {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Blob { rev, path }) = target else {
+            return None;
+        };
+
+        let (rev, path) = (rev.clone(), path.clone());
+        let action = Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["checkout", &rev, "--"]).arg(&path);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        });
+
+        Some(create_confirm_prompt(
+            action,
+            "Really checkout file, replacing it in the work tree?",
+            |config| config.general.confirm_checkout_file.enabled,
+        ))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Checkout file".into()
+    }
+}
+
+pub(crate) struct CheckoutNewBranch;
+impl OpTrait for CheckoutNewBranch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            state.prompt.set(PromptData {
+                prompt_text: "Create and checkout branch:".into(),
+                update_fn: Rc::new(checkout_new_branch_prompt_update),
+            });
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Checkout new branch".into()
+    }
+}
+
+fn checkout_new_branch_prompt_update(state: &mut State, term: &mut Term) -> Res<()> {
+    if state.prompt.state.status().is_done() {
+        let name = state.prompt.state.value().to_string();
+        state.prompt.reset(term)?;
+
+        let mut cmd = Command::new("git");
+        cmd.args(["checkout", "-b", &name]);
+
+        state.run_cmd(term, &[], cmd)?;
+    }
+    Ok(())
+}
+
+pub(crate) struct RenameBranch;
+impl OpTrait for RenameBranch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_picker_prompt(
+            "Rename branch",
+            rename_branch_prompt,
+            selected_or_current_branch,
+            picker::local_branches,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Rename branch".into()
+    }
+}
+
+fn selected_or_current_branch(state: &State) -> Option<String> {
+    selected_branch(state).or_else(|| {
+        let head = state.repo.head().ok()?;
+        if head.is_branch() {
+            head.shorthand().map(String::from)
+        } else {
+            None
+        }
+    })
+}
+
+fn rename_branch_prompt(state: &mut State, _term: &mut Term, branch: &str) -> Res<()> {
+    if branch.is_empty() {
+        state.close_menu();
+        return Err("Branch name required".into());
+    }
+
+    let branch = branch.to_string();
+    state.close_menu();
+    set_prompt(
+        state,
+        "Rename to",
+        Box::new(move |state, term, new_name| rename_branch(state, term, &branch, new_name)),
+        Box::new(|_| None),
+        true,
+    );
+    Ok(())
+}
+
+fn rename_branch(state: &mut State, term: &mut Term, branch: &str, new_name: &str) -> Res<()> {
+    if new_name.is_empty() {
+        return Err("Branch name required".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "-m", branch, new_name]);
+
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct DeleteBranch;
+impl OpTrait for DeleteBranch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_picker_prompt(
+            "Delete branch",
+            delete_branch,
+            selected_branch,
+            picker::local_branches,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Delete branch".into()
+    }
+}
+
+fn delete_branch(state: &mut State, term: &mut Term, branch: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "-d", branch]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct ForceDeleteBranch;
+impl OpTrait for ForceDeleteBranch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_picker_prompt(
+            "Force delete branch",
+            force_delete_branch,
+            selected_branch,
+            picker::local_branches,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Delete branch (force)".into()
+    }
+}
+
+fn force_delete_branch(state: &mut State, term: &mut Term, branch: &str) -> Res<()> {
+    let branch = branch.to_string();
+    let delete: Action = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["branch", "-D", &branch]);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    });
+
+    let mut action = create_confirm_prompt(delete, "Really force delete branch?", |config| {
+        config.general.confirm_force_delete_branch.enabled
+    });
+    Rc::get_mut(&mut action).unwrap()(state, term)
+}
+
+pub(crate) struct SetUpstream;
+impl OpTrait for SetUpstream {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_picker_prompt(
+            "Set upstream to",
+            set_upstream,
+            selected_branch,
+            picker::branches,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Set upstream".into()
+    }
+}
+
+fn set_upstream(state: &mut State, term: &mut Term, upstream: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "--set-upstream-to", upstream]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
@@ -0,0 +1,103 @@
+use std::{process::Command, rc::Rc};
+
+use crate::{git, items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+
+use super::{create_range_prompt, Action, OpTrait};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("-x", "Reference cherry in commit message", false),
+        Arg::new_flag("--ff", "Attempt fast-forward", false),
+        Arg::new_flag("--edit", "Edit commit message", false),
+        Arg::new_flag("--signoff", "Add Signed-off-by lines", false),
+    ]
+}
+
+pub(crate) struct CherryPick;
+impl OpTrait for CherryPick {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_range_prompt("Cherry-pick", cherry_pick, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Pick commit(s)".into()
+    }
+}
+
+fn cherry_pick(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["cherry-pick"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.args(input.split_whitespace());
+
+    state.screen_mut().clear_mark();
+    state.close_menu();
+    state.run_cmd_interactive(term, cmd)
+}
+
+pub(crate) struct CherryPickContinue;
+impl OpTrait for CherryPickContinue {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["cherry-pick", "--continue"]);
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_cherry_picking(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Continue".into()
+    }
+}
+
+pub(crate) struct CherryPickSkip;
+impl OpTrait for CherryPickSkip {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["cherry-pick", "--skip"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_cherry_picking(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Skip".into()
+    }
+}
+
+pub(crate) struct CherryPickAbort;
+impl OpTrait for CherryPickAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["cherry-pick", "--abort"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_cherry_picking(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Abort".into()
+    }
+}
+
+fn is_cherry_picking(state: &State) -> bool {
+    git::cherry_pick_status(&state.repo).is_ok_and(|status| status.is_some())
+}
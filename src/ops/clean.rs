@@ -0,0 +1,93 @@
+use super::{create_confirm_prompt, Action, OpTrait};
+use crate::{
+    items::TargetData,
+    menu::{arg::Arg, Menu},
+    screen,
+    state::State,
+    term::Term,
+};
+use std::{process::Command, rc::Rc};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("-d", "Remove untracked directories", false),
+        Arg::new_flag("-x", "Remove ignored files too", false),
+    ]
+}
+
+pub(crate) struct Clean;
+impl OpTrait for Clean {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let menu = state.pending_menu.as_ref().expect("Clean menu is open");
+            let is_active = |name| menu.args.get(name).is_some_and(|arg| arg.is_active());
+            let (directories, ignored) = (is_active("-d"), is_active("-x"));
+            let menu_args = menu.active_args();
+
+            let screen = screen::clean::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+                directories,
+                ignored,
+            )?
+            .with_menu_args(Menu::Clean, menu_args);
+
+            state.screens.push(screen);
+            state.close_menu();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Clean (preview first)".into()
+    }
+}
+
+/// Removes what the clean screen lists, with the args it was opened with.
+pub(crate) struct CleanConfirm;
+impl OpTrait for CleanConfirm {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let clean: Action = Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["clean", "--force"]);
+            cmd.args(
+                state
+                    .screen()
+                    .menu_args(Menu::Clean)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|(name, _)| *name),
+            );
+
+            state.pop_screen();
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        });
+
+        Some(create_confirm_prompt(
+            clean,
+            "Really delete these files?",
+            |config| config.general.confirm_clean.enabled,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Delete files".into()
+    }
+}
+
+pub(crate) struct CleanAbort;
+impl OpTrait for CleanAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.pop_screen();
+            state.close_menu();
+            state.update_screen_if_changed()
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Abort".into()
+    }
+}
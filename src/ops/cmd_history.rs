@@ -0,0 +1,29 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term};
+use std::rc::Rc;
+
+pub(crate) struct ToggleCmdHistory;
+impl OpTrait for ToggleCmdHistory {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+
+            if state.screen().name() == Some(screen::cmd_history::NAME) {
+                state.pop_screen();
+                return state.update_screen_if_changed();
+            }
+
+            let size = state.screen().size;
+            state.screens.push(screen::cmd_history::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.cmd_history),
+                size,
+            )?);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Command history".into()
+    }
+}
@@ -1,7 +1,18 @@
-use super::{Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term};
+use super::{create_rev_prompt, Action, OpTrait};
+use crate::{
+    git::log_filter,
+    items::TargetData,
+    menu::arg::{self, Arg},
+    picker,
+    screen::commit_msg::{self, CommitMsg},
+    state::State,
+    term::Term,
+    Res,
+};
+use chrono::{Local, TimeZone};
 use std::{
     ffi::{OsStr, OsString},
+    iter,
     process::Command,
     rc::Rc,
 };
@@ -17,8 +28,11 @@ pub(crate) fn init_args() -> Vec<Arg> {
             "Claim authorship and reset author date",
             false,
         ),
-        // TODO -A Override the author (--author=)
+        Arg::new_arg("--author", "Override the author", None, arg::any_string)
+            .with_candidates(picker::authors),
+        Arg::new_arg("--date", "Override the date", None, arg::date),
         Arg::new_flag("--signoff", "Add Signed-off-by line", false),
+        Arg::new_flag("--gpg-sign", "Sign using gpg", false),
         // TODO -C Reuse commit message (--reuse-message=)
     ]
 }
@@ -27,9 +41,13 @@ pub(crate) struct Commit;
 impl OpTrait for Commit {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
-            let mut cmd = Command::new("git");
-            cmd.args(["commit"]);
-            cmd.args(state.pending_menu.as_ref().unwrap().args());
+            let args = state.pending_menu.as_ref().unwrap().args();
+            if state.config.general.builtin_commit_editor.enabled {
+                let template = commit_msg::template(&state.config, &state.repo);
+                return open_commit_msg(state, args, &template);
+            }
+
+            let cmd = commit_cmd(&[], &args);
 
             state.close_menu();
             state.run_cmd_interactive(term, cmd)?;
@@ -46,41 +64,204 @@ pub(crate) struct CommitAmend;
 impl OpTrait for CommitAmend {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
-            let mut cmd = Command::new("git");
-            cmd.args(["commit", "--amend"]);
-            cmd.args(state.pending_menu.as_ref().unwrap().args());
+            amend(state, term, &[])
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "amend".into()
+    }
+}
+
+pub(crate) struct CommitExtend;
+impl OpTrait for CommitExtend {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let args = state.pending_menu.as_ref().unwrap().args();
+
+            let cmd = commit_cmd(&["--amend", "--no-edit"], &args);
 
             state.close_menu();
-            state.run_cmd_interactive(term, cmd)?;
+            if is_signed(&args) {
+                state.run_cmd_interactive(term, cmd)
+            } else {
+                state.run_cmd(term, &[], cmd)
+            }
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "extend".into()
+    }
+}
+
+/// Amends only the message, leaving out any staged changes.
+pub(crate) struct CommitReword;
+impl OpTrait for CommitReword {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            amend(state, term, &["--only"])
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "reword".into()
+    }
+}
+
+fn amend(state: &mut State, term: &mut Term, extra_args: &[&str]) -> Res<()> {
+    let args = state.pending_menu.as_ref().unwrap().args();
+    if state.config.general.builtin_commit_editor.enabled {
+        let message = state
+            .repo
+            .head()?
+            .peel_to_commit()?
+            .message()
+            .unwrap_or("")
+            .to_string();
+        let args = iter::once("--amend")
+            .chain(extra_args.iter().copied())
+            .map(OsString::from)
+            .chain(args)
+            .collect();
+        return open_commit_msg(state, args, &message);
+    }
+
+    let flags = iter::once("--amend")
+        .chain(extra_args.iter().copied())
+        .collect::<Vec<_>>();
+    let cmd = commit_cmd(&flags, &args);
+
+    state.close_menu();
+    state.run_cmd_interactive(term, cmd)?;
+    Ok(())
+}
+
+/// Writes the message in the built-in editor, above the changes to be committed.
+fn open_commit_msg(state: &mut State, args: Vec<OsString>, message: &str) -> Res<()> {
+    let verbose = args.iter().any(|arg| arg == "--verbose");
+    let size = state.screen().size;
+
+    state.screens.push(commit_msg::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        size,
+        verbose,
+    )?);
+    let history = commit_msg::load_history(&state.repo);
+    state.commit_msg = Some(CommitMsg::new(args, message, history));
+    state.close_menu();
+    Ok(())
+}
+
+pub(crate) struct CommitMsgSubmit;
+impl OpTrait for CommitMsgSubmit {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let Some(commit_msg) = state.commit_msg.take() else {
+                return Ok(());
+            };
+
+            state.pop_screen();
+            state.close_menu();
+
+            let message = commit_msg.message();
+            commit_msg::save_to_history(&state.repo, &message)?;
+
+            let mut cmd = commit_cmd(&["--cleanup=strip"], &commit_msg.args);
+
+            if is_signed(&commit_msg.args) {
+                // The message is passed in a file instead, as stdin is left to gpg
+                let path = commit_msg::write_message_file(&state.repo, &message)?;
+                cmd.arg("--file").arg(path);
+                return state.run_cmd_interactive(term, cmd);
+            }
+
+            cmd.arg("--file=-");
+            state.run_cmd(term, message.as_bytes(), cmd)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Commit".into()
+    }
+}
+
+pub(crate) struct CommitMsgAbort;
+impl OpTrait for CommitMsgAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            if let Some(commit_msg) = state.commit_msg.take() {
+                commit_msg::save_to_history(&state.repo, &commit_msg.message())?;
+            }
+
+            state.pop_screen();
+            state.close_menu();
+            state.screen_mut().update()
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Abort".into()
+    }
+}
+
+pub(crate) struct CommitMsgPrevious;
+impl OpTrait for CommitMsgPrevious {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            if let Some(commit_msg) = &mut state.commit_msg {
+                commit_msg.previous_message();
+            }
             Ok(())
         }))
     }
 
     fn display(&self, _state: &State) -> String {
-        "amend".into()
+        "Previous message".into()
     }
 }
 
-pub(crate) struct CommitFixup;
-impl OpTrait for CommitFixup {
-    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
-        match target {
-            Some(TargetData::Commit(r)) => {
-                let rev = OsString::from(r);
+pub(crate) struct CommitMsgNext;
+impl OpTrait for CommitMsgNext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            if let Some(commit_msg) = &mut state.commit_msg {
+                commit_msg.next_message();
+            }
+            Ok(())
+        }))
+    }
 
-                Some(Rc::new(move |state: &mut State, term: &mut Term| {
-                    let args = state.pending_menu.as_ref().unwrap().args();
+    fn display(&self, _state: &State) -> String {
+        "Next message".into()
+    }
+}
 
-                    state.close_menu();
-                    state.run_cmd_interactive(term, commit_fixup_cmd(&args, &rev))
-                }))
+pub(crate) struct CommitMsgComplete;
+impl OpTrait for CommitMsgComplete {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            if let Some(commit_msg) = &mut state.commit_msg {
+                commit_msg.complete();
             }
-            _ => None,
-        }
+            Ok(())
+        }))
     }
 
-    fn is_target_op(&self) -> bool {
-        true
+    fn display(&self, _state: &State) -> String {
+        "Complete commit type".into()
+    }
+}
+
+pub(crate) struct CommitFixup;
+impl OpTrait for CommitFixup {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Fixup commit",
+            |state, term, rev| fixup(state, term, rev, "--fixup", false),
+            true,
+        ))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -88,41 +269,110 @@ impl OpTrait for CommitFixup {
     }
 }
 
-fn commit_fixup_cmd(args: &[OsString], rev: &OsStr) -> Command {
-    let mut cmd = Command::new("git");
-    cmd.args(["commit", "--fixup"]);
-    cmd.arg(rev);
-    cmd.args(args);
-    cmd
+pub(crate) struct CommitSquash;
+impl OpTrait for CommitSquash {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Squash commit",
+            |state, term, rev| fixup(state, term, rev, "--squash", false),
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "squash".into()
+    }
 }
 
 pub(crate) struct CommitInstantFixup;
 impl OpTrait for CommitInstantFixup {
-    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
-        match target {
-            Some(TargetData::Commit(r)) => {
-                let rev = OsString::from(r);
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Instant fixup commit",
+            |state, term, rev| fixup(state, term, rev, "--fixup", true),
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "instant fixup".into()
+    }
+}
 
-                Some(Rc::new(move |state: &mut State, term: &mut Term| {
-                    let args = state.pending_menu.as_ref().unwrap().args();
+pub(crate) struct CommitInstantSquash;
+impl OpTrait for CommitInstantSquash {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Instant squash commit",
+            |state, term, rev| fixup(state, term, rev, "--squash", true),
+            true,
+        ))
+    }
 
-                    state.close_menu();
+    fn display(&self, _state: &State) -> String {
+        "instant squash".into()
+    }
+}
 
-                    state.run_cmd(term, &[], commit_fixup_cmd(&args, &rev))?;
-                    state.run_cmd(term, &[], rebase_autosquash_cmd(&rev))
-                }))
-            }
-            _ => None,
-        }
+/// Commits the staged changes as a `--fixup` or `--squash` of `rev`, `instant` squashes them
+/// into it right away with an autosquash rebase.
+fn fixup(state: &mut State, term: &mut Term, rev: &str, kind: &str, instant: bool) -> Res<()> {
+    let args = state.pending_menu.as_ref().unwrap().args();
+    state.close_menu();
+
+    // Resolved up front, a relative rev like HEAD~1 would point elsewhere after committing
+    let target = state.repo.revparse_single(rev)?.peel_to_commit()?.id();
+    let target = target.to_string();
+    let cmd = commit_cmd(&[kind, &target], &args);
+
+    let squash = kind == "--squash";
+    if instant && !squash && !is_signed(&args) {
+        state.run_cmd(term, &[], cmd)?;
+    } else {
+        // Git opens an editor for the message of a squash, gpg may ask for a passphrase
+        state.run_cmd_interactive(term, cmd)?;
     }
 
-    fn is_target_op(&self) -> bool {
-        true
+    if !instant {
+        return Ok(());
     }
 
-    fn display(&self, _state: &State) -> String {
-        "instant fixup".into()
+    if squash {
+        // The squashed messages are combined in an editor
+        state.run_cmd_interactive(term, rebase_autosquash_cmd(target.as_ref()))
+    } else {
+        state.run_cmd(term, &[], rebase_autosquash_cmd(target.as_ref()))
+    }
+}
+
+/// `git commit` with `flags` and the args of the menu. As `--date` only sets the author date,
+/// the committer date is set to the same.
+fn commit_cmd(flags: &[&str], args: &[OsString]) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.arg("commit");
+    cmd.args(flags);
+    cmd.args(args);
+
+    let date = args
+        .iter()
+        .find_map(|arg| arg.to_str()?.strip_prefix("--date="));
+    if let Some(date) = date.and_then(committer_date) {
+        cmd.env("GIT_COMMITTER_DATE", date);
     }
+
+    cmd
+}
+
+/// In git's own format, as it doesn't take relative dates from the environment.
+fn committer_date(date: &str) -> Option<String> {
+    let timestamp = log_filter::parse_date(date, Local::now().timestamp()).ok()?;
+    let date_time = Local.timestamp_opt(timestamp, 0).single()?;
+    Some(format!("@{} {}", timestamp, date_time.format("%z")))
+}
+
+/// gpg may need the terminal to ask for a passphrase, so signing commits are run interactively.
+fn is_signed(args: &[OsString]) -> bool {
+    args.iter().any(|arg| arg == "--gpg-sign")
 }
 
 fn rebase_autosquash_cmd(rev: &OsStr) -> Command {
@@ -0,0 +1,203 @@
+use super::{Action, OpTrait};
+use crate::{config::ClipboardBackend, git, items::TargetData, state::State, term::Term, Res};
+use std::{
+    env,
+    io::Write,
+    process::{Command, Stdio},
+    rc::Rc,
+};
+
+pub(crate) struct CopyHash;
+impl OpTrait for CopyHash {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Stash { commit, .. }) => Some(copy("Commit hash", commit.clone())),
+            Some(target) => {
+                let rev = target.commitish()?.to_string();
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    let hash = state.repo.revparse_single(&rev)?.peel_to_commit()?.id();
+                    copy_text(state, term, "Commit hash", &hash.to_string())
+                }))
+            }
+            None => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Copy hash".into()
+    }
+}
+
+pub(crate) struct CopyBranchName;
+impl OpTrait for CopyBranchName {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Branch(branch)) => Some(copy("Branch name", branch.clone())),
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Copy branch name".into()
+    }
+}
+
+pub(crate) struct CopyPath;
+impl OpTrait for CopyPath {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let path = match target? {
+            TargetData::File(path)
+            | TargetData::Submodule(path)
+            | TargetData::Conflict { file: path, .. }
+            | TargetData::Rename { new_file: path, .. } => path,
+            TargetData::Delta(delta) => &delta.new_file,
+            TargetData::ModeChange(mode_change) => &mode_change.file,
+            TargetData::Hunk(hunk) | TargetData::HunkLine(hunk, _) => &hunk.new_file,
+            _ => return None,
+        };
+
+        Some(copy("Path", path.to_string_lossy().into_owned()))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Copy path".into()
+    }
+}
+
+pub(crate) struct CopyHunk;
+impl OpTrait for CopyHunk {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Hunk(hunk)) | Some(TargetData::HunkLine(hunk, _)) => {
+                Some(copy("Hunk", format!("{}\n{}\n", hunk.header, hunk.content)))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Copy hunk".into()
+    }
+}
+
+pub(crate) struct CopyMessage;
+impl OpTrait for CopyMessage {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let r = target?.commitish()?.to_string();
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let message = state
+                .repo
+                .revparse_single(&r)?
+                .peel_to_commit()?
+                .message()
+                .ok_or("Commit message isn't UTF-8")?
+                .to_string();
+
+            copy_text(state, term, "Commit message", &message)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Copy message".into()
+    }
+}
+
+pub(crate) struct CopyDescribe;
+impl OpTrait for CopyDescribe {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let r = target?.commitish()?.to_string();
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let describe = git::describe(&state.repo, &r).ok_or("No tag to describe commit by")?;
+            copy_text(state, term, "Description", &describe)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Copy description (git describe --tags)".into()
+    }
+}
+
+fn copy(what: &'static str, text: String) -> Action {
+    Rc::new(move |state, term| copy_text(state, term, what, &text))
+}
+
+fn copy_text(state: &mut State, term: &mut Term, what: &str, text: &str) -> Res<()> {
+    state.close_menu();
+    set_clipboard(state, term, text)?;
+    state.display_info(format!("{} copied to clipboard", what));
+    Ok(())
+}
+
+/// Over SSH, the system clipboard would be the remote machine's. There, and wherever there isn't one,
+/// `auto` hands the text to the terminal with OSC 52 instead.
+fn set_clipboard(state: &mut State, term: &mut Term, text: &str) -> Res<()> {
+    match state.config.general.clipboard.backend {
+        ClipboardBackend::Auto => {
+            let remote =
+                env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some();
+
+            if let (false, Some(clipboard)) = (remote, &mut state.clipboard) {
+                if clipboard.set_text(text).is_ok() {
+                    return Ok(());
+                }
+            }
+
+            term.backend_mut().set_clipboard(text)?;
+        }
+        ClipboardBackend::System => match &mut state.clipboard {
+            Some(clipboard) => clipboard.set_text(text)?,
+            None => return Err("Clipboard not available".into()),
+        },
+        ClipboardBackend::Osc52 => term.backend_mut().set_clipboard(text)?,
+        ClipboardBackend::Command => {
+            run_clipboard_command(&state.config.general.clipboard.command, text)?
+        }
+    }
+
+    Ok(())
+}
+
+fn run_clipboard_command(command: &[String], text: &str) -> Res<()> {
+    let [program, args @ ..] = command else {
+        return Err("No clipboard.command configured".into());
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("Couldn't run {}: {}", program, err))?;
+
+    child.stdin.take().unwrap().write_all(text.as_bytes())?;
+    if !child.wait()?.success() {
+        return Err(format!("{} failed", program).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use super::{create_prompt_with_default, marked_commits, selected_rev, Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term, Res};
+use std::{path::PathBuf, process::Command, rc::Rc};
+
+pub(crate) struct DiffRange;
+impl OpTrait for DiffRange {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Diff range",
+            diff_range,
+            selected_range,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Diff range".into()
+    }
+}
+
+/// Two commits marked in a log are diffed from the older to the newer one.
+fn selected_range(state: &State) -> Option<String> {
+    match marked_commits(state) {
+        Some((oldest, newest)) => Some(format!("{}..{}", oldest, newest)),
+        None => selected_rev(state),
+    }
+}
+
+fn diff_range(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    state.close_menu();
+    let screen = screen::diff::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        term.size()?,
+        state.enable_async_cmds,
+        Some(input.trim().to_string()),
+        vec![],
+    )?;
+
+    state.screen_mut().clear_mark();
+    state.screens.push(screen);
+    Ok(())
+}
+
+/// How the work tree differs from a file as of a revision.
+pub(crate) struct DiffWorktree;
+impl OpTrait for DiffWorktree {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Blob { rev, path }) = target else {
+            return None;
+        };
+
+        let (rev, path) = (rev.clone(), path.clone());
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            state.close_menu();
+            let screen = screen::diff::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+                state.enable_async_cmds,
+                Some(rev.clone()),
+                vec![path.clone()],
+            )?;
+
+            state.screens.push(screen);
+            Ok(())
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Diff file against work tree".into()
+    }
+}
+
+pub(crate) struct Difftool;
+impl OpTrait for Difftool {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let target = DifftoolTarget::of(target?)?;
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let difftool = &state.config.general.difftool;
+            let mut cmd = Command::new("git");
+            cmd.args(["difftool", "--no-prompt"]);
+
+            if difftool.tool.is_empty() {
+                cmd.arg("--gui");
+            } else {
+                cmd.arg(format!("--tool={}", difftool.tool));
+            }
+
+            if difftool.dir_diff {
+                cmd.arg("--dir-diff");
+            }
+
+            match &target {
+                DifftoolTarget::Files(files) => {
+                    if in_staged_section(state) {
+                        cmd.arg("--cached");
+                    }
+
+                    cmd.arg("--");
+                    cmd.args(files);
+                }
+                // `<rev>^!` is the commit against its first parent
+                DifftoolTarget::Commit(commit) => {
+                    cmd.arg(format!("{}^!", commit));
+                }
+            }
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Open difftool".into()
+    }
+}
+
+/// Shows the diff of the file or commit at point in an external pager, like `delta`.
+pub(crate) struct DiffPager;
+impl OpTrait for DiffPager {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let target = DifftoolTarget::of(target?)?;
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let pager = &state.config.general.difftool.pager;
+            let mut cmd = Command::new("git");
+            if !pager.is_empty() {
+                cmd.args(["-c", &format!("core.pager={}", pager)]);
+            }
+            cmd.arg("--paginate");
+
+            match &target {
+                DifftoolTarget::Files(files) => {
+                    cmd.arg("diff");
+                    if in_staged_section(state) {
+                        cmd.arg("--cached");
+                    }
+
+                    cmd.arg("--");
+                    cmd.args(files);
+                }
+                DifftoolTarget::Commit(commit) => {
+                    cmd.args(["show", commit]);
+                }
+            }
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Open in pager".into()
+    }
+}
+
+enum DifftoolTarget {
+    Files(Vec<PathBuf>),
+    Commit(String),
+}
+
+impl DifftoolTarget {
+    fn of(target: &TargetData) -> Option<Self> {
+        Some(match target {
+            TargetData::Delta(delta) => Self::Files(vec![delta.new_file.clone()]),
+            TargetData::Rename { old_file, new_file } => {
+                Self::Files(vec![old_file.clone(), new_file.clone()])
+            }
+            TargetData::Stash { commit, .. } => Self::Commit(commit.clone()),
+            target => Self::Commit(target.commitish()?.to_string()),
+        })
+    }
+}
+
+/// Files listed under "Staged changes" are compared from HEAD to the index, others from the index to the work tree.
+fn in_staged_section(state: &State) -> bool {
+    let screen = state.screen();
+    screen.items()[..=screen.selected_item_index()]
+        .iter()
+        .rev()
+        .find(|item| item.depth == 0)
+        .is_some_and(|section| matches!(section.target_data, Some(TargetData::AllStaged)))
+}
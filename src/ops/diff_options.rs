@@ -0,0 +1,82 @@
+use crate::{
+    config::DiffSettings,
+    menu::{
+        arg::{positive_number, Arg},
+        Menu, PendingMenu,
+    },
+    state::State,
+    Res,
+};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("-w", "Ignore whitespace", false),
+        Arg::new_flag("--ignore-blank-lines", "Ignore blank lines", false),
+        Arg::new_flag("--patience", "Patience algorithm", true),
+        Arg::new_arg("-U", "Context lines", None, positive_number),
+        Arg::new_flag("--side-by-side", "Side by side", false),
+    ]
+}
+
+/// The menu reflects the settings in use, rather than starting over from the defaults.
+pub(crate) fn active_args(settings: &DiffSettings) -> Vec<(&'static str, Option<String>)> {
+    [
+        ("-w", settings.ignore_whitespace),
+        ("--ignore-blank-lines", settings.ignore_blank_lines),
+        ("--patience", settings.patience),
+        ("--side-by-side", settings.side_by_side),
+    ]
+    .into_iter()
+    .filter(|(_, active)| *active)
+    .map(|(arg, _)| (arg, None))
+    .chain(
+        settings
+            .context_lines
+            .map(|lines| ("-U", Some(lines.to_string()))),
+    )
+    .collect()
+}
+
+/// Puts the args of an open diff options menu in use, redrawing the screen with them.
+pub(crate) fn apply(state: &mut State) -> Res<()> {
+    let Some(menu) = state
+        .pending_menu
+        .as_ref()
+        .filter(|menu| menu.menu == Menu::DiffOptions)
+    else {
+        return Ok(());
+    };
+
+    let settings = settings_from(menu);
+    {
+        let mut current = state
+            .config
+            .diff_settings
+            .write()
+            .expect("Diff settings lock poisoned");
+
+        if *current == settings {
+            return Ok(());
+        }
+
+        *current = settings;
+    }
+
+    state.screen_mut().update()
+}
+
+fn settings_from(menu: &PendingMenu) -> DiffSettings {
+    let is_active = |name| menu.args.get(name).is_some_and(|arg| arg.is_active());
+
+    DiffSettings {
+        ignore_whitespace: is_active("-w"),
+        ignore_blank_lines: is_active("--ignore-blank-lines"),
+        patience: is_active("--patience"),
+        context_lines: menu
+            .args
+            .get("-U")
+            .and_then(|arg| arg.value_as::<u32>())
+            .copied(),
+        side_by_side: is_active("--side-by-side"),
+    }
+}
@@ -1,23 +1,34 @@
 use super::{Action, OpTrait};
-use crate::{git::diff::Hunk, items::TargetData, state::State};
-use std::{path::PathBuf, process::Command, rc::Rc};
+use crate::{
+    git::{
+        diff::{Hunk, ModeChange, PatchMode},
+        trash,
+    },
+    items::TargetData,
+    state::State,
+    term::Term,
+};
+use std::{fs, ops::Range, path::PathBuf, process::Command, rc::Rc, sync::Arc};
 
 pub(crate) struct Discard;
 impl OpTrait for Discard {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
-        let action = match target.cloned() {
-            Some(TargetData::Branch(branch)) => discard_branch(branch),
-            Some(TargetData::File(file)) => clean_file(file),
-            Some(TargetData::Delta(d)) => match d.status {
-                git2::Delta::Added => remove_file(d.new_file),
-                git2::Delta::Renamed => rename_file(d.new_file, d.old_file),
-                _ => checkout_file(d.old_file),
-            },
-            Some(TargetData::Hunk(h)) => discard_unstaged_patch(h),
-            _ => return None,
+        let action = discard(target.cloned())?;
+        Some(confirm(action, target.into_iter().all(is_untracked)))
+    }
+
+    fn get_region_action(&self, targets: &[TargetData]) -> Option<Action> {
+        let action = match super::hunk_line_range(targets) {
+            Some((h, lines)) => discard_unstaged_lines(h, lines),
+            None => super::chain_actions(
+                targets
+                    .iter()
+                    .filter_map(|target| discard(Some(target.clone())))
+                    .collect(),
+            )?,
         };
 
-        Some(super::create_y_n_prompt(action, "Really discard?"))
+        Some(confirm(action, targets.iter().all(is_untracked)))
     }
 
     fn is_target_op(&self) -> bool {
@@ -29,6 +40,89 @@ impl OpTrait for Discard {
     }
 }
 
+/// Untracked files are removed by `git clean`, which is confirmed separately.
+fn is_untracked(target: &TargetData) -> bool {
+    matches!(target, TargetData::File(_))
+}
+
+fn confirm(action: Action, clean: bool) -> Action {
+    if clean {
+        super::create_confirm_prompt(action, "Really delete untracked files?", |config| {
+            config.general.confirm_clean.enabled
+        })
+    } else {
+        super::create_confirm_prompt(action, "Really discard?", |config| {
+            config.general.confirm_discard.enabled
+        })
+    }
+}
+
+pub(crate) struct UndoDiscard;
+impl OpTrait for UndoDiscard {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            let restored = trash::restore_last(&state.repo)?;
+
+            let files = restored
+                .iter()
+                .map(|path| path.to_string_lossy())
+                .collect::<Vec<_>>();
+            state.display_info(format!("Restored {}", files.join(", ")));
+
+            state.screen_mut().update()
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        !trash::is_empty(&state.repo)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Undo discard".into()
+    }
+}
+
+/// Discarded work tree changes are put in the trash first, to be brought back with `UndoDiscard`.
+fn discard(target: Option<TargetData>) -> Option<Action> {
+    let mut paths = match &target {
+        Some(TargetData::File(file)) => vec![file.clone()],
+        Some(TargetData::Delta(d)) => vec![d.new_file.clone(), d.old_file.clone()],
+        Some(TargetData::Rename { old_file, new_file }) => vec![new_file.clone(), old_file.clone()],
+        Some(TargetData::Hunk(h)) | Some(TargetData::HunkLine(h, _)) => vec![h.new_file.clone()],
+        _ => vec![],
+    };
+    paths.dedup();
+
+    let mut action = discard_without_trash(target)?;
+    if paths.is_empty() {
+        return Some(action);
+    }
+
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        trash::save(&state.repo, &paths)?;
+        Rc::get_mut(&mut action).unwrap()(state, term)
+    }))
+}
+
+fn discard_without_trash(target: Option<TargetData>) -> Option<Action> {
+    Some(match target {
+        Some(TargetData::Branch(branch)) => discard_branch(branch),
+        Some(TargetData::Tag(tag)) => discard_tag(tag),
+        Some(TargetData::File(file)) => clean_file(file),
+        Some(TargetData::Delta(d)) => match d.status {
+            git2::Delta::Added => remove_file(d.new_file),
+            _ => checkout_file(d.old_file),
+        },
+        Some(TargetData::Rename { old_file, new_file }) => rename_file(new_file, old_file),
+        Some(TargetData::Hunk(h)) => discard_unstaged_patch(h),
+        Some(TargetData::ModeChange(m)) => discard_unstaged_mode_change(m),
+        Some(TargetData::HunkLine(h, i)) => discard_unstaged_lines(h, i..(i + 1)),
+        Some(TargetData::Stash { id, commit: _ }) => drop_stash(id),
+        _ => return None,
+    })
+}
+
 fn discard_branch(branch: String) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
@@ -40,6 +134,28 @@ fn discard_branch(branch: String) -> Action {
     })
 }
 
+fn discard_tag(tag: String) -> Action {
+    Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["tag", "--delete"]);
+        cmd.arg(&tag);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
+
+fn drop_stash(id: usize) -> Action {
+    Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["stash", "drop"]);
+        cmd.arg(id.to_string());
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
+
 fn clean_file(file: PathBuf) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
@@ -51,14 +167,26 @@ fn clean_file(file: PathBuf) -> Action {
     })
 }
 
+/// Moves a file back. If it was moved in the work tree only, the new path is
+/// removed and the old one checked out from the index instead.
 fn rename_file(src: PathBuf, dest: PathBuf) -> Action {
     Rc::new(move |state, term| {
+        let staged = state.repo.index()?.get_path(&src, 0).is_some();
+        state.close_menu();
+
+        if !staged {
+            fs::remove_file(state.repo.workdir().ok_or("No workdir")?.join(&src))?;
+
+            let mut cmd = Command::new("git");
+            cmd.args(["checkout", "--"]);
+            cmd.arg(&dest);
+            return state.run_cmd(term, &[], cmd);
+        }
+
         let mut cmd = Command::new("git");
         cmd.args(["mv", "--force"]);
         cmd.arg(&src);
         cmd.arg(&dest);
-
-        state.close_menu();
         state.run_cmd(term, &[], cmd)
     })
 }
@@ -85,12 +213,34 @@ fn checkout_file(file: PathBuf) -> Action {
     })
 }
 
-fn discard_unstaged_patch(h: Rc<Hunk>) -> Action {
+fn discard_unstaged_patch(h: Arc<Hunk>) -> Action {
+    Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["apply", "--reverse"]);
+
+        state.close_menu();
+        state.run_cmd(term, &h.format_patch(), cmd)
+    })
+}
+
+fn discard_unstaged_mode_change(m: Arc<ModeChange>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--reverse"]);
 
         state.close_menu();
-        state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
+        state.run_cmd(term, &m.format_patch(), cmd)
+    })
+}
+
+fn discard_unstaged_lines(h: Arc<Hunk>, lines: Range<usize>) -> Action {
+    Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["apply", "--reverse", "--recount"]);
+
+        let input = h.format_line_patch(lines.clone(), PatchMode::Reverse);
+
+        state.close_menu();
+        state.run_cmd(term, &input, cmd)
     })
 }
@@ -1,13 +1,30 @@
-use super::{set_prompt, Action, OpTrait};
+use super::{set_prompt, Action, Op, OpTrait};
 use crate::{
+    config,
     items::TargetData,
-    menu::PendingMenu,
-    screen::NavMode,
+    menu::{Menu, PendingMenu},
+    picker::{self, Candidate, CandidateKind, Picker},
+    screen::{self, help::HelpEntry, NavMode},
     state::{root_menu, State},
-    term::Term,
+    term::{self, Term},
+    Res,
 };
 use std::rc::Rc;
 
+pub(crate) struct Suspend;
+impl OpTrait for Suspend {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            let _paused_input = state.input.pause();
+            term::suspend(term)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Suspend".into()
+    }
+}
+
 pub(crate) struct Quit;
 impl OpTrait for Quit {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
@@ -24,17 +41,15 @@ impl OpTrait for Quit {
                         Ok(())
                     });
 
-                    let mut action = if state.config.general.confirm_quit.enabled {
-                        super::create_y_n_prompt(quit, "Really quit?")
-                    } else {
-                        quit
-                    };
+                    let mut action = super::create_confirm_prompt(quit, "Really quit?", |config| {
+                        config.general.confirm_quit.enabled
+                    });
 
                     Rc::get_mut(&mut action).unwrap()(state, term)?;
                 } else {
-                    state.screens.pop();
-                    if let Some(screen) = state.screens.last_mut() {
-                        screen.update()?;
+                    state.pop_screen();
+                    if !state.screens.is_empty() {
+                        state.update_screen_if_changed()?;
                     }
                 }
             } else {
@@ -56,7 +71,19 @@ impl OpTrait for OpenMenu {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         let submenu = self.0;
         Some(Rc::new(move |state, _term| {
-            state.pending_menu = Some(PendingMenu::init(submenu));
+            let mut pending_menu = PendingMenu::init(submenu);
+            if let Some(args) = state.screen().menu_args(submenu) {
+                pending_menu.restore_args(args)?;
+            } else if submenu == Menu::DiffOptions {
+                let settings = state
+                    .config
+                    .diff_settings
+                    .read()
+                    .expect("Diff settings lock poisoned");
+                pending_menu.restore_args(&super::diff_options::active_args(&settings))?;
+            }
+
+            state.pending_menu = Some(pending_menu);
             Ok(())
         }))
     }
@@ -66,6 +93,73 @@ impl OpTrait for OpenMenu {
     }
 }
 
+/// Opens a screen listing the bindings of every menu, searchable like any other.
+pub(crate) struct ShowHelp;
+impl OpTrait for ShowHelp {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            if state.screen().name() == Some(screen::help::NAME) {
+                return Ok(());
+            }
+
+            let entries = help_entries(state)?;
+            let size = state.screen().size;
+            state.screens.push(screen::help::create(
+                Rc::clone(&state.config),
+                Rc::new(entries),
+                size,
+            )?);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "All keybindings".into()
+    }
+}
+
+fn help_entries(state: &State) -> Res<Vec<HelpEntry>> {
+    let defaults = config::default_bindings()?;
+
+    Ok(state
+        .config
+        .bindings
+        .iter()
+        .flat_map(|(menu, ops)| {
+            let args = PendingMenu::init(*menu).args;
+            let defaults = &defaults;
+
+            ops.iter().filter_map(move |(op, keys)| {
+                let default_keys = defaults
+                    .get(menu)
+                    .and_then(|ops| ops.get(op))
+                    .cloned()
+                    .unwrap_or_default();
+                if keys.is_empty() && default_keys.is_empty() {
+                    return None;
+                }
+
+                let description = match op {
+                    Op::OpenMenu(submenu) => format!("{} menu", submenu),
+                    Op::ToggleArg(name) => match args.get(name.as_str()) {
+                        Some(arg) => format!("{} ({})", arg.display, arg.arg),
+                        None => name.clone(),
+                    },
+                    op => op.clone().implementation().display(state),
+                };
+
+                Some(HelpEntry {
+                    menu: *menu,
+                    keys: keys.clone(),
+                    default_keys: (keys != &default_keys).then_some(default_keys),
+                    description,
+                })
+            })
+        })
+        .collect())
+}
+
 pub(crate) struct Refresh;
 impl OpTrait for Refresh {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
@@ -87,6 +181,8 @@ impl OpTrait for ToggleArg {
         Some(Rc::new(move |state, _term| {
             let mut need_prompt = None;
             let mut default = None;
+            let mut set_flag = false;
+            let mut candidates = None;
 
             let maybe_entry = if let Some(menu) = &mut state.pending_menu {
                 Some(menu.args.entry(arg_name.clone().into()))
@@ -100,23 +196,31 @@ impl OpTrait for ToggleArg {
                         arg.unset();
                     } else if arg.expects_value() {
                         default = arg.default_as_string();
+                        candidates = arg.candidates;
                         need_prompt = Some(arg.display);
                     } else {
                         arg.set("").expect("Should succeed");
+                        set_flag = true;
                     }
                 });
             }
 
+            if set_flag {
+                if let Some(menu) = &mut state.pending_menu {
+                    menu.unset_opposite(&arg_name);
+                }
+            }
+
             let arg_name = arg_name.clone();
             let parse_and_set_arg =
                 Box::new(move |state: &mut State, _term: &mut Term, value: &str| {
                     if let Some(menu) = &mut state.pending_menu {
                         if let Some(entry) = menu.args.get_mut(arg_name.as_str()) {
-                            return entry.set(value);
+                            entry.set(value)?;
                         }
                     }
 
-                    Ok(())
+                    super::diff_options::apply(state)
                 });
 
             if let Some(display) = need_prompt {
@@ -127,9 +231,12 @@ impl OpTrait for ToggleArg {
                     Box::new(move |_| default.clone()),
                     false,
                 );
+                if let Some(candidates) = candidates {
+                    state.prompt.picker = Some(Picker::new(candidates(state)?));
+                }
             }
 
-            Ok(())
+            super::diff_options::apply(state)
         }))
     }
 
@@ -138,6 +245,21 @@ impl OpTrait for ToggleArg {
     }
 }
 
+pub(crate) struct ToggleMark;
+impl OpTrait for ToggleMark {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().toggle_mark();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Toggle mark".into()
+    }
+}
+
 pub(crate) struct ToggleSection;
 impl OpTrait for ToggleSection {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
@@ -153,6 +275,52 @@ impl OpTrait for ToggleSection {
     }
 }
 
+pub(crate) struct CollapseAll;
+impl OpTrait for CollapseAll {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().collapse_all();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Collapse all".into()
+    }
+}
+
+pub(crate) struct ExpandAll;
+impl OpTrait for ExpandAll {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().expand_all();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Expand all".into()
+    }
+}
+
+pub(crate) struct ShowLevel(pub usize);
+impl OpTrait for ShowLevel {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let level = self.0;
+        Some(Rc::new(move |state, _term| {
+            state.close_menu();
+            state.screen_mut().show_level(level);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        format!("Show level {}", self.0)
+    }
+}
+
 pub(crate) struct MoveUp;
 impl OpTrait for MoveUp {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
@@ -267,6 +435,167 @@ impl OpTrait for MoveParentSection {
     }
 }
 
+/// Jumps to an item on the screen picked by fuzzily matching its text.
+pub(crate) struct Goto;
+impl OpTrait for Goto {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            let candidates = picker::screen_items(state);
+
+            set_prompt(
+                state,
+                "Go to",
+                Box::new({
+                    let candidates = candidates.clone();
+                    move |state, _term, text| {
+                        // What's typed needn't be picked, the best match is gone to
+                        let picked = state.prompt.picked.take().or_else(|| {
+                            let mut best = Picker::new(candidates.clone());
+                            best.filter(text);
+                            best.next();
+                            best.selected().cloned()
+                        });
+
+                        let Some(item_i) = picked.and_then(|candidate| candidate.item) else {
+                            return Err(format!("Nothing to go to matching '{}'", text).into());
+                        };
+
+                        state.screen_mut().select_item(item_i);
+                        Ok(())
+                    }
+                }),
+                Box::new(|_| None),
+                true,
+            );
+            state.prompt.picker = Some(Picker::new(candidates));
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Go to".into()
+    }
+}
+
+/// Runs any op of the root menu or its submenus, picked by fuzzily matching its name.
+pub(crate) struct CommandPalette;
+impl OpTrait for CommandPalette {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            let commands = palette_commands(state);
+            let candidates = commands
+                .iter()
+                .map(|(_, _, candidate)| candidate.clone())
+                .collect();
+
+            set_prompt(
+                state,
+                "Command",
+                Box::new(move |state, term, text| {
+                    let mut best = Picker::new(
+                        commands
+                            .iter()
+                            .map(|(_, _, candidate)| candidate.clone())
+                            .collect(),
+                    );
+                    best.filter(text);
+                    let value = best.next();
+
+                    let Some((menu, op, _)) = commands
+                        .iter()
+                        .find(|(_, _, candidate)| Some(&candidate.value) == value.as_ref())
+                    else {
+                        return Err(format!("No command matching '{}'", text).into());
+                    };
+
+                    // Ops of a submenu expect it to be open, with its arguments
+                    if *menu != Menu::Root {
+                        state.handle_op(Op::OpenMenu(*menu), term)?;
+                    }
+                    state.handle_op(op.clone(), term)
+                }),
+                Box::new(|_| None),
+                true,
+            );
+            state.prompt.picker = Some(Picker::new(candidates));
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Command palette".into()
+    }
+}
+
+/// The ops available for the selected item, named after their menu if it isn't the root one
+/// and described by the keys they're bound to.
+fn palette_commands(state: &State) -> Vec<(Menu, Op, Candidate)> {
+    let target = state.screen().get_selected_item().target_data.as_ref();
+
+    state
+        .config
+        .bindings
+        .iter()
+        .filter(|(menu, _)| {
+            !matches!(
+                menu,
+                Menu::Help | Menu::CleanPreview | Menu::CommitMsg | Menu::RebaseTodo
+            )
+        })
+        .flat_map(|(menu, ops)| {
+            // The key opening the menu comes before those of its ops
+            let prefix = state
+                .config
+                .bindings
+                .get(&Menu::Root)
+                .and_then(|ops| ops.get(&Op::OpenMenu(*menu)))
+                .and_then(|keys| keys.first())
+                .cloned()
+                .unwrap_or_default();
+
+            ops.iter().filter_map(move |(op, keys)| {
+                if matches!(
+                    op,
+                    Op::ToggleArg(_) | Op::OpenMenu(_) | Op::Quit | Op::CommandPalette
+                ) {
+                    return None;
+                }
+
+                let implementation = op.clone().implementation();
+                let available = if implementation.is_target_op() {
+                    implementation.get_action(target).is_some()
+                } else {
+                    implementation.is_available(state)
+                };
+                if !available {
+                    return None;
+                }
+
+                let name = match menu {
+                    Menu::Root => implementation.display(state),
+                    menu => format!("{}: {}", menu, implementation.display(state)),
+                };
+                let keys = match menu {
+                    Menu::Root => keys.join("/"),
+                    _ => keys
+                        .iter()
+                        .map(|key| format!("{}{}", prefix, key))
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                };
+
+                Some((
+                    *menu,
+                    op.clone(),
+                    Candidate::new(name, CandidateKind::Command).with_description(keys),
+                ))
+            })
+        })
+        .collect()
+}
+
 pub(crate) struct HalfPageUp;
 impl OpTrait for HalfPageUp {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
@@ -296,3 +625,118 @@ impl OpTrait for HalfPageDown {
         "Half page down".into()
     }
 }
+
+pub(crate) struct PageUp;
+impl OpTrait for PageUp {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().scroll_page_up();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Page up".into()
+    }
+}
+
+pub(crate) struct PageDown;
+impl OpTrait for PageDown {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().scroll_page_down();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Page down".into()
+    }
+}
+
+pub(crate) struct MoveToTop;
+impl OpTrait for MoveToTop {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().select_first();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Top".into()
+    }
+}
+
+pub(crate) struct MoveToBottom;
+impl OpTrait for MoveToBottom {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().select_last();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Bottom".into()
+    }
+}
+
+/// Jumps to the next or previous hunk, expanding the file it's in.
+pub(crate) struct MoveToHunk {
+    pub(crate) forward: bool,
+}
+impl OpTrait for MoveToHunk {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let forward = self.forward;
+        Some(Rc::new(move |state, _term| {
+            state.close_menu();
+            state
+                .screen_mut()
+                .select_next_target(forward, |target| matches!(target, TargetData::Hunk(_)));
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        if self.forward {
+            "Next hunk"
+        } else {
+            "Prev hunk"
+        }
+        .into()
+    }
+}
+
+/// Jumps to the next or previous file of a diff, or in a list of files.
+pub(crate) struct MoveToFile {
+    pub(crate) forward: bool,
+}
+impl OpTrait for MoveToFile {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let forward = self.forward;
+        Some(Rc::new(move |state, _term| {
+            state.close_menu();
+            state.screen_mut().select_next_target(forward, |target| {
+                matches!(
+                    target,
+                    TargetData::Delta(_) | TargetData::Rename { .. } | TargetData::File(_)
+                )
+            });
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        if self.forward {
+            "Next file"
+        } else {
+            "Prev file"
+        }
+        .into()
+    }
+}
@@ -1,5 +1,12 @@
-use super::{create_prompt, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{create_prompt_with_default, remote::selected_remote, Action, OpTrait};
+use crate::{
+    git::remote::{get_push_remote, get_upstream_components},
+    items::TargetData,
+    menu::arg::Arg,
+    state::State,
+    term::Term,
+    Res,
+};
 use std::{process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
@@ -15,6 +22,7 @@ impl OpTrait for FetchAll {
         Some(Rc::new(|state, term| {
             let mut cmd = Command::new("git");
             cmd.args(["fetch", "--all", "--jobs", "10"]);
+            cmd.args(super::progress_arg(state));
             cmd.args(state.pending_menu.as_ref().unwrap().args());
 
             state.close_menu();
@@ -28,10 +36,73 @@ impl OpTrait for FetchAll {
     }
 }
 
+pub(crate) struct FetchFromPushRemote;
+impl OpTrait for FetchFromPushRemote {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            let Some(remote) = get_push_remote(&state.repo)? else {
+                state.close_menu();
+                return Err("No pushRemote configured".into());
+            };
+
+            fetch_elsewhere(state, term, &remote)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        get_push_remote(&state.repo).is_ok_and(|remote| remote.is_some())
+    }
+
+    fn display(&self, state: &State) -> String {
+        match get_push_remote(&state.repo) {
+            Ok(Some(remote)) => format!("from {}", remote),
+            _ => "from pushRemote".into(),
+        }
+    }
+}
+
+pub(crate) struct FetchFromUpstream;
+impl OpTrait for FetchFromUpstream {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            let Some(remote) = upstream_remote(state) else {
+                state.close_menu();
+                return Err("No upstream remote configured".into());
+            };
+
+            fetch_elsewhere(state, term, &remote)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        upstream_remote(state).is_some()
+    }
+
+    fn display(&self, state: &State) -> String {
+        match upstream_remote(state) {
+            Some(remote) => format!("from {}", remote),
+            None => "from upstream".into(),
+        }
+    }
+}
+
+/// The remote of the upstream branch, unless that's a local branch.
+fn upstream_remote(state: &State) -> Option<String> {
+    match get_upstream_components(&state.repo).ok().flatten() {
+        Some((remote, _branch)) if remote != "." => Some(remote),
+        _ => None,
+    }
+}
+
 pub(crate) struct FetchElsewhere;
 impl OpTrait for FetchElsewhere {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt("Select remote", push_elsewhere, true))
+        Some(create_prompt_with_default(
+            "Select remote",
+            fetch_elsewhere,
+            selected_remote,
+            true,
+        ))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -39,9 +110,10 @@ impl OpTrait for FetchElsewhere {
     }
 }
 
-fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
+fn fetch_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["fetch"]);
+    cmd.args(super::progress_arg(state));
     cmd.args(state.pending_menu.as_ref().unwrap().args());
     cmd.arg(remote);
 
@@ -0,0 +1,498 @@
+use super::{Action, OpTrait};
+use crate::{
+    config::ForgeTemplates, git::remote::get_upstream_components, items::TargetData, state::State,
+    term::Term, Res,
+};
+use git2::{BranchType, Repository};
+use std::{path::PathBuf, process::Command, rc::Rc};
+
+#[cfg(feature = "forge")]
+use super::set_prompt;
+#[cfg(feature = "forge")]
+use crate::{ci, github::GitHub, screen};
+
+pub(crate) struct OpenInForge;
+impl OpTrait for OpenInForge {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let target = match target {
+            Some(TargetData::Branch(branch)) => ForgeTarget::Branch(branch.clone()),
+            Some(TargetData::File(file)) => ForgeTarget::File(file.clone(), None),
+            Some(TargetData::Delta(delta)) => ForgeTarget::File(delta.new_file.clone(), None),
+            Some(TargetData::Rename { new_file, .. }) => ForgeTarget::File(new_file.clone(), None),
+            Some(TargetData::Hunk(hunk)) => {
+                ForgeTarget::File(hunk.new_file.clone(), Some(hunk.first_diff_line()))
+            }
+            #[cfg(feature = "forge")]
+            Some(TargetData::PullRequest(number)) => ForgeTarget::PullRequest(*number),
+            Some(target) => ForgeTarget::Commit(target.commitish()?.to_string()),
+            None => return None,
+        };
+
+        Some(Rc::new(move |state, term| {
+            state.close_menu();
+
+            let forge = Forge::of_repo(state)?;
+            let url = target.url(&state.repo, &forge.templates, &forge.base, &forge.remote)?;
+            open_in_browser(state, term, url)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Open in browser".into()
+    }
+}
+
+enum ForgeTarget {
+    Commit(String),
+    Branch(String),
+    File(PathBuf, Option<u32>),
+    #[cfg(feature = "forge")]
+    PullRequest(u64),
+}
+
+impl ForgeTarget {
+    fn url(
+        &self,
+        repo: &Repository,
+        templates: &ForgeTemplates,
+        base: &str,
+        remote: &str,
+    ) -> Res<String> {
+        let url = match self {
+            ForgeTarget::Commit(commit) => templates.commit.replace(
+                "{commit}",
+                &repo
+                    .revparse_single(commit)?
+                    .peel_to_commit()?
+                    .id()
+                    .to_string(),
+            ),
+            ForgeTarget::Branch(branch) => templates.branch.replace(
+                "{branch}",
+                &encode_path(&remote_branch(repo, branch, remote)),
+            ),
+            // Files are linked at HEAD, as they're shown in the status screen
+            ForgeTarget::File(file, line) => {
+                let file_url = templates
+                    .file
+                    .replace("{commit}", &repo.head()?.peel_to_commit()?.id().to_string())
+                    .replace("{file}", &encode_path(&file.to_string_lossy()));
+
+                match line {
+                    Some(line) => file_url + &templates.line.replace("{line}", &line.to_string()),
+                    None => file_url,
+                }
+            }
+            #[cfg(feature = "forge")]
+            ForgeTarget::PullRequest(number) => templates
+                .pull_request
+                .replace("{number}", &number.to_string()),
+        };
+
+        Ok(url.replace("{base}", base))
+    }
+}
+
+pub(crate) struct ShowPullRequests;
+impl OpTrait for ShowPullRequests {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        #[cfg(feature = "forge")]
+        return Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            goto_pull_requests_screen(state)
+        }));
+
+        #[cfg(not(feature = "forge"))]
+        None
+    }
+
+    fn is_available(&self, _state: &State) -> bool {
+        cfg!(feature = "forge")
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show pull requests".into()
+    }
+}
+
+#[cfg(feature = "forge")]
+fn goto_pull_requests_screen(state: &mut State) -> Res<()> {
+    let github = GitHub::from_repo(&state.repo, &state.config)?;
+
+    state.pop_screens_to_root();
+    let size = state.screens.last().unwrap().size;
+    state.close_menu();
+    state.screens.push(screen::pull_requests::create(
+        Rc::clone(&state.config),
+        size,
+        github.remote.clone(),
+        state.enable_async_cmds,
+        move || github.pull_requests(),
+    )?);
+    Ok(())
+}
+
+/// Fetches the head of a pull request into a local `pr-<number>` branch and checks it out.
+pub(crate) struct CheckoutPullRequest;
+impl OpTrait for CheckoutPullRequest {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            #[cfg(feature = "forge")]
+            Some(TargetData::PullRequest(number)) => {
+                let number = *number;
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    checkout_pull_request(state, term, number)
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn is_available(&self, _state: &State) -> bool {
+        cfg!(feature = "forge")
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Checkout pull request".into()
+    }
+}
+
+#[cfg(feature = "forge")]
+fn checkout_pull_request(state: &mut State, term: &mut Term, number: u64) -> Res<()> {
+    let remote = forge_remote(&state.repo)?;
+    let branch = format!("pr-{}", number);
+    state.close_menu();
+
+    // Not forced, so commits made on the branch since are kept
+    let mut fetch = Command::new("git");
+    fetch.args([
+        "fetch",
+        &remote,
+        &format!("refs/pull/{}/head:{}", number, branch),
+    ]);
+    state.run_cmd(term, &[], fetch)?;
+
+    let mut checkout = Command::new("git");
+    checkout.args(["checkout", &branch]);
+    state.run_cmd(term, &[], checkout)
+}
+
+/// Opens the page of a check run that failed on the commit, branch or tag at point.
+pub(crate) struct OpenFailedCheck;
+impl OpTrait for OpenFailedCheck {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            #[cfg(feature = "forge")]
+            Some(target)
+                if matches!(target, TargetData::Branch(_)) || target.commitish().is_some() =>
+            {
+                let target = target.clone();
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    state.close_menu();
+                    let oid = ci::target_commit(&state.repo, Some(&target))
+                        .ok_or("Couldn't find the commit")?;
+
+                    let cached = state.ci_statuses.as_ref().and_then(|ci| ci.get(oid));
+                    let checks = match cached {
+                        Some(checks) => checks,
+                        None => GitHub::from_repo(&state.repo, &state.config)?
+                            .checks(&oid.to_string())?,
+                    };
+
+                    let url = checks.failed_url.ok_or("No check failed")?;
+                    open_in_browser(state, term, url)
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn is_available(&self, _state: &State) -> bool {
+        cfg!(feature = "forge")
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Open failed check".into()
+    }
+}
+
+/// Pushes the current branch, setting its upstream if it has none, and opens the page
+/// creating a pull request of it. On GitHub, with the `forge` feature, it's instead
+/// created right away from a title and description that are prompted for.
+pub(crate) struct CreatePullRequest;
+impl OpTrait for CreatePullRequest {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+
+            let head = state.repo.head()?;
+            if !head.is_branch() {
+                return Err(
+                    "Head is detached, there's no branch to create a pull request of".into(),
+                );
+            }
+            let branch = head
+                .shorthand()
+                .ok_or("Branch name isn't valid utf-8")?
+                .to_string();
+            let has_upstream = state
+                .repo
+                .find_branch(&branch, BranchType::Local)?
+                .upstream()
+                .is_ok();
+            drop(head);
+
+            let forge = Forge::of_repo(state)?;
+            if forge.templates.new_pull_request.is_empty() {
+                return Err(format!("No new_pull_request template for {}", forge.host).into());
+            }
+
+            let mut push = Command::new("git");
+            push.arg("push");
+            if !has_upstream {
+                push.arg("--set-upstream");
+            }
+            push.args([&forge.remote, &branch]);
+            state.run_cmd(term, &[], push)?;
+
+            let remote_branch = remote_branch(&state.repo, &branch, &forge.remote);
+
+            #[cfg(feature = "forge")]
+            if let Ok(github) = GitHub::from_repo(&state.repo, &state.config) {
+                prompt_pull_request(state, github, remote_branch);
+                return Ok(());
+            }
+
+            let url = forge
+                .templates
+                .new_pull_request
+                .replace("{branch}", &encode_path(&remote_branch))
+                .replace("{base}", &forge.base);
+            open_in_browser(state, term, url)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Create pull request".into()
+    }
+}
+
+#[cfg(feature = "forge")]
+fn prompt_pull_request(state: &mut State, github: GitHub, head: String) {
+    set_prompt(
+        state,
+        "Title",
+        Box::new(move |state, _term, title| {
+            if title.is_empty() {
+                return Err("Title required".into());
+            }
+
+            let (github, head, title) = (github.clone(), head.clone(), title.to_string());
+            set_prompt(
+                state,
+                "Description",
+                Box::new(move |state, _term, body| {
+                    let url = github.create_pull_request(&head, &title, body)?;
+                    state.display_info(format!("Created {}", url));
+                    Ok(())
+                }),
+                Box::new(|_| None),
+                true,
+            );
+            Ok(())
+        }),
+        Box::new(|state| head_summary(&state.repo)),
+        true,
+    );
+}
+
+/// The subject of the last commit, which pull requests of a single commit are titled by.
+#[cfg(feature = "forge")]
+fn head_summary(repo: &Repository) -> Option<String> {
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    commit.summary().map(String::from)
+}
+
+/// A remote on a forge, and the templates of the URLs it has.
+struct Forge {
+    remote: String,
+    host: String,
+    /// `https://<host>/<repository path>`
+    base: String,
+    templates: ForgeTemplates,
+}
+
+impl Forge {
+    fn of_repo(state: &State) -> Res<Self> {
+        let remote = forge_remote(&state.repo)?;
+        let remote_url = state.repo.find_remote(&remote)?.url().map(String::from);
+        let (host, path) = remote_url
+            .as_deref()
+            .and_then(parse_remote_url)
+            .ok_or_else(|| format!("Couldn't make out the url of remote {}", remote))?;
+
+        let forge = &state.config.general.forge;
+        let templates = forge
+            .hosts
+            .get(&host)
+            .and_then(|name| forge.templates.get(name))
+            .ok_or_else(|| format!("No forge set up for {} in general.forge.hosts", host))?
+            .clone();
+
+        Ok(Self {
+            base: format!("https://{}/{}", host, path),
+            remote,
+            host,
+            templates,
+        })
+    }
+}
+
+fn open_in_browser(state: &mut State, term: &mut Term, url: String) -> Res<()> {
+    let mut cmd = browser_cmd(&state.config.general.forge.browser);
+    cmd.arg(url);
+    state.run_cmd_async(term, &[], cmd)
+}
+
+/// The remote of the upstream, or else `origin` or the only remote there is.
+pub(crate) fn forge_remote(repo: &Repository) -> Res<String> {
+    if let Ok(Some((remote, _))) = get_upstream_components(repo) {
+        if remote != "." {
+            return Ok(remote);
+        }
+    }
+
+    let remotes = repo.remotes()?;
+    let names = remotes.iter().flatten().collect::<Vec<_>>();
+    match &names[..] {
+        [name] => Ok(name.to_string()),
+        _ if names.contains(&"origin") => Ok("origin".into()),
+        [] => Err("No remote to open in the browser".into()),
+        _ => Err("No upstream or origin remote to open in the browser".into()),
+    }
+}
+
+/// Local branches are shown as their upstream on `remote`, all else by the same name.
+fn remote_branch(repo: &Repository, branch: &str, remote: &str) -> String {
+    let remote_prefix = format!("{}/", remote);
+    if let Some(name) = branch.strip_prefix(&remote_prefix) {
+        return name.to_string();
+    }
+
+    repo.find_branch(branch, BranchType::Local)
+        .and_then(|local| local.upstream())
+        .ok()
+        .and_then(|upstream| {
+            upstream
+                .get()
+                .shorthand()
+                .and_then(|name| name.strip_prefix(&remote_prefix))
+                .map(String::from)
+        })
+        .unwrap_or(branch.to_string())
+}
+
+/// Splits a remote url such as `git@github.com:altsem/gitu.git` into its host and repository path,
+/// e.g. `github.com` and `altsem/gitu`. Ports and user names are left out.
+pub(crate) fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let (host, path) = match url.split_once("://") {
+        Some((_scheme, rest)) => rest.split_once('/')?,
+        // scp-like syntax
+        None => url.split_once(':')?,
+    };
+
+    let host = host.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some((host.to_string(), path.to_string()))
+}
+
+/// Percent-encodes what a URL path can't contain as is, keeping the slashes between its parts.
+fn encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn browser_cmd(browser: &str) -> Command {
+    let args = browser.split_whitespace().collect::<Vec<_>>();
+    if let [program, args @ ..] = &args[..] {
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        return cmd;
+    }
+
+    if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        // Unlike `start`, which goes through cmd and its parsing of `&` and the like
+        let mut cmd = Command::new("rundll32");
+        cmd.arg("url.dll,FileProtocolHandler");
+        cmd
+    } else {
+        Command::new("xdg-open")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_path, parse_remote_url};
+
+    fn parsed(host: &str, path: &str) -> Option<(String, String)> {
+        Some((host.to_string(), path.to_string()))
+    }
+
+    #[test]
+    fn parse_remote_urls() {
+        let gitu = parsed("github.com", "altsem/gitu");
+        assert_eq!(parse_remote_url("git@github.com:altsem/gitu.git"), gitu);
+        assert_eq!(parse_remote_url("https://github.com/altsem/gitu.git"), gitu);
+        assert_eq!(
+            parse_remote_url("https://user@github.com/altsem/gitu/"),
+            gitu
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@github.com:22/altsem/gitu.git"),
+            gitu
+        );
+        assert_eq!(
+            parse_remote_url("https://gitlab.example.com/group/subgroup/project"),
+            parsed("gitlab.example.com", "group/subgroup/project")
+        );
+    }
+
+    #[test]
+    fn parse_local_paths() {
+        assert_eq!(parse_remote_url("/tmp/remote"), None);
+        assert_eq!(parse_remote_url("../remote.git"), None);
+    }
+
+    #[test]
+    fn encode_paths() {
+        assert_eq!(encode_path("feature/a-b_c.d~e"), "feature/a-b_c.d~e");
+        assert_eq!(encode_path("a&b #1%"), "a%26b%20%231%25");
+        assert_eq!(encode_path("smörgås"), "sm%C3%B6rg%C3%A5s");
+    }
+}
@@ -0,0 +1,77 @@
+use super::{create_prompt_with_default, Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term, Res};
+use std::{fs, path::PathBuf};
+
+pub(crate) struct IgnoreToplevel;
+impl OpTrait for IgnoreToplevel {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Ignore in .gitignore",
+            ignore_toplevel,
+            selected_pattern,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "shared at toplevel (.gitignore)".into()
+    }
+}
+
+fn ignore_toplevel(state: &mut State, _term: &mut Term, pattern: &str) -> Res<()> {
+    let path = state.repo.workdir().ok_or("No workdir")?.join(".gitignore");
+    ignore(state, path, pattern)
+}
+
+pub(crate) struct IgnorePrivately;
+impl OpTrait for IgnorePrivately {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Ignore in .git/info/exclude",
+            ignore_privately,
+            selected_pattern,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "privately (.git/info/exclude)".into()
+    }
+}
+
+fn ignore_privately(state: &mut State, _term: &mut Term, pattern: &str) -> Res<()> {
+    let path = state.repo.path().join("info").join("exclude");
+    ignore(state, path, pattern)
+}
+
+/// The selected untracked file, anchored to the top of the work tree.
+fn selected_pattern(state: &State) -> Option<String> {
+    match &state.screen().get_selected_item().target_data {
+        Some(TargetData::File(file)) => Some(format!("/{}", file.to_string_lossy())),
+        _ => None,
+    }
+}
+
+fn ignore(state: &mut State, path: PathBuf, pattern: &str) -> Res<()> {
+    state.close_menu();
+
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err("Nothing to ignore".into());
+    }
+
+    let mut content = fs::read_to_string(&path).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(pattern);
+    content.push('\n');
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, content)?;
+
+    state.display_info(format!("Ignored {}", pattern));
+    state.screen_mut().update()
+}
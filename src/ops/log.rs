@@ -1,7 +1,11 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use super::{create_range_prompt, create_rev_prompt, Action, OpTrait};
 use crate::{
-    items::TargetData,
-    menu::arg::{any_regex, positive_number, Arg},
+    git::log_filter::{self, LogFilter},
+    items::{LogOptions, TargetData},
+    menu::{
+        arg::{any_regex, any_string, date, positive_number, Arg},
+        Menu, PendingMenu,
+    },
     screen,
     state::State,
     term::Term,
@@ -20,7 +24,14 @@ pub(crate) fn init_args() -> Vec<Arg> {
             positive_number,
         ),
         Arg::new_arg("--grep", "Search messages", None, any_regex),
-        // Arg::new_str("-S", "Search occurences"), // TOOD: Implement search
+        Arg::new_arg("--author", "Limit to author", None, any_regex),
+        Arg::new_arg("--since", "Limit to commits since", None, date),
+        Arg::new_arg("--until", "Limit to commits until", None, date),
+        Arg::new_arg("-S", "Search occurrences", None, any_string),
+        Arg::new_arg("--", "Limit to files", None, any_string),
+        Arg::new_flag("--follow", "Follow renames", false),
+        Arg::new_flag("--graph", "Show graph", true),
+        Arg::new_flag("--show-signature", "Show signatures", false),
     ]
 }
 
@@ -28,8 +39,7 @@ pub(crate) struct LogCurrent;
 impl OpTrait for LogCurrent {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, _term: &mut Term| {
-            goto_log_screen(state, None);
-            Ok(())
+            goto_log_screen(state, None)
         }))
     }
 
@@ -38,15 +48,41 @@ impl OpTrait for LogCurrent {
     }
 }
 
+pub(crate) struct LogFile;
+impl OpTrait for LogFile {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let file = match target {
+            Some(TargetData::File(file)) => file.clone(),
+            Some(TargetData::Delta(delta)) => delta.new_file.clone(),
+            Some(TargetData::Rename { new_file, .. }) => new_file.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            let menu = state.pending_menu.as_mut().expect("Log menu is open");
+            for (name, value) in [("--", file.to_string_lossy().as_ref()), ("--follow", "")] {
+                if let Some(arg) = menu.args.get_mut(name) {
+                    arg.set(value)?;
+                }
+            }
+
+            goto_log_screen(state, None)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "file".into()
+    }
+}
+
 pub(crate) struct LogOther;
 impl OpTrait for LogOther {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
-            "Log rev",
-            log_other,
-            selected_rev,
-            true,
-        ))
+        Some(create_rev_prompt("Log rev", log_other, true))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -54,6 +90,79 @@ impl OpTrait for LogOther {
     }
 }
 
+pub(crate) struct LogReflog;
+impl OpTrait for LogReflog {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.pop_screens_to_root();
+            let size = state.screens.last().unwrap().size;
+            let limit = log_limit(state);
+
+            state.close_menu();
+            state.screens.push(screen::reflog::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                size,
+                limit,
+            )?);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "reflog".into()
+    }
+}
+
+pub(crate) struct LogShortlog;
+impl OpTrait for LogShortlog {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_range_prompt("Shortlog of", shortlog, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "shortlog".into()
+    }
+}
+
+/// Limited by the filter of the log menu, but not by `-n`, as that would leave out commits from the counts.
+fn shortlog(state: &mut State, _term: &mut Term, range: &str) -> Res<()> {
+    let range = match range.trim() {
+        "" => "HEAD",
+        range => range,
+    };
+
+    let menu = state.pending_menu.as_ref().expect("Log menu is open");
+    let filter = log_filter(menu);
+    let revspec_result = match state
+        .repo
+        .revparse(screen::shortlog::split_max_count(range).1)
+    {
+        Ok(_) => Ok(()),
+        Err(err) => Err(format!("Failed due to: {:?}", err.code())),
+    };
+
+    if revspec_result.is_err() {
+        state.close_menu();
+    }
+
+    revspec_result?;
+
+    state.screen_mut().clear_mark();
+    state.pop_screens_to_root();
+    let size = state.screens.last().unwrap().size;
+    state.close_menu();
+
+    state.screens.push(screen::shortlog::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        size,
+        range.to_string(),
+        filter?,
+    )?);
+    Ok(())
+}
+
 fn log_other(state: &mut State, _term: &mut Term, result: &str) -> Res<()> {
     let oid_result = match state.repo.revparse_single(result) {
         Ok(rev) => Ok(rev.id()),
@@ -66,26 +175,22 @@ fn log_other(state: &mut State, _term: &mut Term, result: &str) -> Res<()> {
 
     let oid = oid_result?;
 
-    goto_log_screen(state, Some(oid));
-    Ok(())
+    goto_log_screen(state, Some(oid))
 }
 
-fn goto_log_screen(state: &mut State, rev: Option<Oid>) {
-    state.screens.drain(1..);
+fn goto_log_screen(state: &mut State, rev: Option<Oid>) -> Res<()> {
+    state.pop_screens_to_root();
     let size = state.screens.last().unwrap().size;
-    let limit = *state
-        .pending_menu
-        .as_ref()
-        .and_then(|m| m.args.get("-n"))
-        .and_then(|arg| arg.value_as::<u32>())
-        .unwrap_or(&u32::MAX);
-
-    let msg_regex_menu = state
-        .pending_menu
-        .as_ref()
-        .and_then(|m| m.args.get("--grep"));
+    let limit = log_limit(state);
 
-    let msg_regex = msg_regex_menu.and_then(|arg| arg.value_as::<Regex>().cloned());
+    let menu = state.pending_menu.as_ref().expect("Log menu is open");
+    let filter = log_filter(menu)?;
+    let is_active = |name| menu.args.get(name).is_some_and(|arg| arg.is_active());
+    let options = LogOptions {
+        graph: is_active("--graph"),
+        signatures: is_active("--show-signature"),
+    };
+    let menu_args = menu.active_args();
 
     state.close_menu();
 
@@ -94,10 +199,56 @@ fn goto_log_screen(state: &mut State, rev: Option<Oid>) {
             Rc::clone(&state.config),
             Rc::clone(&state.repo),
             size,
-            limit as usize,
+            state.enable_async_cmds,
+            limit,
             rev,
-            msg_regex,
-        )
-        .expect("Couldn't create screen"),
+            filter,
+            options,
+        )?
+        .with_menu_args(Menu::Log, menu_args),
     );
+    Ok(())
+}
+
+fn log_filter(menu: &PendingMenu) -> Res<LogFilter> {
+    let value = |name: &str| menu.args.get(name).and_then(|arg| arg.value_as::<String>());
+    let now = chrono::Local::now().timestamp();
+    let date = |name: &str| {
+        value(name)
+            .map(|date| log_filter::parse_date(date, now))
+            .transpose()
+    };
+
+    Ok(LogFilter {
+        grep: menu
+            .args
+            .get("--grep")
+            .and_then(|arg| arg.value_as::<Regex>().cloned()),
+        author: menu
+            .args
+            .get("--author")
+            .and_then(|arg| arg.value_as::<Regex>().cloned()),
+        since: date("--since")?,
+        until: date("--until")?,
+        pickaxe: value("-S").cloned(),
+        paths: value("--")
+            .map(|paths| paths.split_whitespace().map(String::from).collect())
+            .unwrap_or_default(),
+        follow: menu.args.get("--follow").is_some_and(|arg| arg.is_active()),
+        args: menu
+            .args
+            .values()
+            .filter(|arg| arg.is_active() && !["-n", "--graph"].contains(&arg.arg))
+            .map(|arg| arg.get_cli_token())
+            .collect(),
+    })
+}
+
+fn log_limit(state: &State) -> usize {
+    *state
+        .pending_menu
+        .as_ref()
+        .and_then(|m| m.args.get("-n"))
+        .and_then(|arg| arg.value_as::<u32>())
+        .unwrap_or(&u32::MAX) as usize
 }
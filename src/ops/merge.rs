@@ -0,0 +1,109 @@
+use std::{process::Command, rc::Rc};
+
+use crate::{git, items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+
+use super::{create_rev_prompt, Action, OpTrait};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--ff-only", "Fast-forward only", false),
+        Arg::new_flag("--no-ff", "No fast-forward", false),
+        Arg::new_flag("--no-edit", "Don't edit commit message", false),
+    ]
+}
+
+pub(crate) struct Merge;
+impl OpTrait for Merge {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt("Merge", merge, true))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        !is_merging(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Merge".into()
+    }
+}
+
+fn merge(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["merge"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(input);
+
+    state.close_menu();
+    state.run_cmd_interactive(term, cmd)
+}
+
+pub(crate) struct MergeSquash;
+impl OpTrait for MergeSquash {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt("Squash", merge_squash, true))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        !is_merging(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Squash merge".into()
+    }
+}
+
+fn merge_squash(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["merge", "--squash"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(input);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct MergeContinue;
+impl OpTrait for MergeContinue {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["merge", "--continue"]);
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_merging(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Continue".into()
+    }
+}
+
+pub(crate) struct MergeAbort;
+impl OpTrait for MergeAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["merge", "--abort"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_merging(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Abort".into()
+    }
+}
+
+fn is_merging(state: &State) -> bool {
+    git::merge_status(&state.repo).is_ok_and(|status| status.is_some())
+}
@@ -1,28 +1,59 @@
+use git2::ObjectType;
 use serde::{Deserialize, Serialize};
 use tui_prompts::State as _;
 
 use crate::{
-    cmd_log::CmdLogEntry, items::TargetData, menu::Menu, prompt::PromptData, state::State,
-    term::Term, Res,
+    cmd_log::CmdLogEntry,
+    config::Config,
+    git::diff::Hunk,
+    items::TargetData,
+    menu::Menu,
+    picker::{self, Candidate, Picker},
+    prompt::{self, History, PromptData},
+    screen::rebase_todo::TodoAction,
+    state::State,
+    term::Term,
+    Res,
 };
-use std::{fmt::Display, rc::Rc};
+use std::{cell::RefCell, fmt::Display, ops::Range, rc::Rc, sync::Arc};
 
-pub(crate) mod checkout;
+pub(crate) mod bisect;
+pub(crate) mod blame;
+pub(crate) mod branch;
+pub(crate) mod cherry_pick;
+pub(crate) mod clean;
+pub(crate) mod cmd_history;
 pub(crate) mod commit;
-pub(crate) mod copy_hash;
+pub(crate) mod copy;
+pub(crate) mod diff;
+pub(crate) mod diff_options;
 pub(crate) mod discard;
 pub(crate) mod editor;
 pub(crate) mod fetch;
+pub(crate) mod forge;
+pub(crate) mod ignore;
 pub(crate) mod log;
+pub(crate) mod merge;
+pub(crate) mod patch;
+pub(crate) mod pending_cmd;
 pub(crate) mod pull;
 pub(crate) mod push;
 pub(crate) mod rebase;
+pub(crate) mod rebase_todo;
+pub(crate) mod remote;
+pub(crate) mod repositories;
 pub(crate) mod reset;
+pub(crate) mod resolve;
 pub(crate) mod revert;
+pub(crate) mod search;
+pub(crate) mod shell;
 pub(crate) mod show;
 pub(crate) mod show_refs;
 pub(crate) mod stage;
 pub(crate) mod stash;
+pub(crate) mod submodule;
+pub(crate) mod tag;
+pub(crate) mod tree;
 pub(crate) mod unstage;
 
 pub(crate) type Action = Rc<dyn FnMut(&mut State, &mut Term) -> Res<()>>;
@@ -32,35 +63,127 @@ pub(crate) trait OpTrait {
     /// This indirection allows Gitu to show a contextual menu of applicable actions.
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action>;
 
+    /// Like `get_action`, but acting on all targets within a marked region at once.
+    /// Ops that don't support regions act on the selected item only.
+    fn get_region_action(&self, _targets: &[TargetData]) -> Option<Action> {
+        None
+    }
+
     /// This indicates whether the Op is meant to read and
     /// act on TargetData. Those are listed differently in the help menu.
     fn is_target_op(&self) -> bool {
         false
     }
 
+    /// Whether the Op makes sense in the current state of the repository,
+    /// e.g. "continue" only while an operation is in progress.
+    /// Unavailable ops are hidden from menus.
+    fn is_available(&self, _state: &State) -> bool {
+        true
+    }
+
     fn display(&self, state: &State) -> String;
 }
 
 #[derive(Clone, PartialOrd, Ord, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum Op {
+    BisectStart,
+    BisectGood,
+    BisectBad,
+    BisectSkip,
+    BisectReset,
     Checkout,
+    Clean,
+    CleanConfirm,
+    CleanAbort,
+    IgnoreToplevel,
+    IgnorePrivately,
+    IntentToAdd,
     CheckoutNewBranch,
+    CheckoutFile,
+    CherryPick,
+    CherryPickAbort,
+    CherryPickContinue,
+    CherryPickSkip,
+    RenameBranch,
+    DeleteBranch,
+    ForceDeleteBranch,
+    SetUpstream,
     Commit,
     CommitAmend,
+    CommitExtend,
+    CommitReword,
+    CommitMsgPrevious,
+    CommitMsgNext,
+    CommitMsgComplete,
+    CommitMsgSubmit,
+    CommitMsgAbort,
+    DiffRange,
+    Difftool,
+    DiffPager,
+    DiffWorktree,
     FetchAll,
     FetchElsewhere,
+    FetchFromPushRemote,
+    FetchFromUpstream,
     LogCurrent,
+    LogFile,
+    Merge,
+    MergeSquash,
+    MergeContinue,
+    MergeAbort,
     PullFromPushRemote,
     PullFromUpstream,
     PullFromElsewhere,
     PushToPushRemote,
     PushToUpstream,
     PushToElsewhere,
+    PushTags,
     RebaseAbort,
     RebaseContinue,
     RebaseElsewhere,
+    RebaseSplit,
+    RebaseTodoPick,
+    RebaseTodoReword,
+    RebaseTodoEdit,
+    RebaseTodoSquash,
+    RebaseTodoFixup,
+    RebaseTodoDrop,
+    RebaseTodoMoveUp,
+    RebaseTodoMoveDown,
+    RebaseTodoSubmit,
+    RebaseTodoAbort,
+    AddRemote,
+    RenameRemote,
+    RemoveRemote,
+    PruneRemote,
+    SetRemoteUrl,
+    KeepOurs,
+    KeepTheirs,
+    KeepBoth,
+    Mergetool,
+    FormatPatch,
+    ApplyPatches,
+    ApplyPatchesFromClipboard,
+    ApplyPatch,
+    ApplyPatchToIndex,
+    ReverseApply,
+    AmContinue,
+    AmSkip,
+    AmAbort,
     ShowRefs,
+    BrowseTree,
+    BlameLine,
+    ShowFileAtRevision,
+    ShowRepositories,
+    ShowHelp,
+    ShellCommand,
+    Shell,
+    ToggleCmdHistory,
+    KillCmd,
+    ToggleCmdOutput,
+    DismissCmdOutput,
     Stash,
     StashApply,
     StashIndex,
@@ -68,25 +191,69 @@ pub(crate) enum Op {
     StashKeepIndex,
     StashPop,
     StashDrop,
+    SubmoduleInit,
+    SubmoduleUpdate,
+    SubmoduleSync,
+    SubmoduleEnter,
+    CreateTag,
+    CreateAnnotatedTag,
+    DeleteTag,
+    PushTag,
     CommitFixup,
+    CommitSquash,
     CommitInstantFixup,
+    CommitInstantSquash,
     LogOther,
+    LogReflog,
+    LogShortlog,
     RebaseAutosquash,
     RebaseInteractive,
+    RebaseReword,
+    RebaseDrop,
+    RebaseSquash,
+    RebaseEdit,
+    RebaseSubset,
     ResetSoft,
     ResetMixed,
     ResetHard,
+    ResetKeep,
+    ResetFile,
     RevertAbort,
     RevertContinue,
     RevertCommit,
 
     Stage,
+    StageAllTracked,
+    StageAll,
     Unstage,
+    UnstageAll,
     Show,
     Discard,
+    UndoDiscard,
     CopyHash,
+    CopyBranchName,
+    CopyPath,
+    CopyHunk,
+    CopyMessage,
+    CopyDescribe,
+    OpenInForge,
+    ShowPullRequests,
+    CheckoutPullRequest,
+    CreatePullRequest,
+    OpenFailedCheck,
 
     ToggleSection,
+    CollapseAll,
+    ExpandAll,
+    #[serde(rename = "show_level_1")]
+    ShowLevel1,
+    #[serde(rename = "show_level_2")]
+    ShowLevel2,
+    #[serde(rename = "show_level_3")]
+    ShowLevel3,
+    #[serde(rename = "show_level_4")]
+    ShowLevel4,
+    ToggleMark,
     MoveUp,
     MoveDown,
     MoveUpLine,
@@ -94,10 +261,24 @@ pub(crate) enum Op {
     MovePrevSection,
     MoveNextSection,
     MoveParentSection,
+    Goto,
+    CommandPalette,
     HalfPageUp,
     HalfPageDown,
+    PageUp,
+    PageDown,
+    MoveToTop,
+    MoveToBottom,
+    MoveNextHunk,
+    MovePrevHunk,
+    MoveNextFile,
+    MovePrevFile,
+    Search,
+    SearchNext,
+    SearchPrevious,
 
     Refresh,
+    Suspend,
     Quit,
 
     #[serde(untagged)]
@@ -109,11 +290,19 @@ pub(crate) enum Op {
 impl Op {
     pub fn implementation(self) -> Box<dyn OpTrait> {
         match self {
+            Op::Suspend => Box::new(editor::Suspend),
             Op::Quit => Box::new(editor::Quit),
             Op::OpenMenu(menu) => Box::new(editor::OpenMenu(menu)),
             Op::Refresh => Box::new(editor::Refresh),
             Op::ToggleArg(name) => Box::new(editor::ToggleArg(name)),
             Op::ToggleSection => Box::new(editor::ToggleSection),
+            Op::CollapseAll => Box::new(editor::CollapseAll),
+            Op::ExpandAll => Box::new(editor::ExpandAll),
+            Op::ShowLevel1 => Box::new(editor::ShowLevel(1)),
+            Op::ShowLevel2 => Box::new(editor::ShowLevel(2)),
+            Op::ShowLevel3 => Box::new(editor::ShowLevel(3)),
+            Op::ShowLevel4 => Box::new(editor::ShowLevel(4)),
+            Op::ToggleMark => Box::new(editor::ToggleMark),
             Op::MoveDown => Box::new(editor::MoveDown),
             Op::MoveUp => Box::new(editor::MoveUp),
             Op::MoveDownLine => Box::new(editor::MoveDownLine),
@@ -121,26 +310,120 @@ impl Op {
             Op::MoveNextSection => Box::new(editor::MoveNextSection),
             Op::MovePrevSection => Box::new(editor::MovePrevSection),
             Op::MoveParentSection => Box::new(editor::MoveParentSection),
+            Op::Goto => Box::new(editor::Goto),
+            Op::CommandPalette => Box::new(editor::CommandPalette),
             Op::HalfPageUp => Box::new(editor::HalfPageUp),
             Op::HalfPageDown => Box::new(editor::HalfPageDown),
+            Op::PageUp => Box::new(editor::PageUp),
+            Op::PageDown => Box::new(editor::PageDown),
+            Op::MoveToTop => Box::new(editor::MoveToTop),
+            Op::MoveToBottom => Box::new(editor::MoveToBottom),
+            Op::MoveNextHunk => Box::new(editor::MoveToHunk { forward: true }),
+            Op::MovePrevHunk => Box::new(editor::MoveToHunk { forward: false }),
+            Op::MoveNextFile => Box::new(editor::MoveToFile { forward: true }),
+            Op::MovePrevFile => Box::new(editor::MoveToFile { forward: false }),
+            Op::Search => Box::new(search::Search),
+            Op::SearchNext => Box::new(search::SearchNext),
+            Op::SearchPrevious => Box::new(search::SearchPrevious),
 
-            Op::Checkout => Box::new(checkout::Checkout),
-            Op::CheckoutNewBranch => Box::new(checkout::CheckoutNewBranch),
+            Op::BisectStart => Box::new(bisect::BisectStart),
+            Op::BisectGood => Box::new(bisect::BisectGood),
+            Op::BisectBad => Box::new(bisect::BisectBad),
+            Op::BisectSkip => Box::new(bisect::BisectSkip),
+            Op::BisectReset => Box::new(bisect::BisectReset),
+            Op::Checkout => Box::new(branch::Checkout),
+            Op::Clean => Box::new(clean::Clean),
+            Op::CleanConfirm => Box::new(clean::CleanConfirm),
+            Op::CleanAbort => Box::new(clean::CleanAbort),
+            Op::IgnoreToplevel => Box::new(ignore::IgnoreToplevel),
+            Op::IgnorePrivately => Box::new(ignore::IgnorePrivately),
+            Op::IntentToAdd => Box::new(stage::IntentToAdd),
+            Op::CheckoutNewBranch => Box::new(branch::CheckoutNewBranch),
+            Op::CheckoutFile => Box::new(branch::CheckoutFile),
+            Op::CherryPick => Box::new(cherry_pick::CherryPick),
+            Op::CherryPickAbort => Box::new(cherry_pick::CherryPickAbort),
+            Op::CherryPickContinue => Box::new(cherry_pick::CherryPickContinue),
+            Op::CherryPickSkip => Box::new(cherry_pick::CherryPickSkip),
+            Op::RenameBranch => Box::new(branch::RenameBranch),
+            Op::DeleteBranch => Box::new(branch::DeleteBranch),
+            Op::ForceDeleteBranch => Box::new(branch::ForceDeleteBranch),
+            Op::SetUpstream => Box::new(branch::SetUpstream),
             Op::Commit => Box::new(commit::Commit),
             Op::CommitAmend => Box::new(commit::CommitAmend),
+            Op::CommitExtend => Box::new(commit::CommitExtend),
+            Op::CommitReword => Box::new(commit::CommitReword),
+            Op::CommitMsgPrevious => Box::new(commit::CommitMsgPrevious),
+            Op::CommitMsgNext => Box::new(commit::CommitMsgNext),
+            Op::CommitMsgComplete => Box::new(commit::CommitMsgComplete),
+            Op::CommitMsgSubmit => Box::new(commit::CommitMsgSubmit),
+            Op::CommitMsgAbort => Box::new(commit::CommitMsgAbort),
+            Op::DiffRange => Box::new(diff::DiffRange),
+            Op::Difftool => Box::new(diff::Difftool),
+            Op::DiffPager => Box::new(diff::DiffPager),
+            Op::DiffWorktree => Box::new(diff::DiffWorktree),
             Op::FetchAll => Box::new(fetch::FetchAll),
             Op::FetchElsewhere => Box::new(fetch::FetchElsewhere),
+            Op::FetchFromPushRemote => Box::new(fetch::FetchFromPushRemote),
+            Op::FetchFromUpstream => Box::new(fetch::FetchFromUpstream),
             Op::LogCurrent => Box::new(log::LogCurrent),
+            Op::LogFile => Box::new(log::LogFile),
+            Op::LogReflog => Box::new(log::LogReflog),
+            Op::LogShortlog => Box::new(log::LogShortlog),
+            Op::Merge => Box::new(merge::Merge),
+            Op::MergeSquash => Box::new(merge::MergeSquash),
+            Op::MergeContinue => Box::new(merge::MergeContinue),
+            Op::MergeAbort => Box::new(merge::MergeAbort),
             Op::PullFromPushRemote => Box::new(pull::PullFromPushRemote),
             Op::PullFromUpstream => Box::new(pull::PullFromUpstream),
             Op::PullFromElsewhere => Box::new(pull::PullFromElsewhere),
             Op::PushToPushRemote => Box::new(push::PushToPushRemote),
             Op::PushToUpstream => Box::new(push::PushToUpstream),
             Op::PushToElsewhere => Box::new(push::PushToElsewhere),
+            Op::PushTags => Box::new(push::PushTags),
             Op::RebaseAbort => Box::new(rebase::RebaseAbort),
             Op::RebaseContinue => Box::new(rebase::RebaseContinue),
             Op::RebaseElsewhere => Box::new(rebase::RebaseElsewhere),
+            Op::RebaseSplit => Box::new(rebase::RebaseSplit),
+            Op::RebaseTodoPick => Box::new(rebase_todo::SetTodoAction(TodoAction::Pick)),
+            Op::RebaseTodoReword => Box::new(rebase_todo::SetTodoAction(TodoAction::Reword)),
+            Op::RebaseTodoEdit => Box::new(rebase_todo::SetTodoAction(TodoAction::Edit)),
+            Op::RebaseTodoSquash => Box::new(rebase_todo::SetTodoAction(TodoAction::Squash)),
+            Op::RebaseTodoFixup => Box::new(rebase_todo::SetTodoAction(TodoAction::Fixup)),
+            Op::RebaseTodoDrop => Box::new(rebase_todo::SetTodoAction(TodoAction::Drop)),
+            Op::RebaseTodoMoveUp => Box::new(rebase_todo::MoveTodoUp),
+            Op::RebaseTodoMoveDown => Box::new(rebase_todo::MoveTodoDown),
+            Op::RebaseTodoSubmit => Box::new(rebase_todo::RebaseTodoSubmit),
+            Op::RebaseTodoAbort => Box::new(rebase_todo::RebaseTodoAbort),
+            Op::AddRemote => Box::new(remote::AddRemote),
+            Op::RenameRemote => Box::new(remote::RenameRemote),
+            Op::RemoveRemote => Box::new(remote::RemoveRemote),
+            Op::PruneRemote => Box::new(remote::PruneRemote),
+            Op::SetRemoteUrl => Box::new(remote::SetRemoteUrl),
+            Op::KeepOurs => Box::new(resolve::KeepOurs),
+            Op::KeepTheirs => Box::new(resolve::KeepTheirs),
+            Op::KeepBoth => Box::new(resolve::KeepBoth),
+            Op::Mergetool => Box::new(resolve::Mergetool),
+            Op::FormatPatch => Box::new(patch::FormatPatch),
+            Op::ApplyPatches => Box::new(patch::ApplyPatches),
+            Op::ApplyPatchesFromClipboard => Box::new(patch::ApplyPatchesFromClipboard),
+            Op::ApplyPatch => Box::new(patch::ApplyPatch),
+            Op::ApplyPatchToIndex => Box::new(patch::ApplyPatchToIndex),
+            Op::ReverseApply => Box::new(patch::ReverseApply),
+            Op::AmContinue => Box::new(patch::AmContinue),
+            Op::AmSkip => Box::new(patch::AmSkip),
+            Op::AmAbort => Box::new(patch::AmAbort),
             Op::ShowRefs => Box::new(show_refs::ShowRefs),
+            Op::BrowseTree => Box::new(tree::BrowseTree),
+            Op::BlameLine => Box::new(blame::BlameLine),
+            Op::ShowFileAtRevision => Box::new(show::ShowFileAtRevision),
+            Op::ShowRepositories => Box::new(repositories::ShowRepositories),
+            Op::ShowHelp => Box::new(editor::ShowHelp),
+            Op::ShellCommand => Box::new(shell::ShellCommand),
+            Op::Shell => Box::new(shell::Shell),
+            Op::ToggleCmdHistory => Box::new(cmd_history::ToggleCmdHistory),
+            Op::KillCmd => Box::new(pending_cmd::KillCmd),
+            Op::ToggleCmdOutput => Box::new(pending_cmd::ToggleCmdOutput),
+            Op::DismissCmdOutput => Box::new(pending_cmd::DismissCmdOutput),
             Op::Stash => Box::new(stash::Stash),
             Op::StashApply => Box::new(stash::StashApply),
             Op::StashIndex => Box::new(stash::StashIndex),
@@ -148,23 +431,54 @@ impl Op {
             Op::StashKeepIndex => Box::new(stash::StashKeepIndex),
             Op::StashPop => Box::new(stash::StashPop),
             Op::StashDrop => Box::new(stash::StashDrop),
+            Op::SubmoduleInit => Box::new(submodule::SubmoduleInit),
+            Op::SubmoduleUpdate => Box::new(submodule::SubmoduleUpdate),
+            Op::SubmoduleSync => Box::new(submodule::SubmoduleSync),
+            Op::SubmoduleEnter => Box::new(submodule::SubmoduleEnter),
+            Op::CreateTag => Box::new(tag::CreateTag),
+            Op::CreateAnnotatedTag => Box::new(tag::CreateAnnotatedTag),
+            Op::DeleteTag => Box::new(tag::DeleteTag),
+            Op::PushTag => Box::new(tag::PushTag),
 
             Op::CommitFixup => Box::new(commit::CommitFixup),
+            Op::CommitSquash => Box::new(commit::CommitSquash),
             Op::CommitInstantFixup => Box::new(commit::CommitInstantFixup),
+            Op::CommitInstantSquash => Box::new(commit::CommitInstantSquash),
             Op::Discard => Box::new(discard::Discard),
+            Op::UndoDiscard => Box::new(discard::UndoDiscard),
             Op::LogOther => Box::new(log::LogOther),
             Op::RebaseAutosquash => Box::new(rebase::RebaseAutosquash),
             Op::RebaseInteractive => Box::new(rebase::RebaseInteractive),
+            Op::RebaseReword => Box::new(rebase::RebaseCommit(TodoAction::Reword)),
+            Op::RebaseDrop => Box::new(rebase::RebaseCommit(TodoAction::Drop)),
+            Op::RebaseSquash => Box::new(rebase::RebaseCommit(TodoAction::Squash)),
+            Op::RebaseEdit => Box::new(rebase::RebaseCommit(TodoAction::Edit)),
+            Op::RebaseSubset => Box::new(rebase::RebaseSubset),
             Op::ResetSoft => Box::new(reset::ResetSoft),
             Op::ResetMixed => Box::new(reset::ResetMixed),
             Op::ResetHard => Box::new(reset::ResetHard),
+            Op::ResetKeep => Box::new(reset::ResetKeep),
+            Op::ResetFile => Box::new(reset::ResetFile),
             Op::RevertAbort => Box::new(revert::RevertAbort),
             Op::RevertContinue => Box::new(revert::RevertContinue),
             Op::RevertCommit => Box::new(revert::RevertCommit),
             Op::Show => Box::new(show::Show),
             Op::Stage => Box::new(stage::Stage),
+            Op::StageAllTracked => Box::new(stage::StageAllTracked),
+            Op::StageAll => Box::new(stage::StageAll),
             Op::Unstage => Box::new(unstage::Unstage),
-            Op::CopyHash => Box::new(copy_hash::CopyHash),
+            Op::UnstageAll => Box::new(unstage::UnstageAll),
+            Op::CopyHash => Box::new(copy::CopyHash),
+            Op::CopyBranchName => Box::new(copy::CopyBranchName),
+            Op::CopyPath => Box::new(copy::CopyPath),
+            Op::CopyHunk => Box::new(copy::CopyHunk),
+            Op::CopyMessage => Box::new(copy::CopyMessage),
+            Op::CopyDescribe => Box::new(copy::CopyDescribe),
+            Op::OpenInForge => Box::new(forge::OpenInForge),
+            Op::ShowPullRequests => Box::new(forge::ShowPullRequests),
+            Op::CheckoutPullRequest => Box::new(forge::CheckoutPullRequest),
+            Op::CreatePullRequest => Box::new(forge::CreatePullRequest),
+            Op::OpenFailedCheck => Box::new(forge::OpenFailedCheck),
         }
     }
 }
@@ -173,27 +487,63 @@ impl Display for Menu {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
             Menu::Root => "Root",
+            Menu::Bisect => "Bisect",
             Menu::Branch => "Branch",
+            Menu::CherryPick => "Cherry-pick",
+            Menu::Clean => "Clean",
+            Menu::CleanPreview => "Clean",
             Menu::Commit => "Commit",
+            Menu::Copy => "Copy",
+            Menu::CommitMsg => "Commit message",
+            Menu::Diff => "Diff",
+            Menu::DiffOptions => "Diff options",
             Menu::Fetch => "Fetch",
+            Menu::Forge => "Forge",
             Menu::Help => "Help",
+            Menu::Ignore => "Ignore",
             Menu::Log => "Log",
+            Menu::Merge => "Merge",
+            Menu::Patch => "Patch",
             Menu::Pull => "Pull",
             Menu::Push => "Push",
             Menu::Rebase => "Rebase",
+            Menu::RebaseTodo => "Rebase todo",
+            Menu::Remote => "Remote",
             Menu::Reset => "Reset",
+            Menu::Resolve => "Resolve",
             Menu::Revert => "Revert",
+            Menu::Shell => "Shell",
             Menu::Stash => "Stash",
+            Menu::Submodule => "Submodule",
+            Menu::Tag => "Tag",
         })
     }
 }
 
-pub(crate) fn create_y_n_prompt(mut action: Action, prompt: &'static str) -> Action {
+/// Git only reports progress to terminals unless asked to. While commands run synchronously,
+/// nothing would be shown before they're done anyway.
+pub(crate) fn progress_arg(state: &State) -> Option<&'static str> {
+    state.enable_async_cmds.then_some("--progress")
+}
+
+pub(crate) fn create_y_n_prompt(action: Action, prompt: &'static str) -> Action {
+    create_confirm_prompt(action, prompt, |_| true)
+}
+
+/// Like `create_y_n_prompt`, unless `enabled` says confirming is turned off in the config.
+pub(crate) fn create_confirm_prompt(
+    action: Action,
+    prompt: &'static str,
+    enabled: fn(&Config) -> bool,
+) -> Action {
+    let action = Rc::new(RefCell::new(action));
+    let confirmed_action = Rc::clone(&action);
+
     let update_fn = Rc::new(move |state: &mut State, term: &mut Term| {
         if state.prompt.state.status().is_pending() {
             match state.prompt.state.value() {
                 "y" => {
-                    Rc::get_mut(&mut action).unwrap()(state, term)?;
+                    run_shared(&confirmed_action, state, term)?;
                     state.prompt.reset(term)?;
                 }
                 "" => (),
@@ -208,7 +558,11 @@ pub(crate) fn create_y_n_prompt(mut action: Action, prompt: &'static str) -> Act
         Ok(())
     });
 
-    Rc::new(move |state: &mut State, _term: &mut Term| {
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        if !enabled(&state.config) {
+            return run_shared(&action, state, term);
+        }
+
         state.prompt.set(PromptData {
             prompt_text: format!("{} (y or n)", prompt).into(),
             update_fn: update_fn.clone(),
@@ -218,6 +572,11 @@ pub(crate) fn create_y_n_prompt(mut action: Action, prompt: &'static str) -> Act
     })
 }
 
+fn run_shared(action: &RefCell<Action>, state: &mut State, term: &mut Term) -> Res<()> {
+    let mut action = action.borrow_mut();
+    Rc::get_mut(&mut action).unwrap()(state, term)
+}
+
 pub(crate) fn create_prompt(
     prompt: &'static str,
     on_success: fn(&mut State, &mut Term, &str) -> Res<()>,
@@ -244,6 +603,90 @@ pub(crate) fn create_prompt_with_default(
     })
 }
 
+/// Like `create_prompt_with_default`, listing the `candidates` matching what's typed to pick from.
+pub(crate) fn create_picker_prompt(
+    prompt: &'static str,
+    on_success: fn(&mut State, &mut Term, &str) -> Res<()>,
+    create_default_value: fn(&State) -> Option<String>,
+    candidates: fn(&State) -> Res<Vec<Candidate>>,
+    hide_menu: bool,
+) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        set_prompt(
+            state,
+            prompt,
+            Box::new(on_success),
+            Box::new(create_default_value),
+            hide_menu,
+        );
+        state.prompt.picker = Some(Picker::new(candidates(state)?));
+        Ok(())
+    })
+}
+
+/// Like `create_prompt_with_default` with the revision at the cursor as default,
+/// picking from branches, tags and recent commits, and stepping through the revisions
+/// entered before with up/down.
+pub(crate) fn create_rev_prompt(
+    prompt: &'static str,
+    on_success: fn(&mut State, &mut Term, &str) -> Res<()>,
+    hide_menu: bool,
+) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        set_rev_prompt(state, prompt, Box::new(on_success), hide_menu)
+    })
+}
+
+/// Like `create_rev_prompt`, but commits marked in a log default to the range of all of them.
+pub(crate) fn create_range_prompt(
+    prompt: &'static str,
+    on_success: fn(&mut State, &mut Term, &str) -> Res<()>,
+    hide_menu: bool,
+) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        set_rev_prompt_with_default(
+            state,
+            prompt,
+            Box::new(on_success),
+            Box::new(|state| marked_range(state).or_else(|| selected_rev(state))),
+            hide_menu,
+        )
+    })
+}
+
+pub(crate) fn set_rev_prompt(
+    state: &mut State,
+    prompt: &'static str,
+    on_success: PromptAction,
+    hide_menu: bool,
+) -> Res<()> {
+    set_rev_prompt_with_default(state, prompt, on_success, Box::new(selected_rev), hide_menu)
+}
+
+pub(crate) fn set_rev_prompt_with_default(
+    state: &mut State,
+    prompt: &'static str,
+    on_success: PromptAction,
+    default_fn: DefaultFn,
+    hide_menu: bool,
+) -> Res<()> {
+    set_prompt(
+        state,
+        prompt,
+        Box::new(move |state, term, rev| {
+            if let Err(err) = prompt::save_to_history(&state.repo, rev) {
+                ::log::warn!("Couldn't save revision history: {}", err);
+            }
+            on_success(state, term, rev)
+        }),
+        default_fn,
+        hide_menu,
+    );
+    state.prompt.picker = Some(Picker::new(picker::revs(state)?));
+    state.prompt.history = Some(History::new(prompt::load_history(&state.repo)));
+    Ok(())
+}
+
 type DefaultFn = Box<dyn Fn(&State) -> Option<String>>;
 type PromptAction = Box<dyn Fn(&mut State, &mut Term, &str) -> Res<()>>;
 
@@ -269,7 +712,15 @@ pub(crate) fn set_prompt(
         update_fn: Rc::new(move |state, term| {
             if state.prompt.state.status().is_done() {
                 let input = state.prompt.state.value().to_string();
+                let picked = state
+                    .prompt
+                    .picker
+                    .as_ref()
+                    .and_then(Picker::selected)
+                    .filter(|candidate| candidate.value == input)
+                    .cloned();
                 state.prompt.reset(term)?;
+                state.prompt.picked = picked;
 
                 let default_value = default_fn(state);
                 let value = match (input.as_str(), &default_value) {
@@ -280,7 +731,8 @@ pub(crate) fn set_prompt(
 
                 on_success(state, term, value)?;
 
-                if hide_menu {
+                // Keep the menu hidden if on_success opened another prompt
+                if hide_menu && state.prompt.data.is_none() {
                     state.unhide_menu();
                 }
             }
@@ -289,10 +741,100 @@ pub(crate) fn set_prompt(
     });
 }
 
+/// Runs the actions one after another, stopping at the first error.
+pub(crate) fn chain_actions(mut actions: Vec<Action>) -> Option<Action> {
+    if actions.is_empty() {
+        return None;
+    }
+
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        for action in actions.iter_mut() {
+            Rc::get_mut(action).unwrap()(state, term)?;
+        }
+        Ok(())
+    }))
+}
+
+/// If the targets are all lines of the same hunk, the hunk and the range of lines spanned.
+pub(crate) fn hunk_line_range(targets: &[TargetData]) -> Option<(Arc<Hunk>, Range<usize>)> {
+    let Some(TargetData::HunkLine(hunk, _)) = targets.first() else {
+        return None;
+    };
+
+    let lines = targets
+        .iter()
+        .map(|target| match target {
+            TargetData::HunkLine(h, i) if Arc::ptr_eq(h, hunk) => Some(*i),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let start = *lines.iter().min()?;
+    let end = *lines.iter().max()? + 1;
+    Some((Arc::clone(hunk), start..end))
+}
+
+pub(crate) fn selected_branch(state: &State) -> Option<String> {
+    match &state.screen().get_selected_item().target_data {
+        Some(TargetData::Branch(branch)) => Some(branch.to_owned()),
+        _ => None,
+    }
+}
+
 pub(crate) fn selected_rev(state: &State) -> Option<String> {
     match &state.screen().get_selected_item().target_data {
         Some(TargetData::Branch(branch)) => Some(branch.to_owned()),
         Some(TargetData::Commit(commit)) => Some(commit.to_owned()),
+        Some(TargetData::Tag(tag)) => Some(tag.to_owned()),
+        _ => None,
+    }
+}
+
+/// The oldest and the newest of the commits marked in a log, abbreviated,
+/// if more than one is marked.
+pub(crate) fn marked_commits(state: &State) -> Option<(String, String)> {
+    let commits = state
+        .screen()
+        .get_region_targets()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|target| short_id(state, target.commitish()?))
+        .collect::<Vec<_>>();
+
+    match &commits[..] {
+        [newest, .., oldest] => Some((oldest.clone(), newest.clone())),
         _ => None,
     }
 }
+
+/// The commits marked in a log as a range, like `oldest^..newest`. A root commit has no parent to
+/// start after, then it's as many commits as there are up to the newest, like `-3 newest`.
+pub(crate) fn marked_range(state: &State) -> Option<String> {
+    let (oldest, newest) = marked_commits(state)?;
+    let oldest_commit = state
+        .repo
+        .revparse_single(&oldest)
+        .ok()?
+        .peel_to_commit()
+        .ok()?;
+    if oldest_commit.parent_count() > 0 {
+        return Some(format!("{}^..{}", oldest, newest));
+    }
+
+    let mut revwalk = state.repo.revwalk().ok()?;
+    revwalk
+        .push(state.repo.revparse_single(&newest).ok()?.id())
+        .ok()?;
+    Some(format!("-{} {}", revwalk.count(), newest))
+}
+
+pub(crate) fn short_id(state: &State, rev: &str) -> Option<String> {
+    let object = state
+        .repo
+        .revparse_single(rev)
+        .ok()?
+        .peel(ObjectType::Commit)
+        .ok()?;
+    let short_id = object.short_id().ok()?;
+    short_id.as_str().map(String::from)
+}
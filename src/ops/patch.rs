@@ -0,0 +1,308 @@
+use super::{
+    create_prompt, create_prompt_with_default, marked_range, set_prompt, short_id, Action, OpTrait,
+};
+use crate::{git, items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use std::{ffi::OsString, fs, path::Path, process::Command, rc::Rc};
+
+const AM_ARGS: &[&str] = &["--3way", "--signoff"];
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--3way", "Fall back on 3-way merge", false),
+        Arg::new_flag("--signoff", "Add Signed-off-by lines", false),
+    ]
+}
+
+/// The menu's args among those a command takes, as `git apply` and `format-patch` take only some of `am`'s.
+fn menu_args(state: &State, accepted: &[&str]) -> Vec<OsString> {
+    state
+        .pending_menu
+        .as_ref()
+        .map(|menu| menu.args())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|arg| accepted.iter().any(|accepted| arg == accepted))
+        .collect()
+}
+
+pub(crate) struct FormatPatch;
+impl OpTrait for FormatPatch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Create patches of",
+            format_patch_prompt,
+            selected_commits,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Create patches".into()
+    }
+}
+
+/// The commits marked in a log, oldest to newest, or else the one at point.
+fn selected_commits(state: &State) -> Option<String> {
+    marked_range(state).or_else(|| {
+        // Unlike `<commit>^!`, this works for a root commit too
+        let target = state.screen().get_selected_item().target_data.as_ref();
+        Some(format!("-1 {}", short_id(state, target?.commitish()?)?))
+    })
+}
+
+fn format_patch_prompt(state: &mut State, _term: &mut Term, range: &str) -> Res<()> {
+    if range.is_empty() {
+        state.close_menu();
+        return Err("Commits required".into());
+    }
+
+    let range = range.to_string();
+    set_prompt(
+        state,
+        "Into directory",
+        Box::new(move |state, term, dir| format_patch(state, term, &range, dir)),
+        Box::new(|_| Some(".".into())),
+        true,
+    );
+    Ok(())
+}
+
+fn format_patch(state: &mut State, term: &mut Term, range: &str, dir: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["format-patch"]);
+    cmd.args(menu_args(state, &["--signoff"]));
+    cmd.args(["--output-directory", dir]);
+    cmd.args(range.split_whitespace());
+
+    state.screen_mut().clear_mark();
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct ApplyPatches;
+impl OpTrait for ApplyPatches {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt(
+            "Apply patches from file or directory",
+            apply_patches,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Apply patches".into()
+    }
+}
+
+/// A directory is taken to hold patches made with `format-patch`, applied in the order of their names.
+fn apply_patches(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    if input.is_empty() {
+        state.close_menu();
+        return Err("Patch file required".into());
+    }
+
+    let workdir = state.repo.workdir().ok_or("No workdir")?;
+    let files = if workdir.join(input).is_dir() {
+        patches_in(workdir, Path::new(input))?
+    } else {
+        vec![input.into()]
+    };
+
+    let mut cmd = Command::new("git");
+    cmd.args(["am"]);
+    cmd.args(menu_args(state, AM_ARGS));
+    cmd.args(files);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+fn patches_in(workdir: &Path, dir: &Path) -> Res<Vec<OsString>> {
+    let mut files = fs::read_dir(workdir.join(dir))?
+        .map(|entry| Ok(dir.join(entry?.file_name())))
+        .collect::<Res<Vec<_>>>()?
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "patch"))
+        .map(|path| path.into_os_string())
+        .collect::<Vec<_>>();
+
+    if files.is_empty() {
+        return Err(format!("No .patch files in {}", dir.display()).into());
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+pub(crate) struct ApplyPatchesFromClipboard;
+impl OpTrait for ApplyPatchesFromClipboard {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            let patches = match &mut state.clipboard {
+                Some(clipboard) => clipboard.get_text()?,
+                None => return Err("Clipboard not available".into()),
+            };
+
+            let mut cmd = Command::new("git");
+            cmd.args(["am"]);
+            cmd.args(menu_args(state, AM_ARGS));
+            state.run_cmd(term, patches.as_bytes(), cmd)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Apply patches from clipboard".into()
+    }
+}
+
+pub(crate) struct ApplyPatch;
+impl OpTrait for ApplyPatch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt(
+            "Apply patch to worktree",
+            |state, term, file| apply_patch(state, term, file, false),
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Apply patch to worktree".into()
+    }
+}
+
+pub(crate) struct ApplyPatchToIndex;
+impl OpTrait for ApplyPatchToIndex {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt(
+            "Apply patch to index",
+            |state, term, file| apply_patch(state, term, file, true),
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Apply patch to index".into()
+    }
+}
+
+fn apply_patch(state: &mut State, term: &mut Term, file: &str, cached: bool) -> Res<()> {
+    if file.is_empty() {
+        state.close_menu();
+        return Err("Patch file required".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["apply"]);
+    if cached {
+        cmd.args(["--cached"]);
+    }
+    cmd.args(menu_args(state, &["--3way"]));
+    cmd.args([file]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+/// Undoes the changes of a hunk or commit in the worktree, without committing anything like `git revert` would.
+pub(crate) struct ReverseApply;
+impl OpTrait for ReverseApply {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let patch: ReversedPatch = match target.cloned() {
+            Some(TargetData::Hunk(hunk)) => Box::new(move |_state| Ok(hunk.format_patch())),
+            Some(target) => {
+                let commit = target.commitish()?.to_string();
+                Box::new(move |state| git::commit_patch(&state.repo, &commit))
+            }
+            None => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let input = patch(state)?;
+
+            let mut cmd = Command::new("git");
+            cmd.args(["apply", "--reverse"]);
+            cmd.args(menu_args(state, &["--3way"]));
+
+            state.close_menu();
+            state.run_cmd(term, &input, cmd)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Reverse-apply to worktree".into()
+    }
+}
+
+type ReversedPatch = Box<dyn Fn(&State) -> Res<Vec<u8>>>;
+
+pub(crate) struct AmContinue;
+impl OpTrait for AmContinue {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["am", "--continue"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_applying_patches(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Continue".into()
+    }
+}
+
+pub(crate) struct AmSkip;
+impl OpTrait for AmSkip {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["am", "--skip"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_applying_patches(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Skip".into()
+    }
+}
+
+pub(crate) struct AmAbort;
+impl OpTrait for AmAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["am", "--abort"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        is_applying_patches(state)
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Abort".into()
+    }
+}
+
+fn is_applying_patches(state: &State) -> bool {
+    git::am_status(&state.repo).is_ok_and(|status| status.is_some())
+}
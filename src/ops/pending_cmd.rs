@@ -0,0 +1,61 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term};
+use std::rc::Rc;
+
+pub(crate) struct KillCmd;
+impl OpTrait for KillCmd {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let Some(pending_cmd) = &mut state.pending_cmd else {
+                return Err("No command is running".into());
+            };
+
+            pending_cmd.kill()
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        state.pending_cmd.is_some()
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Kill command".into()
+    }
+}
+
+pub(crate) struct ToggleCmdOutput;
+impl OpTrait for ToggleCmdOutput {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.cmd_output_expanded = !state.cmd_output_expanded;
+            Ok(())
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        state.pending_cmd.is_some()
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Expand/collapse command output".into()
+    }
+}
+
+pub(crate) struct DismissCmdOutput;
+impl OpTrait for DismissCmdOutput {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            // The output is still in the command history
+            state.current_cmd_log.clear();
+            Ok(())
+        }))
+    }
+
+    fn is_available(&self, state: &State) -> bool {
+        state.pending_cmd.is_some()
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Dismiss command output".into()
+    }
+}
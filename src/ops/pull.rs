@@ -1,4 +1,4 @@
-use super::{create_prompt, Action, OpTrait};
+use super::{create_prompt, create_prompt_with_default, remote::selected_remote, Action, OpTrait};
 use crate::{
     git::{
         self,
@@ -13,7 +13,12 @@ use crate::{
 use std::{process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
-    vec![Arg::new_flag("--rebase", "Rebase local commits", false)]
+    vec![
+        Arg::new_flag("--rebase", "Rebase local commits", false),
+        Arg::new_flag("--prune", "Prune deleted branches", false),
+        Arg::new_flag("--tags", "Fetch all tags", false),
+        Arg::new_flag("--all", "Fetch from all remotes", false),
+    ]
 }
 
 pub(crate) struct PullFromPushRemote;
@@ -99,7 +104,12 @@ fn set_upstream_and_pull(state: &mut State, term: &mut Term, upstream_name: &str
 pub(crate) struct PullFromElsewhere;
 impl OpTrait for PullFromElsewhere {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt("Select remote", pull_elsewhere, true))
+        Some(create_prompt_with_default(
+            "Select remote",
+            pull_elsewhere,
+            selected_remote,
+            true,
+        ))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -112,10 +122,17 @@ fn pull_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
 }
 
 fn pull(state: &mut State, term: &mut Term, extra_args: &[&str]) -> Res<()> {
+    let args = state.pending_menu.as_ref().unwrap().args();
+
     let mut cmd = Command::new("git");
     cmd.args(["pull"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.args(extra_args);
+    cmd.args(super::progress_arg(state));
+    // Git refuses a remote and refspec along with `--all`, which pulls from the upstream
+    let all = args.iter().any(|arg| arg == "--all");
+    cmd.args(args);
+    if !all {
+        cmd.args(extra_args);
+    }
 
     state.close_menu();
     state.run_cmd_async(term, &[], cmd)?;
@@ -1,4 +1,4 @@
-use super::{create_prompt, Action, OpTrait};
+use super::{create_prompt, create_prompt_with_default, tag, Action, OpTrait};
 use crate::git;
 use crate::git::remote::{
     get_push_remote, get_upstream_components, get_upstream_shortname, set_push_remote,
@@ -10,6 +10,7 @@ pub(crate) fn init_args() -> Vec<Arg> {
     vec![
         Arg::new_flag("--force-with-lease", "Force with lease", false),
         Arg::new_flag("--force", "Force", false),
+        Arg::new_flag("--set-upstream", "Set upstream", false),
         Arg::new_flag("--no-verify", "Disable hooks", false),
         Arg::new_flag("--dry-run", "Dry run", false),
     ]
@@ -96,7 +97,11 @@ fn set_upstream_and_push(state: &mut State, term: &mut Term, upstream_name: &str
 pub(crate) struct PushToElsewhere;
 impl OpTrait for PushToElsewhere {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt("Select remote", push_elsewhere, true))
+        Some(create_prompt(
+            "Select remote or remote/branch",
+            push_elsewhere,
+            true,
+        ))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -104,8 +109,38 @@ impl OpTrait for PushToElsewhere {
     }
 }
 
-fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
-    push(state, term, &[remote])
+/// Pushes to `remote`, or HEAD to `branch` if given as `remote/branch`.
+/// Anything else, such as a url, is passed to `git push` as is.
+fn push_elsewhere(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    if state.repo.find_remote(input).is_err() {
+        if let Some((remote, branch)) = input.split_once('/') {
+            if state.repo.find_remote(remote).is_ok() && !branch.is_empty() {
+                return push_head_to(state, term, remote, branch);
+            }
+        }
+    }
+
+    push(state, term, &[input])
+}
+
+pub(crate) struct PushTags;
+impl OpTrait for PushTags {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Push tags to",
+            push_tags,
+            tag::default_remote,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "tags".into()
+    }
+}
+
+fn push_tags(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
+    push(state, term, &[remote, "--tags"])
 }
 
 fn push_head_to(state: &mut State, term: &mut Term, remote: &str, branch: &str) -> Res<()> {
@@ -117,6 +152,7 @@ fn push_head_to(state: &mut State, term: &mut Term, remote: &str, branch: &str)
 fn push(state: &mut State, term: &mut Term, extra_args: &[&str]) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["push"]);
+    cmd.args(super::progress_arg(state));
     cmd.args(state.pending_menu.as_ref().unwrap().args());
     cmd.args(extra_args);
 
@@ -1,6 +1,21 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{
+    create_confirm_prompt, create_rev_prompt, rebase_todo, set_rev_prompt_with_default, Action,
+    OpTrait,
+};
+use crate::{
+    git,
+    items::TargetData,
+    menu::arg::Arg,
+    screen::{
+        self,
+        rebase_todo::{RebaseTodo, TodoAction},
+    },
+    state::State,
+    term::Term,
+    Res,
+};
 use std::{
+    cell::RefCell,
     ffi::{OsStr, OsString},
     process::Command,
     rc::Rc,
@@ -61,12 +76,7 @@ impl OpTrait for RebaseAbort {
 pub(crate) struct RebaseElsewhere;
 impl OpTrait for RebaseElsewhere {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
-            "Rebase onto",
-            rebase_elsewhere,
-            selected_rev,
-            true,
-        ))
+        Some(create_rev_prompt("Rebase onto", rebase_elsewhere, true))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -85,16 +95,101 @@ fn rebase_elsewhere(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
     Ok(())
 }
 
+/// Moves commits onto another base: those marked in a log, or those from the one at point up to HEAD.
+pub(crate) struct RebaseSubset;
+impl OpTrait for RebaseSubset {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(rebase_subset(target?.commitish()?.to_string(), None))
+    }
+
+    fn get_region_action(&self, targets: &[TargetData]) -> Option<Action> {
+        let commits = targets
+            .iter()
+            .filter_map(TargetData::commitish)
+            .collect::<Vec<_>>();
+
+        match (commits.first(), commits.last()) {
+            (Some(newest), Some(oldest)) => {
+                Some(rebase_subset(oldest.to_string(), Some(newest.to_string())))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "subset onto elsewhere".into()
+    }
+}
+
+fn rebase_subset(oldest: String, newest: Option<String>) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        let (oldest, newest) = (oldest.clone(), newest.clone());
+        set_rev_prompt_with_default(
+            state,
+            "Rebase subset onto",
+            Box::new(move |state, term, onto| {
+                rebase_onto(state, term, onto, &oldest, newest.as_deref())
+            }),
+            Box::new(|_| None),
+            true,
+        )
+    })
+}
+
+fn rebase_onto(
+    state: &mut State,
+    term: &mut Term,
+    onto: &str,
+    oldest: &str,
+    newest: Option<&str>,
+) -> Res<()> {
+    if onto.is_empty() {
+        state.close_menu();
+        return Err("New base required".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.arg("rebase");
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg("--onto").arg(onto).arg(parent(OsStr::new(oldest)));
+
+    // Other than HEAD, the newest commit is what git checks out and rebases, detached
+    if let Some(newest) = newest.filter(|newest| !is_head(state, newest)) {
+        cmd.arg(newest);
+    }
+
+    state.close_menu();
+    state.run_cmd_interactive(term, cmd)
+}
+
+fn is_head(state: &State, rev: &str) -> bool {
+    let id = |rev: &str| {
+        Some(
+            state
+                .repo
+                .revparse_single(rev)
+                .ok()?
+                .peel_to_commit()
+                .ok()?
+                .id(),
+        )
+    };
+    id(rev).is_some_and(|id_of_rev| id("HEAD") == Some(id_of_rev))
+}
+
 pub(crate) struct RebaseInteractive;
 impl OpTrait for RebaseInteractive {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => {
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => {
                 let rev = OsString::from(r);
-                Rc::new(move |state: &mut State, term: &mut Term| {
+                Rc::new(move |state: &mut State, _term: &mut Term| {
                     let args = state.pending_menu.as_ref().unwrap().args();
-                    state.close_menu();
-                    state.run_cmd_interactive(term, rebase_interactive_cmd(&args, &rev))
+                    goto_rebase_todo_screen(state, &rev, args)
                 })
             }
             _ => return None,
@@ -111,12 +206,22 @@ impl OpTrait for RebaseInteractive {
     }
 }
 
-fn rebase_interactive_cmd(args: &[OsString], rev: &OsStr) -> Command {
-    let mut cmd = Command::new("git");
-    cmd.args(["rebase", "-i"]);
-    cmd.args(args);
-    cmd.arg(parent(rev));
-    cmd
+fn goto_rebase_todo_screen(state: &mut State, rev: &OsStr, args: Vec<OsString>) -> Res<()> {
+    let base = parent(rev);
+    let todo = RebaseTodo::new(&state.repo, &base.to_string_lossy(), args)?;
+    if todo.lines.is_empty() {
+        state.close_menu();
+        return Err("Nothing to rebase".into());
+    }
+
+    let size = state.screen().size;
+    state.screens.push(screen::rebase_todo::create(
+        Rc::clone(&state.config),
+        Rc::new(RefCell::new(todo)),
+        size,
+    )?);
+    state.close_menu();
+    Ok(())
 }
 
 fn parent(reference: &OsStr) -> OsString {
@@ -125,11 +230,135 @@ fn parent(reference: &OsStr) -> OsString {
     parent
 }
 
+/// Rebases the commit at point with `TodoAction`, without editing the todo: reword, drop or edit it,
+/// or squash it into its parent.
+pub(crate) struct RebaseCommit(pub TodoAction);
+impl OpTrait for RebaseCommit {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let action = match target.and_then(TargetData::commitish) {
+            Some(r) => {
+                let (rev, todo_action) = (OsString::from(r), self.0);
+                Rc::new(move |state: &mut State, term: &mut Term| {
+                    let args = state.pending_menu.as_ref().unwrap().args();
+                    state.close_menu();
+                    rebase_commit(state, term, &rev, args, todo_action)
+                })
+            }
+            _ => return None,
+        };
+
+        if self.0 == TodoAction::Drop {
+            return Some(create_confirm_prompt(
+                action,
+                "Really drop commit?",
+                |config| config.general.confirm_drop_commit.enabled,
+            ));
+        }
+
+        Some(action)
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        match self.0 {
+            TodoAction::Squash => "squash into parent".into(),
+            action => action.as_str().into(),
+        }
+    }
+}
+
+/// Rebases with only the commit at `rev` changed to `action`, a squash goes into the parent.
+fn rebase_commit(
+    state: &mut State,
+    term: &mut Term,
+    rev: &OsStr,
+    args: Vec<OsString>,
+    action: TodoAction,
+) -> Res<()> {
+    let (commit, parent_id, grandparents) = {
+        let commit = state
+            .repo
+            .revparse_single(&rev.to_string_lossy())?
+            .peel_to_commit()?;
+        let parent = commit.parents().next();
+        (
+            commit.id(),
+            parent.as_ref().map(|parent| parent.id()),
+            parent.map_or(0, |parent| parent.parent_count()),
+        )
+    };
+
+    let squash = action == TodoAction::Squash;
+    let base = match (parent_id, squash) {
+        (None, _) => return Err("Can't rebase the root commit".into()),
+        (Some(_), true) if grandparents == 0 => {
+            return Err("Can't squash into the root commit".into())
+        }
+        (Some(_), true) => parent(&parent(rev)),
+        (Some(_), false) => parent(rev),
+    };
+
+    let mut todo = RebaseTodo::new(&state.repo, &base.to_string_lossy(), args)?;
+    let i = todo
+        .lines
+        .iter()
+        .position(|line| line.commit == commit)
+        .ok_or("Commit isn't on the current branch")?;
+    let mut line = todo.lines.remove(i);
+    line.action = action;
+
+    // Squashes go into the commit before them, which is the parent only on a straight history
+    let at = match squash {
+        true => {
+            let parent_id = parent_id.expect("Checked above");
+            todo.lines
+                .iter()
+                .position(|line| line.commit == parent_id)
+                .ok_or("Parent is a merge, can't squash into it")?
+                + 1
+        }
+        false => i,
+    };
+    todo.lines.insert(at, line);
+
+    rebase_todo::run_rebase(state, term, &todo)
+}
+
+/// Undoes the commit a rebase stopped to edit, leaving its changes to be committed in parts.
+pub(crate) struct RebaseSplit;
+impl OpTrait for RebaseSplit {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["reset", "HEAD^"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    /// Until it's split or amended
+    fn is_available(&self, state: &State) -> bool {
+        let head = state.repo.head().ok().and_then(|head| head.target());
+        git::rebase_status(&state.repo)
+            .ok()
+            .flatten()
+            .and_then(|rebase| rebase.edit)
+            .is_some_and(|edit| head.is_some_and(|head| head.to_string() == edit))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "split commit".into()
+    }
+}
+
 pub(crate) struct RebaseAutosquash;
 impl OpTrait for RebaseAutosquash {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => {
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => {
                 let rev = OsString::from(r);
                 Rc::new(move |state: &mut State, term: &mut Term| {
                     let args = state.pending_menu.as_ref().unwrap().args();
@@ -0,0 +1,149 @@
+use super::{Action, OpTrait};
+use crate::{
+    items::TargetData,
+    screen::{
+        rebase_todo::{RebaseTodo, TodoAction},
+        NavMode,
+    },
+    state::State,
+    term::Term,
+    Res,
+};
+use std::{fs, process::Command, rc::Rc};
+
+pub(crate) struct SetTodoAction(pub TodoAction);
+impl OpTrait for SetTodoAction {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let action = match target {
+            Some(TargetData::RebaseTodo(todo, i)) => {
+                let (todo, i, todo_action) = (Rc::clone(todo), *i, self.0);
+                Rc::new(move |state: &mut State, _term: &mut Term| {
+                    todo.borrow_mut().lines[i].action = todo_action;
+                    state.screen_mut().update()?;
+                    state.screen_mut().select_next(NavMode::Normal);
+                    Ok(())
+                })
+            }
+            _ => return None,
+        };
+
+        Some(action)
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        self.0.as_str().into()
+    }
+}
+
+pub(crate) struct MoveTodoUp;
+impl OpTrait for MoveTodoUp {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let Some(TargetData::RebaseTodo(todo, i)) = selected_todo(state) else {
+                return Ok(());
+            };
+
+            if i > 0 {
+                // The cursor follows the commit as the screen updates
+                todo.borrow_mut().lines.swap(i, i - 1);
+                state.screen_mut().update()?;
+            }
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Move commit up".into()
+    }
+}
+
+pub(crate) struct MoveTodoDown;
+impl OpTrait for MoveTodoDown {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let Some(TargetData::RebaseTodo(todo, i)) = selected_todo(state) else {
+                return Ok(());
+            };
+
+            let len = todo.borrow().lines.len();
+            if i + 1 < len {
+                todo.borrow_mut().lines.swap(i, i + 1);
+                state.screen_mut().update()?;
+            }
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Move commit down".into()
+    }
+}
+
+pub(crate) struct RebaseTodoSubmit;
+impl OpTrait for RebaseTodoSubmit {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let Some(TargetData::RebaseTodo(todo, _)) = selected_todo(state) else {
+                return Ok(());
+            };
+
+            close_todo_screen(state)?;
+            let todo = todo.borrow();
+            run_rebase(state, term, &todo)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Start rebase".into()
+    }
+}
+
+pub(crate) struct RebaseTodoAbort;
+impl OpTrait for RebaseTodoAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            close_todo_screen(state)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Abort".into()
+    }
+}
+
+fn selected_todo(state: &State) -> Option<TargetData> {
+    state.screen().get_selected_item().target_data.clone()
+}
+
+fn close_todo_screen(state: &mut State) -> Res<()> {
+    state.pop_screen();
+    state.close_menu();
+    state.screen_mut().update()
+}
+
+pub(crate) fn run_rebase(state: &mut State, term: &mut Term, todo: &RebaseTodo) -> Res<()> {
+    let todo_path = state.repo.path().join("gitu-rebase-todo");
+    fs::write(&todo_path, todo.format())?;
+
+    // Git hands its own todo file to the sequence editor, replace it with ours
+    let sequence_editor = format!(
+        "cp '{}'",
+        todo_path.to_string_lossy().replace('\'', "'\\''")
+    );
+
+    let mut cmd = Command::new("git");
+    cmd.args(["rebase", "-i"]);
+    cmd.args(&todo.args);
+    cmd.arg(todo.base.to_string());
+    cmd.env("GIT_SEQUENCE_EDITOR", sequence_editor);
+
+    let result = state.run_cmd_interactive(term, cmd);
+    if let Err(err) = fs::remove_file(&todo_path) {
+        log::warn!("Couldn't remove {}: {}", todo_path.display(), err);
+    }
+    result
+}
@@ -0,0 +1,192 @@
+use super::{
+    create_confirm_prompt, create_prompt, create_prompt_with_default, set_prompt, Action, OpTrait,
+};
+use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use std::{process::Command, rc::Rc};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![Arg::new_flag("-f", "Fetch after add", false)]
+}
+
+pub(crate) struct AddRemote;
+impl OpTrait for AddRemote {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Remote name", add_remote_prompt, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Add remote".into()
+    }
+}
+
+fn add_remote_prompt(state: &mut State, _term: &mut Term, name: &str) -> Res<()> {
+    if name.is_empty() {
+        state.close_menu();
+        return Err("Remote name required".into());
+    }
+
+    let name = name.to_string();
+    set_prompt(
+        state,
+        "Remote url",
+        Box::new(move |state, term, url| add_remote(state, term, &name, url)),
+        Box::new(|_| None),
+        true,
+    );
+    Ok(())
+}
+
+fn add_remote(state: &mut State, term: &mut Term, name: &str, url: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", "add"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.args([name, url]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct RenameRemote;
+impl OpTrait for RenameRemote {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Rename remote",
+            rename_remote_prompt,
+            selected_remote,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Rename remote".into()
+    }
+}
+
+fn rename_remote_prompt(state: &mut State, _term: &mut Term, remote: &str) -> Res<()> {
+    if remote.is_empty() {
+        state.close_menu();
+        return Err("Remote name required".into());
+    }
+
+    let remote = remote.to_string();
+    set_prompt(
+        state,
+        "Rename to",
+        Box::new(move |state, term, new_name| rename_remote(state, term, &remote, new_name)),
+        Box::new(|_| None),
+        true,
+    );
+    Ok(())
+}
+
+fn rename_remote(state: &mut State, term: &mut Term, remote: &str, new_name: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", "rename", remote, new_name]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct RemoveRemote;
+impl OpTrait for RemoveRemote {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Remove remote",
+            remove_remote,
+            selected_remote,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Remove remote".into()
+    }
+}
+
+fn remove_remote(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
+    let remote = remote.to_string();
+    let remove: Action = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["remote", "remove", &remote]);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    });
+
+    let mut action = create_confirm_prompt(remove, "Really remove remote?", |config| {
+        config.general.confirm_remove_remote.enabled
+    });
+    Rc::get_mut(&mut action).unwrap()(state, term)
+}
+
+pub(crate) struct PruneRemote;
+impl OpTrait for PruneRemote {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Prune remote",
+            prune_remote,
+            selected_remote,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Prune stale branches".into()
+    }
+}
+
+fn prune_remote(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", "prune", remote]);
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)
+}
+
+pub(crate) struct SetRemoteUrl;
+impl OpTrait for SetRemoteUrl {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Set url of remote",
+            set_remote_url_prompt,
+            selected_remote,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Set url".into()
+    }
+}
+
+fn set_remote_url_prompt(state: &mut State, _term: &mut Term, remote: &str) -> Res<()> {
+    if remote.is_empty() {
+        state.close_menu();
+        return Err("Remote name required".into());
+    }
+
+    let remote = remote.to_string();
+    set_prompt(
+        state,
+        "Remote url",
+        Box::new(move |state, term, url| set_remote_url(state, term, &remote, url)),
+        Box::new(|_| None),
+        true,
+    );
+    Ok(())
+}
+
+fn set_remote_url(state: &mut State, term: &mut Term, remote: &str, url: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", "set-url", remote, url]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) fn selected_remote(state: &State) -> Option<String> {
+    match &state.screen().get_selected_item().target_data {
+        Some(TargetData::Remote(remote)) => Some(remote.to_owned()),
+        _ => None,
+    }
+}
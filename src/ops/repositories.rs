@@ -0,0 +1,48 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term, Res};
+use std::{path::PathBuf, rc::Rc};
+
+pub(crate) struct ShowRepositories;
+impl OpTrait for ShowRepositories {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            goto_repositories_screen(state)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show repositories".into()
+    }
+}
+
+fn goto_repositories_screen(state: &mut State) -> Res<()> {
+    state.pop_screens_to_root();
+    let size = state.screens.last().unwrap().size;
+    state.close_menu();
+    state.screens.push(screen::repositories::create(
+        Rc::clone(&state.config),
+        state.recent_repos_file.clone(),
+        size,
+    )?);
+    Ok(())
+}
+
+/// Switches to another repository, leaving the screens of this one like quitting and starting gitu there would.
+pub(crate) fn open_repository(path: PathBuf) -> Option<Action> {
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        state.close_menu();
+
+        let repo = Rc::new(crate::open_repo(Some(&path))?);
+        state.screens = vec![screen::status::create(
+            Rc::clone(&state.config),
+            Rc::clone(&repo),
+            term.size()?,
+            state.enable_async_cmds,
+        )?];
+        state.repo = repo;
+        state.last_fetch = None;
+
+        state.remember_repo();
+        Ok(())
+    }))
+}
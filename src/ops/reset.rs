@@ -1,6 +1,6 @@
-use super::{create_prompt_with_default, selected_rev, OpTrait};
+use super::{create_confirm_prompt, create_rev_prompt, set_prompt, OpTrait};
 use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Action, Res};
-use std::process::Command;
+use std::{process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![]
@@ -9,12 +9,7 @@ pub(crate) fn init_args() -> Vec<Arg> {
 pub(crate) struct ResetSoft;
 impl OpTrait for ResetSoft {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
-            "Soft reset to",
-            reset_soft,
-            selected_rev,
-            true,
-        ))
+        Some(create_rev_prompt("Soft reset to", reset_soft, true))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -35,12 +30,7 @@ fn reset_soft(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
 pub(crate) struct ResetMixed;
 impl OpTrait for ResetMixed {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
-            "Mixed reset to",
-            reset_mixed,
-            selected_rev,
-            true,
-        ))
+        Some(create_rev_prompt("Mixed reset to", reset_mixed, true))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -58,15 +48,31 @@ fn reset_mixed(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     state.run_cmd(term, &[], cmd)
 }
 
+pub(crate) struct ResetKeep;
+impl OpTrait for ResetKeep {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt("Keep reset to", reset_keep, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "keep".into()
+    }
+}
+
+fn reset_keep(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["reset", "--keep"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(input);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
 pub(crate) struct ResetHard;
 impl OpTrait for ResetHard {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
-            "Hard reset to",
-            reset_hard,
-            selected_rev,
-            true,
-        ))
+        Some(create_rev_prompt("Hard reset to", reset_hard, true))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -75,11 +81,73 @@ impl OpTrait for ResetHard {
 }
 
 fn reset_hard(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let input = input.to_string();
+    let reset: Action = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["reset", "--hard"]);
+        cmd.args(state.pending_menu.as_ref().unwrap().args());
+        cmd.arg(&input);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    });
+
+    let mut action = create_confirm_prompt(reset, "Really hard reset?", |config| {
+        config.general.confirm_reset_hard.enabled
+    });
+    Rc::get_mut(&mut action).unwrap()(state, term)
+}
+
+pub(crate) struct ResetFile;
+impl OpTrait for ResetFile {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Reset file from",
+            reset_file_prompt,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "a file".into()
+    }
+}
+
+fn reset_file_prompt(state: &mut State, _term: &mut Term, rev: &str) -> Res<()> {
+    if rev.is_empty() {
+        state.close_menu();
+        return Err("Revision required".into());
+    }
+
+    let rev = rev.to_string();
+    set_prompt(
+        state,
+        "File",
+        Box::new(move |state, term, file| reset_file(state, term, &rev, file)),
+        Box::new(selected_file),
+        true,
+    );
+    Ok(())
+}
+
+fn reset_file(state: &mut State, term: &mut Term, rev: &str, file: &str) -> Res<()> {
+    if file.is_empty() {
+        state.close_menu();
+        return Err("File required".into());
+    }
+
     let mut cmd = Command::new("git");
-    cmd.args(["reset", "--hard"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(input);
+    cmd.args(["checkout", rev, "--", file]);
 
     state.close_menu();
     state.run_cmd(term, &[], cmd)
 }
+
+fn selected_file(state: &State) -> Option<String> {
+    match &state.screen().get_selected_item().target_data {
+        Some(TargetData::File(file)) => Some(file.to_string_lossy().into_owned()),
+        Some(TargetData::Delta(delta)) => Some(delta.new_file.to_string_lossy().into_owned()),
+        Some(TargetData::Rename { new_file, .. }) => Some(new_file.to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
@@ -0,0 +1,129 @@
+use std::{fs, path::Path, process::Command, rc::Rc};
+
+use super::{Action, OpTrait};
+use crate::{
+    git::conflict::{self, Side},
+    items::TargetData,
+    state::State,
+    term::Term,
+    Res,
+};
+
+pub(crate) struct KeepOurs;
+impl OpTrait for KeepOurs {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        resolve(target, Side::Ours)
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Keep ours".into()
+    }
+}
+
+pub(crate) struct KeepTheirs;
+impl OpTrait for KeepTheirs {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        resolve(target, Side::Theirs)
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Keep theirs".into()
+    }
+}
+
+pub(crate) struct KeepBoth;
+impl OpTrait for KeepBoth {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        resolve(target, Side::Both)
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Keep both".into()
+    }
+}
+
+/// Whole files are checked out from either side with git, to handle binary files and deletions too.
+/// Single conflicts, and keeping both sides, rewrite the conflict markers in the work tree file.
+fn resolve(target: Option<&TargetData>, side: Side) -> Option<Action> {
+    let action: Action = match target.cloned() {
+        Some(TargetData::File(file)) if side != Side::Both => {
+            Rc::new(move |state: &mut State, term: &mut Term| {
+                let mut cmd = Command::new("git");
+                cmd.arg("checkout");
+                cmd.arg(if side == Side::Ours {
+                    "--ours"
+                } else {
+                    "--theirs"
+                });
+                cmd.arg("--");
+                cmd.arg(&file);
+
+                state.close_menu();
+                state.run_cmd(term, &[], cmd)
+            })
+        }
+        Some(TargetData::File(file)) => Rc::new(move |state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            rewrite_conflicts(state, &file, None, side)
+        }),
+        Some(TargetData::Conflict { file, index }) => {
+            Rc::new(move |state: &mut State, _term: &mut Term| {
+                state.close_menu();
+                rewrite_conflicts(state, &file, Some(index), side)
+            })
+        }
+        _ => return None,
+    };
+
+    Some(action)
+}
+
+fn rewrite_conflicts(state: &mut State, file: &Path, index: Option<usize>, side: Side) -> Res<()> {
+    let path = state.repo.workdir().ok_or("No workdir")?.join(file);
+    let content = fs::read_to_string(&path)?;
+    if conflict::parse(&content).is_empty() {
+        return Err(format!("No conflicts left in {}", file.display()).into());
+    }
+
+    fs::write(&path, conflict::resolve(&content, index, side))?;
+    state.screen_mut().update()
+}
+
+pub(crate) struct Mergetool;
+impl OpTrait for Mergetool {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let file = match target {
+            Some(TargetData::File(file)) | Some(TargetData::Conflict { file, .. }) => file.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["mergetool", "--"]);
+            cmd.arg(&file);
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Open mergetool".into()
+    }
+}
@@ -1,13 +1,26 @@
 use std::{process::Command, rc::Rc};
 
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use crate::{
+    git,
+    items::TargetData,
+    menu::arg::{positive_number, Arg},
+    state::State,
+    term::Term,
+    Res,
+};
 
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use super::{create_range_prompt, Action, OpTrait};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
-        // -m Replay merge relative to parent (--mainline=)
+        Arg::new_arg(
+            "--mainline",
+            "Replay merge relative to parent",
+            None,
+            positive_number,
+        ),
         Arg::new_flag("--edit", "Edit commit message", true),
+        Arg::new_flag("--no-edit", "Don't edit commit message", false),
         // =s Strategy (--strategy=)
         Arg::new_flag("--signoff", "Add Signed-off-by lines", false),
     ]
@@ -26,6 +39,10 @@ impl OpTrait for RevertAbort {
         }))
     }
 
+    fn is_available(&self, state: &State) -> bool {
+        is_reverting(state)
+    }
+
     fn display(&self, _state: &State) -> String {
         "Abort".into()
     }
@@ -44,6 +61,10 @@ impl OpTrait for RevertContinue {
         }))
     }
 
+    fn is_available(&self, state: &State) -> bool {
+        is_reverting(state)
+    }
+
     fn display(&self, _state: &State) -> String {
         "Continue".into()
     }
@@ -52,12 +73,7 @@ impl OpTrait for RevertContinue {
 pub(crate) struct RevertCommit;
 impl OpTrait for RevertCommit {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
-            "Revert commit",
-            revert_commit,
-            selected_rev,
-            true,
-        ))
+        Some(create_range_prompt("Revert commit", revert_commit, true))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -69,8 +85,13 @@ fn revert_commit(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["revert"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(input);
+    cmd.args(input.split_whitespace());
 
+    state.screen_mut().clear_mark();
     state.close_menu();
     state.run_cmd_interactive(term, cmd)
 }
+
+fn is_reverting(state: &State) -> bool {
+    git::revert_status(&state.repo).is_ok_and(|status| status.is_some())
+}
@@ -0,0 +1,77 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, prompt::PromptData, state::State, term::Term};
+use std::rc::Rc;
+use tui_prompts::State as _;
+
+/// Searches the screen as the query is typed, starting from the selected item.
+pub(crate) struct Search;
+impl OpTrait for Search {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let start = state.screen().selected_item_index();
+            let mut last_query = String::new();
+
+            state.close_menu();
+            state.hide_menu();
+            state.prompt.set(PromptData {
+                prompt_text: "Search:".into(),
+                update_fn: Rc::new(move |state, term| {
+                    let query = state.prompt.state.value().to_string();
+
+                    if state.prompt.state.status().is_done() {
+                        state.prompt.reset(term)?;
+                        state.unhide_menu();
+
+                        if query.is_empty() {
+                            state.screen_mut().set_search(None);
+                        } else if !state.screen_mut().search_from(start) {
+                            state.display_error(format!("No match for '{}'", query));
+                        }
+                    } else if query != last_query {
+                        let screen = state.screen_mut();
+                        screen.set_search(Some(query.clone()).filter(|q| !q.is_empty()));
+                        screen.search_from(start);
+                        last_query = query;
+                    }
+
+                    Ok(())
+                }),
+            });
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Search".into()
+    }
+}
+
+pub(crate) struct SearchNext;
+impl OpTrait for SearchNext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            state.screen_mut().search_next();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Next match".into()
+    }
+}
+
+pub(crate) struct SearchPrevious;
+impl OpTrait for SearchPrevious {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            state.screen_mut().search_previous();
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Previous match".into()
+    }
+}
@@ -0,0 +1,43 @@
+use super::{create_prompt, Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term, Res};
+use std::{env, process::Command, rc::Rc};
+
+pub(crate) struct ShellCommand;
+impl OpTrait for ShellCommand {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Shell command", shell_command, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Run shell command".into()
+    }
+}
+
+fn shell_command(state: &mut State, term: &mut Term, command: &str) -> Res<()> {
+    state.close_menu();
+
+    if command.trim().is_empty() {
+        return Err("Shell command required".into());
+    }
+
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    state.run_cmd_async(term, &[], cmd)
+}
+
+pub(crate) struct Shell;
+impl OpTrait for Shell {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+
+            // The shell is left with `exit` or <ctrl+d>, going back to where it was opened
+            let shell = env::var("SHELL").unwrap_or("sh".into());
+            state.run_cmd_in_terminal(term, Command::new(shell))
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Open shell".into()
+    }
+}
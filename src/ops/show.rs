@@ -1,16 +1,33 @@
-use super::OpTrait;
-use crate::{items::TargetData, screen, state::State, Action};
-use std::{path::Path, process::Command, rc::Rc};
+use super::{repositories, set_rev_prompt_with_default, submodule, OpTrait};
+use crate::{
+    git::log_filter::LogFilter, items::TargetData, menu::Menu, screen, state::State, term::Term,
+    Action, Res,
+};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+};
 
 pub(crate) struct Show;
 impl OpTrait for Show {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => goto_show_screen(r.clone()),
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => {
+                goto_show_screen(r.clone())
+            }
             Some(TargetData::File(u)) => editor(u.as_path(), None),
             Some(TargetData::Delta(d)) => editor(d.new_file.as_path(), None),
+            Some(TargetData::ModeChange(m)) => editor(m.file.as_path(), None),
+            Some(TargetData::Rename { new_file, .. }) => editor(new_file.as_path(), None),
             Some(TargetData::Hunk(h)) => editor(h.new_file.as_path(), Some(h.first_diff_line())),
             Some(TargetData::Stash { id: _, commit }) => goto_show_screen(commit.clone()),
+            Some(TargetData::Submodule(path)) => submodule::goto_submodule_screen(path.clone()),
+            Some(TargetData::Repository(path)) => repositories::open_repository(path.clone()),
+            Some(TargetData::Blob { rev, path }) => goto_blob_screen(rev.clone(), path.clone()),
+            Some(TargetData::BlobLine { path, line, .. }) => editor(path.as_path(), Some(*line)),
+            Some(TargetData::DiffStat(path)) => goto_file_in_diff(path.clone()),
+            Some(TargetData::LargeDiff(path)) => show_large_diff(path.clone()),
             _ => None,
         }
     }
@@ -23,34 +40,151 @@ impl OpTrait for Show {
     }
 }
 
+/// From a log limited to files, the diff is limited to them as well.
 fn goto_show_screen(r: String) -> Option<Action> {
     Some(Rc::new(move |state, term| {
+        let log_args = state.screen().menu_args(Menu::Log).unwrap_or_default();
+        let mut filter = LogFilter {
+            paths: log_args
+                .iter()
+                .find(|(name, _)| *name == "--")
+                .and_then(|(_, paths)| paths.as_ref())
+                .map(|paths| paths.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+            follow: log_args.iter().any(|(name, _)| *name == "--follow"),
+            ..Default::default()
+        };
+
+        if filter.follow && filter.paths.len() == 1 {
+            let commit = state.repo.revparse_single(&r)?.peel_to_commit()?;
+            filter.follow_to(&state.repo, commit.id())?;
+        }
+
         state.close_menu();
-        state.screens.push(
-            screen::show::create(
-                Rc::clone(&state.config),
-                Rc::clone(&state.repo),
-                term.size()?,
-                r.clone(),
-            )
-            .expect("Couldn't create screen"),
-        );
+        state.screens.push(screen::show::create(
+            Rc::clone(&state.config),
+            Rc::clone(&state.repo),
+            term.size()?,
+            state.enable_async_cmds,
+            r.clone(),
+            filter.paths.iter().map(PathBuf::from).collect(),
+        )?);
         Ok(())
     }))
 }
 
-fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
-    let file = file.to_str().unwrap().to_string();
+/// The file at point as of a revision, which is prompted for.
+pub(crate) struct ShowFileAtRevision;
+impl OpTrait for ShowFileAtRevision {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let path = match target? {
+            TargetData::File(path)
+            | TargetData::Rename { new_file: path, .. }
+            | TargetData::Blob { path, .. }
+            | TargetData::BlobLine { path, .. } => path.clone(),
+            TargetData::Delta(delta) => delta.new_file.clone(),
+            TargetData::Hunk(hunk) | TargetData::HunkLine(hunk, _) => hunk.new_file.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            let path = path.clone();
+            set_rev_prompt_with_default(
+                state,
+                "Show file at revision",
+                Box::new(move |state, term, rev| show_blob(state, term, rev.trim(), &path)),
+                Box::new(|_| Some("HEAD".into())),
+                false,
+            )
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show file at revision".into()
+    }
+}
+
+/// From the diffstat to the first hunk of the file further down, or to the file if it has none.
+fn goto_file_in_diff(path: PathBuf) -> Option<Action> {
+    // Diffstats are only shown above the diff of a commit or revision range
+    Some(Rc::new(move |state, _term| {
+        select_file_in_diff(state, "diff", &path)
+    }))
+}
+
+/// Diffs a file that was too large to be diffed right away, for the rest of the session.
+fn show_large_diff(path: PathBuf) -> Option<Action> {
+    Some(Rc::new(move |state, _term| {
+        // Such as the unstaged or staged changes of the status screen
+        let large_diff_id = format!("_large_diff_{}", path.to_string_lossy());
+        let section = state
+            .screen()
+            .get_selected_item()
+            .id
+            .strip_suffix(&large_diff_id)
+            .unwrap_or("diff")
+            .to_string();
+
+        state
+            .config
+            .shown_large_diffs
+            .write()
+            .expect("Shown large diffs lock poisoned")
+            .insert(path.clone());
+
+        state.screen_mut().update()?;
+        select_file_in_diff(state, &section, &path)
+    }))
+}
 
+fn select_file_in_diff(state: &mut State, section: &str, path: &Path) -> Res<()> {
+    let id = format!("{}_file_{}", section, path.to_string_lossy());
+    let screen = state.screen_mut();
+    let file_i = screen
+        .items()
+        .iter()
+        .position(|item| item.id == id)
+        .ok_or("File not found in diff")?;
+
+    let first_hunk = file_i + 1;
+    let item_i = match screen.items().get(first_hunk) {
+        Some(item) if matches!(item.target_data, Some(TargetData::Hunk(_))) => first_hunk,
+        _ => file_i,
+    };
+
+    screen.select_item(item_i);
+    Ok(())
+}
+
+fn goto_blob_screen(rev: String, path: PathBuf) -> Option<Action> {
     Some(Rc::new(move |state, term| {
-        const EDITOR_VARS: [&str; 3] = ["VISUAL", "EDITOR", "GIT_EDITOR"];
-        let configured_editor = EDITOR_VARS
-            .into_iter()
-            .find_map(|var| std::env::var(var).ok());
+        show_blob(state, term, &rev, &path)
+    }))
+}
+
+fn show_blob(state: &mut State, term: &mut Term, rev: &str, path: &Path) -> Res<()> {
+    state.close_menu();
+    state.screens.push(screen::blob::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        term.size()?,
+        rev.to_string(),
+        path.to_path_buf(),
+    )?);
+    Ok(())
+}
 
-        let Some(editor) = configured_editor else {
+fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
+    let file = file.to_string_lossy().into_owned();
+
+    Some(Rc::new(move |state, term| {
+        let Some(editor) = configured_editor(state) else {
             return Err(format!(
-                "No editor environment variable set ({})",
+                "No editor configured ({} or core.editor)",
                 EDITOR_VARS.join(", ")
             )
             .into());
@@ -67,25 +201,51 @@ fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
     }))
 }
 
+const EDITOR_VARS: [&str; 3] = ["VISUAL", "EDITOR", "GIT_EDITOR"];
+
+/// Like git, the environment trumps `core.editor`. Unlike git, there's no fallback to `vi`.
+fn configured_editor(state: &State) -> Option<String> {
+    EDITOR_VARS
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .chain(
+            state
+                .repo
+                .config()
+                .and_then(|config| config.get_string("core.editor"))
+                .ok(),
+        )
+        .find(|editor| !editor.trim().is_empty())
+}
+
 fn parse_editor_command(editor: &str, file: &str, maybe_line: Option<u32>) -> Command {
     let args = &editor.split_whitespace().collect::<Vec<_>>();
     let mut cmd = Command::new(args[0]);
     cmd.args(&args[1..]);
 
-    let lower = args[0].to_lowercase();
-
-    if let Some(line) = maybe_line {
-        if lower.ends_with("vi")
-            || lower.ends_with("vim")
-            || lower.ends_with("nvim")
-            || lower.ends_with("nano")
-        {
-            cmd.args([&format!("+{}", line), file]);
-        } else {
-            cmd.args([&format!("{}:{}", file, line)]);
-        }
+    let name = Path::new(args[0])
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let Some(line) = maybe_line else {
+        cmd.arg(file);
+        return cmd;
+    };
+
+    // Also matches the likes of gvim and nvim
+    const PLUS_LINE_EDITORS: [&str; 6] = ["vi", "vim", "nano", "emacs", "emacsclient", "kak"];
+
+    if PLUS_LINE_EDITORS
+        .iter()
+        .any(|editor| name.ends_with(editor))
+    {
+        cmd.args([&format!("+{}", line), file]);
+    } else if name.starts_with("code") || name == "codium" {
+        cmd.args(["--goto", &format!("{}:{}:1", file, line)]);
     } else {
-        cmd.args([file.to_string()]);
+        // Helix, Sublime Text, Zed, micro and others
+        cmd.arg(format!("{}:{}", file, line));
     }
     cmd
 }
@@ -103,4 +263,28 @@ mod tests {
             &["-f", "+42", "README.md"]
         );
     }
+
+    #[test]
+    fn parse_editor_command_line_args() {
+        let args = |editor| {
+            let cmd = super::parse_editor_command(editor, "README.md", Some(42));
+            cmd.get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(args("emacsclient -t"), ["-t", "+42", "README.md"]);
+        assert_eq!(args("kak"), ["+42", "README.md"]);
+        assert_eq!(args("code --wait"), ["--wait", "--goto", "README.md:42:1"]);
+        assert_eq!(args("hx"), ["README.md:42"]);
+    }
+
+    #[test]
+    fn parse_editor_command_without_line() {
+        let cmd = super::parse_editor_command("code --wait", "README.md", None);
+        assert_eq!(
+            &cmd.get_args().collect::<Vec<_>>(),
+            &["--wait", "README.md"]
+        );
+    }
 }
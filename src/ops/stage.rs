@@ -1,12 +1,13 @@
 use super::OpTrait;
 use crate::{
-    git::diff::{Hunk, PatchMode},
+    git::diff::{Hunk, ModeChange, PatchMode},
+    git2_opts,
     items::TargetData,
     state::State,
     term::Term,
     Action,
 };
-use std::{ffi::OsString, process::Command, rc::Rc};
+use std::{ffi::OsString, ops::Range, path::PathBuf, process::Command, rc::Rc, sync::Arc};
 
 pub(crate) struct Stage;
 impl OpTrait for Stage {
@@ -16,14 +17,29 @@ impl OpTrait for Stage {
             Some(TargetData::AllUntracked(untracked)) => stage_untracked(untracked),
             Some(TargetData::File(u)) => stage_file(u.into()),
             Some(TargetData::Delta(d)) => stage_file(d.new_file.into()),
+            Some(TargetData::Rename { old_file, new_file }) => stage_rename(old_file, new_file),
             Some(TargetData::Hunk(h)) => stage_patch(h),
-            Some(TargetData::HunkLine(h, i)) => stage_line(h, i),
+            Some(TargetData::ModeChange(m)) => stage_mode_change(m),
+            Some(TargetData::HunkLine(h, i)) => stage_lines(h, i..(i + 1)),
             _ => return None,
         };
 
         Some(action)
     }
 
+    fn get_region_action(&self, targets: &[TargetData]) -> Option<Action> {
+        if let Some((h, lines)) = super::hunk_line_range(targets) {
+            return Some(stage_lines(h, lines));
+        }
+
+        super::chain_actions(
+            targets
+                .iter()
+                .filter_map(|target| self.get_action(Some(target)))
+                .collect(),
+        )
+    }
+
     fn is_target_op(&self) -> bool {
         true
     }
@@ -33,6 +49,82 @@ impl OpTrait for Stage {
     }
 }
 
+pub(crate) struct IntentToAdd;
+impl OpTrait for IntentToAdd {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::File(file)) = target.cloned() else {
+            return None;
+        };
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["add", "--intent-to-add"]);
+            cmd.arg(&file);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Intent to add".into()
+    }
+}
+
+pub(crate) struct StageAllTracked;
+impl OpTrait for StageAllTracked {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["add", "--update"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Stage all tracked".into()
+    }
+}
+
+pub(crate) struct StageAll;
+impl OpTrait for StageAll {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let stage_all: Action = Rc::new(|state: &mut State, term: &mut Term| {
+                let mut cmd = Command::new("git");
+                cmd.args(["add", "--all"]);
+
+                state.close_menu();
+                state.run_cmd(term, &[], cmd)
+            });
+
+            let has_untracked = state
+                .repo
+                .statuses(Some(&mut git2_opts::status(&state.repo)?))?
+                .iter()
+                .any(|entry| entry.status().is_wt_new());
+
+            let mut action = if has_untracked {
+                super::create_y_n_prompt(stage_all, "Really stage untracked files?")
+            } else {
+                stage_all
+            };
+
+            Rc::get_mut(&mut action).unwrap()(state, term)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Stage all, including untracked".into()
+    }
+}
+
 fn stage_unstaged() -> Action {
     Rc::new(move |state: &mut State, term: &mut Term| {
         let mut cmd = Command::new("git");
@@ -54,6 +146,17 @@ fn stage_untracked(untracked: Vec<std::path::PathBuf>) -> Action {
     })
 }
 
+fn stage_rename(old_file: PathBuf, new_file: PathBuf) -> Action {
+    Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["add", "--all", "--"]);
+        cmd.args([&old_file, &new_file]);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
+
 fn stage_file(file: OsString) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
@@ -65,24 +168,32 @@ fn stage_file(file: OsString) -> Action {
     })
 }
 
-fn stage_patch(h: Rc<Hunk>) -> Action {
+fn stage_patch(h: Arc<Hunk>) -> Action {
+    Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["apply", "--cached"]);
+
+        state.close_menu();
+        state.run_cmd(term, &h.format_patch(), cmd)
+    })
+}
+
+fn stage_mode_change(m: Arc<ModeChange>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached"]);
 
         state.close_menu();
-        state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
+        state.run_cmd(term, &m.format_patch(), cmd)
     })
 }
 
-fn stage_line(h: Rc<Hunk>, i: usize) -> Action {
+fn stage_lines(h: Arc<Hunk>, lines: Range<usize>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached", "--recount"]);
 
-        let input = h
-            .format_line_patch(i..(i + 1), PatchMode::Normal)
-            .into_bytes();
+        let input = h.format_line_patch(lines.clone(), PatchMode::Normal);
 
         state.close_menu();
         state.run_cmd(term, &input, cmd)
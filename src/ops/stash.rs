@@ -1,5 +1,5 @@
-use super::{create_prompt, create_prompt_with_default, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{create_picker_prompt, create_prompt, Action, OpTrait};
+use crate::{items::TargetData, menu::arg::Arg, picker, state::State, term::Term, Res};
 use git2::{Repository, Status, StatusOptions};
 use std::{process::Command, rc::Rc};
 
@@ -172,10 +172,11 @@ fn stash_push_keep_index(state: &mut State, term: &mut Term, input: &str) -> Res
 pub(crate) struct StashPop;
 impl OpTrait for StashPop {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Pop stash",
             stash_pop,
             selected_stash,
+            picker::stashes,
             true,
         ))
     }
@@ -198,10 +199,11 @@ fn stash_pop(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
 pub(crate) struct StashApply;
 impl OpTrait for StashApply {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Apply stash",
             stash_apply,
             selected_stash,
+            picker::stashes,
             true,
         ))
     }
@@ -224,10 +226,11 @@ fn stash_apply(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
 pub(crate) struct StashDrop;
 impl OpTrait for StashDrop {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Drop stash",
             stash_drop,
             selected_stash,
+            picker::stashes,
             true,
         ))
     }
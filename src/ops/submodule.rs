@@ -0,0 +1,111 @@
+use std::{path::PathBuf, process::Command, rc::Rc};
+
+use git2::Repository;
+
+use super::{Action, OpTrait};
+use crate::{items::TargetData, menu::arg::Arg, screen, state::State, term::Term};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--init", "Initialize if needed", false),
+        Arg::new_flag("--recursive", "Recursive", false),
+    ]
+}
+
+pub(crate) struct SubmoduleInit;
+impl OpTrait for SubmoduleInit {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(submodule_cmd("init", false, target))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Init".into()
+    }
+}
+
+pub(crate) struct SubmoduleUpdate;
+impl OpTrait for SubmoduleUpdate {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(submodule_cmd("update", true, target))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Update".into()
+    }
+}
+
+pub(crate) struct SubmoduleSync;
+impl OpTrait for SubmoduleSync {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(submodule_cmd("sync", false, target))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Sync".into()
+    }
+}
+
+/// Acts on the submodule at point, or on all of them if there's none selected.
+fn submodule_cmd(subcommand: &'static str, use_args: bool, target: Option<&TargetData>) -> Action {
+    let path = match target {
+        Some(TargetData::Submodule(path)) => Some(path.clone()),
+        _ => None,
+    };
+
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["submodule", subcommand]);
+        if use_args {
+            cmd.args(state.pending_menu.as_ref().unwrap().args());
+        }
+        if let Some(path) = &path {
+            cmd.arg("--");
+            cmd.arg(path);
+        }
+
+        state.close_menu();
+        state.run_cmd_async(term, &[], cmd)
+    })
+}
+
+pub(crate) struct SubmoduleEnter;
+impl OpTrait for SubmoduleEnter {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Submodule(path)) => goto_submodule_screen(path.clone()),
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Enter submodule".into()
+    }
+}
+
+pub(crate) fn goto_submodule_screen(path: PathBuf) -> Option<Action> {
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        state.close_menu();
+
+        let dir = state.repo.workdir().ok_or("No workdir")?.join(&path);
+        let Ok(repo) = Repository::open(&dir) else {
+            return Err(format!("Submodule '{}' is not checked out", path.display()).into());
+        };
+
+        let repo = Rc::new(repo);
+        let parent_repo = std::mem::replace(&mut state.repo, Rc::clone(&repo));
+        state.screens.push(
+            screen::status::create(
+                Rc::clone(&state.config),
+                repo,
+                term.size()?,
+                state.enable_async_cmds,
+            )?
+            .with_parent_repo(parent_repo),
+        );
+        Ok(())
+    }))
+}
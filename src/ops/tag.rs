@@ -0,0 +1,171 @@
+use super::{create_prompt, create_prompt_with_default, set_rev_prompt, Action, OpTrait};
+use crate::{
+    git::remote::{get_push_remote, get_upstream_components},
+    items::TargetData,
+    menu::arg::Arg,
+    state::State,
+    term::Term,
+    Res,
+};
+use std::process::Command;
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--force", "Force", false),
+        Arg::new_flag("--sign", "Sign", false),
+    ]
+}
+
+pub(crate) struct CreateTag;
+impl OpTrait for CreateTag {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Tag name", create_tag_prompt, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Create tag".into()
+    }
+}
+
+fn create_tag_prompt(state: &mut State, _term: &mut Term, name: &str) -> Res<()> {
+    tag_at_prompt(state, name, false)
+}
+
+pub(crate) struct CreateAnnotatedTag;
+impl OpTrait for CreateAnnotatedTag {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Tag name", create_annotated_tag_prompt, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Create annotated tag".into()
+    }
+}
+
+fn create_annotated_tag_prompt(state: &mut State, _term: &mut Term, name: &str) -> Res<()> {
+    tag_at_prompt(state, name, true)
+}
+
+fn tag_at_prompt(state: &mut State, name: &str, annotated: bool) -> Res<()> {
+    if name.is_empty() {
+        state.close_menu();
+        return Err("Tag name required".into());
+    }
+
+    let name = name.to_string();
+    set_rev_prompt(
+        state,
+        "Tag at",
+        Box::new(move |state, term, rev| create_tag(state, term, &name, rev, annotated)),
+        true,
+    )
+}
+
+fn create_tag(
+    state: &mut State,
+    term: &mut Term,
+    name: &str,
+    rev: &str,
+    annotated: bool,
+) -> Res<()> {
+    let args = state.pending_menu.as_ref().unwrap().args();
+    let signed = args.iter().any(|arg| arg == "--sign");
+
+    let mut cmd = Command::new("git");
+    cmd.arg("tag");
+    cmd.args(args);
+    if annotated {
+        cmd.arg("--annotate");
+    }
+    cmd.arg(name);
+    if !rev.is_empty() {
+        cmd.arg(rev);
+    }
+
+    state.close_menu();
+    if annotated || signed {
+        // Git opens an editor for the tag message, signed tags are annotated as well.
+        // gpg may need the terminal too, to ask for a passphrase.
+        state.run_cmd_interactive(term, cmd)
+    } else {
+        state.run_cmd(term, &[], cmd)
+    }
+}
+
+pub(crate) struct DeleteTag;
+impl OpTrait for DeleteTag {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Delete tag",
+            delete_tag,
+            selected_tag,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Delete tag".into()
+    }
+}
+
+fn delete_tag(state: &mut State, term: &mut Term, tag: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["tag", "--delete", tag]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct PushTag;
+impl OpTrait for PushTag {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Push tag",
+            push_tag,
+            selected_tag,
+            true,
+        ))
+    }
+
+    fn display(&self, state: &State) -> String {
+        match default_remote(state) {
+            Some(remote) => format!("Push tag to {}", remote),
+            None => "Push tag".into(),
+        }
+    }
+}
+
+fn push_tag(state: &mut State, term: &mut Term, tag: &str) -> Res<()> {
+    let Some(remote) = default_remote(state) else {
+        state.close_menu();
+        return Err("No pushRemote or upstream remote configured".into());
+    };
+
+    let mut cmd = Command::new("git");
+    cmd.arg("push");
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(remote);
+    cmd.arg(format!("refs/tags/{}", tag));
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)
+}
+
+/// The pushRemote, or else the upstream's remote.
+pub(crate) fn default_remote(state: &State) -> Option<String> {
+    if let Some(push_remote) = get_push_remote(&state.repo).ok().flatten() {
+        return Some(push_remote);
+    }
+
+    match get_upstream_components(&state.repo).ok().flatten() {
+        Some((remote, _branch)) if remote != "." => Some(remote),
+        _ => None,
+    }
+}
+
+pub(crate) fn selected_tag(state: &State) -> Option<String> {
+    match &state.screen().get_selected_item().target_data {
+        Some(TargetData::Tag(tag)) => Some(tag.to_owned()),
+        _ => None,
+    }
+}
@@ -0,0 +1,33 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term};
+use std::rc::Rc;
+
+pub(crate) struct BrowseTree;
+impl OpTrait for BrowseTree {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let rev = match target {
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => r.clone(),
+            Some(TargetData::Stash { commit, .. }) => commit.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            state.close_menu();
+            state.screens.push(screen::tree::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+                rev.clone(),
+            )?);
+            Ok(())
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Browse tree".into()
+    }
+}
@@ -1,6 +1,6 @@
 use super::OpTrait;
 use crate::{git::diff::PatchMode, items::TargetData, state::State, term::Term, Action};
-use std::{ffi::OsString, process::Command, rc::Rc};
+use std::{ffi::OsString, path::PathBuf, process::Command, rc::Rc};
 
 pub(crate) struct Unstage;
 impl OpTrait for Unstage {
@@ -8,16 +8,31 @@ impl OpTrait for Unstage {
         let action = match target.cloned() {
             Some(TargetData::AllStaged) => unstage_staged(),
             Some(TargetData::Delta(d)) => unstage_file(d.new_file.into()),
-            Some(TargetData::Hunk(h)) => unstage_patch(h.format_patch().into_bytes()),
-            Some(TargetData::HunkLine(h, i)) => unstage_line(
-                h.format_line_patch(i..(i + 1), PatchMode::Reverse)
-                    .into_bytes(),
-            ),
+            Some(TargetData::Rename { old_file, new_file }) => unstage_rename(old_file, new_file),
+            Some(TargetData::Hunk(h)) => unstage_patch(h.format_patch()),
+            Some(TargetData::ModeChange(m)) => unstage_patch(m.format_patch()),
+            Some(TargetData::HunkLine(h, i)) => {
+                unstage_line(h.format_line_patch(i..(i + 1), PatchMode::Reverse))
+            }
             _ => return None,
         };
 
         Some(action)
     }
+
+    fn get_region_action(&self, targets: &[TargetData]) -> Option<Action> {
+        if let Some((h, lines)) = super::hunk_line_range(targets) {
+            return Some(unstage_line(h.format_line_patch(lines, PatchMode::Reverse)));
+        }
+
+        super::chain_actions(
+            targets
+                .iter()
+                .filter_map(|target| self.get_action(Some(target)))
+                .collect(),
+        )
+    }
+
     fn is_target_op(&self) -> bool {
         true
     }
@@ -27,6 +42,17 @@ impl OpTrait for Unstage {
     }
 }
 
+pub(crate) struct UnstageAll;
+impl OpTrait for UnstageAll {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(unstage_staged())
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Unstage all".into()
+    }
+}
+
 fn unstage_staged() -> Action {
     Rc::new(move |state: &mut State, term: &mut Term| {
         let mut cmd = Command::new("git");
@@ -37,6 +63,18 @@ fn unstage_staged() -> Action {
     })
 }
 
+/// Unstages both sides, otherwise the old path would stay staged as deleted
+fn unstage_rename(old_file: PathBuf, new_file: PathBuf) -> Action {
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["restore", "--staged", "--"]);
+        cmd.args([&old_file, &new_file]);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
+
 fn unstage_file(file: OsString) -> Action {
     Rc::new(move |state: &mut State, term: &mut Term| {
         let mut cmd = Command::new("git");
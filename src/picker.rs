@@ -0,0 +1,415 @@
+use crate::{items::TargetData, state::State, Res};
+use git2::{BranchType, Repository};
+
+const RECENT_COMMITS_LIMIT: usize = 20;
+/// How many commits back authors are suggested from
+const RECENT_AUTHORS_LIMIT: usize = 200;
+/// How many of the matches are listed at once
+const SHOWN_MATCHES: usize = 5;
+
+const MATCH_SCORE: i64 = 16;
+const BOUNDARY_BONUS: i64 = 10;
+const CONSECUTIVE_BONUS: i64 = 8;
+/// The most taken off for a match starting further into the text
+const MAX_LEADING_PENALTY: i64 = 10;
+
+/// What a candidate is, deciding how it's styled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CandidateKind {
+    Branch,
+    Remote,
+    Tag,
+    Commit,
+    Stash,
+    Author,
+    /// An item on the screen
+    Item,
+    /// An op, run when picked
+    Command,
+}
+
+#[derive(Clone)]
+pub(crate) struct Candidate {
+    /// What's filled into the prompt when picked
+    pub(crate) value: String,
+    pub(crate) kind: CandidateKind,
+    /// Shown after the value, such as the summary of a commit. Matched if the value isn't.
+    pub(crate) description: String,
+    /// The index of the item on the screen it stands for, which others may read the same as
+    pub(crate) item: Option<usize>,
+}
+
+impl Candidate {
+    pub(crate) fn new(value: impl Into<String>, kind: CandidateKind) -> Self {
+        Self {
+            value: value.into(),
+            kind,
+            description: String::new(),
+            item: None,
+        }
+    }
+
+    pub(crate) fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub(crate) fn with_item(mut self, item: usize) -> Self {
+        self.item = Some(item);
+        self
+    }
+}
+
+pub(crate) struct Match {
+    candidate: usize,
+    score: i64,
+    /// Whether the input matched the description rather than the value
+    pub(crate) in_description: bool,
+    /// Indices of the matched characters
+    pub(crate) positions: Vec<usize>,
+}
+
+/// A fuzzy finder over the choices of a prompt. The candidates matching the input are listed
+/// below it, best first, and tab or the arrow keys fill in one of them.
+pub(crate) struct Picker {
+    candidates: Vec<Candidate>,
+    matches: Vec<Match>,
+    /// The match last filled into the prompt
+    selected: Option<usize>,
+    /// What the matches were narrowed down by
+    input: String,
+}
+
+impl Picker {
+    pub(crate) fn new(candidates: Vec<Candidate>) -> Self {
+        let mut picker = Self {
+            candidates,
+            matches: vec![],
+            selected: None,
+            input: String::new(),
+        };
+        picker.filter("");
+        picker
+    }
+
+    pub(crate) fn filter(&mut self, input: &str) {
+        let mut matches = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, candidate)| {
+                let (score, positions, in_description) = fuzzy_match(input, &candidate.value)
+                    .map(|(score, positions)| (score, positions, false))
+                    .or_else(|| {
+                        let (score, positions) = fuzzy_match(input, &candidate.description)?;
+                        Some((score, positions, true))
+                    })?;
+
+                Some(Match {
+                    candidate: i,
+                    // Candidates are foremost found by their value
+                    score: if in_description { score - 1000 } else { score },
+                    in_description,
+                    positions,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Stable, so that candidates scoring the same keep their order
+        matches.sort_by_key(|m| -m.score);
+
+        self.matches = matches;
+        self.selected = None;
+        self.input = input.to_string();
+    }
+
+    /// Whether to list the matches, once something's typed or one is picked.
+    pub(crate) fn is_shown(&self) -> bool {
+        !self.input.is_empty() || self.selected.is_some()
+    }
+
+    /// The matches to list, scrolled to keep the selected one in view.
+    pub(crate) fn shown_matches(&self) -> impl Iterator<Item = (&Candidate, &Match, bool)> {
+        let skip = self
+            .selected
+            .map_or(0, |selected| (selected + 1).saturating_sub(SHOWN_MATCHES));
+        let shown = if self.is_shown() { SHOWN_MATCHES } else { 0 };
+
+        self.matches
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(shown)
+            .map(|(i, m)| (&self.candidates[m.candidate], m, Some(i) == self.selected))
+    }
+
+    pub(crate) fn next(&mut self) -> Option<String> {
+        if self.matches.is_empty() {
+            return None;
+        }
+
+        let selected = self.selected.map_or(0, |i| (i + 1) % self.matches.len());
+        self.select(selected)
+    }
+
+    pub(crate) fn previous(&mut self) -> Option<String> {
+        if self.matches.is_empty() {
+            return None;
+        }
+
+        let last = self.matches.len() - 1;
+        let selected = self
+            .selected
+            .map_or(last, |i| i.checked_sub(1).unwrap_or(last));
+        self.select(selected)
+    }
+
+    /// The candidate last filled into the prompt.
+    pub(crate) fn selected(&self) -> Option<&Candidate> {
+        Some(&self.candidates[self.matches[self.selected?].candidate])
+    }
+
+    pub(crate) fn deselect(&mut self) {
+        self.selected = None;
+    }
+
+    fn select(&mut self, selected: usize) -> Option<String> {
+        self.selected = Some(selected);
+        Some(
+            self.candidates[self.matches[selected].candidate]
+                .value
+                .clone(),
+        )
+    }
+}
+
+/// Matches the characters of `input` in order, ignoring case. Higher scores are better matches:
+/// consecutive characters and ones starting a word count more, gaps between them less.
+/// Returns the score along with the indices of the matched characters.
+pub(crate) fn fuzzy_match(input: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let input = lowercase_chars(input);
+    let text = lowercase_chars(text);
+
+    let Some(&first) = input.first() else {
+        return Some((0, vec![]));
+    };
+
+    // The first match isn't always the best one, "ma" in "format-main" is better at "main"
+    (0..text.len())
+        .filter(|&start| text[start] == first)
+        .filter_map(|start| match_from(&input, &text, start))
+        .fold(
+            None,
+            |best: Option<(i64, Vec<usize>)>, candidate| match best {
+                Some(best) if best.0 >= candidate.0 => Some(best),
+                _ => Some(candidate),
+            },
+        )
+}
+
+fn lowercase_chars(text: &str) -> Vec<char> {
+    // One char per char, so that indices stay the same
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn match_from(input: &[char], text: &[char], start: usize) -> Option<(i64, Vec<usize>)> {
+    let mut positions = Vec::with_capacity(input.len());
+    let mut at = start;
+    for &c in input {
+        at += text.get(at..)?.iter().position(|&t| t == c)?;
+        positions.push(at);
+        at += 1;
+    }
+
+    let mut score = -(start as i64).min(MAX_LEADING_PENALTY);
+    for (i, &position) in positions.iter().enumerate() {
+        score += MATCH_SCORE;
+
+        if position == 0 || !text[position - 1].is_alphanumeric() {
+            score += BOUNDARY_BONUS;
+        }
+
+        if i > 0 {
+            match position - positions[i - 1] - 1 {
+                0 => score += CONSECUTIVE_BONUS,
+                gap => score -= gap as i64,
+            }
+        }
+    }
+
+    Some((score, positions))
+}
+
+/// Branches, tags and the most recent commits.
+pub(crate) fn revs(state: &State) -> Res<Vec<Candidate>> {
+    let repo = &state.repo;
+    let mut candidates = branches(state)?;
+
+    for tag in repo.tag_names(None)?.iter().flatten() {
+        candidates.push(Candidate::new(tag, CandidateKind::Tag));
+    }
+
+    // An unborn HEAD has no commits to walk
+    let mut revwalk = repo.revwalk()?;
+    if revwalk.push_head().is_ok() {
+        for oid in revwalk.take(RECENT_COMMITS_LIMIT) {
+            let commit = repo.find_commit(oid?)?;
+            let short_id = commit.as_object().short_id()?;
+            candidates.push(
+                Candidate::new(short_id.as_str().unwrap_or(""), CandidateKind::Commit)
+                    .with_description(commit.summary().unwrap_or("")),
+            );
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// Local branches, followed by remote ones.
+pub(crate) fn branches(state: &State) -> Res<Vec<Candidate>> {
+    let mut candidates = local_branches(state)?;
+    candidates.extend(branches_of_type(
+        &state.repo,
+        BranchType::Remote,
+        CandidateKind::Remote,
+    )?);
+    Ok(candidates)
+}
+
+pub(crate) fn local_branches(state: &State) -> Res<Vec<Candidate>> {
+    branches_of_type(&state.repo, BranchType::Local, CandidateKind::Branch)
+}
+
+fn branches_of_type(
+    repo: &Repository,
+    branch_type: BranchType,
+    kind: CandidateKind,
+) -> Res<Vec<Candidate>> {
+    let mut candidates = vec![];
+    for branch in repo.branches(Some(branch_type))? {
+        let (branch, _) = branch?;
+        if let Some(name) = branch.name()? {
+            candidates.push(Candidate::new(name, kind));
+        }
+    }
+    Ok(candidates)
+}
+
+/// The authors of recent commits as `Name <email>`, most recent first.
+pub(crate) fn authors(state: &State) -> Res<Vec<Candidate>> {
+    let mut authors = vec![];
+
+    let mut revwalk = state.repo.revwalk()?;
+    if revwalk.push_head().is_ok() {
+        for oid in revwalk.take(RECENT_AUTHORS_LIMIT) {
+            let commit = state.repo.find_commit(oid?)?;
+            let author = commit.author();
+            let author = format!(
+                "{} <{}>",
+                author.name().unwrap_or(""),
+                author.email().unwrap_or("")
+            );
+            if !authors.contains(&author) {
+                authors.push(author);
+            }
+        }
+    }
+
+    Ok(authors
+        .into_iter()
+        .map(|author| Candidate::new(author, CandidateKind::Author))
+        .collect())
+}
+
+/// Stashes by their index, described by their message.
+pub(crate) fn stashes(state: &State) -> Res<Vec<Candidate>> {
+    let Ok(reflog) = state.repo.reflog("refs/stash") else {
+        return Ok(vec![]);
+    };
+
+    Ok(reflog
+        .iter()
+        .enumerate()
+        .map(|(i, stash)| {
+            Candidate::new(i.to_string(), CandidateKind::Stash)
+                .with_description(stash.message().unwrap_or(""))
+        })
+        .collect())
+}
+
+/// The items of the current screen worth jumping to: sections, files, commits and such.
+/// Indices of the items are returned along with them.
+pub(crate) fn screen_items(state: &State) -> Vec<Candidate> {
+    state
+        .screen()
+        .items()
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            !item.unselectable
+                && (item.section || item.target_data.is_some())
+                && !matches!(
+                    item.target_data,
+                    Some(TargetData::Hunk(_) | TargetData::HunkLine(..))
+                )
+        })
+        .map(|(i, item)| {
+            let text = item
+                .display
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>();
+            Candidate::new(text.trim_end(), CandidateKind::Item).with_item(i)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picked(picker: &Picker) -> Vec<&str> {
+        picker
+            .shown_matches()
+            .map(|(candidate, _, _)| candidate.value.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn best_matches_first() {
+        let mut picker = Picker::new(
+            ["fix-main", "feature", "main", "origin/main", "other"]
+                .map(|value| Candidate::new(value, CandidateKind::Branch))
+                .into(),
+        );
+
+        picker.filter("MAin");
+        assert_eq!(picked(&picker), ["main", "fix-main", "origin/main"]);
+
+        picker.filter("fmn");
+        assert_eq!(picked(&picker), ["fix-main"]);
+    }
+
+    #[test]
+    fn matches_word_starts() {
+        assert_eq!(
+            fuzzy_match("ma", "format-main").map(|(_, positions)| positions),
+            Some(vec![7, 8])
+        );
+        assert_eq!(fuzzy_match("xyz", "format-main"), None);
+    }
+
+    #[test]
+    fn falls_back_to_description() {
+        let mut picker = Picker::new(vec![
+            Candidate::new("abc1234", CandidateKind::Commit).with_description("add feature"),
+            Candidate::new("feature", CandidateKind::Branch),
+        ]);
+
+        picker.filter("feat");
+        assert_eq!(picked(&picker), ["feature", "abc1234"]);
+    }
+}
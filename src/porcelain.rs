@@ -0,0 +1,267 @@
+//! The status, log and diff as gitu reads them, printed as JSON by `gitu status --porcelain`,
+//! `gitu log --json` and `gitu diff --json` for scripts and editor plugins.
+
+use crate::{
+    cli::Commands,
+    config::Config,
+    git::{self, diff::Diff},
+    git2_opts, Res,
+};
+use git2::Repository;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+pub(crate) struct Status {
+    /// `None` on a detached HEAD
+    branch: Option<String>,
+    /// `None` before the first commit
+    head: Option<String>,
+    upstream: Option<Upstream>,
+    /// A rebase, merge, revert, cherry-pick, bisect or `am` in progress
+    operation: Option<&'static str>,
+    untracked: Vec<String>,
+    unmerged: Vec<String>,
+    unstaged: Vec<FileDiff>,
+    staged: Vec<FileDiff>,
+    stashes: usize,
+}
+
+#[derive(Serialize)]
+struct Upstream {
+    name: String,
+    /// `None` when the upstream branch is gone
+    ahead: Option<usize>,
+    behind: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct FileDiff {
+    /// Lowercase, like `modified` or `renamed`
+    status: String,
+    old_file: String,
+    new_file: String,
+    hunks: Vec<HunkDiff>,
+}
+
+#[derive(Serialize)]
+struct HunkDiff {
+    header: String,
+    new_start: u32,
+    /// Prefixed by `+`, `-` or a space, as in a patch
+    lines: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogEntry {
+    hash: String,
+    short_hash: String,
+    summary: String,
+    message: String,
+    author: Person,
+    committer: Person,
+    parents: Vec<String>,
+    /// Branches, remote branches and tags pointing at the commit
+    refs: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Person {
+    name: String,
+    email: String,
+    /// RFC 3339, in the person's own time zone
+    time: String,
+}
+
+/// The JSON that `command` asks for.
+pub(crate) fn json(config: &Config, repo: &Repository, command: &Commands) -> Res<String> {
+    Ok(match command {
+        Commands::Status { porcelain: true } => {
+            serde_json::to_string_pretty(&status(config, repo)?)?
+        }
+        Commands::Log {
+            json: true,
+            max_count,
+            rev,
+        } => serde_json::to_string_pretty(&log(
+            repo,
+            rev.as_deref(),
+            max_count.unwrap_or(usize::MAX),
+        )?)?,
+        Commands::Diff { json: true, range } => {
+            serde_json::to_string_pretty(&diff(config, repo, range.as_deref())?)?
+        }
+        _ => return Err("Nothing to print".into()),
+    })
+}
+
+/// Bare repositories only have a branch, upstream and stashes, with no work tree or index.
+pub(crate) fn status(config: &Config, repo: &Repository) -> Res<Status> {
+    let statuses = match repo.is_bare() {
+        true => None,
+        false => Some(repo.statuses(Some(&mut git2_opts::status(repo)?))?),
+    };
+    let paths = |filter: fn(&git2::Status) -> bool| {
+        statuses
+            .iter()
+            .flat_map(|statuses| statuses.iter())
+            .filter(|entry| filter(&entry.status()))
+            .filter_map(|entry| entry.path().map(String::from))
+            .collect::<Vec<_>>()
+    };
+
+    let head = repo.head().ok();
+    let branch = head
+        .as_ref()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(String::from));
+
+    Ok(Status {
+        upstream: match &head {
+            Some(head) if head.is_branch() => upstream(repo, head)?,
+            _ => None,
+        },
+        head: head
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string()),
+        branch,
+        operation: operation(repo)?,
+        untracked: paths(git2::Status::is_wt_new),
+        unmerged: paths(git2::Status::is_conflicted),
+        unstaged: match repo.is_bare() {
+            true => vec![],
+            false => file_diffs(git::diff_unstaged(config, repo)?),
+        },
+        staged: match repo.is_bare() {
+            true => vec![],
+            false => file_diffs(git::diff_staged(config, repo)?),
+        },
+        stashes: repo.reflog("refs/stash").map_or(0, |reflog| reflog.len()),
+    })
+}
+
+fn upstream(repo: &Repository, head: &git2::Reference) -> Res<Option<Upstream>> {
+    let Ok(upstream) = repo.branch_upstream_name(head.name().ok_or("Invalid branch name")?) else {
+        return Ok(None);
+    };
+    let upstream = upstream.as_str().ok_or("Invalid upstream name")?;
+    let name = upstream
+        .strip_prefix("refs/remotes/")
+        .unwrap_or(upstream)
+        .to_string();
+
+    let counts = match (head.target(), repo.refname_to_id(upstream)) {
+        (Some(local), Ok(upstream)) => Some(repo.graph_ahead_behind(local, upstream)?),
+        _ => None,
+    };
+
+    Ok(Some(Upstream {
+        name,
+        ahead: counts.map(|(ahead, _)| ahead),
+        behind: counts.map(|(_, behind)| behind),
+    }))
+}
+
+fn operation(repo: &Repository) -> Res<Option<&'static str>> {
+    Ok(if git::rebase_status(repo)?.is_some() {
+        Some("rebase")
+    } else if git::merge_status(repo)?.is_some() {
+        Some("merge")
+    } else if git::revert_status(repo)?.is_some() {
+        Some("revert")
+    } else if git::cherry_pick_status(repo)?.is_some() {
+        Some("cherry_pick")
+    } else if git::is_bisecting(repo) {
+        Some("bisect")
+    } else if git::am_status(repo)?.is_some() {
+        Some("am")
+    } else {
+        None
+    })
+}
+
+/// Like `git log -n <max_count> [<rev>]`, from HEAD if there's no `rev`.
+pub(crate) fn log(repo: &Repository, rev: Option<&str>, max_count: usize) -> Res<Vec<LogEntry>> {
+    let mut revwalk = repo.revwalk()?;
+    match rev {
+        Some(rev) => revwalk.push(repo.revparse_single(rev)?.peel_to_commit()?.id())?,
+        None if repo.head().is_err() => return Ok(vec![]),
+        None => revwalk.push_head()?,
+    }
+
+    let references = repo
+        .references()?
+        .filter_map(Result::ok)
+        .filter_map(|reference| {
+            let name = reference.shorthand()?.to_string();
+            let target = reference.peel_to_commit().ok()?.id();
+            (!name.ends_with("/HEAD")).then_some((target, name))
+        })
+        .collect::<Vec<_>>();
+
+    revwalk
+        .take(max_count)
+        .map(|oid| {
+            let commit = repo.find_commit(oid?)?;
+            let entry = LogEntry {
+                hash: commit.id().to_string(),
+                short_hash: commit.as_object().short_id()?.as_str().unwrap_or("").into(),
+                summary: commit.summary().unwrap_or("").into(),
+                message: commit.message().unwrap_or("").into(),
+                author: person(&commit.author()),
+                committer: person(&commit.committer()),
+                parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+                refs: references
+                    .iter()
+                    .filter(|(target, _)| *target == commit.id())
+                    .map(|(_, name)| name.clone())
+                    .collect(),
+            };
+            Ok(entry)
+        })
+        .collect()
+}
+
+fn person(signature: &git2::Signature) -> Person {
+    let when = signature.when();
+    let time = chrono::FixedOffset::east_opt(when.offset_minutes() * 60)
+        .zip(chrono::DateTime::from_timestamp(when.seconds(), 0))
+        .map(|(offset, time)| time.with_timezone(&offset).to_rfc3339())
+        .unwrap_or_default();
+
+    Person {
+        name: signature.name().unwrap_or("").into(),
+        email: signature.email().unwrap_or("").into(),
+        time,
+    }
+}
+
+/// Like `git diff [<range>]`, the unstaged changes if there's no `range`.
+pub(crate) fn diff(config: &Config, repo: &Repository, range: Option<&str>) -> Res<Vec<FileDiff>> {
+    Ok(file_diffs(match range {
+        Some(range) => git::diff_range(config, repo, range, &[])?,
+        None => git::diff_unstaged(config, repo)?,
+    }))
+}
+
+fn file_diffs(diff: Diff) -> Vec<FileDiff> {
+    let path = |path: &Path| path.to_string_lossy().into_owned();
+
+    diff.deltas
+        .into_iter()
+        .map(|delta| FileDiff {
+            status: format!("{:?}", delta.status).to_lowercase(),
+            old_file: path(&delta.old_file),
+            new_file: path(&delta.new_file),
+            hunks: delta
+                .hunks
+                .iter()
+                .map(|hunk| HunkDiff {
+                    header: hunk.header.clone(),
+                    new_start: hunk.new_start,
+                    lines: hunk.content.lines.iter().map(ToString::to_string).collect(),
+                })
+                .collect(),
+        })
+        .collect()
+}
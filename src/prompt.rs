@@ -1,9 +1,15 @@
 use super::Res;
 use crate::ops::Action;
+use crate::picker::{Candidate, Picker};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use git2::Repository;
 use ratatui::{backend::Backend, Terminal};
-use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fs, path::PathBuf};
 use tui_prompts::{State as _, TextState};
 
+const REV_HISTORY_LIMIT: usize = 50;
+
 pub(crate) struct PromptData {
     pub(crate) prompt_text: Cow<'static, str>,
     pub(crate) update_fn: Action,
@@ -12,6 +18,11 @@ pub(crate) struct PromptData {
 pub(crate) struct Prompt {
     pub(crate) data: Option<PromptData>,
     pub(crate) state: TextState<'static>,
+    /// Choices listed below the input, narrowed down as it's typed
+    pub(crate) picker: Option<Picker>,
+    /// What was picked from the picker, if the input submitted was
+    pub(crate) picked: Option<Candidate>,
+    pub(crate) history: Option<History>,
 }
 
 impl Prompt {
@@ -19,18 +30,178 @@ impl Prompt {
         Prompt {
             data: None,
             state: TextState::new(),
+            picker: None,
+            picked: None,
+            history: None,
         }
     }
 
     pub(crate) fn set(&mut self, data: PromptData) {
         self.data = Some(data);
+        self.picker = None;
+        self.picked = None;
+        self.history = None;
         self.state.focus();
     }
 
     pub(crate) fn reset<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Res<()> {
         self.data = None;
         self.state = TextState::new();
+        self.picker = None;
+        self.picked = None;
+        self.history = None;
         terminal.hide_cursor()?;
         Ok(())
     }
+
+    pub(crate) fn handle_key_event(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press || (self.picker.is_none() && self.history.is_none()) {
+            self.state.handle_key_event(key);
+            return;
+        }
+
+        let browsing = self.history.as_ref().is_some_and(History::is_browsing);
+        let picking = !browsing && self.picker.as_ref().is_some_and(Picker::is_shown);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        let replacement = match key.code {
+            KeyCode::Tab => self.picker.as_mut().and_then(Picker::next),
+            KeyCode::BackTab => self.picker.as_mut().and_then(Picker::previous),
+            KeyCode::Char('n') if ctrl => self.picker.as_mut().and_then(Picker::next),
+            KeyCode::Char('p') if ctrl => self.picker.as_mut().and_then(Picker::previous),
+            KeyCode::Down if picking => self.picker.as_mut().and_then(Picker::next),
+            KeyCode::Up if picking => self.picker.as_mut().and_then(Picker::previous),
+            KeyCode::Up | KeyCode::Down => {
+                let history = self.history.as_mut();
+                let entry = history.and_then(|history| match key.code {
+                    KeyCode::Up => history.older(),
+                    _ => history.newer(),
+                });
+
+                if entry.is_some() {
+                    if let Some(picker) = &mut self.picker {
+                        picker.deselect();
+                    }
+                }
+                entry
+            }
+            _ => {
+                let before = self.state.value().to_string();
+                self.state.handle_key_event(key);
+                let input = self.state.value();
+                // Keys leaving the input as it was, like <enter>, keep what's picked
+                if let Some(picker) = self.picker.as_mut().filter(|_| input != before) {
+                    picker.filter(input);
+                }
+                if let Some(history) = &mut self.history {
+                    history.typed(input);
+                }
+                return;
+            }
+        };
+
+        if let Some(value) = replacement {
+            *self.state.value_mut() = value;
+            self.state.move_end();
+        }
+    }
+
+    /// Types pasted text, as a single line. A trailing newline, as copied along with a line, is left out.
+    pub(crate) fn paste(&mut self, text: &str) {
+        for c in text.trim_end_matches(['\r', '\n']).chars() {
+            let c = if c == '\r' || c == '\n' { ' ' } else { c };
+            self.handle_key_event(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+}
+
+/// Previous input of a prompt, to step through with up and down.
+pub(crate) struct History {
+    entries: Vec<String>,
+    /// The entry being shown, `None` while typing
+    index: Option<usize>,
+    /// What was typed, before stepping through the entries
+    input: String,
+}
+
+impl History {
+    pub(crate) fn new(entries: Vec<String>) -> Self {
+        Self {
+            entries,
+            index: None,
+            input: String::new(),
+        }
+    }
+
+    fn is_browsing(&self) -> bool {
+        self.index.is_some()
+    }
+
+    fn typed(&mut self, input: &str) {
+        self.index = None;
+        self.input = input.to_string();
+    }
+
+    fn older(&mut self) -> Option<String> {
+        let index = self.index.map_or(0, |i| i + 1);
+        let entry = self.entries.get(index)?.clone();
+        self.index = Some(index);
+        Some(entry)
+    }
+
+    fn newer(&mut self) -> Option<String> {
+        match self.index? {
+            0 => {
+                self.index = None;
+                Some(self.input.clone())
+            }
+            i => {
+                self.index = Some(i - 1);
+                Some(self.entries[i - 1].clone())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RevHistory {
+    revs: Vec<String>,
+}
+
+fn history_path(repo: &Repository) -> PathBuf {
+    repo.path().join("gitu").join("rev_history.toml")
+}
+
+/// Revisions previously entered in this repository, most recent first.
+pub(crate) fn load_history(repo: &Repository) -> Vec<String> {
+    let path = history_path(repo);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return vec![];
+    };
+
+    match toml::from_str::<RevHistory>(&content) {
+        Ok(history) => history.revs,
+        Err(err) => {
+            log::warn!("Couldn't read revision history {:?}: {}", path, err);
+            vec![]
+        }
+    }
+}
+
+/// Remembers a revision entered, to be stepped back to in later prompts.
+pub(crate) fn save_to_history(repo: &Repository, rev: &str) -> Res<()> {
+    let rev = rev.trim();
+    if rev.is_empty() {
+        return Ok(());
+    }
+
+    let mut revs = load_history(repo);
+    revs.retain(|previous| previous != rev);
+    revs.insert(0, rev.to_string());
+    revs.truncate(REV_HISTORY_LIMIT);
+
+    let path = history_path(repo);
+    fs::create_dir_all(path.parent().expect("History path has a parent"))?;
+    fs::write(path, toml::to_string(&RevHistory { revs })?)?;
+    Ok(())
 }
@@ -0,0 +1,78 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    items::{Item, TargetData},
+    syntax_highlight, Res,
+};
+use git2::Repository;
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+use std::{iter, path::PathBuf, rc::Rc};
+
+/// A file as of a revision, like `git show <rev>:<path>`, highlighted and with line numbers.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    rev: String,
+    path: PathBuf,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let commit = repo.revparse_single(&rev)?.peel_to_commit()?;
+            let blob = commit
+                .tree()?
+                .get_path(&path)?
+                .to_object(&repo)?
+                .peel_to_blob()?;
+
+            let header = iter::once(Item {
+                id: format!("blob_section_{}", path.display()).into(),
+                display: Line::styled(format!("{}:{}", rev, path.display()), &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            });
+
+            if blob.is_binary() {
+                return Ok(header
+                    .chain([Item {
+                        display: Line::raw("Binary file"),
+                        depth: 1,
+                        unselectable: true,
+                        ..Default::default()
+                    }])
+                    .collect());
+            }
+
+            let content = String::from_utf8_lossy(blob.content());
+            let lines = syntax_highlight::highlight_lines(&config, &path, &content);
+            let width = lines.len().to_string().len();
+
+            Ok(header
+                .chain(lines.into_iter().zip(1..).map(|(line, number)| {
+                    let gutter = Span::styled(format!("{:>width$} ", number), &style.line_number);
+
+                    Item {
+                        id: format!("blob_line_{}", number).into(),
+                        display: Line::from(
+                            iter::once(gutter).chain(line.spans).collect::<Vec<_>>(),
+                        ),
+                        depth: 1,
+                        target_data: Some(TargetData::BlobLine {
+                            rev: rev.clone(),
+                            path: path.clone(),
+                            line: number,
+                        }),
+                        ..Default::default()
+                    }
+                }))
+                .collect())
+        }),
+    )
+}
@@ -0,0 +1,56 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git,
+    items::{Item, TargetData},
+    menu::Menu,
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+
+pub(crate) const NAME: &str = "clean";
+
+/// Lists what `git clean` is about to remove, for it to be confirmed before anything is deleted.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    directories: bool,
+    ignored: bool,
+) -> Res<Screen> {
+    Ok(Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let files = git::clean_preview(&repo, directories, ignored)?;
+
+            Ok(iter::once(Item {
+                id: "clean_preview".into(),
+                display: Line::from(vec![
+                    Span::styled("Would remove", &style.section_header),
+                    format!(" ({})", files.len()).into(),
+                ]),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(files.into_iter().map(|path| Item {
+                id: path.to_string_lossy().to_string().into(),
+                display: Line::styled(path.to_string_lossy().to_string(), &style.file_header),
+                depth: 1,
+                target_data: Some(TargetData::File(path)),
+                ..Default::default()
+            }))
+            .collect())
+        }),
+    )?
+    .with_menu(Menu::CleanPreview)
+    .with_name(NAME))
+}
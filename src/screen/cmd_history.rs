@@ -0,0 +1,90 @@
+use super::Screen;
+use crate::{
+    cmd_log::{CmdLog, CmdLogEntry},
+    config::Config,
+    items::Item,
+    Res,
+};
+use ratatui::{
+    layout::Size,
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+};
+use std::{cell::RefCell, rc::Rc};
+
+pub(crate) const NAME: &str = "cmd_history";
+
+/// Lists every command run during the session, the most recent first.
+pub(crate) fn create(config: Rc<Config>, history: Rc<RefCell<CmdLog>>, size: Size) -> Res<Screen> {
+    Ok(Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let history = history.borrow();
+
+            let items = history
+                .entries
+                .iter()
+                .enumerate()
+                .rev()
+                .flat_map(|(i, entry)| {
+                    let CmdLogEntry::Cmd {
+                        args,
+                        out,
+                        exit_code,
+                        ..
+                    } = &*entry.read().unwrap()
+                    else {
+                        return vec![];
+                    };
+
+                    let status = match (out, exit_code) {
+                        (None, _) => Span::raw(" (running)"),
+                        (Some(_), Some(0)) => Span::raw(" (exit code 0)").dim(),
+                        (Some(_), Some(code)) => {
+                            Span::styled(format!(" (exit code {})", code), Style::new().red())
+                        }
+                        (Some(_), None) => Span::styled(" (killed)", Style::new().red()),
+                    };
+
+                    let header = Item {
+                        id: format!("cmd_{}", i).into(),
+                        display: Line::from(vec![
+                            Span::styled(format!("$ {}", args), &style.command),
+                            status,
+                        ]),
+                        section: true,
+                        depth: 0,
+                        ..Default::default()
+                    };
+
+                    let output = out.iter().flat_map(|out| {
+                        Text::raw(out.to_string())
+                            .lines
+                            .into_iter()
+                            .map(|line| Item {
+                                display: line,
+                                depth: 1,
+                                unselectable: true,
+                                ..Default::default()
+                            })
+                            .collect::<Vec<_>>()
+                    });
+
+                    [header].into_iter().chain(output).collect()
+                })
+                .collect::<Vec<_>>();
+
+            if items.is_empty() {
+                Ok(vec![Item {
+                    display: Line::raw("No commands run yet"),
+                    ..Default::default()
+                }])
+            } else {
+                Ok(items)
+            }
+        }),
+    )?
+    .with_name(NAME))
+}
@@ -0,0 +1,361 @@
+use std::{ffi::OsString, fs, iter, path::PathBuf, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git,
+    items::{self, Item},
+    menu::Menu,
+    Res,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use git2::Repository;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+use serde::{Deserialize, Serialize};
+
+pub(crate) const NAME: &str = "commit_msg";
+
+const CONVENTIONAL_TYPES: [&str; 11] = [
+    "build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style", "test",
+];
+
+const HISTORY_LIMIT: usize = 100;
+
+/// A commit message written within gitu rather than in `$EDITOR`,
+/// handed to `git commit --file=-` once done.
+pub(crate) struct CommitMsg {
+    pub(crate) args: Vec<OsString>,
+    lines: Vec<String>,
+    row: usize,
+    /// In chars, not bytes
+    col: usize,
+    /// Previously written messages, most recent first
+    history: Vec<String>,
+    /// The message from `history` being shown, `None` while writing a new one
+    history_index: Option<usize>,
+    draft: String,
+}
+
+impl CommitMsg {
+    pub(crate) fn new(args: Vec<OsString>, message: &str, history: Vec<String>) -> Self {
+        let mut commit_msg = Self {
+            args,
+            lines: vec![],
+            row: 0,
+            col: 0,
+            history,
+            history_index: None,
+            draft: String::new(),
+        };
+
+        commit_msg.set_message(message);
+        commit_msg
+    }
+
+    fn set_message(&mut self, message: &str) {
+        self.lines = message.lines().map(String::from).collect();
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+
+        self.row = 0;
+        self.col = self.line_len();
+    }
+
+    pub(crate) fn message(&self) -> String {
+        format!("{}\n", self.lines.join("\n"))
+    }
+
+    pub(crate) fn handle_key(&mut self, key: KeyEvent) {
+        if !(key.modifiers - KeyModifiers::SHIFT).is_empty() {
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char(c) => {
+                let i = self.byte_index();
+                self.lines[self.row].insert(i, c);
+                self.col += 1;
+            }
+            KeyCode::Enter => {
+                let i = self.byte_index();
+                let rest = self.lines[self.row].split_off(i);
+                self.lines.insert(self.row + 1, rest);
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Backspace if self.col > 0 => {
+                self.col -= 1;
+                let i = self.byte_index();
+                self.lines[self.row].remove(i);
+            }
+            KeyCode::Backspace if self.row > 0 => {
+                let line = self.lines.remove(self.row);
+                self.row -= 1;
+                self.col = self.line_len();
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Delete if self.col < self.line_len() => {
+                let i = self.byte_index();
+                self.lines[self.row].remove(i);
+            }
+            KeyCode::Delete if self.row + 1 < self.lines.len() => {
+                let line = self.lines.remove(self.row + 1);
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Left if self.col > 0 => self.col -= 1,
+            KeyCode::Left if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.line_len();
+            }
+            KeyCode::Right if self.col < self.line_len() => self.col += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len());
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len());
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(),
+            _ => (),
+        }
+    }
+
+    pub(crate) fn paste(&mut self, text: &str) {
+        for c in text.chars().filter(|&c| c != '\r') {
+            self.handle_key(KeyEvent::from(match c {
+                '\n' => KeyCode::Enter,
+                c => KeyCode::Char(c),
+            }));
+        }
+    }
+
+    pub(crate) fn previous_message(&mut self) {
+        let index = self.history_index.map_or(0, |i| i + 1);
+        let Some(message) = self.history.get(index).cloned() else {
+            return;
+        };
+
+        if self.history_index.is_none() {
+            self.draft = self.message();
+        }
+
+        self.history_index = Some(index);
+        self.set_message(&message);
+    }
+
+    pub(crate) fn next_message(&mut self) {
+        match self.history_index {
+            None => (),
+            Some(0) => {
+                self.history_index = None;
+                let draft = std::mem::take(&mut self.draft);
+                self.set_message(&draft);
+            }
+            Some(i) => {
+                self.history_index = Some(i - 1);
+                let message = self.history[i - 1].clone();
+                self.set_message(&message);
+            }
+        }
+    }
+
+    /// Completes a conventional commit type at the start of the summary line,
+    /// as far as it's unambiguous.
+    pub(crate) fn complete(&mut self) {
+        let line = &self.lines[self.row];
+        let prefix = &line[..self.byte_index()];
+        if self.row != 0 || prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_lowercase()) {
+            return;
+        }
+
+        let matches = CONVENTIONAL_TYPES
+            .iter()
+            .filter(|conventional_type| conventional_type.starts_with(prefix))
+            .collect::<Vec<_>>();
+
+        let rest = &line[prefix.len()..];
+        let completion = match matches[..] {
+            [] => return,
+            [conventional_type] if rest.is_empty() => format!("{}: ", conventional_type),
+            [conventional_type] => conventional_type.to_string(),
+            [first, ..] => first
+                .chars()
+                .enumerate()
+                .take_while(|&(i, c)| matches.iter().all(|m| m.chars().nth(i) == Some(c)))
+                .map(|(_, c)| c)
+                .collect(),
+        };
+
+        self.col = completion.len();
+        self.lines[self.row] = format!("{}{}", completion, rest);
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    fn byte_index(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map(|(i, _)| i)
+            .unwrap_or(line.len())
+    }
+
+    /// Lines taken up when shown above a screen of `height`, including the border.
+    pub(crate) fn height(&self, height: u16) -> u16 {
+        (self.lines.len() as u16 + 1).clamp(2, (height / 2).max(2))
+    }
+
+    fn scroll(&self, area: Rect) -> usize {
+        let visible = area.height.saturating_sub(1).max(1) as usize;
+        self.row.saturating_sub(visible - 1)
+    }
+
+    /// Where the terminal cursor goes when rendered to `area`.
+    pub(crate) fn cursor(&self, area: Rect) -> (u16, u16) {
+        let before_cursor: String = self.lines[self.row].chars().take(self.col).collect();
+        (
+            area.x + 1 + Line::raw(before_cursor).width() as u16,
+            area.y + (self.row - self.scroll(area)) as u16,
+        )
+    }
+}
+
+impl Widget for &CommitMsg {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let text = self
+            .lines
+            .iter()
+            .map(|line| Line::raw(format!(" {}", line)))
+            .collect::<Vec<_>>();
+
+        Paragraph::new(text)
+            .scroll((self.scroll(area) as u16, 0))
+            .block(
+                Block::new()
+                    .borders(Borders::BOTTOM)
+                    .border_style(Style::new().dim()),
+            )
+            .render(area, buf);
+    }
+}
+
+/// The message new commits start out with.
+pub(crate) fn template(config: &Config, repo: &Repository) -> String {
+    let template = &config.general.builtin_commit_editor.template;
+    if !template.is_empty() {
+        return template.clone();
+    }
+
+    repo.config()
+        .and_then(|git_config| git_config.get_path("commit.template"))
+        .ok()
+        .map(|path| match repo.workdir() {
+            Some(workdir) if path.is_relative() => workdir.join(path),
+            _ => path,
+        })
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct History {
+    messages: Vec<String>,
+}
+
+/// Writes the message where git can read it from, for when stdin isn't available.
+pub(crate) fn write_message_file(repo: &Repository, message: &str) -> Res<PathBuf> {
+    let path = repo.path().join("gitu").join("COMMIT_EDITMSG");
+    fs::create_dir_all(path.parent().expect("Message path has a parent"))?;
+    fs::write(&path, message)?;
+    Ok(path)
+}
+
+fn history_path(repo: &Repository) -> PathBuf {
+    repo.path().join("gitu").join("commit_msg_history.toml")
+}
+
+/// Messages previously written in this repository, most recent first.
+pub(crate) fn load_history(repo: &Repository) -> Vec<String> {
+    let path = history_path(repo);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return vec![];
+    };
+
+    match toml::from_str::<History>(&content) {
+        Ok(history) => history.messages,
+        Err(err) => {
+            log::warn!("Couldn't read commit message history {:?}: {}", path, err);
+            vec![]
+        }
+    }
+}
+
+/// Remembers a message, so that it's not lost if the commit fails or is aborted.
+pub(crate) fn save_to_history(repo: &Repository, message: &str) -> Res<()> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Ok(());
+    }
+
+    let mut messages = load_history(repo);
+    messages.retain(|previous| previous != message);
+    messages.insert(0, message.to_string());
+    messages.truncate(HISTORY_LIMIT);
+
+    let path = history_path(repo);
+    fs::create_dir_all(path.parent().expect("History path has a parent"))?;
+    fs::write(path, toml::to_string(&History { messages })?)?;
+    Ok(())
+}
+
+/// The changes to be committed, shown below the message.
+/// These start out collapsed to the file names, unless committing with `--verbose`.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    verbose: bool,
+) -> Res<Screen> {
+    Ok(Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let diff = git::diff_staged(&config, repo.as_ref())?;
+
+            Ok(iter::once(Item {
+                id: "commit_msg_changes".into(),
+                display: Line::from(vec![
+                    Span::styled("Changes to be committed", &style.section_header),
+                    format!(" ({})", diff.deltas.len()).into(),
+                ]),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(items::create_diff_items(
+                Rc::clone(&config),
+                "staged_changes",
+                &diff,
+                &1,
+                !verbose,
+            ))
+            .chain([items::blank_line()])
+            .collect())
+        }),
+    )?
+    .with_menu(Menu::CommitMsg)
+    .with_name(NAME))
+}
@@ -0,0 +1,84 @@
+use std::{iter, path::PathBuf, rc::Rc};
+
+use crate::{
+    config::Config,
+    git::{self, diff::Diff},
+    items::{self, Item},
+    Res,
+};
+use git2::Repository;
+use itertools::Itertools;
+use ratatui::{layout::Size, text::Line};
+
+use super::Screen;
+
+/// The diff of `range`, like `git diff <range> [-- <path>...]`, or of the unstaged changes.
+/// With `background_refresh`, refreshes load the diff off the main thread.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    background_refresh: bool,
+    range: Option<String>,
+    paths: Vec<PathBuf>,
+) -> Res<Screen> {
+    let id = format!("diff_section_{}", range.as_deref().unwrap_or_default());
+    let load = move |config: &Config, repo: &Repository| -> Res<(Diff, String)> {
+        Ok(match &range {
+            Some(range) => (
+                git::diff_range(config, repo, range, &paths)?,
+                match paths.is_empty() {
+                    true => format!("diff {}", range),
+                    false => format!(
+                        "diff {} -- {}",
+                        range,
+                        paths.iter().map(|path| path.display()).join(" ")
+                    ),
+                },
+            ),
+            None => (git::diff_unstaged(config, repo)?, "diff".to_string()),
+        })
+    };
+
+    Screen::new_from_repo(
+        Rc::clone(&config),
+        repo,
+        size,
+        background_refresh,
+        load,
+        move |(diff, title): (Diff, String)| {
+            let style = &config.style;
+            let header = iter::once(Item {
+                id: id.clone().into(),
+                display: Line::styled(title, &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            });
+
+            if diff.deltas.is_empty() {
+                return Ok(header
+                    .chain([Item {
+                        display: Line::raw("No differences"),
+                        depth: 1,
+                        unselectable: true,
+                        ..Default::default()
+                    }])
+                    .collect());
+            }
+
+            Ok(header
+                .chain([items::blank_line()])
+                .chain(items::create_diffstat_items(&config, &diff, 0))
+                .chain([items::blank_line()])
+                .chain(items::create_diff_items(
+                    Rc::clone(&config),
+                    "diff",
+                    &diff,
+                    &0,
+                    false,
+                ))
+                .collect())
+        },
+    )
+}
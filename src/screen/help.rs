@@ -0,0 +1,91 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    items::{self, Item},
+    menu::Menu,
+    Res,
+};
+use itertools::Itertools;
+use ratatui::{
+    layout::Size,
+    style::Stylize,
+    text::{Line, Span},
+};
+use std::{iter, rc::Rc};
+use unicode_width::UnicodeWidthStr;
+
+pub(crate) const NAME: &str = "help";
+
+/// An op as it's bound in a menu.
+pub(crate) struct HelpEntry {
+    pub(crate) menu: Menu,
+    pub(crate) keys: Vec<String>,
+    /// What the op is bound to by default, if the user's config changed it
+    pub(crate) default_keys: Option<Vec<String>>,
+    pub(crate) description: String,
+}
+
+/// Lists every keybinding, a section per menu. Bindings changed in the user's config are
+/// marked along with their defaults.
+pub(crate) fn create(config: Rc<Config>, entries: Rc<Vec<HelpEntry>>, size: Size) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+
+            Ok(entries
+                .iter()
+                .chunk_by(|entry| entry.menu)
+                .into_iter()
+                .flat_map(|(menu, entries)| {
+                    let entries = entries.collect::<Vec<_>>();
+                    let width = entries
+                        .iter()
+                        .map(|entry| entry.keys.join("/").width())
+                        .max()
+                        .unwrap_or(0);
+
+                    let header = Item {
+                        id: format!("help_{:?}", menu).into(),
+                        display: Line::styled(menu.to_string(), &style.section_header),
+                        section: true,
+                        depth: 0,
+                        ..Default::default()
+                    };
+
+                    let bindings = entries.into_iter().map(move |entry| {
+                        let mut spans = vec![
+                            Span::styled(
+                                items::pad_to_width(&entry.keys.join("/"), width),
+                                &style.hotkey,
+                            ),
+                            Span::raw(format!(" {}", entry.description)),
+                        ];
+
+                        match &entry.default_keys {
+                            Some(default_keys) if default_keys.is_empty() => {
+                                spans.push(Span::raw(" (added)").dim());
+                            }
+                            Some(default_keys) => spans.push(
+                                Span::raw(format!(" (default {})", default_keys.join("/"))).dim(),
+                            ),
+                            None => (),
+                        }
+
+                        Item {
+                            display: Line::from(spans),
+                            depth: 1,
+                            ..Default::default()
+                        }
+                    });
+
+                    iter::once(header)
+                        .chain(bindings)
+                        .chain(iter::once(items::blank_line()))
+                        .collect::<Vec<_>>()
+                })
+                .collect())
+        }),
+    )
+}
@@ -1,21 +1,198 @@
-use super::Screen;
-use crate::{config::Config, items::log, Res};
+use super::{open_repo, Screen};
+use crate::{
+    config::Config,
+    git::log_filter::LogFilter,
+    items::{self, commit_items, log_items, CommitLines, Item, LogLines, LogOptions, TargetData},
+    Res,
+};
 use git2::{Oid, Repository};
-use ratatui::layout::Size;
-use regex::Regex;
-use std::rc::Rc;
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+use std::{
+    cell::{Cell, RefCell},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread,
+};
 
+/// The args of a filter are shown above the commits.
+/// The graph is left out of filtered logs, where it would point at commits that aren't shown.
+/// Commits are loaded `general.log_page_size` at a time, up to `limit`, as the end comes close.
+/// A refresh walks the log from the start, later pages carry on where the walk left off.
+/// With `background_refresh`, refreshes and pages load the commits off the main thread.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn create(
     config: Rc<Config>,
     repo: Rc<Repository>,
     size: Size,
+    background_refresh: bool,
     limit: usize,
     rev: Option<Oid>,
-    msg_regex: Option<Regex>,
+    filter: LogFilter,
+    mut options: LogOptions,
 ) -> Res<Screen> {
-    Screen::new(
-        Rc::clone(&config),
-        size,
-        Box::new(move || log(&config, &repo, limit, rev, msg_regex.clone())),
+    options.graph &= filter.is_empty();
+
+    let page_size = config.general.log_page_size.max(1);
+    let loaded = Arc::new(AtomicUsize::new(page_size.min(limit)));
+    let has_more = Rc::new(Cell::new(false));
+    let walk = Rc::new(RefCell::new(None::<LogWalk>));
+
+    let load = {
+        let (loaded, filter) = (Arc::clone(&loaded), filter.clone());
+        let walk_config = Arc::new(Config::clone(&config));
+        move |_config: &Config, repo: &Repository| -> Res<(usize, Option<LogLines>, LogWalk)> {
+            let requested = loaded.load(Ordering::Relaxed);
+            let walk = LogWalk::start(Arc::clone(&walk_config), repo, rev, filter.clone(), options);
+            Ok((requested, walk.next_page(requested)?, walk))
+        }
+    };
+
+    let build = {
+        let (config, has_more, walk) = (Rc::clone(&config), Rc::clone(&has_more), Rc::clone(&walk));
+        move |(requested, commits, log_walk): (usize, Option<LogLines>, LogWalk)| {
+            let header = (!filter.args.is_empty()).then(|| {
+                [
+                    Item {
+                        id: "log_filters".into(),
+                        display: Line::from(vec![
+                            Span::styled("Filtered by", &config.style.section_header),
+                            format!(" {}", filter.args.join(" ")).into(),
+                        ]),
+                        unselectable: true,
+                        ..Default::default()
+                    },
+                    items::blank_line(),
+                ]
+            });
+
+            let commits = log_items(commits);
+            has_more.set(commit_count(&commits) == requested && requested < limit);
+            walk.replace(Some(log_walk));
+
+            Ok(header.into_iter().flatten().chain(commits).collect())
+        }
+    };
+
+    let screen = Screen::new_from_repo(config, repo, size, background_refresh, load, build)?;
+
+    let next_page = {
+        let (loaded, has_more) = (Arc::clone(&loaded), Rc::clone(&has_more));
+        move || {
+            let walk = walk.borrow().clone().filter(|_| has_more.get())?;
+            let requested = page_size.min(limit.saturating_sub(loaded.load(Ordering::Relaxed)));
+            Some(move || -> Res<(usize, LogLines)> {
+                Ok((requested, walk.next_page(requested)?.unwrap_or_default()))
+            })
+        }
+    };
+
+    Ok(
+        screen.with_load_more(next_page, move |(requested, commits): (usize, LogLines)| {
+            let commits = commit_items(commits);
+            let count = commit_count(&commits);
+            let total = loaded.fetch_add(count, Ordering::Relaxed) + count;
+            has_more.set(count == requested && total < limit);
+            Ok(commits)
+        }),
     )
 }
+
+fn commit_count(items: &[Item]) -> usize {
+    items
+        .iter()
+        .filter(|item| matches!(item.target_data, Some(TargetData::Commit(_))))
+        .count()
+}
+
+type PageRequest = (usize, Sender<Result<Option<LogLines>, String>>);
+
+/// Walks a log on a thread of its own, which keeps its place from one page to the next.
+/// The thread ends once the last clone is dropped.
+#[derive(Clone)]
+struct LogWalk {
+    requests: Sender<PageRequest>,
+}
+
+impl LogWalk {
+    fn start(
+        config: Arc<Config>,
+        repo: &Repository,
+        rev: Option<Oid>,
+        filter: LogFilter,
+        options: LogOptions,
+    ) -> Self {
+        let (requests, receiver) = mpsc::channel();
+        let git_dir = repo.path().to_path_buf();
+        let workdir = repo.workdir().map(Path::to_path_buf);
+
+        thread::spawn(move || walk(&config, &git_dir, workdir, rev, &filter, options, receiver));
+
+        Self { requests }
+    }
+
+    /// The next `count` commits, or `None` if there is no HEAD to start from.
+    fn next_page(&self, count: usize) -> Res<Option<LogLines>> {
+        let (sender, page) = mpsc::channel();
+        self.requests
+            .send((count, sender))
+            .map_err(|_| "Log walk stopped")?;
+        Ok(page.recv().map_err(|_| "Log walk stopped")??)
+    }
+}
+
+fn walk(
+    config: &Config,
+    git_dir: &Path,
+    workdir: Option<PathBuf>,
+    rev: Option<Oid>,
+    filter: &LogFilter,
+    options: LogOptions,
+    requests: Receiver<PageRequest>,
+) {
+    let repo = match open_repo(git_dir, workdir.as_deref()) {
+        Ok(repo) => repo,
+        Err(err) => {
+            for (_, reply) in requests {
+                let _ = reply.send(Err(err.to_string()));
+            }
+            return;
+        }
+    };
+    let mut walk = start_walk(config, &repo, rev, filter, options).map_err(|err| err.to_string());
+
+    for (count, reply) in requests {
+        let page = match &mut walk {
+            Ok(Some((revwalk, lines))) => lines
+                .next_page(config, &repo, revwalk, count)
+                .map(Some)
+                .map_err(|err| err.to_string()),
+            Ok(None) => Ok(None),
+            Err(err) => Err(err.clone()),
+        };
+        let _ = reply.send(page);
+    }
+}
+
+fn start_walk<'repo>(
+    config: &Config,
+    repo: &'repo Repository,
+    rev: Option<Oid>,
+    filter: &LogFilter,
+    options: LogOptions,
+) -> Res<Option<(git2::Revwalk<'repo>, CommitLines)>> {
+    let Some(revwalk) = items::log_revwalk(repo, rev, options)? else {
+        return Ok(None);
+    };
+    Ok(Some((
+        revwalk,
+        CommitLines::new(config, repo, filter, options)?,
+    )))
+}
@@ -1,17 +1,62 @@
 use ratatui::prelude::*;
 
-use crate::{config::Config, items::TargetData, Res};
+use crate::{
+    config::{Config, DiffSettings},
+    image::Image,
+    items::TargetData,
+    menu::Menu,
+    Res,
+};
+use git2::{Oid, Repository};
+use itertools::Itertools;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use super::Item;
-use std::{borrow::Cow, collections::HashSet, rc::Rc};
+use std::{
+    any::Any,
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::RangeInclusive,
+    path::Path,
+    rc::Rc,
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+    time::{Instant, SystemTime},
+};
 
+pub(crate) mod blob;
+pub(crate) mod clean;
+pub(crate) mod cmd_history;
+pub(crate) mod commit_msg;
+pub(crate) mod diff;
+pub(crate) mod help;
 pub(crate) mod log;
+#[cfg(feature = "forge")]
+pub(crate) mod pull_requests;
+pub(crate) mod rebase_todo;
+pub(crate) mod reflog;
+pub(crate) mod repositories;
+pub(crate) mod shortlog;
 pub(crate) mod show;
 pub(crate) mod show_refs;
 pub(crate) mod status;
+pub(crate) mod tree;
 
 const BOTTOM_CONTEXT_LINES: usize = 2;
 
+/// Opens another handle to the repository, since `Repository` can't be shared across threads.
+pub(crate) fn open_repo(git_dir: &Path, workdir: Option<&Path>) -> Res<Repository> {
+    let repo = Repository::open(git_dir)?;
+    if let Some(workdir) = workdir {
+        repo.set_workdir(workdir, false)?;
+    }
+    Ok(repo)
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) enum NavMode {
     Normal,
@@ -26,8 +71,142 @@ pub(crate) struct Screen {
     config: Rc<Config>,
     refresh_items: Box<dyn Fn() -> Res<Vec<Item>>>,
     items: Vec<Item>,
+    /// The items as built, when side-by-side rows were wrapped to the width into `items`
+    unwrapped_items: Option<Vec<Item>>,
     line_index: Vec<usize>,
     collapsed: HashSet<Cow<'static, str>>,
+    menu: Option<Menu>,
+    mark: Option<usize>,
+    parent_repo: Option<Rc<Repository>>,
+    name: Option<&'static str>,
+    background: Option<BackgroundRefresh>,
+    search: Option<Search>,
+    menu_args: Option<(Menu, MenuArgs)>,
+    load_more: Option<LoadMore>,
+    stamp: Option<RepoStamp>,
+}
+
+/// What the items of a screen were built from, as far as it's cheap to tell.
+/// A screen returned to while it's unchanged keeps its items, rather than asking git again.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RepoStamp {
+    /// The name and target of HEAD
+    pub head: Option<(Option<String>, Option<Oid>)>,
+    /// When the index was last written, and its size
+    pub index: Option<(SystemTime, u64)>,
+    /// Counts the commands gitu ran and the changes its file watcher saw
+    pub changes: u64,
+    pub diff_settings: DiffSettings,
+}
+
+/// Loads the items of the next page, handed to `append` once they arrive.
+type LoadPage = Box<dyn FnOnce() -> LoadResult + Send>;
+
+/// Loads items a page at a time, appending each to those already there.
+struct LoadMore {
+    /// The next page to load, or `None` if there are no more
+    next_page: Box<dyn Fn() -> Option<LoadPage>>,
+    append: BuildFn,
+    pending: Option<Receiver<LoadResult>>,
+}
+
+type MenuArgs = Vec<(&'static str, Option<String>)>;
+
+/// Identifies an item across refreshes.
+struct ItemKey {
+    /// From its id and those of the sections it's in
+    exact: u64,
+    /// From where it's placed among the items of its sections, for when it's gone
+    position: u64,
+    parent: Option<usize>,
+    parent_position: u64,
+    /// Which child of its parent it is
+    nth: usize,
+}
+
+fn item_keys(items: &[Item]) -> Vec<ItemKey> {
+    // Indices of the sections the current item is in, and how many children they've had so far
+    let mut sections: Vec<(usize, usize)> = vec![];
+    let mut top_level = 0;
+    let mut keys = Vec::<ItemKey>::with_capacity(items.len());
+
+    for (i, item) in items.iter().enumerate() {
+        while sections
+            .last()
+            .is_some_and(|&(section, _)| items[section].depth >= item.depth)
+        {
+            sections.pop();
+        }
+
+        let (parent, nth) = match sections.last_mut() {
+            Some((section, children)) => {
+                *children += 1;
+                (Some(*section), *children - 1)
+            }
+            None => {
+                top_level += 1;
+                (None, top_level - 1)
+            }
+        };
+
+        let (parent_exact, parent_position) =
+            parent.map_or((0, 0), |parent| (keys[parent].exact, keys[parent].position));
+
+        // Items without an id, like the lines of a hunk, are told apart by their place
+        let exact = if item.id.is_empty() {
+            hash((parent_exact, nth))
+        } else {
+            hash((parent_exact, &item.id))
+        };
+
+        keys.push(ItemKey {
+            exact,
+            position: hash((parent_position, nth)),
+            parent,
+            parent_position,
+            nth,
+        });
+        sections.push((i, 0));
+    }
+
+    keys
+}
+
+/// The result of a load on a background thread, if it's done.
+fn try_recv(receiver: &Receiver<LoadResult>) -> Option<LoadResult> {
+    match receiver.try_recv() {
+        Ok(result) => Some(result),
+        Err(TryRecvError::Empty) => None,
+        Err(TryRecvError::Disconnected) => Some(Err("Refresh thread panicked".to_string())),
+    }
+}
+
+fn hash(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The query being searched for, and where it was last found.
+struct Search {
+    query: String,
+    /// Item index of the match, and the cursor it was selected with
+    last_match: Option<(usize, usize)>,
+}
+
+type LoadedData = Box<dyn Any + Send>;
+type LoadResult = Result<LoadedData, String>;
+type LoadFn = Arc<dyn Fn() -> LoadResult + Send + Sync>;
+type BuildFn = Rc<dyn Fn(LoadedData) -> Res<Vec<Item>>>;
+
+/// Splits refreshing a screen into loading data on a background thread,
+/// and building the items from it once it arrives.
+struct BackgroundRefresh {
+    load: LoadFn,
+    build: BuildFn,
+    pending: Option<(Receiver<LoadResult>, Instant)>,
+    /// Whether the first refresh arrived yet
+    loaded: bool,
 }
 
 impl Screen {
@@ -36,6 +215,17 @@ impl Screen {
         size: Size,
         refresh_items: Box<dyn Fn() -> Res<Vec<Item>>>,
     ) -> Res<Self> {
+        let mut screen = Self::unloaded(config, size, refresh_items);
+        screen.update()?;
+        screen.init_view();
+        Ok(screen)
+    }
+
+    fn unloaded(
+        config: Rc<Config>,
+        size: Size,
+        refresh_items: Box<dyn Fn() -> Res<Vec<Item>>>,
+    ) -> Self {
         let collapsed = config
             .general
             .collapsed_sections
@@ -44,37 +234,269 @@ impl Screen {
             .map(Cow::Owned)
             .collect();
 
-        let mut screen = Self {
+        Self {
             cursor: 0,
             scroll: 0,
             size,
             config,
             refresh_items,
             items: vec![],
+            unwrapped_items: None,
             line_index: vec![],
             collapsed,
-        };
-
-        screen.update()?;
+            menu: None,
+            mark: None,
+            parent_repo: None,
+            name: None,
+            background: None,
+            search: None,
+            menu_args: None,
+            load_more: None,
+            stamp: None,
+        }
+    }
 
-        // TODO Maybe this should be done on update. Better keep track of toggled sections rather than collapsed then.
-        screen
-            .items
+    /// Folds the sections and places the cursor, as the first items call for.
+    fn init_view(&mut self) {
+        // Folds are kept from here on, by the ids of the sections, as the screen refreshes
+        self.items
             .iter()
             .filter(|item| item.default_collapsed)
             .for_each(|item| {
-                screen.collapsed.insert(item.id.clone());
+                self.collapsed.insert(item.id.clone());
             });
-        screen.update_line_index();
+        self.collapse_below(self.config.general.section_level);
+        self.update_line_index();
 
-        screen.cursor = screen
+        self.cursor = self
             .find_first_hunk()
-            .or_else(|| screen.find_first_selectable())
+            .or_else(|| self.find_first_selectable())
             .unwrap_or(0);
+    }
 
+    /// Like `new`, but refreshes run `load` on a background thread, and `build`
+    /// once `poll_refresh` picks up its result. Until the first one is done, the screen
+    /// shows that it's loading, so it can be drawn right away.
+    pub(crate) fn new_background<T: Send + 'static>(
+        config: Rc<Config>,
+        size: Size,
+        load: impl Fn() -> Res<T> + Send + Sync + 'static,
+        build: impl Fn(T) -> Res<Vec<Item>> + 'static,
+    ) -> Res<Self> {
+        let load: LoadFn = Arc::new(move || {
+            load()
+                .map(|data| Box::new(data) as LoadedData)
+                .map_err(|error| error.to_string())
+        });
+        let build: BuildFn = Rc::new(move |data| build(*data.downcast::<T>().unwrap()));
+
+        let (sync_load, sync_build) = (Arc::clone(&load), Rc::clone(&build));
+        let mut screen = Self::unloaded(config, size, Box::new(move || sync_build(sync_load()?)));
+        screen.items = vec![Item {
+            id: "loading".into(),
+            display: Line::raw("Loading..."),
+            unselectable: true,
+            ..Default::default()
+        }];
+        screen.update_line_index();
+
+        screen.background = Some(BackgroundRefresh {
+            load,
+            build,
+            pending: None,
+            loaded: false,
+        });
+        screen.update()?;
         Ok(screen)
     }
 
+    /// Loads with a config and repository of its own, as neither can be shared with another thread.
+    /// With `background_refresh`, that's like `new_background`, otherwise both run on the main thread like `new`.
+    pub(crate) fn new_from_repo<T: Send + 'static>(
+        config: Rc<Config>,
+        repo: Rc<Repository>,
+        size: Size,
+        background_refresh: bool,
+        load: impl Fn(&Config, &Repository) -> Res<T> + Send + Sync + 'static,
+        build: impl Fn(T) -> Res<Vec<Item>> + 'static,
+    ) -> Res<Self> {
+        if !background_refresh {
+            let load_config = Rc::clone(&config);
+            return Self::new(
+                config,
+                size,
+                Box::new(move || build(load(&load_config, &repo)?)),
+            );
+        }
+
+        let load_config = Arc::new(Config::clone(&config));
+        let git_dir = repo.path().to_path_buf();
+        let workdir = repo.workdir().map(Path::to_path_buf);
+
+        Self::new_background(
+            config,
+            size,
+            move || load(&load_config, &open_repo(&git_dir, workdir.as_deref())?),
+            build,
+        )
+    }
+
+    /// Waits for a pending background refresh, or page of items, to finish, e.g. before keys are sent to the screen.
+    pub(crate) fn await_refresh(&mut self) -> Res<()> {
+        if let Some(receiver) = self
+            .load_more
+            .as_mut()
+            .and_then(|load_more| load_more.pending.take())
+        {
+            let result = receiver
+                .recv()
+                .unwrap_or_else(|_| Err("Refresh thread panicked".to_string()));
+            self.append_page(result)?;
+        }
+
+        let Some((receiver, _)) = self
+            .background
+            .as_mut()
+            .and_then(|background| background.pending.take())
+        else {
+            return Ok(());
+        };
+
+        let result = receiver
+            .recv()
+            .unwrap_or_else(|_| Err("Refresh thread panicked".to_string()));
+        self.apply_refresh(result)
+    }
+
+    /// Keeps `menu` open while this screen is shown, instead of the root menu.
+    pub(crate) fn with_menu(mut self, menu: Menu) -> Self {
+        self.menu = Some(menu);
+        self
+    }
+
+    pub(crate) fn menu(&self) -> Option<Menu> {
+        self.menu
+    }
+
+    /// Opens `menu` with these args while this screen is shown, e.g. the filters a log was created with.
+    pub(crate) fn with_menu_args(mut self, menu: Menu, args: MenuArgs) -> Self {
+        self.menu_args = Some((menu, args));
+        self
+    }
+
+    pub(crate) fn menu_args(&self, menu: Menu) -> Option<&[(&'static str, Option<String>)]> {
+        self.menu_args
+            .as_ref()
+            .filter(|(args_menu, _)| *args_menu == menu)
+            .map(|(_, args)| &args[..])
+    }
+
+    /// Loads items a page at a time, e.g. the commits of a log. Once the end of those loaded comes close,
+    /// `next_page` is asked for the next one, and what it loads is handed to `append`
+    /// for the items to add. Pages load on a background thread for screens that refresh on one.
+    pub(crate) fn with_load_more<T: Send + 'static, F: FnOnce() -> Res<T> + Send + 'static>(
+        mut self,
+        next_page: impl Fn() -> Option<F> + 'static,
+        append: impl Fn(T) -> Res<Vec<Item>> + 'static,
+    ) -> Self {
+        self.load_more = Some(LoadMore {
+            next_page: Box::new(move || {
+                let load = next_page()?;
+                Some(Box::new(move || {
+                    load()
+                        .map(|data| Box::new(data) as LoadedData)
+                        .map_err(|error| error.to_string())
+                }))
+            }),
+            append: Rc::new(move |data| append(*data.downcast::<T>().unwrap())),
+            pending: None,
+        });
+        self
+    }
+
+    /// Loads more items while there are less than a page below what's shown.
+    /// Returns whether the items changed.
+    pub(crate) fn load_more_near_end(&mut self) -> Res<bool> {
+        let height = self.size.height as usize;
+        let mut loaded = false;
+
+        while self.cursor.max(self.scroll + height) + height >= self.line_index.len()
+            // A refresh in progress starts over with what was loaded by then
+            && self.refreshing_since().is_none()
+        {
+            let Some(load_more) = &mut self.load_more else {
+                break;
+            };
+            if load_more.pending.is_some() {
+                break;
+            }
+            let Some(load_page) = (load_more.next_page)() else {
+                break;
+            };
+
+            if self.background.is_some() {
+                let (sender, receiver) = mpsc::channel();
+                thread::spawn(move || sender.send(load_page()));
+                load_more.pending = Some(receiver);
+                break;
+            }
+
+            self.append_page(load_page())?;
+            loaded = true;
+        }
+
+        Ok(loaded)
+    }
+
+    fn append_page(&mut self, result: LoadResult) -> Res<()> {
+        let load_more = self.load_more.as_ref().expect("Loads pages");
+        let items = (load_more.append)(result?)?;
+
+        if self.unwrapped_items.is_some() || items.iter().any(|item| item.right_display.is_some()) {
+            let mut all = self
+                .unwrapped_items
+                .clone()
+                .unwrap_or_else(|| self.items.clone());
+            all.extend(items);
+            self.set_items(all);
+        } else {
+            // What's there already stays as it is
+            self.items.extend(items);
+            self.update_line_index();
+        }
+        Ok(())
+    }
+
+    /// The repository to return to once this screen is closed,
+    /// for screens rooted at another repository (e.g. a submodule).
+    pub(crate) fn with_parent_repo(mut self, repo: Rc<Repository>) -> Self {
+        self.parent_repo = Some(repo);
+        self
+    }
+
+    /// Tells this screen apart from others, e.g. for ops that toggle it.
+    pub(crate) fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub(crate) fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    pub(crate) fn into_parent_repo(self) -> Option<Rc<Repository>> {
+        self.parent_repo
+    }
+
+    /// Whether this is the first screen of a repository entered from another one.
+    pub(crate) fn has_parent_repo(&self) -> bool {
+        self.parent_repo.is_some()
+    }
+
+    pub(crate) fn items(&self) -> &[Item] {
+        &self.items
+    }
+
     fn find_first_hunk(&mut self) -> Option<usize> {
         (0..self.line_index.len()).find(|&line_i| {
             !self.at_line(line_i).unselectable
@@ -163,22 +585,36 @@ impl Screen {
     }
 
     pub(crate) fn scroll_half_page_up(&mut self) {
-        let half_screen = self.size.height as usize / 2;
-        self.scroll = self.scroll.saturating_sub(half_screen);
+        self.scroll_up(self.size.height as usize / 2);
+    }
+
+    pub(crate) fn scroll_half_page_down(&mut self) {
+        self.scroll_down(self.size.height as usize / 2);
+    }
+
+    pub(crate) fn scroll_page_up(&mut self) {
+        self.scroll_up(self.size.height as usize);
+    }
+
+    pub(crate) fn scroll_page_down(&mut self) {
+        self.scroll_down(self.size.height as usize);
+    }
+
+    fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
 
         let nav_mode = self.selected_item_nav_mode();
         self.update_cursor(nav_mode);
     }
 
-    pub(crate) fn scroll_half_page_down(&mut self) {
-        let half_screen = self.size.height as usize / 2;
-        self.scroll = (self.scroll + half_screen).min(
+    fn scroll_down(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(
             self.line_index
                 .iter()
                 .copied()
                 .enumerate()
-                .map(|(line, _)| (line + 1).saturating_sub(half_screen))
-                .last()
+                .map(|(line, _)| (line + 1).saturating_sub(lines))
+                .next_back()
                 .unwrap_or(0),
         );
 
@@ -186,6 +622,137 @@ impl Screen {
         self.update_cursor(nav_mode);
     }
 
+    pub(crate) fn select_first(&mut self) {
+        self.cursor = self.find_first_selectable().unwrap_or(0);
+        self.scroll_fit_end();
+        self.scroll_fit_start();
+    }
+
+    pub(crate) fn select_last(&mut self) {
+        self.cursor = (0..self.line_index.len())
+            .rev()
+            .find(|&line_i| self.nav_filter(line_i, NavMode::Normal))
+            .unwrap_or(self.cursor);
+        self.scroll_fit_end();
+        self.scroll_fit_start();
+    }
+
+    /// Moves to the next item, or the previous one if not `forward`, that `is_target` accepts.
+    /// Items within folded sections are found too, expanding them.
+    pub(crate) fn select_next_target(
+        &mut self,
+        forward: bool,
+        is_target: impl Fn(&TargetData) -> bool,
+    ) {
+        let start = self.selected_item_index();
+        let is_match = |item_i: &usize| {
+            let item: &Item = &self.items[*item_i];
+            !item.unselectable && item.target_data.as_ref().is_some_and(&is_target)
+        };
+
+        let found = if forward {
+            (start + 1..self.items.len()).find(is_match)
+        } else {
+            (0..start).rev().find(is_match)
+        };
+
+        if let Some(item_i) = found {
+            self.select_item(item_i);
+        }
+    }
+
+    /// Highlights `query`, or clears the search if it's `None`.
+    pub(crate) fn set_search(&mut self, query: Option<String>) {
+        self.search = query.map(|query| Search {
+            query,
+            last_match: None,
+        });
+    }
+
+    pub(crate) fn selected_item_index(&self) -> usize {
+        self.line_index[self.cursor]
+    }
+
+    /// Selects the first match at or after the item at `item_i`.
+    pub(crate) fn search_from(&mut self, item_i: usize) -> bool {
+        self.search_step(item_i, true, true)
+    }
+
+    pub(crate) fn search_next(&mut self) -> bool {
+        let start = self.search_start();
+        self.search_step(start, false, true)
+    }
+
+    pub(crate) fn search_previous(&mut self) -> bool {
+        let start = self.search_start();
+        self.search_step(start, false, false)
+    }
+
+    /// Continues from the last match, unless the cursor moved away from it since.
+    fn search_start(&self) -> usize {
+        match self.search.as_ref().and_then(|search| search.last_match) {
+            Some((item_i, cursor)) if cursor == self.cursor => item_i,
+            _ => self.selected_item_index(),
+        }
+    }
+
+    fn search_step(&mut self, start: usize, include_start: bool, forward: bool) -> bool {
+        let Some(search) = &self.search else {
+            return false;
+        };
+
+        let len = self.items.len();
+        let offsets = (if include_start { 0 } else { 1 })..len;
+        let found = offsets
+            .map(|offset| match forward {
+                true => (start + offset) % len,
+                false => (start + len - offset) % len,
+            })
+            .find(|&item_i| {
+                let item = &self.items[item_i];
+                std::iter::once(&item.display)
+                    .chain(&item.right_display)
+                    .any(|display| !find_matches(display, &search.query).is_empty())
+            });
+
+        let Some(item_i) = found else {
+            return false;
+        };
+
+        self.select_item(item_i);
+        if let Some(search) = &mut self.search {
+            search.last_match = Some((item_i, self.cursor));
+        }
+        true
+    }
+
+    /// Moves the cursor to an item, expanding the sections it's hidden within.
+    pub(crate) fn select_item(&mut self, item_i: usize) {
+        let mut depth = self.items[item_i].depth;
+        for parent in self.items[..item_i].iter().rev() {
+            if depth == 0 {
+                break;
+            }
+
+            if parent.depth < depth {
+                depth = parent.depth;
+                self.collapsed.remove(&parent.id);
+            }
+        }
+
+        self.update_line_index();
+        self.cursor = self
+            .line_index
+            .iter()
+            .position(|&i| i == item_i)
+            .unwrap_or(self.cursor);
+
+        // Matches on e.g. diff context lines can't be selected, settle on the closest line before
+        self.move_from_unselectable(NavMode::IncludeHunkLines);
+        self.scroll_fit_end();
+        self.scroll_fit_start();
+    }
+
     pub(crate) fn toggle_section(&mut self) {
         let selected = &self.items[self.line_index[self.cursor]];
 
@@ -200,12 +767,286 @@ impl Screen {
         self.update_line_index();
     }
 
+    /// Shows sections down to `level`, like Magit: 1 shows only the top sections, 2 their files,
+    /// 3 the hunks of those and 4 everything.
+    pub(crate) fn show_level(&mut self, level: usize) {
+        self.collapsed.clear();
+        self.collapse_below(level);
+        self.refold();
+    }
+
+    pub(crate) fn collapse_all(&mut self) {
+        self.collapse_below(1);
+        self.refold();
+    }
+
+    pub(crate) fn expand_all(&mut self) {
+        self.collapsed.clear();
+        self.refold();
+    }
+
+    fn collapse_below(&mut self, level: usize) {
+        for item in &self.items {
+            if item.section && item.depth + 1 >= level {
+                self.collapsed.insert(item.id.clone());
+            }
+        }
+    }
+
+    /// Keeps the cursor on the selected item, or the section it was folded into.
+    fn refold(&mut self) {
+        let selected = self.line_index.get(self.cursor).copied();
+        self.update_line_index();
+
+        if let Some(selected) = selected {
+            self.cursor = self
+                .line_index
+                .iter()
+                .rposition(|&item_i| item_i <= selected)
+                .unwrap_or(0);
+        }
+
+        self.clamp_cursor();
+        self.move_from_unselectable(NavMode::Normal);
+        self.scroll_fit_end();
+        self.scroll_fit_start();
+    }
+
+    pub(crate) fn toggle_mark(&mut self) {
+        self.mark = match self.mark {
+            Some(_) => None,
+            None => Some(self.cursor),
+        };
+    }
+
+    pub(crate) fn clear_mark(&mut self) {
+        self.mark = None;
+    }
+
+    fn region(&self) -> Option<RangeInclusive<usize>> {
+        self.mark
+            .map(|mark| mark.min(self.cursor)..=mark.max(self.cursor))
+    }
+
+    /// The targets of all items between the mark and the cursor,
+    /// at the same depth as the selected item.
+    pub(crate) fn get_region_targets(&self) -> Option<Vec<TargetData>> {
+        let depth = self.get_selected_item().depth;
+
+        self.region().map(|region| {
+            region
+                .map(|line_i| &self.items[self.line_index[line_i]])
+                .filter(|item| !item.unselectable && item.depth == depth)
+                .filter_map(|item| item.target_data.clone())
+                .collect()
+        })
+    }
+
     pub(crate) fn update(&mut self) -> Res<()> {
+        if let Some(load_more) = &mut self.load_more {
+            // Its items would be appended to those of the refresh
+            load_more.pending = None;
+        }
+
+        if let Some(background) = &mut self.background {
+            let (sender, receiver) = mpsc::channel();
+            let load = Arc::clone(&background.load);
+            thread::spawn(move || sender.send(load()));
+
+            // Replacing an older pending refresh discards its result
+            background.pending = Some((receiver, Instant::now()));
+            return Ok(());
+        }
+
+        let items = (self.refresh_items)()?;
+        self.set_items(items);
+        Ok(())
+    }
+
+    /// Like `update`, unless the items were built from the same `stamp`, e.g. when returning to the screen.
+    /// Without one, when changes could go unnoticed, it always refreshes.
+    pub(crate) fn update_if_changed(&mut self, stamp: Option<RepoStamp>) -> Res<()> {
+        if stamp.is_some() && stamp == self.stamp {
+            return Ok(());
+        }
+
+        self.update()
+    }
+
+    /// Tells the screen that its items are up to date with `stamp`.
+    pub(crate) fn set_stamp(&mut self, stamp: Option<RepoStamp>) {
+        self.stamp = stamp;
+    }
+
+    /// Applies the result of a background refresh, if one has arrived.
+    /// Returns whether the items changed.
+    pub(crate) fn poll_refresh(&mut self) -> Res<bool> {
+        if let Some(load_more) = &mut self.load_more {
+            if let Some(result) = load_more.pending.as_ref().and_then(try_recv) {
+                load_more.pending = None;
+                self.append_page(result)?;
+                return Ok(true);
+            }
+        }
+
+        let Some(background) = &mut self.background else {
+            return Ok(false);
+        };
+        let Some(result) = background
+            .pending
+            .as_ref()
+            .and_then(|(receiver, _)| try_recv(receiver))
+        else {
+            return Ok(false);
+        };
+
+        background.pending = None;
+        self.apply_refresh(result)?;
+        Ok(true)
+    }
+
+    fn apply_refresh(&mut self, result: LoadResult) -> Res<()> {
+        let background = self
+            .background
+            .as_mut()
+            .expect("Refreshes in the background");
+        let items = (background.build)(result?)?;
+        let first = !std::mem::replace(&mut background.loaded, true);
+
+        self.set_items(items);
+        if first {
+            self.init_view();
+        }
+        Ok(())
+    }
+
+    /// The first line shown, the line of the cursor and how many lines there are.
+    pub(crate) fn scroll_position(&self) -> (usize, usize, usize) {
+        (self.scroll, self.cursor, self.line_index.len())
+    }
+
+    /// When the pending background refresh started, if there is one.
+    pub(crate) fn refreshing_since(&self) -> Option<Instant> {
+        self.background
+            .as_ref()
+            .and_then(|background| background.pending.as_ref())
+            .map(|(_, started)| *started)
+    }
+
+    /// Re-wraps side-by-side rows when the width changes.
+    pub(crate) fn resize(&mut self, size: Size) {
+        let width_changed = size.width != self.size.width;
+        let height_shrunk = size.height < self.size.height;
+        self.size = size;
+
+        if width_changed {
+            if let Some(items) = self.unwrapped_items.take() {
+                self.set_items(items);
+            }
+        }
+
+        // Such as when a row is taken for the scroll position, the cursor is kept in view
+        let height = self.size.height as usize;
+        if height_shrunk && height > 0 && self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+    }
+
+    fn set_items(&mut self, items: Vec<Item>) {
         let nav_mode = self.selected_item_nav_mode();
-        self.items = (self.refresh_items)()?;
+        let selected = self.line_index.get(self.cursor).copied();
+        let old_keys = item_keys(&self.items);
+        self.keep_folds_of_moved_files(&items);
+
+        if items.iter().any(|item| item.right_display.is_some()) {
+            self.items = wrap_side_by_side(&items, self.size.width);
+            self.unwrapped_items = Some(items);
+        } else {
+            self.items = items;
+            self.unwrapped_items = None;
+        }
         self.update_line_index();
-        self.update_cursor(nav_mode);
-        Ok(())
+
+        match selected.and_then(|selected| self.find_same_item(selected, &old_keys)) {
+            Some(item_i) => {
+                // Hidden within a folded section, that section is selected instead
+                self.cursor = self
+                    .line_index
+                    .iter()
+                    .rposition(|&i| i <= item_i)
+                    .unwrap_or(0);
+                self.move_from_unselectable(nav_mode);
+                self.scroll_fit_end();
+                self.scroll_fit_start();
+            }
+            None => self.update_cursor(nav_mode),
+        }
+    }
+
+    /// Folds are kept by id, which differs between sections. A file that moved to another one, like when
+    /// it's staged, is folded as it was where it came from.
+    fn keep_folds_of_moved_files(&mut self, items: &[Item]) {
+        fn file(item: &Item) -> Option<&Path> {
+            match &item.target_data {
+                Some(TargetData::Delta(delta)) => Some(&delta.new_file),
+                Some(TargetData::Rename { new_file, .. }) => Some(new_file),
+                _ => None,
+            }
+        }
+
+        let old_ids = self
+            .items
+            .iter()
+            .map(|item| &item.id)
+            .collect::<HashSet<_>>();
+        let new_ids = items.iter().map(|item| &item.id).collect::<HashSet<_>>();
+        let gone = self
+            .items
+            .iter()
+            .filter(|item| !new_ids.contains(&item.id))
+            .filter_map(|item| Some((file(item)?, self.is_collapsed(item))))
+            .collect::<HashMap<_, _>>();
+
+        for item in items.iter().filter(|item| !old_ids.contains(&item.id)) {
+            match file(item).and_then(|file| gone.get(file)) {
+                Some(true) => self.collapsed.insert(item.id.clone()),
+                Some(false) => self.collapsed.remove(&item.id),
+                None => continue,
+            };
+        }
+    }
+
+    /// The item of a refresh that was the `selected` one before it. If that one's gone, such as
+    /// a file moving to another section when staged, what took its place is picked instead,
+    /// or else the one before it or the section it was in.
+    fn find_same_item(&self, selected: usize, old_keys: &[ItemKey]) -> Option<usize> {
+        let mut exact = HashMap::new();
+        let mut position = HashMap::new();
+        for (i, key) in item_keys(&self.items).into_iter().enumerate() {
+            exact.entry(key.exact).or_insert(i);
+            position.entry(key.position).or_insert(i);
+        }
+
+        if let Some(&i) = exact.get(&old_keys[selected].exact) {
+            return Some(i);
+        }
+
+        // Gone along with its top-level section, the cursor's left where it was
+        let mut old_i = selected;
+        while let Some(parent) = old_keys[old_i].parent {
+            let key = &old_keys[old_i];
+            let found = (0..=key.nth)
+                .rev()
+                .find_map(|nth| position.get(&hash((key.parent_position, nth))));
+
+            if let Some(&i) = found {
+                return Some(i);
+            }
+
+            old_i = parent;
+        }
+
+        None
     }
 
     fn update_cursor(&mut self, nav_mode: NavMode) {
@@ -230,6 +1071,8 @@ impl Screen {
     }
 
     fn update_line_index(&mut self) {
+        // Line indices are about to shift, the mark would point elsewhere
+        self.mark = None;
         self.line_index = self
             .items
             .iter()
@@ -284,15 +1127,43 @@ impl Screen {
         &self.items[self.line_index[self.cursor]]
     }
 
-    fn line_views(&self, area: Size) -> impl Iterator<Item = LineView> {
+    /// The item of each line shown within `area`, from the top.
+    #[cfg(feature = "forge")]
+    pub(crate) fn visible_items(&self, area: Size) -> impl Iterator<Item = &Item> {
+        self.line_views(area).map(|line| line.item)
+    }
+
+    /// The images of the items shown within `area`, each with the space it may take up from its line down.
+    pub(crate) fn images(&self, area: Rect) -> Vec<(Rect, Arc<Image>)> {
+        let max_rows = self.config.general.image_preview.rows;
+
+        self.line_views(area.as_size())
+            .enumerate()
+            .filter_map(|(line_index, line)| {
+                let image = line.item.image.as_ref()?;
+                let y = area.y + line_index as u16;
+                let image_area = Rect {
+                    x: area.x + 1,
+                    y,
+                    width: area.width.saturating_sub(1),
+                    height: (area.bottom() - y).min(max_rows),
+                };
+                Some((image_area, Arc::clone(image)))
+            })
+            .collect()
+    }
+
+    fn line_views(&self, area: Size) -> impl Iterator<Item = LineView<'_>> {
         let scan_start = self.scroll.min(self.cursor);
         let scan_end = (self.scroll + area.height as usize).min(self.line_index.len());
         let scan_highlight_range = scan_start..(scan_end);
         let context_lines = self.scroll - scan_start;
+        let region = self.region();
 
         self.line_index[scan_highlight_range]
             .iter()
-            .scan(None, |highlight_depth, item_i| {
+            .enumerate()
+            .scan(None, move |highlight_depth, (i, item_i)| {
                 let item = &self.items[*item_i];
                 if self.line_index[self.cursor] == *item_i {
                     *highlight_depth = Some(item.depth);
@@ -305,17 +1176,140 @@ impl Screen {
                     item,
                     display: &item.display,
                     highlighted: highlight_depth.is_some(),
+                    in_region: region
+                        .as_ref()
+                        .is_some_and(|region| region.contains(&(scan_start + i))),
                 })
             })
             .skip(context_lines)
     }
 }
 
+/// Columns of each occurrence of `query` within `line`.
+/// Case is ignored, unless the query contains uppercase letters.
+/// Puts a '…' at `x`. A wide character cut in half by it is blanked, rather than left to spill into it.
+fn set_ellipsis(buf: &mut Buffer, x: u16, y: u16, line_start: u16) {
+    if x > line_start && buf[(x - 1, y)].symbol().width() > 1 {
+        buf[(x - 1, y)].set_char(' ');
+    }
+    buf[(x, y)].set_char('…');
+}
+
+fn find_matches(line: &Line, query: &str) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return vec![];
+    }
+
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let normalize = |text: &str| -> Vec<char> {
+        if ignore_case {
+            text.chars().flat_map(char::to_lowercase).collect()
+        } else {
+            text.chars().collect()
+        }
+    };
+
+    let text = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect::<String>();
+    let (chars, query) = (normalize(&text), normalize(query));
+    if chars.len() < query.len() {
+        return vec![];
+    }
+
+    let column = |char_i: usize| Span::raw(chars[..char_i].iter().collect::<String>()).width();
+
+    (0..=chars.len() - query.len())
+        .filter(|&i| chars[i..i + query.len()] == query[..])
+        .map(|i| (column(i), column(i + query.len())))
+        .collect()
+}
+
+/// How wide the left and right column of a side-by-side row are, with a separator between them.
+fn side_by_side_columns(width: u16) -> (u16, u16) {
+    let left = width.saturating_sub(1) / 2;
+    (left, width.saturating_sub(1 + left))
+}
+
+/// Splits side-by-side rows that don't fit their columns onto more rows, which each
+/// column wraps onto separately. `width` is that of the screen, the rows are indented by one.
+fn wrap_side_by_side(items: &[Item], width: u16) -> Vec<Item> {
+    let (left_width, right_width) = side_by_side_columns(width.saturating_sub(1));
+
+    items
+        .iter()
+        .flat_map(|item| {
+            let Some(right) = &item.right_display else {
+                return vec![item.clone()];
+            };
+
+            let left = wrap_line(&item.display, left_width as usize);
+            let right = wrap_line(right, right_width as usize);
+            left.into_iter()
+                .zip_longest(right)
+                .enumerate()
+                .map(|(i, pair)| {
+                    let (left, right) = pair.left_and_right();
+                    let (display, right_display) =
+                        (left.unwrap_or_default(), Some(right.unwrap_or_default()));
+
+                    match i {
+                        0 => Item {
+                            display,
+                            right_display,
+                            ..item.clone()
+                        },
+                        _ => Item {
+                            display,
+                            right_display,
+                            depth: item.depth,
+                            unselectable: true,
+                            target_data: item.target_data.clone(),
+                            ..Default::default()
+                        },
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Breaks `line` into lines no wider than `width`, keeping the style of each character.
+fn wrap_line(line: &Line<'static>, width: usize) -> Vec<Line<'static>> {
+    let width = width.max(1);
+    let mut lines = vec![Line::default()];
+    let mut line_width = 0;
+
+    for span in &line.spans {
+        for char in span.content.chars() {
+            let char_width = char.width().unwrap_or(0);
+            if line_width + char_width > width && line_width > 0 {
+                lines.push(Line::default());
+                line_width = 0;
+            }
+
+            let current = lines.last_mut().unwrap();
+            match current.spans.last_mut() {
+                Some(last) if last.style == span.style => last.content.to_mut().push(char),
+                _ => current
+                    .spans
+                    .push(Span::styled(char.to_string(), span.style)),
+            }
+            line_width += char_width;
+        }
+    }
+
+    lines
+}
+
 struct LineView<'a> {
     item_index: usize,
     item: &'a Item,
     display: &'a Line<'a>,
     highlighted: bool,
+    in_region: bool,
 }
 
 impl Widget for &Screen {
@@ -324,37 +1318,97 @@ impl Widget for &Screen {
 
         for (line_index, line) in self.line_views(area.as_size()).enumerate() {
             let line_area = Rect {
-                x: 0,
-                y: line_index as u16,
-                width: buf.area.width,
+                x: area.x,
+                y: area.y + line_index as u16,
+                width: area.width,
                 height: 1,
             };
+            let (x, y) = (line_area.x, line_area.y);
 
-            let indented_line_area = Rect { x: 1, ..line_area };
+            let indented_line_area = Rect {
+                x: x + 1,
+                width: line_area.width.saturating_sub(1),
+                ..line_area
+            };
 
-            if line.highlighted {
+            if line.highlighted || line.in_region {
                 buf.set_style(line_area, &style.selection_area);
 
                 if self.line_index[self.cursor] == line.item_index {
                     buf.set_style(line_area, &style.selection_line);
                 } else {
-                    buf[(0, line_index as u16)]
+                    buf[(x, y)]
                         .set_char(style.selection_bar.symbol)
                         .set_style(&style.selection_bar);
                 }
             }
 
-            line.display.render(indented_line_area, buf);
-            let overflow = line.display.width() > line_area.width as usize;
+            if let Some(right) = &line.item.right_display {
+                let (left_width, right_width) = side_by_side_columns(indented_line_area.width);
+                let separator_x = indented_line_area.x + left_width;
+                line.display.render(
+                    Rect {
+                        width: left_width,
+                        ..indented_line_area
+                    },
+                    buf,
+                );
+                buf[(separator_x, y)].set_char('│');
+                right.render(
+                    Rect {
+                        x: separator_x + 1,
+                        width: right_width,
+                        ..indented_line_area
+                    },
+                    buf,
+                );
+            } else {
+                line.display.render(indented_line_area, buf);
+            }
+
+            if let Some(search) = &self.search {
+                // Each column of a side-by-side row has its own matches
+                let columns = match &line.item.right_display {
+                    Some(right) => {
+                        let (left_width, right_width) =
+                            side_by_side_columns(indented_line_area.width);
+                        vec![
+                            (line.display, indented_line_area.x, left_width),
+                            (right, indented_line_area.x + left_width + 1, right_width),
+                        ]
+                    }
+                    None => vec![(line.display, indented_line_area.x, indented_line_area.width)],
+                };
+
+                for (display, column_x, column_width) in columns {
+                    let column_area = Rect {
+                        x: column_x,
+                        width: column_width,
+                        ..indented_line_area
+                    };
+                    for (start, end) in find_matches(display, &search.query) {
+                        let match_area = Rect {
+                            x: column_x + start as u16,
+                            width: (end - start) as u16,
+                            ..indented_line_area
+                        };
+                        buf.set_style(match_area.intersection(column_area), &style.search_match);
+                    }
+                }
+            }
+
+            // Side-by-side rows are wrapped to fit instead
+            let overflow = line.item.right_display.is_none()
+                && line.display.width() > indented_line_area.width as usize;
 
             if self.is_collapsed(line.item) && line.display.width() > 0 || overflow {
                 let line_end =
-                    (indented_line_area.x + line.display.width() as u16).min(area.width - 1);
-                buf[(line_end, line_index as u16)].set_char('…');
+                    (indented_line_area.x + line.display.width() as u16).min(area.right() - 1);
+                set_ellipsis(buf, line_end, y, indented_line_area.x);
             }
 
             if self.line_index[self.cursor] == line.item_index {
-                buf[(0, line_index as u16)]
+                buf[(x, y)]
                     .set_char(style.cursor.symbol)
                     .set_style(&style.cursor);
             }
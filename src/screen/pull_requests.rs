@@ -0,0 +1,91 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    github::PullRequest,
+    items::{self, Item, TargetData},
+    Res,
+};
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+
+/// Lists the pull requests `load` fetches from `remote`.
+/// With `background_refresh`, refreshes after the first one fetch them off the main thread.
+pub(crate) fn create(
+    config: Rc<Config>,
+    size: Size,
+    remote: String,
+    background_refresh: bool,
+    load: impl Fn() -> Res<Vec<PullRequest>> + Send + Sync + 'static,
+) -> Res<Screen> {
+    let build_config = Rc::clone(&config);
+    let build = move |pulls| Ok(build(&build_config, &remote, pulls));
+
+    if !background_refresh {
+        return Screen::new(config, size, Box::new(move || build(load()?)));
+    }
+
+    Screen::new_background(config, size, load, build)
+}
+
+fn build(config: &Config, remote: &str, pulls: Vec<PullRequest>) -> Vec<Item> {
+    let style = &config.style;
+    let width = pulls
+        .iter()
+        .map(|pull| pull.number.to_string().len())
+        .max()
+        .unwrap_or(0);
+
+    let header = Item {
+        id: "pull_requests".into(),
+        display: Line::styled(
+            format!("Pull requests on {} ({})", remote, pulls.len()),
+            &style.section_header,
+        ),
+        section: true,
+        depth: 0,
+        ..Default::default()
+    };
+
+    if pulls.is_empty() {
+        return vec![header, items::blank_line()];
+    }
+
+    iter::once(header)
+        .chain(pulls.into_iter().map(|pull| {
+            let ci = match pull.ci.map(|ci| ci.glyph(style)) {
+                Some((glyph, glyph_style)) => Span::styled(glyph, glyph_style),
+                None => Span::raw(" "),
+            };
+
+            let mut spans = vec![
+                Span::styled(format!("#{:<width$}", pull.number), &style.hash),
+                " ".into(),
+                ci,
+                " ".into(),
+            ];
+            if pull.draft {
+                spans.push("[draft] ".into());
+            }
+            // Like GitHub shows the head, e.g. `octocat:fix-thing`
+            spans.extend([
+                Span::raw(pull.title),
+                " ".into(),
+                Span::styled(pull.author, &style.remote),
+                ":".into(),
+                Span::styled(pull.head_ref, &style.branch),
+            ]);
+
+            Item {
+                id: format!("pull_request_{}", pull.number).into(),
+                display: Line::from(spans),
+                depth: 1,
+                target_data: Some(TargetData::PullRequest(pull.number)),
+                ..Default::default()
+            }
+        }))
+        .collect()
+}
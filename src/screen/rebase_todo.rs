@@ -0,0 +1,158 @@
+use std::{cell::RefCell, ffi::OsString, iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    items::{Item, TargetData},
+    menu::Menu,
+    Res,
+};
+use git2::{Oid, Repository, Sort};
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TodoAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl TodoAction {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            TodoAction::Pick => "pick",
+            TodoAction::Reword => "reword",
+            TodoAction::Edit => "edit",
+            TodoAction::Squash => "squash",
+            TodoAction::Fixup => "fixup",
+            TodoAction::Drop => "drop",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TodoLine {
+    pub(crate) action: TodoAction,
+    pub(crate) commit: Oid,
+    pub(crate) short_id: String,
+    pub(crate) summary: String,
+}
+
+/// A rebase todo list that is edited within gitu and handed to
+/// `git rebase --interactive` once done.
+#[derive(Debug)]
+pub(crate) struct RebaseTodo {
+    pub(crate) base: Oid,
+    pub(crate) base_short_id: String,
+    pub(crate) args: Vec<OsString>,
+    pub(crate) lines: Vec<TodoLine>,
+}
+
+impl RebaseTodo {
+    /// Lists the commits after `base` up until HEAD, oldest first, like `git rebase -i` would.
+    /// Merges among them are refused, rather than flattened like a rebase without `--rebase-merges` does.
+    pub(crate) fn new(repo: &Repository, base: &str, args: Vec<OsString>) -> Res<Self> {
+        let base_commit = repo.revparse_single(base)?.peel_to_commit()?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        revwalk.push_head()?;
+        revwalk.hide(base_commit.id())?;
+
+        let lines = revwalk
+            .map(|oid| -> Res<TodoLine> {
+                let commit = repo.find_commit(oid?)?;
+                let short_id = commit.as_object().short_id()?.as_str().unwrap().to_string();
+                if commit.parent_count() > 1 {
+                    return Err(format!(
+                        "Can't rebase across the merge {}, it would be flattened",
+                        short_id
+                    )
+                    .into());
+                }
+
+                Ok(TodoLine {
+                    action: TodoAction::Pick,
+                    commit: commit.id(),
+                    short_id,
+                    summary: commit.summary().unwrap_or("").to_string(),
+                })
+            })
+            .collect::<Res<Vec<_>>>()?;
+
+        Ok(Self {
+            base: base_commit.id(),
+            base_short_id: base_commit
+                .as_object()
+                .short_id()?
+                .as_str()
+                .unwrap()
+                .to_string(),
+            args,
+            lines,
+        })
+    }
+
+    pub(crate) fn format(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| {
+                format!(
+                    "{} {} {}\n",
+                    line.action.as_str(),
+                    line.commit,
+                    line.summary
+                )
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn create(config: Rc<Config>, todo: Rc<RefCell<RebaseTodo>>, size: Size) -> Res<Screen> {
+    Ok(Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let lines = todo
+                .borrow()
+                .lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| Item {
+                    id: line.commit.to_string().into(),
+                    display: Line::from(vec![
+                        Span::styled(format!("{:6}", line.action.as_str()), &style.command),
+                        Span::raw(" "),
+                        Span::styled(line.short_id.clone(), &style.hash),
+                        Span::raw(" "),
+                        Span::raw(line.summary.clone()),
+                    ]),
+                    depth: 1,
+                    target_data: Some(TargetData::RebaseTodo(Rc::clone(&todo), i)),
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>();
+
+            Ok(iter::once(Item {
+                id: "rebase_todo".into(),
+                display: Line::styled(
+                    format!("Rebase onto {}", todo.borrow().base_short_id),
+                    &style.section_header,
+                ),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            })
+            .chain(lines)
+            .collect())
+        }),
+    )?
+    .with_menu(Menu::RebaseTodo))
+}
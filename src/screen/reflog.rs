@@ -0,0 +1,61 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+use std::rc::Rc;
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    limit: usize,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let reflog = repo.reflog("HEAD")?;
+
+            let items = reflog
+                .iter()
+                .enumerate()
+                .take(limit)
+                .map(|(i, entry)| -> Res<Item> {
+                    let oid = entry.id_new();
+                    let short_id = repo.find_object(oid, None)?.short_id()?;
+
+                    Ok(Item {
+                        id: format!("reflog_{}", i).into(),
+                        display: Line::from(vec![
+                            Span::styled(short_id.as_str().unwrap().to_string(), &style.hash),
+                            Span::raw(" "),
+                            Span::styled(format!("HEAD@{{{}}}", i), &style.branch),
+                            Span::raw(" "),
+                            Span::raw(entry.message().unwrap_or("").to_string()),
+                        ]),
+                        depth: 1,
+                        target_data: Some(TargetData::Commit(oid.to_string())),
+                        ..Default::default()
+                    })
+                })
+                .collect::<Res<Vec<_>>>()?;
+
+            if items.is_empty() {
+                Ok(vec![Item {
+                    display: Line::raw("No reflog entries found"),
+                    ..Default::default()
+                }])
+            } else {
+                Ok(items)
+            }
+        }),
+    )
+}
@@ -0,0 +1,181 @@
+use std::{
+    fs, iter,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use super::Screen;
+use crate::{
+    config::{Config, RepositoriesConfig},
+    items::{self, Item, TargetData},
+    Res,
+};
+use etcetera::{choose_base_strategy, BaseStrategy};
+use git2::Repository;
+use ratatui::{layout::Size, text::Line};
+use serde::{Deserialize, Serialize};
+
+const RECENT_LIMIT: usize = 20;
+
+#[derive(Default, Deserialize, Serialize)]
+struct RecentRepositories {
+    repositories: Vec<PathBuf>,
+}
+
+/// Where recently opened repositories are remembered, across all of them.
+pub(crate) fn recent_file() -> Res<PathBuf> {
+    Ok(choose_base_strategy()?
+        .data_dir()
+        .join("gitu")
+        .join("recent_repositories.toml"))
+}
+
+/// Repositories opened before, most recent first. Those that are gone are left out.
+pub(crate) fn load_recent(file: &Path) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(file) else {
+        return vec![];
+    };
+
+    match toml::from_str::<RecentRepositories>(&content) {
+        Ok(recent) => recent
+            .repositories
+            .into_iter()
+            .filter(|path| path.exists())
+            .collect(),
+        Err(err) => {
+            log::warn!("Couldn't read recent repositories {:?}: {}", file, err);
+            vec![]
+        }
+    }
+}
+
+/// Remembers a repository as the one opened last, by its work tree or else its git dir.
+pub(crate) fn save_to_recent(file: &Path, repo: &Repository) -> Res<()> {
+    let path = repo.workdir().unwrap_or(repo.path());
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    let mut repositories = load_recent(file);
+    repositories.retain(|previous| *previous != path);
+    repositories.insert(0, path);
+    repositories.truncate(RECENT_LIMIT);
+
+    fs::create_dir_all(file.parent().expect("Recent file has a parent"))?;
+    fs::write(file, toml::to_string(&RecentRepositories { repositories })?)?;
+    Ok(())
+}
+
+/// The repositories in a root and its directories, down to `depth` levels below it.
+/// Hidden directories and the insides of repositories aren't searched.
+fn discover(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    if dir.join(".git").exists() {
+        return vec![dir.to_path_buf()];
+    }
+    if depth == 0 {
+        return vec![];
+    }
+
+    let mut dirs = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    dirs.sort();
+
+    dirs.iter()
+        .flat_map(|dir| discover(dir, depth - 1))
+        .collect()
+}
+
+/// A leading `~` stands for the home directory.
+fn expand_home(root: &str) -> PathBuf {
+    match (root.strip_prefix('~'), etcetera::home_dir()) {
+        (Some(rest), Ok(home)) => home.join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(root),
+    }
+}
+
+pub(crate) fn create(config: Rc<Config>, recent_file: Option<PathBuf>, size: Size) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let recent = recent_file.as_deref().map(load_recent).unwrap_or_default();
+            let RepositoriesConfig { roots, depth } = &config.general.repositories;
+
+            let repository = |id: String, path: PathBuf, display: String| Item {
+                id: id.into(),
+                display: Line::styled(display, &style.file_header),
+                depth: 1,
+                target_data: Some(TargetData::Repository(path)),
+                ..Default::default()
+            };
+
+            let recent_section = (!recent.is_empty()).then(|| {
+                iter::once(Item {
+                    id: "recent_repositories".into(),
+                    display: Line::styled("Recent repositories", &style.section_header),
+                    section: true,
+                    depth: 0,
+                    ..Default::default()
+                })
+                .chain(recent.into_iter().map(|path| {
+                    let display = path.to_string_lossy().to_string();
+                    repository(format!("recent_{}", display), path, display)
+                }))
+                .collect::<Vec<_>>()
+            });
+
+            let root_sections = roots
+                .iter()
+                .map(|root| {
+                    let root_path = expand_home(root);
+                    let found = discover(&root_path, *depth);
+                    iter::once(Item {
+                        id: format!("root_{}", root).into(),
+                        display: Line::styled(
+                            format!("Repositories in {}", root),
+                            &style.section_header,
+                        ),
+                        section: true,
+                        depth: 0,
+                        ..Default::default()
+                    })
+                    .chain(found.into_iter().map(|path| {
+                        let display = path
+                            .strip_prefix(&root_path)
+                            .unwrap_or(&path)
+                            .to_string_lossy()
+                            .to_string();
+                        repository(format!("root_{}_{}", root, display), path, display)
+                    }))
+                    .collect::<Vec<_>>()
+                })
+                .filter(|section| section.len() > 1);
+
+            let sections = recent_section
+                .into_iter()
+                .chain(root_sections)
+                .collect::<Vec<_>>();
+
+            if sections.is_empty() {
+                return Ok(vec![Item {
+                    display: Line::raw(
+                        "No repositories yet, set `general.repositories.roots` to find them",
+                    ),
+                    unselectable: true,
+                    ..Default::default()
+                }]);
+            }
+
+            Ok(sections
+                .into_iter()
+                .enumerate()
+                .flat_map(|(i, section)| (i > 0).then(items::blank_line).into_iter().chain(section))
+                .collect())
+        }),
+    )
+}
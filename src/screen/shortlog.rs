@@ -0,0 +1,142 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::log_filter::LogFilter,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::{Oid, Repository};
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+use std::{collections::HashMap, iter, rc::Rc};
+
+/// The commits of an author, newest first.
+struct Author {
+    name: String,
+    commits: Vec<(Oid, String, String)>,
+}
+
+/// Who made the commits of a range, like `git shortlog -sn`, most commits first.
+/// Each author expands into their commits.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    range: String,
+    filter: LogFilter,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let authors = authors(&repo, &range, filter.clone())?;
+
+            if authors.is_empty() {
+                return Ok(vec![Item {
+                    display: Line::raw("No commits found"),
+                    ..Default::default()
+                }]);
+            }
+
+            let width = authors[0].commits.len().to_string().len();
+
+            Ok(authors
+                .into_iter()
+                .flat_map(|author| {
+                    let header = Item {
+                        id: format!("author_{}", author.name).into(),
+                        display: Line::from(vec![
+                            Span::raw(format!("{:>width$} ", author.commits.len())),
+                            Span::styled(author.name.clone(), &style.section_header),
+                        ]),
+                        section: true,
+                        default_collapsed: true,
+                        depth: 0,
+                        ..Default::default()
+                    };
+
+                    let commits = author
+                        .commits
+                        .into_iter()
+                        .map(|(oid, short_id, summary)| Item {
+                            id: format!("author_{}_{}", author.name, oid).into(),
+                            display: Line::from(vec![
+                                Span::styled(short_id, &style.hash),
+                                Span::raw(" "),
+                                Span::raw(summary),
+                            ]),
+                            depth: 1,
+                            target_data: Some(TargetData::Commit(oid.to_string())),
+                            ..Default::default()
+                        });
+
+                    iter::once(header).chain(commits).collect::<Vec<_>>()
+                })
+                .collect())
+        }),
+    )
+}
+
+/// Splits a count like that of `-3 newest` off a range, which takes only as many commits.
+pub(crate) fn split_max_count(range: &str) -> (Option<usize>, &str) {
+    let count = range
+        .split_once(' ')
+        .and_then(|(count, rev)| Some((count.strip_prefix('-')?.parse().ok()?, rev.trim())));
+
+    match count {
+        Some((count, rev)) => (Some(count), rev),
+        None => (None, range),
+    }
+}
+
+/// The authors of the commits in `range`, as the mailmap names them.
+/// A single revision stands for all the commits reachable from it.
+fn authors(repo: &Repository, range: &str, mut filter: LogFilter) -> Res<Vec<Author>> {
+    let (max_count, range) = split_max_count(range);
+    let mut revwalk = repo.revwalk()?;
+    let revspec = repo.revparse(range)?;
+    match (revspec.from(), revspec.to()) {
+        (Some(from), Some(to)) => {
+            revwalk.push(to.id())?;
+            revwalk.hide(from.id())?;
+        }
+        (Some(rev), None) | (None, Some(rev)) => revwalk.push(rev.id())?,
+        (None, None) => return Ok(vec![]),
+    }
+
+    let mailmap = repo.mailmap()?;
+    let mut commits_by_author = HashMap::<String, Vec<_>>::new();
+
+    for oid in revwalk.take(max_count.unwrap_or(usize::MAX)) {
+        let commit = repo.find_commit(oid?)?;
+        if !filter.matches(repo, &commit)? {
+            continue;
+        }
+
+        let signature = commit.author_with_mailmap(&mailmap)?;
+        let name = signature.name().unwrap_or("").to_string();
+        let entry = (
+            commit.id(),
+            commit.as_object().short_id()?.as_str().unwrap().to_string(),
+            commit.summary().unwrap_or("").to_string(),
+        );
+
+        commits_by_author.entry(name).or_default().push(entry);
+    }
+
+    let mut authors = commits_by_author
+        .into_iter()
+        .map(|(name, commits)| Author { name, commits })
+        .collect::<Vec<_>>();
+
+    authors.sort_by(|a, b| {
+        b.commits
+            .len()
+            .cmp(&a.commits.len())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(authors)
+}
@@ -1,37 +1,84 @@
-use std::{iter, rc::Rc};
+use std::{iter, path::PathBuf, rc::Rc};
 
 use crate::{
     config::Config,
-    git,
-    items::{self, Item},
+    git::{self, commit::Commit, diff::Diff},
+    items::{self, Item, TargetData},
     Res,
 };
 use git2::Repository;
 use ratatui::{
     layout::Size,
-    text::{Line, Text},
+    text::{Line, Span, Text},
 };
 
 use super::Screen;
 
+/// With `background_refresh`, refreshes load the commit off the main thread.
 pub(crate) fn create(
     config: Rc<Config>,
     repo: Rc<Repository>,
     size: Size,
+    background_refresh: bool,
     reference: String,
+    paths: Vec<PathBuf>,
 ) -> Res<Screen> {
-    Screen::new(
+    let load = move |config: &Config, repo: &Repository| -> Res<(Commit, Diff, Vec<String>)> {
+        Ok((
+            git::show_summary(repo, &reference)?,
+            git::show(config, repo, &reference, &paths)?,
+            git::tags_containing(repo, &reference)?,
+        ))
+    };
+
+    Screen::new_from_repo(
         Rc::clone(&config),
+        repo,
         size,
-        Box::new(move || {
+        background_refresh,
+        load,
+        move |(commit, show, tags): (Commit, Diff, Vec<String>)| {
             let style = &config.style;
-            let commit = git::show_summary(repo.as_ref(), &reference)?;
-            let show = git::show(&config, repo.as_ref(), &reference)?;
             let details = Text::from(commit.details).lines;
+            let diffstat = (!show.deltas.is_empty()).then(|| {
+                let mut diffstat = items::create_diffstat_items(&config, &show, 0);
+                diffstat.push(items::blank_line());
+                diffstat
+            });
+
+            let header = iter::once(Span::raw(format!("commit {}", commit.hash))).chain(
+                commit
+                    .describe
+                    .into_iter()
+                    .flat_map(|describe| [Span::raw(" "), Span::styled(describe, &style.tag)]),
+            );
+
+            let tags_section = (!tags.is_empty()).then(|| {
+                iter::once(items::blank_line())
+                    .chain([Item {
+                        id: "tags_containing".into(),
+                        display: Line::styled(
+                            format!("Tags containing this commit ({})", tags.len()),
+                            &style.section_header,
+                        ),
+                        section: true,
+                        default_collapsed: true,
+                        depth: 0,
+                        ..Default::default()
+                    }])
+                    .chain(tags.into_iter().map(|tag| Item {
+                        id: format!("tags_containing_{}", tag).into(),
+                        display: Line::styled(tag.clone(), &style.tag),
+                        depth: 1,
+                        target_data: Some(TargetData::Tag(tag)),
+                        ..Default::default()
+                    }))
+                    .collect::<Vec<_>>()
+            });
 
             Ok(iter::once(Item {
                 id: format!("commit_section_{}", commit.hash).into(),
-                display: Line::styled(format!("commit {}", commit.hash), &style.section_header),
+                display: Line::from(header.collect::<Vec<_>>()).style(&style.section_header),
                 section: true,
                 depth: 0,
                 ..Default::default()
@@ -43,14 +90,17 @@ pub(crate) fn create(
                 unselectable: true,
                 ..Default::default()
             }))
+            .chain(tags_section.into_iter().flatten())
             .chain([items::blank_line()])
+            .chain(diffstat.into_iter().flatten())
             .chain(items::create_diff_items(
                 Rc::clone(&config),
+                "diff",
                 &show,
                 &0,
                 false,
             ))
             .collect())
-        }),
+        },
     )
 }
@@ -10,11 +10,12 @@ use crate::{
     items::{self, Item, TargetData},
     Res,
 };
-use git2::{Reference, Repository};
+use git2::{BranchType, Reference, Repository};
 use ratatui::{
     layout::Size,
     text::{Line, Span},
 };
+use unicode_width::UnicodeWidthStr;
 
 pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Size) -> Res<Screen> {
     Screen::new(
@@ -30,10 +31,10 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Size) -> Re
                 depth: 0,
                 ..Default::default()
             })
-            .chain(create_references_section(
+            .chain(create_branches_section(
                 &repo,
-                Reference::is_branch,
                 &style.branch,
+                &style.remote,
             )?)
             .chain(create_remotes_sections(
                 &repo,
@@ -50,12 +51,78 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Size) -> Re
     )
 }
 
+/// Local branches, along with the upstream they track and how far apart the two are.
+fn create_branches_section(
+    repo: &Repository,
+    style: &StyleConfigEntry,
+    upstream_style: &StyleConfigEntry,
+) -> Res<Vec<Item>> {
+    let branches = repo
+        .references()?
+        .filter_map(Result::ok)
+        .filter(Reference::is_branch)
+        .collect::<Vec<_>>();
+
+    let width = branches
+        .iter()
+        .filter_map(Reference::shorthand)
+        .map(UnicodeWidthStr::width)
+        .max()
+        .unwrap_or(0);
+
+    Ok(branches
+        .into_iter()
+        .map(|reference| {
+            let shorthand = reference.shorthand().unwrap().to_owned();
+            let mut spans = vec![
+                create_prefix(repo, &reference),
+                Span::styled(shorthand.clone(), style),
+            ];
+
+            if let Some((upstream, ahead, behind)) = upstream(repo, &reference) {
+                spans.push(" ".repeat(width - shorthand.width() + 1).into());
+                spans.push(Span::styled(upstream, upstream_style));
+                if ahead > 0 {
+                    spans.push(format!(" ↑{}", ahead).into());
+                }
+                if behind > 0 {
+                    spans.push(format!(" ↓{}", behind).into());
+                }
+            }
+
+            Item {
+                id: reference.name().unwrap().to_owned().into(),
+                display: Line::from(spans),
+                depth: 1,
+                target_data: Some(TargetData::Branch(shorthand)),
+                ..Default::default()
+            }
+        })
+        .collect())
+}
+
+/// The upstream of a local branch, with the commits the branch is ahead and behind of it.
+fn upstream(repo: &Repository, reference: &Reference) -> Option<(String, usize, usize)> {
+    let branch = repo
+        .find_branch(reference.shorthand()?, BranchType::Local)
+        .ok()?;
+    let upstream = branch.upstream().ok()?;
+    let name = upstream.get().shorthand()?.to_owned();
+    let (ahead, behind) = match (reference.target(), upstream.get().target()) {
+        (Some(local), Some(remote)) => repo.graph_ahead_behind(local, remote).ok()?,
+        _ => (0, 0),
+    };
+
+    Some((name, ahead, behind))
+}
+
 fn create_remotes_sections<'a>(
     repo: &'a Repository,
     header_style: &'a StyleConfigEntry,
     item_style: &'a StyleConfigEntry,
 ) -> Res<impl Iterator<Item = Item> + 'a> {
-    let all_remotes = create_references_section(repo, Reference::is_remote, item_style)?;
+    let all_remotes =
+        create_references_section(repo, Reference::is_remote, item_style, TargetData::Branch)?;
     let mut remotes = BTreeMap::new();
     for remote in all_remotes {
         let name = String::from_utf8_lossy(&repo.branch_remote_name(&remote.id)?).to_string();
@@ -75,10 +142,11 @@ fn create_remotes_sections<'a>(
         vec![
             items::blank_line(),
             Item {
-                id: name.into(),
+                id: name.clone().into(),
                 display: Line::styled(header, header_style),
                 section: true,
                 depth: 0,
+                target_data: Some(TargetData::Remote(name)),
                 ..Default::default()
             },
         ]
@@ -92,7 +160,7 @@ fn create_tags_section<'a>(
     header_style: &'a StyleConfigEntry,
     item_style: &'a StyleConfigEntry,
 ) -> Res<impl Iterator<Item = Item> + 'a> {
-    let mut tags = create_references_section(repo, Reference::is_tag, item_style)?;
+    let mut tags = create_references_section(repo, Reference::is_tag, item_style, TargetData::Tag)?;
     Ok(match tags.next() {
         Some(item) => vec![
             items::blank_line(),
@@ -115,6 +183,7 @@ fn create_references_section<'a, F>(
     repo: &'a Repository,
     filter: F,
     style: &'a StyleConfigEntry,
+    target_data: fn(String) -> TargetData,
 ) -> Res<impl Iterator<Item = Item> + 'a>
 where
     F: FnMut(&Reference<'a>) -> bool + 'a,
@@ -133,7 +202,7 @@ where
                     Span::styled(shorthand.clone(), style),
                 ]),
                 depth: 1,
-                target_data: Some(TargetData::Branch(shorthand)),
+                target_data: Some(target_data(shorthand)),
                 ..Default::default()
             }
         }))
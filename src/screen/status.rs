@@ -1,132 +1,428 @@
 use super::Screen;
 use crate::{
     config::Config,
-    git::{self, diff::Diff},
+    git::{
+        self, conflict::Conflict, diff::Diff, log_filter::LogFilter, merge_status::MergeStatus,
+        rebase_status::RebaseStatus, AmStatus, BisectStatus, CherryPickStatus, RevertStatus,
+        SubmoduleStatus,
+    },
     git2_opts,
-    items::{self, Item, TargetData},
+    items::{self, Item, LogLines, LogOptions, StashLines, TargetData},
     Res,
 };
-use git2::Repository;
+use git2::{BranchType, Repository};
 use ratatui::{
     prelude::Size,
     text::{Line, Span},
 };
-use std::{path::PathBuf, rc::Rc};
-
-pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Size) -> Res<Screen> {
-    Screen::new(
-        Rc::clone(&config),
-        size,
-        Box::new(move || {
-            let style = &config.style;
-            let statuses = repo.statuses(Some(&mut git2_opts::status(&repo)?))?;
-
-            let untracked_files = statuses
-                .iter()
-                .filter(|status| status.status().is_wt_new())
-                .map(|status| PathBuf::from(status.path().unwrap()))
-                .collect::<Vec<_>>();
+use std::{
+    iter,
+    path::{Path, PathBuf},
+    rc::Rc,
+    thread::{self, Scope, ScopedJoinHandle},
+};
 
-            let unmerged_files = statuses
-                .iter()
-                .filter(|status| status.status().is_conflicted())
-                .map(|status| PathBuf::from(status.path().unwrap()))
-                .collect::<Vec<_>>();
+const UPSTREAM_COMMITS_LIMIT: usize = 100;
+const STASHES_LIMIT: usize = 10;
 
-            let untracked = items_list(&config, untracked_files.clone());
-            let unmerged = items_list(&config, unmerged_files);
+/// Everything the status screen shows, which may be loaded off the main thread.
+struct StatusData {
+    banner: Banner,
+    untracked_files: Vec<PathBuf>,
+    unmerged_files: Vec<(PathBuf, Vec<Conflict>)>,
+    submodules: Vec<SubmoduleStatus>,
+    unstaged: Diff,
+    staged: Diff,
+    stashes: StashLines,
+    stash_count: usize,
+    upstream_commits: Vec<UpstreamCommits>,
+    /// `None` without a HEAD to start from
+    recent_commits: Option<LogLines>,
+}
 
-            let items = if let Some(rebase) = git::rebase_status(&repo)? {
-                vec![Item {
-                    id: "rebase_status".into(),
-                    display: Line::styled(
-                        format!("Rebasing {} onto {}", rebase.head_name, &rebase.onto),
-                        &style.section_header,
-                    ),
-                    ..Default::default()
-                }]
+/// What's going on in the repository, shown at the top.
+enum Banner {
+    Rebase {
+        status: RebaseStatus,
+        /// Of the commit the rebase stopped to edit
+        edit_summary: String,
+    },
+    Merge(MergeStatus),
+    Revert(RevertStatus),
+    CherryPick(CherryPickStatus),
+    Am(AmStatus),
+    Bisect(BisectStatus),
+    Branch(BranchStatus),
+}
+
+enum BranchStatus {
+    NoBranch,
+    Detached {
+        head: HeadCommit,
+    },
+    OnBranch {
+        name: String,
+        head: HeadCommit,
+        upstream: Option<Upstream>,
+    },
+}
+
+/// The commit HEAD points at.
+struct HeadCommit {
+    id: String,
+    short_id: String,
+    summary: String,
+}
+
+enum Upstream {
+    Gone {
+        name: String,
+    },
+    Tracking {
+        name: String,
+        ahead: usize,
+        behind: usize,
+    },
+}
+
+/// A section of the commits that pulling from or pushing to the upstream would bring over.
+struct UpstreamCommits {
+    id: &'static str,
+    title: String,
+    count: usize,
+    commits: LogLines,
+}
+
+fn load(config: &Config, repo: &Repository) -> Res<StatusData> {
+    let paths = (repo.path(), repo.workdir());
+    thread::scope(|scope| {
+        // Each part of the work tree is looked up at once, as none depends on another.
+        // Without a work tree or index, a bare repository only has refs and commits to show.
+        let work_tree = (!repo.is_bare()).then(|| {
+            (
+                spawn_on_own_repo(scope, config, paths, |_, repo| load_files(repo)),
+                spawn_on_own_repo(scope, config, paths, |_, repo| {
+                    git::submodule_statuses(repo)
+                }),
+                spawn_on_own_repo(scope, config, paths, git::diff_unstaged),
+                spawn_on_own_repo(scope, config, paths, git::diff_staged),
+            )
+        });
+
+        // Meanwhile, the refs and commits are read here
+        let banner = load_banner(repo)?;
+        let stashes = items::stash_lines(config, repo, STASHES_LIMIT)?;
+        let stash_count = repo.reflog("refs/stash").map_or(0, |reflog| reflog.len());
+        let upstream_commits = load_upstream_commits(config, repo)?;
+        let recent_commits = match config.general.recent_commits_limit {
+            0 => None,
+            limit => items::log_lines(
+                config,
+                repo,
+                limit,
+                None,
+                &LogFilter::default(),
+                LogOptions::default(),
+            )?,
+        };
+
+        let Some((files, submodules, unstaged, staged)) = work_tree else {
+            return Ok(StatusData {
+                banner,
+                untracked_files: vec![],
+                unmerged_files: vec![],
+                submodules: vec![],
+                unstaged: Diff { deltas: vec![] },
+                staged: Diff { deltas: vec![] },
+                stashes,
+                stash_count,
+                upstream_commits,
+                recent_commits,
+            });
+        };
+
+        let (untracked_files, unmerged_files) = join(files)?;
+        Ok(StatusData {
+            banner,
+            untracked_files,
+            unmerged_files,
+            submodules: join(submodules)?,
+            unstaged: join(unstaged)?,
+            staged: join(staged)?,
+            stashes,
+            stash_count,
+            upstream_commits,
+            recent_commits,
+        })
+    })
+}
+
+type StatusFiles = (Vec<PathBuf>, Vec<(PathBuf, Vec<Conflict>)>);
+
+/// The untracked files, and the unmerged ones with their conflicts.
+fn load_files(repo: &Repository) -> Res<StatusFiles> {
+    let statuses = repo.statuses(Some(&mut git2_opts::status(repo)?))?;
+
+    let untracked_files = statuses
+        .iter()
+        .filter(|status| status.status().is_wt_new())
+        .map(|status| git::path_from_bytes(status.path_bytes()))
+        .collect::<Vec<_>>();
+
+    let unmerged_files = statuses
+        .iter()
+        .filter(|status| status.status().is_conflicted())
+        .map(|status| {
+            let path = git::path_from_bytes(status.path_bytes());
+            let conflicts = git::conflicts(repo, &path);
+            (path, conflicts)
+        })
+        .collect::<Vec<_>>();
+
+    Ok((untracked_files, unmerged_files))
+}
+
+/// Runs `load` on a thread, with a handle to the repository of its own.
+fn spawn_on_own_repo<'scope, 'env, T: Send + 'scope>(
+    scope: &'scope Scope<'scope, 'env>,
+    config: &'env Config,
+    (git_dir, workdir): (&'env Path, Option<&'env Path>),
+    load: fn(&Config, &Repository) -> Res<T>,
+) -> ScopedJoinHandle<'scope, Result<T, String>> {
+    scope.spawn(move || {
+        let repo = super::open_repo(git_dir, workdir).map_err(|err| err.to_string())?;
+        load(config, &repo).map_err(|err| err.to_string())
+    })
+}
+
+fn join<T>(handle: ScopedJoinHandle<'_, Result<T, String>>) -> Res<T> {
+    Ok(handle.join().map_err(|_| "Status thread panicked")??)
+}
+
+/// With `background_refresh`, refreshes after the first one load the status off the main thread.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    background_refresh: bool,
+) -> Res<Screen> {
+    let build = {
+        let config = Rc::clone(&config);
+        move |data: StatusData| Ok(build(&config, data))
+    };
+
+    Screen::new_from_repo(config, repo, size, background_refresh, load, build)
+}
+
+/// The first of a rebase, merge, revert, cherry-pick, `git am` or bisect that's going on,
+/// otherwise the branch and how it compares to its upstream.
+fn load_banner(repo: &Repository) -> Res<Banner> {
+    Ok(if let Some(status) = git::rebase_status(repo)? {
+        let edit_summary = status
+            .edit
+            .as_deref()
+            .and_then(|edit| git2::Oid::from_str(edit).ok())
+            .and_then(|oid| repo.find_commit(oid).ok())
+            .map(|commit| commit.summary().unwrap_or("").to_string())
+            .unwrap_or_default();
+
+        Banner::Rebase {
+            status,
+            edit_summary,
+        }
+    } else if let Some(merge) = git::merge_status(repo)? {
+        Banner::Merge(merge)
+    } else if let Some(revert) = git::revert_status(repo)? {
+        Banner::Revert(revert)
+    } else if let Some(cherry_pick) = git::cherry_pick_status(repo)? {
+        Banner::CherryPick(cherry_pick)
+    } else if let Some(am) = git::am_status(repo)? {
+        Banner::Am(am)
+    } else if let Some(bisect) = git::bisect_status(repo)? {
+        Banner::Bisect(bisect)
+    } else {
+        Banner::Branch(branch_status(repo)?)
+    })
+}
+
+/// What a rebase stopped to edit, and how to go on from there.
+fn edit_items(config: &Config, edit: String, summary: String) -> Vec<Item> {
+    let style = &config.style;
+
+    vec![
+        Item {
+            id: "rebase_edit".into(),
+            display: Line::from(vec![
+                Span::raw("Stopped to edit "),
+                Span::styled(edit.chars().take(7).collect::<String>(), &style.hash),
+                Span::raw(" "),
+                Span::raw(summary),
+            ]),
+            target_data: Some(TargetData::Commit(edit)),
+            ..Default::default()
+        },
+        Item {
+            id: "rebase_edit_hint".into(),
+            display: Line::raw(
+                "Amend it, or split it to stage and commit the parts, then continue the rebase",
+            ),
+            unselectable: true,
+            ..Default::default()
+        },
+    ]
+}
+
+fn banner_items(config: &Config, banner: Banner) -> Vec<Item> {
+    let style = &config.style;
+
+    match banner {
+        Banner::Rebase {
+            status,
+            edit_summary,
+        } => iter::once(Item {
+            id: "rebase_status".into(),
+            display: Line::styled(
+                format!("Rebasing {} onto {}", status.head_name, &status.onto),
+                &style.section_header,
+            ),
+            target_data: Some(TargetData::Commit(status.onto)),
+            ..Default::default()
+        })
+        .chain(
+            status
+                .edit
+                .map(|edit| edit_items(config, edit, edit_summary))
                 .into_iter()
-            } else if let Some(merge) = git::merge_status(&repo)? {
-                vec![Item {
-                    id: "merge_status".into(),
-                    display: Line::styled(
-                        format!("Merging {}", &merge.head),
-                        &style.section_header,
+                .flatten(),
+        )
+        .collect(),
+        Banner::Merge(merge) => vec![Item {
+            id: "merge_status".into(),
+            display: Line::styled(format!("Merging {}", &merge.head), &style.section_header),
+            target_data: Some(TargetData::Commit(merge.head)),
+            ..Default::default()
+        }],
+        Banner::Revert(revert) => vec![Item {
+            id: "revert_status".into(),
+            display: Line::styled(format!("Reverting {}", &revert.head), &style.section_header),
+            target_data: Some(TargetData::Commit(revert.head)),
+            ..Default::default()
+        }],
+        Banner::CherryPick(cherry_pick) => vec![Item {
+            id: "cherry_pick_status".into(),
+            display: Line::styled(
+                format!("Cherry-picking {}", &cherry_pick.head),
+                &style.section_header,
+            ),
+            target_data: Some(TargetData::Commit(cherry_pick.head)),
+            ..Default::default()
+        }],
+        Banner::Am(am) => vec![Item {
+            id: "am_status".into(),
+            display: Line::styled(
+                format!("Applying patch {} of {}", am.current, am.total),
+                &style.section_header,
+            ),
+            ..Default::default()
+        }],
+        Banner::Bisect(bisect) => vec![Item {
+            id: "bisect_status".into(),
+            display: Line::styled(
+                match bisect.remaining {
+                    Some((revisions, steps)) => format!(
+                        "Bisecting: {} revision{} left to test (roughly {} step{})",
+                        revisions,
+                        if revisions == 1 { "" } else { "s" },
+                        steps,
+                        if steps == 1 { "" } else { "s" },
                     ),
+                    None => "Bisecting: waiting for both good and bad commits".into(),
+                },
+                &style.section_header,
+            ),
+            ..Default::default()
+        }],
+        Banner::Branch(branch) => branch_status_items(config, branch),
+    }
+}
+
+fn build(config: &Rc<Config>, data: StatusData) -> Vec<Item> {
+    let style = &config.style;
+    let StatusData {
+        banner,
+        untracked_files,
+        unmerged_files,
+        submodules,
+        unstaged,
+        staged,
+        stashes,
+        stash_count,
+        upstream_commits,
+        recent_commits,
+    } = data;
+
+    let untracked = items_list(config, untracked_files.clone());
+    let unmerged = unmerged_items(config, unmerged_files);
+
+    banner_items(config, banner)
+        .into_iter()
+        .chain(if untracked.is_empty() {
+            vec![]
+        } else {
+            vec![
+                items::blank_line(),
+                Item {
+                    id: "untracked".into(),
+                    display: Line::styled("Untracked files", &style.section_header),
+                    section: true,
+                    depth: 0,
+                    target_data: Some(TargetData::AllUntracked(untracked_files)),
                     ..Default::default()
-                }]
-                .into_iter()
-            } else if let Some(revert) = git::revert_status(&repo)? {
-                vec![Item {
-                    id: "revert_status".into(),
-                    display: Line::styled(
-                        format!("Reverting {}", &revert.head),
-                        &style.section_header,
-                    ),
+                },
+            ]
+        })
+        .chain(untracked)
+        .chain(if unmerged.is_empty() {
+            vec![]
+        } else {
+            vec![
+                items::blank_line(),
+                Item {
+                    id: "unmerged".into(),
+                    display: Line::styled("Unmerged", &style.section_header),
+                    section: true,
+                    depth: 0,
                     ..Default::default()
-                }]
-                .into_iter()
-            } else {
-                branch_status_items(&config, &repo)?.into_iter()
-            }
-            .chain(if untracked.is_empty() {
-                vec![]
-            } else {
-                vec![
-                    items::blank_line(),
-                    Item {
-                        id: "untracked".into(),
-                        display: Line::styled("Untracked files", &style.section_header),
-                        section: true,
-                        depth: 0,
-                        target_data: Some(TargetData::AllUntracked(untracked_files)),
-                        ..Default::default()
-                    },
-                ]
-            })
-            .chain(untracked)
-            .chain(if unmerged.is_empty() {
-                vec![]
-            } else {
-                vec![
-                    items::blank_line(),
-                    Item {
-                        id: "unmerged".into(),
-                        display: Line::styled("Unmerged", &style.section_header),
-                        section: true,
-                        depth: 0,
-                        ..Default::default()
-                    },
-                ]
-            })
-            .chain(unmerged)
-            .chain(create_status_section_items(
-                Rc::clone(&config),
-                "unstaged_changes",
-                Some(TargetData::AllUnstaged),
-                &git::diff_unstaged(&config, repo.as_ref())?,
-            ))
-            .chain(create_status_section_items(
-                Rc::clone(&config),
-                "staged_changes",
-                Some(TargetData::AllStaged),
-                &git::diff_staged(&config, repo.as_ref())?,
-            ))
-            .chain(create_stash_list_section_items(
-                Rc::clone(&config),
-                repo.as_ref(),
-                "stashes",
-            ))
-            .chain(create_log_section_items(
-                Rc::clone(&config),
-                repo.as_ref(),
-                "recent_commits",
-            ))
-            .collect();
-
-            Ok(items)
-        }),
-    )
+                },
+            ]
+        })
+        .chain(unmerged)
+        .chain(create_submodule_section_items(config, submodules))
+        .chain(create_status_section_items(
+            Rc::clone(config),
+            "unstaged_changes",
+            Some(TargetData::AllUnstaged),
+            &unstaged,
+        ))
+        .chain(create_status_section_items(
+            Rc::clone(config),
+            "staged_changes",
+            Some(TargetData::AllStaged),
+            &staged,
+        ))
+        .chain(create_stash_list_section_items(
+            config,
+            "stashes",
+            stashes,
+            stash_count,
+        ))
+        .chain(create_upstream_section_items(config, upstream_commits))
+        .chain(create_log_section_items(
+            config,
+            "recent_commits",
+            recent_commits,
+        ))
+        .collect()
 }
 
 fn items_list(config: &Config, files: Vec<PathBuf>) -> Vec<Item> {
@@ -143,31 +439,110 @@ fn items_list(config: &Config, files: Vec<PathBuf>) -> Vec<Item> {
         .collect::<Vec<_>>()
 }
 
-fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
+/// Each conflicted file, followed by the conflicts left in it.
+fn unmerged_items(config: &Config, files: Vec<(PathBuf, Vec<Conflict>)>) -> Vec<Item> {
     let style = &config.style;
-    let Ok(head) = repo.head() else {
-        return Ok(vec![Item {
-            id: "branch_status".into(),
-            display: Line::styled("No branch", &style.section_header),
-            section: true,
-            depth: 0,
-            ..Default::default()
-        }]);
+    files
+        .into_iter()
+        .flat_map(|(path, conflicts)| {
+            let path_string = path.to_string_lossy().to_string();
+            let file_item = Item {
+                id: path_string.clone().into(),
+                display: Line::styled(path_string.clone(), &style.file_header),
+                section: !conflicts.is_empty(),
+                depth: 1,
+                target_data: Some(TargetData::File(path.clone())),
+                ..Default::default()
+            };
+
+            let conflict_items = conflicts
+                .into_iter()
+                .enumerate()
+                .flat_map(move |(index, conflict)| {
+                    conflict_items(config, &path, &path_string, index, conflict)
+                })
+                .collect::<Vec<_>>();
+
+            iter::once(file_item).chain(conflict_items)
+        })
+        .collect()
+}
+
+fn conflict_items(
+    config: &Config,
+    path: &Path,
+    path_string: &str,
+    index: usize,
+    conflict: Conflict,
+) -> Vec<Item> {
+    let style = &config.style;
+    let target_data = TargetData::Conflict {
+        file: path.to_path_buf(),
+        index,
     };
 
-    let mut items = vec![Item {
-        id: "branch_status".into(),
+    let marker = |text: String| Line::styled(text, &style.diff_highlight.unchanged_old);
+    let lines = |lines: Vec<String>, line_style| {
+        lines
+            .into_iter()
+            .map(move |line| Line::styled(line, line_style))
+            .collect::<Vec<_>>()
+    };
+
+    let mut display = vec![marker(format!("<<<<<<< {}", conflict.ours_label))];
+    display.extend(lines(conflict.ours, &style.diff_highlight.changed_old));
+    if let Some(base) = conflict.base {
+        display.push(marker("|||||||".into()));
+        display.extend(lines(base, &style.diff_highlight.unchanged_old));
+    }
+    display.push(marker("=======".into()));
+    display.extend(lines(conflict.theirs, &style.diff_highlight.changed_new));
+    display.push(marker(format!(">>>>>>> {}", conflict.theirs_label)));
+
+    iter::once(Item {
+        id: format!("conflict_{}_{}", path_string, index).into(),
         display: Line::styled(
-            format!("On branch {}", head.shorthand().unwrap()),
-            &style.section_header,
+            format!("@@ conflict at line {} @@", conflict.line),
+            &style.hunk_header,
         ),
         section: true,
-        depth: 0,
+        depth: 2,
+        target_data: Some(target_data.clone()),
         ..Default::default()
-    }];
+    })
+    .chain(display.into_iter().map(|display| Item {
+        display,
+        depth: 3,
+        unselectable: true,
+        target_data: Some(target_data.clone()),
+        ..Default::default()
+    }))
+    .collect()
+}
 
+fn branch_status(repo: &Repository) -> Res<BranchStatus> {
+    let Ok(head) = repo.head() else {
+        return Ok(BranchStatus::NoBranch);
+    };
+
+    let commit = repo.find_commit(head.target().unwrap())?;
+    let head_commit = HeadCommit {
+        id: commit.id().to_string(),
+        short_id: commit.as_object().short_id()?.as_str().unwrap().to_string(),
+        summary: commit.summary().unwrap_or("").to_string(),
+    };
+
+    if repo.head_detached()? {
+        return Ok(BranchStatus::Detached { head: head_commit });
+    }
+
+    let name = head.shorthand().unwrap().to_string();
     let Ok(upstream) = repo.branch_upstream_name(head.name().unwrap()) else {
-        return Ok(items);
+        return Ok(BranchStatus::OnBranch {
+            name,
+            head: head_commit,
+            upstream: None,
+        });
     };
     let upstream_name = upstream.as_str().unwrap().to_string();
     let upstream_shortname = upstream_name
@@ -175,51 +550,178 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
         .unwrap_or(&upstream_name)
         .to_string();
 
-    let Ok(upstream_id) = repo.refname_to_id(&upstream_name) else {
-        items.push(Item {
-            id: "branch_status".into(),
-            display: format!(
-                "Your branch is based on '{}', but the upstream is gone.",
-                upstream_shortname
-            )
-            .into(),
-            depth: 1,
-            unselectable: true,
-            ..Default::default()
-        });
-        return Ok(items);
+    let upstream = match repo.refname_to_id(&upstream_name) {
+        Ok(upstream_id) => {
+            let (ahead, behind) = repo.graph_ahead_behind(head.target().unwrap(), upstream_id)?;
+            Upstream::Tracking {
+                name: upstream_shortname,
+                ahead,
+                behind,
+            }
+        }
+        Err(_) => Upstream::Gone {
+            name: upstream_shortname,
+        },
     };
 
-    let (ahead, behind) = repo.graph_ahead_behind(head.target().unwrap(), upstream_id)?;
+    Ok(BranchStatus::OnBranch {
+        name,
+        head: head_commit,
+        upstream: Some(upstream),
+    })
+}
 
-    items.push(Item {
+/// The branch, the commit it's at and how it compares to its upstream, each with what ops on them need.
+fn branch_status_items(config: &Config, branch: BranchStatus) -> Vec<Item> {
+    let style = &config.style;
+    let branch_item = |text: String, target_data: Option<TargetData>| Item {
         id: "branch_status".into(),
-        display: if ahead == 0 && behind == 0 {
-            Line::raw(format!("Your branch is up to date with '{}'.", upstream_shortname))
-        } else if ahead > 0 && behind == 0 {
-            Line::raw(format!(
-                "Your branch is ahead of '{}' by {} commit.",
-                upstream_shortname, ahead
-            ))
-        } else if ahead == 0 && behind > 0 {
+        display: Line::styled(text, &style.section_header),
+        section: true,
+        depth: 0,
+        target_data,
+        ..Default::default()
+    };
+    let head_item = |head: HeadCommit| Item {
+        id: "branch_status_head".into(),
+        display: Line::from(vec![
+            "Head: ".into(),
+            Span::styled(head.short_id, &style.hash),
+            format!(" {}", head.summary).into(),
+        ]),
+        depth: 1,
+        target_data: Some(TargetData::Commit(head.id)),
+        ..Default::default()
+    };
+
+    let (name, head, upstream) = match branch {
+        BranchStatus::NoBranch => return vec![branch_item("No branch".into(), None)],
+        BranchStatus::Detached { head } => {
+            return vec![
+                branch_item(
+                    format!("HEAD detached at {}", head.short_id),
+                    Some(TargetData::Commit(head.id.clone())),
+                ),
+                head_item(head),
+            ]
+        }
+        BranchStatus::OnBranch {
+            name,
+            head,
+            upstream,
+        } => (name, head, upstream),
+    };
+
+    let mut items = vec![
+        branch_item(
+            format!("On branch {}", name),
+            Some(TargetData::Branch(name)),
+        ),
+        head_item(head),
+    ];
+    let (display, target_data) = match upstream {
+        None => return items,
+        // There's no upstream branch left to act on
+        Some(Upstream::Gone { name }) => (
             Line::raw(format!(
-                "Your branch is behind '{}' by {} commit.",
-                upstream_shortname, behind
-            ))
-        } else {
-            Line::raw(format!("Your branch and '{}' have diverged,\nand have {} and {} different commits each, respectively.", upstream_shortname, ahead, behind))
-        },
+                "Your branch is based on '{}', but the upstream is gone.",
+                name
+            )),
+            None,
+        ),
+        Some(Upstream::Tracking {
+            name,
+            ahead,
+            behind,
+        }) => {
+            let display = if ahead == 0 && behind == 0 {
+                Line::raw(format!("Your branch is up to date with '{}'.", name))
+            } else if ahead > 0 && behind == 0 {
+                Line::raw(format!(
+                    "Your branch is ahead of '{}' by {} commit.",
+                    name, ahead
+                ))
+            } else if ahead == 0 && behind > 0 {
+                Line::raw(format!(
+                    "Your branch is behind '{}' by {} commit.",
+                    name, behind
+                ))
+            } else {
+                Line::raw(format!("Your branch and '{}' have diverged,\nand have {} and {} different commits each, respectively.", name, ahead, behind))
+            };
+            (display, Some(TargetData::Branch(name)))
+        }
+    };
+
+    items.push(Item {
+        id: "branch_status_upstream".into(),
+        display,
         depth: 1,
-        unselectable: true,
+        unselectable: target_data.is_none(),
+        target_data,
         ..Default::default()
     });
 
-    Ok(items)
+    items
+}
+
+fn create_submodule_section_items(config: &Config, submodules: Vec<SubmoduleStatus>) -> Vec<Item> {
+    let style = &config.style;
+    if submodules.is_empty() {
+        return vec![];
+    }
+
+    let header = [
+        items::blank_line(),
+        Item {
+            id: "submodules".into(),
+            display: Line::from(vec![
+                Span::styled("Submodules", &style.section_header),
+                format!(" ({})", submodules.len()).into(),
+            ]),
+            section: true,
+            depth: 0,
+            ..Default::default()
+        },
+    ];
+
+    let items = submodules.into_iter().map(|submodule| {
+        let path = submodule.path.to_string_lossy().to_string();
+
+        let mut states = vec![];
+        if !submodule.checked_out {
+            states.push("not checked out".to_string());
+        }
+        if submodule.ahead > 0 {
+            states.push(format!("{} ahead", submodule.ahead));
+        }
+        if submodule.behind > 0 {
+            states.push(format!("{} behind", submodule.behind));
+        }
+        if submodule.dirty {
+            states.push("modified".to_string());
+        }
+
+        let mut spans = vec![Span::styled(path.clone(), &style.file_header)];
+        if !states.is_empty() {
+            spans.push(format!(" ({})", states.join(", ")).into());
+        }
+
+        Item {
+            id: format!("submodule_{}", path).into(),
+            display: Line::from(spans),
+            depth: 1,
+            target_data: Some(TargetData::Submodule(submodule.path)),
+            ..Default::default()
+        }
+    });
+
+    header.into_iter().chain(items).collect()
 }
 
 fn create_status_section_items<'a>(
     config: Rc<Config>,
-    snake_case_header: &str,
+    snake_case_header: &'static str,
     header_data: Option<TargetData>,
     diff: &'a Diff,
 ) -> impl Iterator<Item = Item> + 'a {
@@ -251,7 +753,13 @@ fn create_status_section_items<'a>(
         ]
     }
     .into_iter()
-    .chain(items::create_diff_items(config, diff, &1, true))
+    .chain(items::create_diff_items(
+        config,
+        snake_case_header,
+        diff,
+        &1,
+        true,
+    ))
 }
 
 fn capitalize(str: &str) -> String {
@@ -260,40 +768,115 @@ fn capitalize(str: &str) -> String {
     format!("{first}{rest}")
 }
 
-fn create_stash_list_section_items<'a>(
-    config: Rc<Config>,
-    repo: &Repository,
+fn create_stash_list_section_items(
+    config: &Config,
     snake_case_header: &str,
-) -> impl Iterator<Item = Item> + 'a {
-    let stashes = items::stash_list(&config, repo, 10).unwrap();
+    stashes: StashLines,
+    count: usize,
+) -> Vec<Item> {
     if stashes.is_empty() {
-        vec![]
-    } else {
-        let style = &config.style;
-        vec![
-            items::blank_line(),
-            Item {
-                id: snake_case_header.to_string().into(),
-                display: Line::styled(
+        return vec![];
+    }
+
+    let style = &config.style;
+    [
+        items::blank_line(),
+        Item {
+            id: snake_case_header.to_string().into(),
+            display: Line::from(vec![
+                Span::styled(
                     capitalize(&snake_case_header.replace("_", " ")),
                     &style.section_header,
                 ),
-                section: true,
-                depth: 0,
-                ..Default::default()
-            },
-        ]
-    }
+                format!(" ({})", count).into(),
+            ]),
+            section: true,
+            depth: 0,
+            ..Default::default()
+        },
+    ]
     .into_iter()
-    .chain(stashes)
+    .chain(items::stash_items(stashes))
+    .collect()
 }
 
-fn create_log_section_items<'a>(
-    config: Rc<Config>,
-    repo: &Repository,
+/// The commits that pulling from and pushing to the upstream of the current branch would bring over.
+fn load_upstream_commits(config: &Config, repo: &Repository) -> Res<Vec<UpstreamCommits>> {
+    let Ok(head) = repo.head() else {
+        return Ok(vec![]);
+    };
+
+    let upstream = match head.shorthand() {
+        Some(name) if head.is_branch() => repo
+            .find_branch(name, BranchType::Local)
+            .and_then(|branch| branch.upstream()),
+        _ => return Ok(vec![]),
+    };
+
+    let Ok(upstream) = upstream else {
+        return Ok(vec![]);
+    };
+
+    let (Some(local), Some(remote)) = (head.target(), upstream.get().target()) else {
+        return Ok(vec![]);
+    };
+
+    let upstream_name = upstream.get().shorthand().unwrap_or("upstream");
+    let (ahead, behind) = repo.graph_ahead_behind(local, remote)?;
+
+    let sections = [
+        ("unpulled_commits", "Unpulled from", behind, remote, local),
+        ("unpushed_commits", "Unmerged into", ahead, local, remote),
+    ];
+
+    sections
+        .into_iter()
+        .filter(|(_, _, count, _, _)| *count > 0)
+        .map(|(id, title, count, rev, hidden)| {
+            Ok(UpstreamCommits {
+                id,
+                title: format!("{} {}", title, upstream_name),
+                count,
+                commits: items::log_range_lines(config, repo, UPSTREAM_COMMITS_LIMIT, rev, hidden)?,
+            })
+        })
+        .collect()
+}
+
+fn create_upstream_section_items(config: &Config, sections: Vec<UpstreamCommits>) -> Vec<Item> {
+    let style = &config.style;
+    sections
+        .into_iter()
+        .flat_map(|section| {
+            [
+                items::blank_line(),
+                Item {
+                    id: section.id.into(),
+                    display: Line::from(vec![
+                        Span::styled(section.title, &style.section_header),
+                        format!(" ({})", section.count).into(),
+                    ]),
+                    section: true,
+                    depth: 0,
+                    ..Default::default()
+                },
+            ]
+            .into_iter()
+            .chain(items::commit_items(section.commits))
+        })
+        .collect()
+}
+
+fn create_log_section_items(
+    config: &Config,
     snake_case_header: &str,
-) -> impl Iterator<Item = Item> + 'a {
+    commits: Option<LogLines>,
+) -> Vec<Item> {
     let style = &config.style;
+    if config.general.recent_commits_limit == 0 {
+        return vec![];
+    }
+
     [
         Item {
             display: Line::raw(""),
@@ -313,5 +896,6 @@ fn create_log_section_items<'a>(
         },
     ]
     .into_iter()
-    .chain(items::log(&config, repo, 10, None, None).unwrap())
+    .chain(items::log_items(commits))
+    .collect()
 }
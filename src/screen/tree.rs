@@ -0,0 +1,90 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::{ObjectType, Repository, Tree};
+use ratatui::{layout::Size, text::Line};
+use std::{iter, path::Path, rc::Rc};
+
+/// The files of a revision, like `git ls-tree -r`, with each directory a section to expand.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    rev: String,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let tree = repo.revparse_single(&rev)?.peel_to_tree()?;
+
+            Ok(iter::once(Item {
+                id: format!("tree_section_{}", rev).into(),
+                display: Line::styled(format!("tree {}", rev), &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(tree_items(&config, &repo, &rev, &tree, Path::new(""), 1)?)
+            .collect())
+        }),
+    )
+}
+
+/// Directories come before files, as in a file manager.
+fn tree_items(
+    config: &Config,
+    repo: &Repository,
+    rev: &str,
+    tree: &Tree,
+    dir: &Path,
+    depth: usize,
+) -> Res<Vec<Item>> {
+    let style = &config.style;
+    let (dirs, files): (Vec<_>, Vec<_>) = tree
+        .iter()
+        .partition(|entry| entry.kind() == Some(ObjectType::Tree));
+
+    let mut items = vec![];
+
+    for entry in dirs {
+        let name = entry.name().unwrap_or("").to_string();
+        let path = dir.join(&name);
+        let subtree = repo.find_tree(entry.id())?;
+
+        items.push(Item {
+            id: format!("tree_{}", path.display()).into(),
+            display: Line::styled(format!("{}/", name), &style.file_header),
+            section: true,
+            default_collapsed: true,
+            depth,
+            ..Default::default()
+        });
+        items.extend(tree_items(config, repo, rev, &subtree, &path, depth + 1)?);
+    }
+
+    for entry in files {
+        let name = entry.name().unwrap_or("").to_string();
+        let path = dir.join(&name);
+
+        // Submodules are commits, rather than files of this repository
+        let target_data = (entry.kind() == Some(ObjectType::Blob)).then(|| TargetData::Blob {
+            rev: rev.to_string(),
+            path: path.clone(),
+        });
+
+        items.push(Item {
+            id: format!("tree_{}", path.display()).into(),
+            display: Line::raw(name),
+            depth,
+            target_data,
+            ..Default::default()
+        });
+    }
+
+    Ok(items)
+}
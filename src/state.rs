@@ -1,12 +1,22 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::error::Error;
+use std::fs;
+use std::io;
 use std::io::Read;
 use std::ops::DerefMut;
+use std::path::PathBuf;
 use std::process::Child;
+use std::process::ChildStderr;
+use std::process::ChildStdout;
 use std::process::Command;
 use std::process::Stdio;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::thread;
+use std::thread::JoinHandle;
 
 use arboard::Clipboard;
 use crossterm::event;
@@ -15,21 +25,34 @@ use crossterm::event::KeyCode;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
 use git2::Repository;
+use ratatui::layout::Rect;
 use ratatui::layout::Size;
 use tui_prompts::State as _;
 use tui_prompts::Status;
 
+use crate::auto_fetch::FetchStatus;
 use crate::bindings::Bindings;
+#[cfg(feature = "forge")]
+use crate::ci::CiStatuses;
 use crate::cli;
+use crate::cmd_log;
 use crate::cmd_log::CmdLog;
 use crate::cmd_log::CmdLogEntry;
 use crate::config::Config;
+use crate::events;
+use crate::git::hooks;
+use crate::git::log_filter::LogFilter;
+use crate::image::Image;
+use crate::items::LogOptions;
 use crate::menu::Menu;
 use crate::menu::PendingMenu;
 use crate::ops::Op;
 use crate::prompt;
 use crate::screen;
+use crate::screen::commit_msg::CommitMsg;
+use crate::screen::RepoStamp;
 use crate::screen::Screen;
+use crate::term;
 use crate::term::Term;
 use crate::ui;
 
@@ -43,11 +66,39 @@ pub(crate) struct State {
     pub quit: bool,
     pub screens: Vec<Screen>,
     pub pending_menu: Option<PendingMenu>,
-    pub pending_cmd: Option<(Child, Arc<RwLock<CmdLogEntry>>)>,
-    enable_async_cmds: bool,
+    pub pending_cmd: Option<PendingCmd>,
+    /// Commands issued while another one was running, with their input and the hooks they run.
+    /// They're set to run in the repository they were issued in, which may not be the current one by then.
+    queued_cmds: VecDeque<(Command, Vec<u8>, Vec<&'static str>)>,
+    /// Shows all output of a running command, rather than just its latest lines
+    pub cmd_output_expanded: bool,
+    pub(crate) enable_async_cmds: bool,
     pub current_cmd_log: CmdLog,
+    /// What the hooks of the command that just failed printed, shown until a key is pressed
+    pub(crate) hook_output: Option<HookOutput>,
+    /// Every command run during the session
+    pub cmd_history: Rc<RefCell<CmdLog>>,
     pub prompt: prompt::Prompt,
+    /// Commit message being written with the built-in editor
+    pub commit_msg: Option<CommitMsg>,
     pub clipboard: Option<Clipboard>,
+    /// The latest background fetch, when enabled
+    pub last_fetch: Option<FetchStatus>,
+    /// Of commits on GitHub, with the `forge` feature
+    #[cfg(feature = "forge")]
+    pub(crate) ci_statuses: Option<CiStatuses>,
+    /// Where the repository switcher remembers the repositories opened, nowhere if `None`
+    pub(crate) recent_repos_file: Option<PathBuf>,
+    /// Whether a file watcher notices the changes made outside of gitu, which screens depend on being cached
+    pub(crate) files_watched: bool,
+    /// Counts the changes to the repository gitu knows of, from its commands and the file watcher
+    pub(crate) repo_changes: u64,
+    /// Wakes the main loop once a command is done
+    pub(crate) waker: events::Waker,
+    /// Paused while a command or the shell has the terminal
+    pub(crate) input: events::Input,
+    /// Where the last frame drawn shows images, which the terminal draws over it
+    pub(crate) images: Vec<(Rect, Arc<Image>)>,
 }
 
 impl State {
@@ -64,13 +115,43 @@ impl State {
                     Rc::clone(&config),
                     Rc::clone(&repo),
                     size,
+                    enable_async_cmds,
                     reference.clone(),
+                    vec![],
                 )?]
             }
-            None => vec![screen::status::create(
+            Some(cli::Commands::Log {
+                max_count, ref rev, ..
+            }) => {
+                let rev = rev
+                    .as_ref()
+                    .map(|rev| repo.revparse_single(rev).map(|object| object.id()))
+                    .transpose()?;
+
+                vec![screen::log::create(
+                    Rc::clone(&config),
+                    Rc::clone(&repo),
+                    size,
+                    enable_async_cmds,
+                    max_count.unwrap_or(usize::MAX),
+                    rev,
+                    LogFilter::default(),
+                    LogOptions::default(),
+                )?]
+            }
+            Some(cli::Commands::Diff { ref range, .. }) => vec![screen::diff::create(
+                Rc::clone(&config),
+                Rc::clone(&repo),
+                size,
+                enable_async_cmds,
+                range.clone(),
+                vec![],
+            )?],
+            Some(cli::Commands::Status { .. }) | None => vec![screen::status::create(
                 Rc::clone(&config),
                 Rc::clone(&repo),
                 size,
+                enable_async_cmds,
             )?],
         };
 
@@ -90,36 +171,67 @@ impl State {
             quit: false,
             screens,
             pending_cmd: None,
+            queued_cmds: VecDeque::new(),
+            cmd_output_expanded: false,
             pending_menu,
             current_cmd_log: CmdLog::new(),
+            hook_output: None,
+            cmd_history: Rc::new(RefCell::new(CmdLog::new())),
             prompt: prompt::Prompt::new(),
+            commit_msg: None,
             clipboard,
+            last_fetch: None,
+            #[cfg(feature = "forge")]
+            ci_statuses: None,
+            recent_repos_file: None,
+            files_watched: false,
+            repo_changes: 0,
+            waker: events::Waker::default(),
+            input: events::Input::default(),
+            images: vec![],
         })
     }
 
     pub fn update(&mut self, term: &mut Term, events: &[Event]) -> Res<()> {
         for event in events {
             match *event {
+                Event::Paste(ref text) => {
+                    if self.prompt.state.is_focused() {
+                        self.prompt.paste(text);
+                    } else if self.key_menu() == Menu::CommitMsg {
+                        if let Some(commit_msg) = &mut self.commit_msg {
+                            commit_msg.paste(text);
+                        }
+                    }
+                }
                 Event::Resize(w, h) => {
                     for screen in self.screens.iter_mut() {
-                        screen.size = Size::new(w, h);
+                        screen.resize(Size::new(w, h));
                     }
                 }
                 Event::Key(key) => {
                     if self.prompt.state.is_focused() {
-                        self.prompt.state.handle_key_event(key)
+                        self.prompt.handle_key_event(key)
                     } else if key.kind == KeyEventKind::Press {
+                        // The key only dismisses it, it could've been meant for it
+                        if self.hook_output.take().is_some() {
+                            continue;
+                        }
+
                         if self.pending_cmd.is_none() {
                             self.current_cmd_log.clear();
                         }
 
-                        self.handle_key_input(term, key)?;
+                        let result = self.handle_key_input(term, key);
+                        self.handle_result(result);
                     }
                 }
                 _ => (),
             }
 
             self.update_prompt(term)?;
+            let load_more_result = self.load_more_near_end();
+            self.handle_result(load_more_result);
         }
 
         let handle_pending_cmd_result = self.handle_pending_cmd();
@@ -127,15 +239,101 @@ impl State {
             .handle_result(handle_pending_cmd_result)
             .unwrap_or(true);
 
-        let needs_redraw = !events.is_empty() || pending_cmd_done;
+        if self.pending_cmd.is_none() {
+            if let Some((cmd, input, hooks)) = self.queued_cmds.pop_front() {
+                let result = self.start_cmd(term, &input, cmd, hooks);
+                self.handle_result(result);
+            }
+        }
+
+        let poll_refresh_result = self.poll_screen_refreshes();
+        let refreshed = self.handle_result(poll_refresh_result).unwrap_or(true);
+        let refreshing = self
+            .screens
+            .last()
+            .is_some_and(|screen| screen.refreshing_since().is_some());
+
+        // Redraw while refreshing to animate the spinner, and while a command runs to show its progress
+        let needs_redraw = events.iter().any(changes_display)
+            || pending_cmd_done
+            || refreshed
+            || refreshing
+            || self.pending_cmd.is_some();
 
         if needs_redraw && self.screens.last_mut().is_some() {
-            term.draw(|frame| ui::ui(frame, self))?;
+            self.draw(term)?;
+        }
+
+        // Whatever changed was refreshed by now, on the screen shown
+        let stamp = self.repo_stamp();
+        if let Some(screen) = self.screens.last_mut() {
+            screen.set_stamp(stamp);
         }
 
         Ok(())
     }
 
+    /// What the screens are built from, or `None` when changes to it could go unnoticed.
+    fn repo_stamp(&self) -> Option<RepoStamp> {
+        if !self.files_watched {
+            return None;
+        }
+
+        let head = self.repo.head().ok();
+        let index = fs::metadata(self.repo.path().join("index")).ok();
+
+        Some(RepoStamp {
+            head: head.map(|head| (head.name().map(String::from), head.target())),
+            index: index.and_then(|index| Some((index.modified().ok()?, index.len()))),
+            changes: self.repo_changes,
+            diff_settings: self
+                .config
+                .diff_settings
+                .read()
+                .expect("Diff settings lock poisoned")
+                .clone(),
+        })
+    }
+
+    fn draw(&mut self, term: &mut Term) -> Res<()> {
+        term.draw(|frame| ui::ui(frame, self))?;
+        term.backend_mut().show_images(&self.images)?;
+        Ok(())
+    }
+
+    /// Whether anything runs in the background that needs looking after soon, like a command.
+    pub(crate) fn is_busy(&self) -> bool {
+        self.pending_cmd.is_some()
+            || !self.queued_cmds.is_empty()
+            || self
+                .screens
+                .iter()
+                .any(|screen| screen.refreshing_since().is_some())
+    }
+
+    /// Refreshes the screen shown, unless the repository is still as it was built from,
+    /// such as when going back to it from another screen.
+    pub(crate) fn update_screen_if_changed(&mut self) -> Res<()> {
+        let stamp = self.repo_stamp();
+        self.screen_mut().update_if_changed(stamp)
+    }
+
+    fn poll_screen_refreshes(&mut self) -> Res<bool> {
+        let mut refreshed = false;
+        for screen in self.screens.iter_mut() {
+            refreshed |= screen.poll_refresh()?;
+        }
+        Ok(refreshed | self.load_more_near_end()?)
+    }
+
+    /// Keeps a page of items loaded past what's shown, as keys scroll further.
+    fn load_more_near_end(&mut self) -> Res<bool> {
+        match self.screens.last_mut() {
+            Some(screen) => screen.load_more_near_end(),
+            None => Ok(false),
+        }
+    }
+
     fn update_prompt(&mut self, term: &mut Term) -> Res<()> {
         if self.prompt.state.status() == Status::Aborted {
             self.unhide_menu();
@@ -145,7 +343,8 @@ impl State {
 
             match result {
                 Ok(()) => {
-                    if self.prompt.state.is_focused() {
+                    // The update_fn may have replaced the prompt with a new one
+                    if self.prompt.state.is_focused() && self.prompt.data.is_none() {
                         self.prompt.data = Some(prompt_data);
                     }
                 }
@@ -158,12 +357,22 @@ impl State {
         Ok(())
     }
 
-    fn handle_key_input(&mut self, term: &mut Term, key: event::KeyEvent) -> Res<()> {
-        let menu = match &self.pending_menu {
+    /// The menu whose bindings keys are matched against.
+    pub(crate) fn key_menu(&self) -> Menu {
+        match &self.pending_menu {
             None => Menu::Root,
             Some(menu) if menu.menu == Menu::Help => Menu::Root,
             Some(menu) => menu.menu,
-        };
+        }
+    }
+
+    /// Keys pressed so far of a binding that takes several.
+    pub(crate) fn pending_keys(&self) -> &[(KeyModifiers, KeyCode)] {
+        &self.pending_keys
+    }
+
+    fn handle_key_input(&mut self, term: &mut Term, key: event::KeyEvent) -> Res<()> {
+        let menu = self.key_menu();
 
         self.pending_keys.push((key.modifiers, key.code));
         let matching_bindings = self
@@ -178,7 +387,14 @@ impl State {
                     self.pending_keys.clear();
                 }
             }
-            [] => self.pending_keys.clear(),
+            [] => {
+                if menu == Menu::CommitMsg && self.pending_keys.len() == 1 {
+                    if let Some(commit_msg) = &mut self.commit_msg {
+                        commit_msg.handle_key(key);
+                    }
+                }
+                self.pending_keys.clear();
+            }
             [_, ..] => (),
         }
 
@@ -186,8 +402,23 @@ impl State {
     }
 
     pub(crate) fn handle_op(&mut self, op: Op, term: &mut Term) -> Res<()> {
-        let target = self.screen().get_selected_item().target_data.as_ref();
-        if let Some(mut action) = op.clone().implementation().get_action(target) {
+        let implementation = op.implementation();
+        let region_action = match self.screen().get_region_targets() {
+            Some(targets) if implementation.is_target_op() => {
+                implementation.get_region_action(&targets)
+            }
+            _ => None,
+        };
+
+        let action = if region_action.is_some() {
+            self.screen_mut().clear_mark();
+            region_action
+        } else {
+            let target = self.screen().get_selected_item().target_data.as_ref();
+            implementation.get_action(target)
+        };
+
+        if let Some(mut action) = action {
             let result = Rc::get_mut(&mut action).unwrap()(self, term);
             self.handle_result(result);
         }
@@ -208,7 +439,43 @@ impl State {
     }
 
     pub fn close_menu(&mut self) {
-        self.pending_menu = root_menu(&self.config).map(PendingMenu::init)
+        let menu = self
+            .screens
+            .last()
+            .and_then(Screen::menu)
+            .or_else(|| root_menu(&self.config));
+
+        self.pending_menu = menu.map(PendingMenu::init)
+    }
+
+    /// Closes the current screen, going back to the repository it was entered from, if any.
+    pub fn pop_screen(&mut self) {
+        if let Some(repo) = self.screens.pop().and_then(Screen::into_parent_repo) {
+            self.repo = repo;
+        }
+    }
+
+    /// Lists the repository as the most recent one in the repository switcher.
+    pub(crate) fn remember_repo(&self) {
+        let Some(file) = &self.recent_repos_file else {
+            return;
+        };
+
+        if let Err(err) = screen::repositories::save_to_recent(file, &self.repo) {
+            log::warn!(
+                "Couldn't remember repository {:?}: {}",
+                self.repo.path(),
+                err
+            );
+        }
+    }
+
+    /// Closes all screens but the first one of the current repository,
+    /// which within a submodule is the one it was entered with.
+    pub fn pop_screens_to_root(&mut self) {
+        while self.screens.len() > 1 && !self.screen().has_parent_repo() {
+            self.pop_screen();
+        }
     }
 
     pub fn screen_mut(&mut self) -> &mut Screen {
@@ -232,6 +499,11 @@ impl State {
     /// Runs a `Command` and handles its output.
     /// Will block awaiting its completion.
     pub fn run_cmd(&mut self, term: &mut Term, input: &[u8], cmd: Command) -> Res<()> {
+        // What follows may depend on it being done, so it can't wait in the queue
+        if self.pending_cmd.is_some() {
+            return Err("A command is already running".into());
+        }
+
         self.run_cmd_async(term, input, cmd)?;
         self.await_pending_cmd()?;
         self.handle_pending_cmd()?;
@@ -239,27 +511,76 @@ impl State {
     }
 
     /// Runs a `Command` and handles its output asynchronously (if async commands are enabled).
-    /// Will return `Ok(())` if one is already running.
+    /// If one is already running, it's queued to run after it.
     pub fn run_cmd_async(&mut self, term: &mut Term, input: &[u8], mut cmd: Command) -> Res<()> {
+        // Bare repositories have no work tree, git finds them from within the git dir instead
+        cmd.current_dir(self.repo.workdir().unwrap_or(self.repo.path()));
+        let hooks = hooks::run_by(&self.repo, &cmd);
+
         if self.pending_cmd.is_some() {
-            return Err("A command is already running".into());
+            self.display_info(format!("Queued: {}", cmd_log::command_args(&cmd)));
+            self.queued_cmds.push_back((cmd, input.to_vec(), hooks));
+            return Ok(());
         }
 
-        cmd.current_dir(self.repo.workdir().expect("No workdir"));
+        self.start_cmd(term, input, cmd, hooks)
+    }
 
-        cmd.stdin(Stdio::piped());
+    fn start_cmd(
+        &mut self,
+        term: &mut Term,
+        input: &[u8],
+        mut cmd: Command,
+        hooks: Vec<&'static str>,
+    ) -> Res<()> {
+        // Commands reading their input would wait for keys that never come
+        cmd.stdin(if input.is_empty() {
+            Stdio::null()
+        } else {
+            Stdio::piped()
+        });
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
+        // In a group of its own, so that killing it reaches whatever it started too
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+
         let log_entry = self.current_cmd_log.push_cmd(&cmd);
-        term.draw(|frame| ui::ui(frame, self))?;
+        self.cmd_history.borrow_mut().push_shared(&log_entry);
+
+        // Shown until the command outputs anything
+        if let (Some(hook), CmdLogEntry::Cmd { progress, .. }) =
+            (hooks.first(), &mut *log_entry.write().unwrap())
+        {
+            *progress = format!("Running the {} hook", hook);
+        }
+
+        self.draw(term)?;
 
         let mut child = cmd.spawn()?;
 
-        use std::io::Write;
-        child.stdin.take().unwrap().write_all(input)?;
+        let stdout = read_output(child.stdout.take().unwrap());
+        if let Some(mut stdin) = child.stdin.take() {
+            use std::io::Write;
+            stdin.write_all(input)?;
+        }
 
-        self.pending_cmd = Some((child, log_entry));
+        let stderr = stream_output(
+            child.stderr.take().unwrap(),
+            Arc::clone(&log_entry),
+            self.waker.clone(),
+        );
+        self.pending_cmd = Some(PendingCmd {
+            child,
+            log_entry,
+            stdout,
+            stderr,
+            killed: false,
+            awaited: false,
+            hooks,
+        });
+        self.cmd_output_expanded = false;
 
         if !self.enable_async_cmds {
             self.await_pending_cmd()?;
@@ -269,26 +590,47 @@ impl State {
     }
 
     fn await_pending_cmd(&mut self) -> Res<()> {
-        if let Some((child, _)) = &mut self.pending_cmd {
-            child.wait()?;
+        if let Some(pending_cmd) = &mut self.pending_cmd {
+            pending_cmd.child.wait()?;
+
+            // The rest of the output might still be on its way, it's read to the end once handled
+            pending_cmd.awaited = true;
         }
         Ok(())
     }
 
     /// Handles any pending_cmd in State without blocking. Returns `true` if a cmd was handled.
     pub fn handle_pending_cmd(&mut self) -> Res<bool> {
-        let Some((ref mut child, ref mut log_rwlock)) = self.pending_cmd else {
+        let Some(ref mut pending_cmd) = self.pending_cmd else {
             return Ok(false);
         };
 
-        let Some(status) = child.try_wait()? else {
+        let Some(status) = pending_cmd.child.try_wait()? else {
             return Ok(false);
         };
 
+        let output_read = pending_cmd.stdout.is_finished() && pending_cmd.stderr.is_finished();
+        if !output_read && !pending_cmd.killed && !pending_cmd.awaited {
+            return Ok(false);
+        }
+
         log::debug!("pending cmd finished with {:?}", status);
 
-        let result = write_child_output_to_log(log_rwlock, child, status);
-        self.pending_cmd = None;
+        let pending_cmd = self.pending_cmd.take().unwrap();
+        let hooks = if pending_cmd.killed {
+            vec![]
+        } else {
+            pending_cmd.hooks.clone()
+        };
+        let log_entry = Arc::clone(&pending_cmd.log_entry);
+        let result = write_child_output_to_log(pending_cmd, status);
+        if result.is_err() {
+            if let CmdLogEntry::Cmd { out: Some(out), .. } = &*log_entry.read().unwrap() {
+                self.hook_output = HookOutput::of_failure(&hooks, out);
+            }
+        }
+        let result = result.map_err(|err| hook_failure(err, &hooks));
+        self.repo_changes += 1;
         self.screen_mut().update()?;
         result?;
 
@@ -300,22 +642,28 @@ impl State {
             return Err("A command is already running".into());
         }
 
-        cmd.current_dir(self.repo.workdir().expect("No workdir"));
+        cmd.current_dir(self.repo.workdir().unwrap_or(self.repo.path()));
 
         cmd.stdin(Stdio::piped());
 
+        // Nothing is drawn while the command runs, slow hooks would look like gitu hung.
+        // Git prints what they output to stderr, and the error only says that it exited.
+        let hooks = hooks::run_by(&self.repo, &cmd);
+        if let Some(hook) = hooks.first() {
+            self.display_info(format!("Running the {} hook", hook));
+            self.draw(term)?;
+            cmd.stderr(Stdio::piped());
+        }
+
+        // The command reads the keys meanwhile
+        let paused_input = self.input.pause();
+
         // git will have staircased output in raw mode (issue #290)
         // disable raw mode temporarily for the git command
         term.backend().disable_raw_mode()?;
 
-        let child = cmd.spawn()?;
-
-        let out = child.wait_with_output()?;
-        let out_utf8 = String::from_utf8(out.stderr.clone())
-            .expect("Error turning command output to String")
-            .into();
-
-        self.current_cmd_log.push_cmd_with_output(&cmd, out_utf8);
+        // The terminal is restored before any error is returned, e.g. when the editor doesn't exist
+        let output = cmd.spawn().and_then(|child| child.wait_with_output());
 
         // restore the raw mode
         term.backend().enable_raw_mode()?;
@@ -327,15 +675,71 @@ impl State {
         term.backend_mut().enter_alternate_screen()?;
 
         term.clear()?;
+        drop(paused_input);
+
+        let out = output?;
+        let out_utf8: Cow<'static, str> = String::from_utf8_lossy(&out.stderr).into_owned().into();
+        if !out.status.success() {
+            self.hook_output = HookOutput::of_failure(&hooks, &out_utf8);
+        }
+
+        let log_entry =
+            self.current_cmd_log
+                .push_cmd_with_output(&cmd, out_utf8, out.status.code());
+        self.cmd_history.borrow_mut().push_shared(&log_entry);
+
+        self.repo_changes += 1;
         self.screen_mut().update()?;
 
         if !out.status.success() {
-            return Err(format!(
+            let error = format!(
                 "exited with code: {}",
                 out.status
                     .code()
                     .map(|c| c.to_string())
                     .unwrap_or("".to_string())
+            );
+            return Err(hook_failure(error.into(), &hooks));
+        }
+
+        Ok(())
+    }
+
+    /// Hands the terminal over to a `Command`, such as a shell, until it exits.
+    /// Unlike `run_cmd_interactive`, its input and output aren't captured.
+    pub fn run_cmd_in_terminal(&mut self, term: &mut Term, mut cmd: Command) -> Res<()> {
+        if self.pending_cmd.is_some() {
+            return Err("A command is already running".into());
+        }
+
+        cmd.current_dir(self.repo.workdir().unwrap_or(self.repo.path()));
+
+        let paused_input = self.input.pause();
+        term.backend_mut().leave_alternate_screen()?;
+        term.backend().disable_raw_mode()?;
+        term.show_cursor()?;
+
+        let status = cmd.status();
+
+        term::resume(term)?;
+        drop(paused_input);
+
+        let status = status?;
+        let log_entry = self
+            .current_cmd_log
+            .push_cmd_with_output(&cmd, "".into(), status.code());
+        self.cmd_history.borrow_mut().push_shared(&log_entry);
+
+        self.repo_changes += 1;
+        self.screen_mut().update()?;
+
+        if !status.success() {
+            return Err(format!(
+                "exited with code: {}",
+                status
+                    .code()
+                    .map(|c| c.to_string())
+                    .unwrap_or("".to_string())
             )
             .into());
         }
@@ -364,38 +768,207 @@ pub(crate) fn root_menu(config: &Config) -> Option<Menu> {
     }
 }
 
+/// A command running in the background.
+pub(crate) struct PendingCmd {
+    child: Child,
+    log_entry: Arc<RwLock<CmdLogEntry>>,
+    /// All of stdout, once the command is done. It's read meanwhile, so that a full pipe doesn't block the command.
+    stdout: JoinHandle<io::Result<Vec<u8>>>,
+    /// All of stderr, once the command is done
+    stderr: JoinHandle<io::Result<Vec<u8>>>,
+    killed: bool,
+    /// Waited for, rather than polled until all of its output is read
+    awaited: bool,
+    /// Hooks that could make it fail
+    hooks: Vec<&'static str>,
+}
+
+impl PendingCmd {
+    /// Kills the command along with the processes it started, such as the ssh connection of a
+    /// hung fetch, and reaps it. It's logged like any other command on the next update.
+    /// Processes out of reach could keep stderr open, so what's been read so far is kept as the output.
+    pub(crate) fn kill(&mut self) -> Res<()> {
+        self.killed = true;
+
+        // The command leads its own process group. Processes that left it are out of reach.
+        #[cfg(unix)]
+        unsafe {
+            libc::kill(-(self.child.id() as libc::pid_t), libc::SIGKILL);
+        }
+
+        self.child.kill()?;
+        self.child.wait()?;
+        Ok(())
+    }
+}
+
+/// Whether handling the event may change what's shown, unlike e.g. releasing a key.
+fn changes_display(event: &Event) -> bool {
+    match event {
+        Event::Key(key) => key.kind != KeyEventKind::Release,
+        Event::FocusLost | Event::Mouse(_) => false,
+        _ => true,
+    }
+}
+
+/// What the hooks of a failed command printed, which git mixes into its own output.
+pub(crate) struct HookOutput {
+    /// Names the hooks, like the error does
+    pub(crate) title: String,
+    pub(crate) output: String,
+}
+
+impl HookOutput {
+    /// `None` when no hooks ran or they printed nothing.
+    fn of_failure(hooks: &[&str], output: &str) -> Option<Self> {
+        let title = hooks::describe_failure(hooks)?;
+        if output.trim().is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            title,
+            output: output.trim_end().to_string(),
+        })
+    }
+}
+
+/// Hooks that fail don't say so themselves, git just exits with their exit code.
+fn hook_failure(error: Box<dyn Error>, hooks: &[&str]) -> Box<dyn Error> {
+    match hooks::describe_failure(hooks) {
+        Some(hint) => format!("{} ({})", error, hint).into(),
+        None => error,
+    }
+}
+
+/// Reads stderr as it comes, so that the progress git reports there is shown while it's running.
+/// `waker` is woken once it's all read, as the command is done by then.
+pub(crate) fn stream_output(
+    mut stderr: ChildStderr,
+    log_entry: Arc<RwLock<CmdLogEntry>>,
+    waker: events::Waker,
+) -> JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut out = vec![];
+        let mut buf = [0; 1024];
+        // Of `out`, what's been added to the progress. A character split between reads waits for the rest.
+        let mut decoded = 0;
+        let mut started = false;
+        loop {
+            let read = stderr.read(&mut buf).inspect_err(|_| waker.wake())?;
+            if read == 0 {
+                waker.wake();
+                return Ok(out);
+            }
+
+            out.extend_from_slice(&buf[..read]);
+            let mut text = String::new();
+            decoded += decode_utf8(&out[decoded..], &mut text);
+            if text.is_empty() {
+                continue;
+            }
+
+            if let CmdLogEntry::Cmd { progress, .. } = &mut *log_entry.write().unwrap() {
+                // Until then it says what's running
+                if !started {
+                    progress.clear();
+                    started = true;
+                }
+                progress.push_str(&text);
+            }
+        }
+    })
+}
+
+/// Decodes `bytes` onto `text` like `String::from_utf8_lossy`, but for a character cut off at the end.
+/// Returns how many bytes were decoded.
+fn decode_utf8(mut bytes: &[u8], text: &mut String) -> usize {
+    let len = bytes.len();
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                text.push_str(valid);
+                return len;
+            }
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+                text.push_str(std::str::from_utf8(valid).unwrap());
+                let Some(invalid) = err.error_len() else {
+                    return len - rest.len();
+                };
+
+                text.push(char::REPLACEMENT_CHARACTER);
+                bytes = &rest[invalid..];
+            }
+        }
+    }
+}
+
+fn read_output(mut stdout: ChildStdout) -> JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut out = vec![];
+        stdout.read_to_end(&mut out)?;
+        Ok(out)
+    })
+}
+
 fn write_child_output_to_log(
-    log_rwlock: &mut Arc<RwLock<CmdLogEntry>>,
-    child: &mut Child,
+    pending_cmd: PendingCmd,
     status: std::process::ExitStatus,
 ) -> Result<(), Box<dyn Error>> {
-    let mut log = log_rwlock.write().unwrap();
-
-    let CmdLogEntry::Cmd { args, out: out_log } = log.deref_mut() else {
-        unreachable!("pending_cmd is always CmdLogEntry::Cmd variant");
-    };
+    let PendingCmd {
+        mut child,
+        log_entry,
+        stdout,
+        stderr,
+        killed,
+        ..
+    } = pending_cmd;
 
     drop(child.stdin.take());
 
-    let mut out_bytes = vec![];
     log::debug!("Reading stderr");
+    let stderr = if stderr.is_finished() || !killed {
+        Some(
+            stderr
+                .join()
+                .map_err(|_| "Couldn't read cmd output")?
+                .map_err(|e| format!("Couldn't read cmd output: {}", e))?,
+        )
+    } else {
+        None
+    };
+
+    let mut log = log_entry.write().unwrap();
+
+    let CmdLogEntry::Cmd {
+        args,
+        out: out_log,
+        progress,
+        exit_code,
+    } = log.deref_mut()
+    else {
+        unreachable!("pending_cmd is always CmdLogEntry::Cmd variant");
+    };
 
-    child
-        .stderr
-        .take()
-        .unwrap()
-        .read_to_end(&mut out_bytes)
-        .map_err(|e| format!("Couldn't read cmd output: {}", e))?;
+    let mut out_bytes = stderr.unwrap_or_else(|| progress.as_bytes().to_vec());
 
-    child
-        .stdout
-        .take()
-        .unwrap()
-        .read_to_end(&mut out_bytes)
-        .map_err(|e| format!("Couldn't read cmd output: {}", e))?;
+    // Processes the killed command started could still hold stdout open
+    if !killed {
+        out_bytes.extend(
+            stdout
+                .join()
+                .map_err(|_| "Couldn't read cmd output")?
+                .map_err(|e| format!("Couldn't read cmd output: {}", e))?,
+        );
+    }
 
-    let out_string = String::from_utf8(out_bytes.clone())?;
-    *out_log = Some(out_string.into());
+    *out_log = Some(String::from_utf8_lossy(&out_bytes).into_owned().into());
+    *exit_code = status.code();
+
+    if killed {
+        return Err(format!("'{}' was killed", args).into());
+    }
 
     if !status.success() {
         return Err(format!(
@@ -411,3 +984,21 @@ fn write_child_output_to_log(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::decode_utf8;
+
+    #[test]
+    fn decode_utf8_waits_for_the_rest_of_a_character() {
+        let bytes = "smörgås".as_bytes();
+        let mut text = String::new();
+        assert_eq!(decode_utf8(&bytes[..3], &mut text), 2);
+        assert_eq!(decode_utf8(&bytes[2..], &mut text), bytes.len() - 2);
+        assert_eq!(text, "smörgås");
+
+        let mut text = String::new();
+        assert_eq!(decode_utf8(b"a\xffb\xc3", &mut text), 3);
+        assert_eq!(text, "a\u{FFFD}b");
+    }
+}
@@ -1,7 +1,10 @@
 use crate::config::Config;
 
 use itertools::Itertools;
-use ratatui::style::Style;
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
 use std::{cell::RefCell, collections::HashMap, iter, ops::Range, path::Path};
 use tree_sitter::Language;
 use tree_sitter_highlight::{Highlight, HighlightConfiguration, HighlightEvent, Highlighter};
@@ -61,7 +64,7 @@ fn styles(style: &crate::config::StyleConfig) -> [Style; 22] {
 pub(crate) fn split_at_newlines<'a, D: Copy + 'a>(
     content: &'a str,
     (range, style): (Range<usize>, D),
-) -> impl Iterator<Item = (Range<usize>, D)> + '_ {
+) -> impl Iterator<Item = (Range<usize>, D)> + 'a {
     let range_indices = iter::once(range.start)
         .chain(
             content[range.clone()]
@@ -211,6 +214,11 @@ pub(crate) fn highlight<'a>(
     let style = &config.style;
     let styles = styles(style);
 
+    let max_file_size = style.syntax_highlight.max_file_size;
+    if max_file_size > 0 && content.len() > max_file_size {
+        return vec![];
+    }
+
     let Some(lang) = determine_lang(path) else {
         return vec![];
     };
@@ -246,3 +254,52 @@ pub(crate) fn highlight<'a>(
         })
     })
 }
+
+/// The lines of a whole file, highlighted if enabled and its language is known.
+pub(crate) fn highlight_lines(config: &Config, path: &Path, content: &str) -> Vec<Line<'static>> {
+    let mut highlights = match config.style.syntax_highlight.enabled {
+        true => highlight(config, path, content),
+        false => vec![],
+    }
+    .into_iter()
+    .peekable();
+
+    let mut start = 0;
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let range = start..start + line.len();
+            start = range.end;
+
+            let mut spans = vec![];
+            let mut at = range.start;
+            while let Some((h_range, h_style)) =
+                highlights.next_if(|(h_range, _)| h_range.start < range.end)
+            {
+                if h_range.start > at {
+                    spans.push(Span::raw(content[at..h_range.start].to_string()));
+                }
+                let h_range = h_range.start.max(at)..h_range.end.min(range.end);
+                spans.push(Span::styled(content[h_range.clone()].to_string(), h_style));
+                at = h_range.end;
+            }
+            if at < range.end {
+                spans.push(Span::raw(content[at..range.end].to_string()));
+            }
+
+            let spans = spans
+                .into_iter()
+                .map(|span| {
+                    let text = span
+                        .content
+                        .trim_end_matches(['\r', '\n'])
+                        .replace('\t', "    ");
+                    Span::styled(text, span.style)
+                })
+                .filter(|span| !span.content.is_empty())
+                .collect::<Vec<_>>();
+
+            Line::from(spans)
+        })
+        .collect()
+}
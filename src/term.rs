@@ -1,5 +1,11 @@
-use crate::Res;
+use crate::{
+    image::{self, Image},
+    Res,
+};
 use crossterm::{
+    cursor::{MoveTo, RestorePosition, SavePosition, Show},
+    event::{DisableBracketedPaste, EnableBracketedPaste},
+    style::{Attribute, SetAttribute, SetBackgroundColor, SetForegroundColor},
     terminal::{
         disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, EnterAlternateScreen,
         LeaveAlternateScreen,
@@ -8,22 +14,32 @@ use crossterm::{
 };
 use ratatui::{
     backend::{Backend, CrosstermBackend, TestBackend},
-    layout::Size,
+    buffer::Buffer,
+    layout::{Rect, Size},
     prelude::{backend::WindowSize, buffer::Cell, Position},
+    style::{Color, Modifier},
     Terminal,
 };
-use std::fmt::Display;
+use std::collections::HashSet;
+use std::fmt::{Display, Write};
 use std::io::{self, stderr, Stderr};
+use std::sync::Arc;
+use unicode_width::UnicodeWidthStr;
 
 pub type Term = Terminal<TermBackend>;
 
 // TODO It would be more logical if the following top-level functions also were in 'TermBackend'.
 //      However left here for now.
 
+/// Pastes come as a whole rather than as typed keys meanwhile, so that they aren't taken for bindings.
 pub fn alternate_screen<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
-    stderr().execute(EnterAlternateScreen)?;
+    stderr()
+        .execute(EnterAlternateScreen)?
+        .execute(EnableBracketedPaste)?;
     let result = fun();
-    stderr().execute(LeaveAlternateScreen)?;
+    stderr()
+        .execute(DisableBracketedPaste)?
+        .execute(LeaveAlternateScreen)?;
     result
 }
 
@@ -44,6 +60,7 @@ pub fn raw_mode<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
 }
 
 pub fn cleanup_alternate_screen() {
+    print_err(stderr().execute(DisableBracketedPaste));
     print_err(stderr().execute(LeaveAlternateScreen));
 }
 
@@ -51,6 +68,31 @@ pub fn cleanup_raw_mode() {
     print_err(disable_raw_mode());
 }
 
+pub fn cleanup_cursor() {
+    print_err(stderr().execute(Show));
+}
+
+/// Hands the terminal back to the shell and stops the process, like `C-z` would outside raw mode.
+/// Returns once the process is continued again, e.g. by `fg`.
+pub fn suspend(term: &mut Term) -> Res<()> {
+    term.backend_mut().leave_alternate_screen()?;
+    term.backend().disable_raw_mode()?;
+    term.show_cursor()?;
+
+    term.backend().stop_process()?;
+
+    resume(term)
+}
+
+/// Sets the terminal up for gitu again after having been stopped, and clears it to force a redraw.
+pub fn resume(term: &mut Term) -> Res<()> {
+    term.backend().enable_raw_mode()?;
+    term.backend_mut().enter_alternate_screen()?;
+    term.hide_cursor()?;
+    term.clear()?;
+    Ok(())
+}
+
 fn print_err<T, E: Display>(result: Result<T, E>) {
     match result {
         Ok(_) => (),
@@ -59,12 +101,12 @@ fn print_err<T, E: Display>(result: Result<T, E>) {
 }
 
 pub fn backend() -> TermBackend {
-    TermBackend::Crossterm(CrosstermBackend::new(stderr()))
+    TermBackend::Crossterm(CrosstermBackend::new(stderr()), ShownImages::default())
 }
 
 pub enum TermBackend {
-    Crossterm(CrosstermBackend<Stderr>),
-    #[allow(dead_code)]
+    Crossterm(CrosstermBackend<Stderr>, ShownImages),
+    /// Draws into a buffer, for tests and `--print`
     Test(TestBackend),
 }
 
@@ -74,63 +116,70 @@ impl Backend for TermBackend {
         I: Iterator<Item = (u16, u16, &'a Cell)>,
     {
         match self {
-            TermBackend::Crossterm(t) => t.draw(content),
+            TermBackend::Crossterm(t, _) => t.draw(content),
             TermBackend::Test(t) => t.draw(content),
         }
     }
 
     fn hide_cursor(&mut self) -> io::Result<()> {
         match self {
-            TermBackend::Crossterm(t) => t.hide_cursor(),
+            TermBackend::Crossterm(t, _) => t.hide_cursor(),
             TermBackend::Test(t) => t.hide_cursor(),
         }
     }
 
     fn show_cursor(&mut self) -> io::Result<()> {
         match self {
-            TermBackend::Crossterm(t) => t.show_cursor(),
+            TermBackend::Crossterm(t, _) => t.show_cursor(),
             TermBackend::Test(t) => t.show_cursor(),
         }
     }
 
     fn get_cursor_position(&mut self) -> io::Result<Position> {
         match self {
-            TermBackend::Crossterm(t) => t.get_cursor_position(),
+            TermBackend::Crossterm(t, _) => t.get_cursor_position(),
             TermBackend::Test(t) => t.get_cursor_position(),
         }
     }
 
     fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
         match self {
-            TermBackend::Crossterm(t) => t.set_cursor_position(position),
+            TermBackend::Crossterm(t, _) => t.set_cursor_position(position),
             TermBackend::Test(t) => t.set_cursor_position(position),
         }
     }
 
     fn clear(&mut self) -> io::Result<()> {
         match self {
-            TermBackend::Crossterm(t) => t.clear(),
+            TermBackend::Crossterm(t, shown) => {
+                // Images aren't cells, clearing them is up to gitu
+                if !shown.placed.is_empty() {
+                    io::Write::write_all(t, KITTY_DELETE_PLACEMENTS.as_bytes())?;
+                }
+                *shown = ShownImages::default();
+                t.clear()
+            }
             TermBackend::Test(t) => t.clear(),
         }
     }
 
     fn size(&self) -> io::Result<Size> {
         match self {
-            TermBackend::Crossterm(t) => t.size(),
+            TermBackend::Crossterm(t, _) => t.size(),
             TermBackend::Test(t) => t.size(),
         }
     }
 
     fn window_size(&mut self) -> io::Result<WindowSize> {
         match self {
-            TermBackend::Crossterm(t) => t.window_size(),
+            TermBackend::Crossterm(t, _) => t.window_size(),
             TermBackend::Test(t) => t.window_size(),
         }
     }
 
     fn flush(&mut self) -> io::Result<()> {
         match self {
-            TermBackend::Crossterm(t) => t.flush(),
+            TermBackend::Crossterm(t, _) => t.flush(),
             TermBackend::Test(t) => t.flush(),
         }
     }
@@ -139,22 +188,310 @@ impl Backend for TermBackend {
 impl TermBackend {
     pub fn enter_alternate_screen(&mut self) -> io::Result<()> {
         match self {
-            TermBackend::Crossterm(c) => c.execute(EnterAlternateScreen).map(|_| ()),
+            TermBackend::Crossterm(c, _) => c
+                .execute(EnterAlternateScreen)?
+                .execute(EnableBracketedPaste)
+                .map(|_| ()),
+            TermBackend::Test(_) => Ok(()),
+        }
+    }
+
+    pub fn leave_alternate_screen(&mut self) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(c, _) => c
+                .execute(DisableBracketedPaste)?
+                .execute(LeaveAlternateScreen)
+                .map(|_| ()),
             TermBackend::Test(_) => Ok(()),
         }
     }
 
     pub fn enable_raw_mode(&self) -> io::Result<()> {
         match self {
-            TermBackend::Crossterm(_) => enable_raw_mode(),
+            TermBackend::Crossterm(..) => enable_raw_mode(),
             TermBackend::Test(_) => Ok(()),
         }
     }
 
     pub fn disable_raw_mode(&self) -> io::Result<()> {
         match self {
-            TermBackend::Crossterm(_) => disable_raw_mode(),
+            TermBackend::Crossterm(..) => disable_raw_mode(),
+            TermBackend::Test(_) => Ok(()),
+        }
+    }
+
+    /// Sets the clipboard of the terminal itself with OSC 52, which works through SSH too.
+    pub fn set_clipboard(&mut self, text: &str) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(c, _) => {
+                let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+                io::Write::write_all(c, sequence.as_bytes())?;
+                io::Write::flush(c)
+            }
             TermBackend::Test(_) => Ok(()),
         }
     }
+
+    /// Draws `images` with the kitty graphics protocol, each fitted into its area. Unless they're
+    /// where they already are, the ones drawn before are taken away first.
+    pub(crate) fn show_images(&mut self, images: &[(Rect, Arc<Image>)]) -> io::Result<()> {
+        let TermBackend::Crossterm(c, shown) = self else {
+            return Ok(());
+        };
+
+        let cell_size = match c.window_size() {
+            Ok(WindowSize {
+                columns_rows,
+                pixels,
+            }) if columns_rows.width > 0 && columns_rows.height > 0 && pixels.width > 0 => (
+                pixels.width / columns_rows.width,
+                pixels.height / columns_rows.height,
+            ),
+            _ => image::DEFAULT_CELL_SIZE,
+        };
+        let placed = images
+            .iter()
+            .map(|(area, image)| {
+                let (width, height) = image.fit(cell_size, area.width, area.height);
+                (
+                    Rect {
+                        width,
+                        height,
+                        ..*area
+                    },
+                    image.id,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if placed == shown.placed {
+            return Ok(());
+        }
+
+        let mut out = SavePosition.to_string();
+        if !shown.placed.is_empty() {
+            out.push_str(KITTY_DELETE_PLACEMENTS);
+        }
+        for (i, ((area, _), (_, image))) in placed.iter().zip(images).enumerate() {
+            if shown.transmitted.insert(image.id) {
+                out.push_str(&kitty_transmit(image));
+            }
+            write!(out, "{}", MoveTo(area.x, area.y)).unwrap();
+            out.push_str(&kitty_place(image.id, i as u32 + 1, *area));
+        }
+        write!(out, "{}", RestorePosition).unwrap();
+
+        io::Write::write_all(c, out.as_bytes())?;
+        io::Write::flush(c)?;
+        shown.placed = placed;
+        Ok(())
+    }
+
+    pub fn stop_process(&self) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            TermBackend::Crossterm(..) => {
+                signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)
+            }
+            #[cfg(not(unix))]
+            TermBackend::Crossterm(..) => Ok(()),
+            TermBackend::Test(_) => Ok(()),
+        }
+    }
+}
+
+/// The images the terminal was sent and where they're shown, which is forgotten when it's cleared.
+#[derive(Default)]
+pub struct ShownImages {
+    transmitted: HashSet<u32>,
+    placed: Vec<(Rect, u32)>,
+}
+
+/// Takes away all images shown, the terminal keeps them to be shown again.
+const KITTY_DELETE_PLACEMENTS: &str = "\x1b_Ga=d,d=a,q=2\x1b\\";
+/// The most base64 the kitty graphics protocol takes in one escape sequence.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Sends `image` to the terminal without showing it, in chunks. Errors aren't reported back (`q=2`),
+/// as they would come in as input.
+fn kitty_transmit(image: &Image) -> String {
+    let data = base64(&image.png);
+    let chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect::<Vec<_>>();
+
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        let chunk = std::str::from_utf8(chunk).unwrap();
+        match i {
+            0 => write!(
+                out,
+                "\x1b_Ga=t,f=100,i={},q=2,m={};{}\x1b\\",
+                image.id, more, chunk
+            ),
+            _ => write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk),
+        }
+        .unwrap();
+    }
+    out
+}
+
+/// Shows the image sent as `id` at the cursor, scaled to `area`, without moving the cursor (`C=1`).
+fn kitty_place(id: u32, placement: u32, area: Rect) -> String {
+    format!(
+        "\x1b_Ga=p,i={},p={},c={},r={},C=1,q=2\x1b\\",
+        id, placement, area.width, area.height
+    )
+}
+
+/// The buffer as lines of text styled with ANSI escape codes, to be printed rather than drawn.
+/// Blank space at the end of lines and below the last one is left out.
+pub(crate) fn to_ansi(buffer: &Buffer) -> String {
+    let rows = buffer
+        .content
+        .chunks(buffer.area.width as usize)
+        .map(|row| {
+            let end = row
+                .iter()
+                .rposition(|cell| cell.symbol() != " " || cell.bg != Color::Reset)
+                .map_or(0, |last| last + 1);
+            &row[..end]
+        })
+        .collect::<Vec<_>>();
+    let height = rows
+        .iter()
+        .rposition(|row| !row.is_empty())
+        .map_or(0, |last| last + 1);
+
+    let mut out = String::new();
+    for row in &rows[..height] {
+        let mut style = (Color::Reset, Color::Reset, Modifier::empty());
+        let mut hidden = 0;
+
+        for cell in row.iter() {
+            // Wide symbols cover the cells after them
+            if hidden > 0 {
+                hidden -= 1;
+                continue;
+            }
+            hidden = cell.symbol().width().saturating_sub(1);
+
+            if (cell.fg, cell.bg, cell.modifier) != style {
+                style = (cell.fg, cell.bg, cell.modifier);
+                write_style(&mut out, cell);
+            }
+            out.push_str(cell.symbol());
+        }
+
+        if style != (Color::Reset, Color::Reset, Modifier::empty()) {
+            write!(out, "{}", SetAttribute(Attribute::Reset)).unwrap();
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+fn write_style(out: &mut String, cell: &Cell) {
+    write!(out, "{}", SetAttribute(Attribute::Reset)).unwrap();
+
+    if cell.fg != Color::Reset {
+        write!(out, "{}", SetForegroundColor(cell.fg.into())).unwrap();
+    }
+    if cell.bg != Color::Reset {
+        write!(out, "{}", SetBackgroundColor(cell.bg.into())).unwrap();
+    }
+
+    let attributes = [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+    ];
+    for (modifier, attribute) in attributes {
+        if cell.modifier.contains(modifier) {
+            write!(out, "{}", SetAttribute(attribute)).unwrap();
+        }
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    bytes
+        .chunks(3)
+        .flat_map(|chunk| {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, &byte)| n | (byte as u32) << (16 - 8 * i));
+
+            (0..4).map(move |i| match i <= chunk.len() {
+                true => ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char,
+                false => '=',
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, kitty_place, kitty_transmit, to_ansi, KITTY_CHUNK_SIZE};
+    use crate::image::{test_png, Image};
+    use ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        style::{Color, Style, Stylize},
+    };
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn kitty_transmit_in_chunks() {
+        let mut png = test_png(64, 32);
+        png.resize(KITTY_CHUNK_SIZE, 0);
+        let image = Image::from_png(png).unwrap();
+        let out = kitty_transmit(&image);
+
+        let sequences = out.split_terminator("\x1b\\").collect::<Vec<_>>();
+        assert_eq!(sequences.len(), 2);
+        assert!(sequences[0].starts_with(&format!("\x1b_Ga=t,f=100,i={},q=2,m=1;", image.id)));
+        assert!(sequences[1].starts_with("\x1b_Gm=0;"));
+        assert_eq!(
+            sequences
+                .iter()
+                .map(|s| s.split_once(';').unwrap().1)
+                .collect::<String>(),
+            base64(&image.png)
+        );
+    }
+
+    #[test]
+    fn kitty_place_scales_to_area() {
+        assert_eq!(
+            kitty_place(7, 1, Rect::new(2, 3, 40, 10)),
+            "\x1b_Ga=p,i=7,p=1,c=40,r=10,C=1,q=2\x1b\\"
+        );
+    }
+
+    #[test]
+    fn to_ansi_styles_and_trims() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 8, 3));
+        buffer.set_string(0, 0, "a", Style::new().fg(Color::Red).bold());
+        buffer.set_string(1, 0, "b", Style::new());
+        buffer.set_string(0, 1, "世c", Style::new());
+
+        assert_eq!(
+            to_ansi(&buffer),
+            "\x1b[0m\x1b[38;5;1m\x1b[1ma\x1b[0mb\n世c\n"
+        );
+    }
 }
@@ -0,0 +1,66 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first commit", "");
+    commit(ctx.dir.path(), "second commit", "");
+    commit(ctx.dir.path(), "third commit", "");
+    commit(ctx.dir.path(), "fourth commit", "");
+    ctx
+}
+
+fn setup_bisecting() -> TestContext {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "bisect", "start", "HEAD", "HEAD~4"],
+    );
+    ctx
+}
+
+#[test]
+fn bisect_menu() {
+    snapshot!(setup(), "B");
+}
+
+#[test]
+fn bisect_menu_while_bisecting() {
+    snapshot!(setup_bisecting(), "B");
+}
+
+#[test]
+fn bisect_start_prompt() {
+    snapshot!(setup(), "BB<enter>");
+}
+
+#[test]
+fn bisect_start() {
+    snapshot!(setup(), "lljjjjBB<enter><enter>");
+}
+
+#[test]
+fn bisect_status() {
+    snapshot!(setup_bisecting(), "");
+}
+
+#[test]
+fn bisect_waiting_status() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "bisect", "start"]);
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn bisect_good() {
+    snapshot!(setup_bisecting(), "Bg");
+}
+
+#[test]
+fn bisect_bad_at_point() {
+    snapshot!(setup_bisecting(), "lljBb");
+}
+
+#[test]
+fn bisect_reset() {
+    snapshot!(setup_bisecting(), "Br");
+}
@@ -0,0 +1,38 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "README", "Hello\n");
+    commit(ctx.dir.path(), "README", "Hello\nWorld\n");
+    commit(
+        ctx.dir.path(),
+        "main.rs",
+        "fn main() {\n\tprintln!(\"hi\");\n}\n",
+    );
+    ctx
+}
+
+#[test]
+fn show_file_highlighted() {
+    snapshot!(setup(), "llTjjj<enter>");
+}
+
+#[test]
+fn show_file_at_revision_prompt() {
+    snapshot!(setup(), "llTj<enter>jE");
+}
+
+#[test]
+fn show_file_at_revision() {
+    snapshot!(setup(), "llTj<enter>jEHEAD~2<enter>");
+}
+
+#[test]
+fn show_file_at_invalid_revision() {
+    snapshot!(setup(), "llTj<enter>jEnonexistent<enter>");
+}
+
+#[test]
+fn blame_line() {
+    snapshot!(setup(), "llTj<enter>jjL");
+}
@@ -0,0 +1,77 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "branch", "other-branch"]);
+    ctx
+}
+
+#[test]
+fn branch_menu() {
+    snapshot!(setup(), "b");
+}
+
+#[test]
+fn rename_branch_prompt() {
+    snapshot!(setup(), "Yjjbm");
+}
+
+#[test]
+fn rename_branch_new_name_prompt() {
+    snapshot!(setup(), "Yjjbm<enter>");
+}
+
+#[test]
+fn rename_branch() {
+    snapshot!(setup(), "Yjjbm<enter>renamed<enter>");
+}
+
+#[test]
+fn rename_current_branch() {
+    snapshot!(setup(), "bm<enter>renamed<enter>");
+}
+
+#[test]
+fn delete_branch_prompt() {
+    snapshot!(setup(), "Yjjbk");
+}
+
+#[test]
+fn delete_branch() {
+    snapshot!(setup(), "Yjjbk<enter>");
+}
+
+#[test]
+fn delete_unmerged_branch() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    snapshot!(ctx, "bkother-branch<enter>");
+}
+
+#[test]
+fn force_delete_unmerged_branch() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    snapshot!(ctx, "bKother-branch<enter>y");
+}
+
+#[test]
+fn set_upstream() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "new-branch"]);
+    snapshot!(ctx, "buorigin/main<enter>");
+}
+
+#[test]
+fn force_delete_branch_prompt() {
+    snapshot!(setup(), "bKother-branch<enter>");
+}
+
+#[test]
+fn delete_branch_picker() {
+    snapshot!(setup(), "bkoth<tab><enter>");
+}
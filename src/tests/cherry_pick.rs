@@ -0,0 +1,65 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "hey");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    ctx
+}
+
+fn setup_conflict() -> TestContext {
+    let ctx = setup();
+    commit(ctx.dir.path(), "new-file", "hi");
+    run(ctx.dir.path(), &["git", "cherry-pick", "other-branch"]);
+    ctx
+}
+
+#[test]
+fn cherry_pick_menu() {
+    snapshot!(setup(), "A");
+}
+
+#[test]
+fn cherry_pick_prompt() {
+    snapshot!(setup(), "loother-branch<enter>AA");
+}
+
+#[test]
+fn cherry_pick() {
+    snapshot!(setup(), "loother-branch<enter>AA<enter>q");
+}
+
+#[test]
+fn cherry_pick_marked_commits() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "other-branch"]);
+    commit(ctx.dir.path(), "other-file", "hello");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    snapshot!(ctx, "loother-branch<enter>vjAA<enter>q");
+}
+
+#[test]
+fn cherry_pick_conflict() {
+    let mut ctx = setup_conflict();
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn cherry_pick_conflict_menu() {
+    snapshot!(setup_conflict(), "A");
+}
+
+#[test]
+fn cherry_pick_abort() {
+    snapshot!(setup_conflict(), "Aa");
+}
+
+#[test]
+fn cherry_pick_head_not_a_full_hash() {
+    let mut ctx = setup();
+    fs::write(ctx.dir.child(".git/CHERRY_PICK_HEAD"), "ab\n").unwrap();
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -0,0 +1,53 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child(".gitignore"), "*.log\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", ".gitignore"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "ignore logs"]);
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    fs::write(ctx.dir.child("ignored.log"), "").unwrap();
+    fs::create_dir(ctx.dir.child("untracked-dir")).unwrap();
+    fs::write(ctx.dir.child("untracked-dir/file"), "").unwrap();
+    ctx
+}
+
+#[test]
+fn clean_menu() {
+    snapshot!(setup(), "C");
+}
+
+#[test]
+fn clean_preview() {
+    snapshot!(setup(), "Cc");
+}
+
+#[test]
+fn clean_preview_directories_and_ignored() {
+    snapshot!(setup(), "C-d-xc");
+}
+
+#[test]
+fn clean_confirm_prompt() {
+    snapshot!(setup(), "Ccc");
+}
+
+#[test]
+fn clean_confirm() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("C-dccy")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert!(!ctx.dir.child("untracked-file").exists());
+    assert!(!ctx.dir.child("untracked-dir").exists());
+    assert!(ctx.dir.child("ignored.log").exists());
+}
+
+#[test]
+fn clean_abort() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("Ccq")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert!(ctx.dir.child("untracked-file").exists());
+}
@@ -0,0 +1,26 @@
+use super::*;
+
+#[test]
+fn cmd_history_empty() {
+    snapshot!(TestContext::setup_clone(), "$");
+}
+
+#[test]
+fn cmd_history() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    snapshot!(ctx, "jjjsbbnonexistent<enter>$");
+}
+
+#[test]
+fn cmd_history_toggle() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    snapshot!(ctx, "jjjs$$");
+}
+
+#[test]
+fn cmd_history_collapse() {
+    let ctx = TestContext::setup_clone();
+    snapshot!(ctx, "bbnonexistent<enter>$<tab>");
+}
@@ -0,0 +1,28 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "");
+    fs::write(ctx.dir.child("first-file"), "changed\n").unwrap();
+    ctx
+}
+
+#[test]
+fn command_palette_lists_matches() {
+    snapshot!(setup(), ":stage");
+}
+
+#[test]
+fn command_palette_runs_op() {
+    snapshot!(setup(), ":stage all tracked<enter>");
+}
+
+#[test]
+fn command_palette_runs_submenu_op() {
+    snapshot!(setup(), ":branch: checkout new<enter>");
+}
+
+#[test]
+fn command_palette_no_match() {
+    snapshot!(setup(), ":xyzzy<enter>");
+}
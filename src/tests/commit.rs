@@ -1,4 +1,5 @@
 use super::*;
+use crossterm::event::Event;
 
 #[test]
 fn commit_instant_fixup() {
@@ -9,7 +10,9 @@ fn commit_instant_fixup() {
     commit(ctx.dir.path(), "instant_fixup.txt", "mistake\n");
     fs::write(ctx.dir.child("instant_fixup.txt"), "fixed\n").unwrap();
     run(ctx.dir.path(), &["git", "add", "."]);
-    state.update(&mut ctx.term, &keys("gjjjjjcF")).unwrap();
+    state
+        .update(&mut ctx.term, &keys("gjjjjjjcF<enter>"))
+        .unwrap();
 
     insta::assert_snapshot!(ctx.redact_buffer());
 }
@@ -28,7 +31,212 @@ fn commit_instant_fixup_stashes_changes_and_keeps_empty() {
     fs::write(ctx.dir.child("instant_fixup.txt"), "fixed\n").unwrap();
     run(ctx.dir.path(), &["git", "add", "."]);
     fs::write(ctx.dir.child("instant_fixup.txt"), "unstaged\n").unwrap();
-    state.update(&mut ctx.term, &keys("gjjjjjjjjjcF")).unwrap();
+    state
+        .update(&mut ctx.term, &keys("gjjjjjjjjjjcF<enter>"))
+        .unwrap();
 
     insta::assert_snapshot!(ctx.redact_buffer());
 }
+
+#[test]
+fn commit_fixup_prompt() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "fixup.txt", "initial\n");
+    commit(ctx.dir.path(), "fixup.txt", "mistake\n");
+    snapshot!(ctx, "gjjjjcf");
+}
+
+#[test]
+fn commit_instant_fixup_relative_rev() {
+    let mut ctx = TestContext::setup_init();
+    let mut state = ctx.init_state();
+
+    commit(ctx.dir.path(), "instant_fixup.txt", "initial\n");
+    commit(ctx.dir.path(), "instant_fixup.txt", "mistake\n");
+    commit(ctx.dir.path(), "other.txt", "other\n");
+    fs::write(ctx.dir.child("instant_fixup.txt"), "fixed\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    state
+        .update(&mut ctx.term, &keys("cFHEAD~1<enter>"))
+        .unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+fn setup_builtin_commit_editor() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.builtin_commit_editor.enabled = true;
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "new-file"]);
+    ctx
+}
+
+#[test]
+fn builtin_commit_editor() {
+    snapshot!(setup_builtin_commit_editor(), "ccadd<enter><enter>a file");
+}
+
+#[test]
+fn builtin_commit_editor_verbose() {
+    snapshot!(setup_builtin_commit_editor(), "c-vc");
+}
+
+#[test]
+fn builtin_commit_editor_submit() {
+    snapshot!(setup_builtin_commit_editor(), "ccadd file<ctrl+c><ctrl+c>");
+}
+
+#[test]
+fn builtin_commit_editor_abort() {
+    snapshot!(setup_builtin_commit_editor(), "ccadd file<ctrl+c><ctrl+k>");
+}
+
+#[test]
+fn builtin_commit_editor_history() {
+    let mut ctx = setup_builtin_commit_editor();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("ccfirst message<ctrl+c><ctrl+c>"))
+        .unwrap();
+    fs::write(ctx.dir.child("other-file"), "hey\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "other-file"]);
+    state
+        .update(&mut ctx.term, &keys("ccdraft<alt+p>"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    state.update(&mut ctx.term, &keys("<alt+n>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn builtin_commit_editor_paste() {
+    let mut ctx = setup_builtin_commit_editor();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("cc")).unwrap();
+    state
+        .update(
+            &mut ctx.term,
+            &[Event::Paste("pasted subject\r\n\npasted body".into())],
+        )
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn builtin_commit_editor_abort_keeps_message_in_history() {
+    snapshot!(
+        setup_builtin_commit_editor(),
+        "ccaborted message<ctrl+c><ctrl+k>cc<alt+p>"
+    );
+}
+
+#[test]
+fn builtin_commit_editor_template() {
+    let mut ctx = setup_builtin_commit_editor();
+    ctx.config().general.builtin_commit_editor.template = "feat: \n\n# body".into();
+    snapshot!(ctx, "cc");
+}
+
+#[test]
+fn builtin_commit_editor_git_commit_template() {
+    let ctx = setup_builtin_commit_editor();
+    fs::write(ctx.dir.child(".gitmessage"), "fix: \n").unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "commit.template", ".gitmessage"],
+    );
+    snapshot!(ctx, "ccbug");
+}
+
+#[test]
+fn builtin_commit_editor_complete_type() {
+    snapshot!(setup_builtin_commit_editor(), "ccfe<tab>new thing");
+}
+
+#[test]
+fn builtin_commit_editor_complete_ambiguous_type() {
+    snapshot!(setup_builtin_commit_editor(), "ccc<tab>h<tab>");
+}
+
+#[test]
+fn commit_extend() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "new-file"]);
+    snapshot!(ctx, "ce");
+}
+
+#[test]
+fn builtin_commit_editor_reword() {
+    snapshot!(
+        setup_builtin_commit_editor(),
+        "cw (reworded)<ctrl+c><ctrl+c>"
+    );
+}
+
+fn setup_failing_pre_commit_hook() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let hook = ctx.dir.child(".git/hooks/pre-commit");
+    fs::write(&hook, "#!/bin/sh\necho 'lint failed'\nexit 1\n").unwrap();
+    run(ctx.dir.path(), &["chmod", "+x", hook.to_str().unwrap()]);
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "new-file"]);
+    ctx
+}
+
+#[test]
+fn commit_pre_commit_hook_fails() {
+    snapshot!(setup_failing_pre_commit_hook(), "cc");
+}
+
+#[test]
+fn pre_commit_hook_output_dismissed_by_key() {
+    snapshot!(setup_failing_pre_commit_hook(), "ccj");
+}
+
+#[test]
+fn builtin_commit_editor_pre_commit_hook_fails() {
+    let mut ctx = setup_failing_pre_commit_hook();
+    ctx.config().general.builtin_commit_editor.enabled = true;
+    snapshot!(ctx, "ccadd file<ctrl+c><ctrl+c>");
+}
+
+#[test]
+fn builtin_commit_editor_no_verify() {
+    let mut ctx = setup_failing_pre_commit_hook();
+    ctx.config().general.builtin_commit_editor.enabled = true;
+    snapshot!(ctx, "c-ncadd file<ctrl+c><ctrl+c>");
+}
+
+#[test]
+fn commit_author_prompt() {
+    snapshot!(TestContext::setup_clone(), "c-AAuth");
+}
+
+#[test]
+fn commit_extend_with_author_and_date() {
+    let mut ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "new-file"]);
+    let mut state = ctx.init_state();
+
+    let events = [
+        keys("c-A"),
+        vec![Event::Paste("Other Name <other@email.com>".into())],
+        keys("<enter>-D"),
+        vec![Event::Paste("2024-01-31 12:00".into())],
+        keys("<enter>e"),
+    ]
+    .concat();
+    state.update(&mut ctx.term, &events).unwrap();
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.author().name(), Some("Other Name"));
+    assert_eq!(head.author().email(), Some("other@email.com"));
+    assert_eq!(head.committer().name(), Some("Committer Name"));
+
+    let date = crate::git::log_filter::parse_date("2024-01-31 12:00", 0).unwrap();
+    assert_eq!(head.author().when().seconds(), date);
+    assert_eq!(head.committer().when().seconds(), date);
+}
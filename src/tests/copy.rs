@@ -0,0 +1,85 @@
+use super::*;
+use crate::config::ClipboardBackend;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hey\n");
+    fs::write(ctx.dir.path().join("new-file"), "hi\n").unwrap();
+    ctx
+}
+
+#[test]
+fn copy_menu_on_hunk() {
+    snapshot!(setup(), "jjjj<tab>jy");
+}
+
+#[test]
+fn copy_hash() {
+    snapshot!(setup(), "llyy");
+}
+
+#[test]
+fn copy_message() {
+    snapshot!(setup(), "llym");
+}
+
+#[test]
+fn copy_branch_name() {
+    snapshot!(setup(), "Yjyb");
+}
+
+#[test]
+fn copy_path() {
+    snapshot!(setup(), "jjjjyf");
+}
+
+#[test]
+fn copy_hunk() {
+    snapshot!(setup(), "jjjj<tab>jyh");
+}
+
+#[test]
+fn copy_with_command() {
+    let mut ctx = setup();
+    ctx.config().general.clipboard.backend = ClipboardBackend::Command;
+    let copied = ctx.dir.child("copied");
+    ctx.config().general.clipboard.command = vec![
+        "sh".into(),
+        "-c".into(),
+        format!("cat > {}", copied.display()),
+    ];
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjyf")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(fs::read_to_string(copied).unwrap(), "new-file");
+}
+
+#[test]
+fn copy_describe() {
+    let mut ctx = setup();
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+    commit(ctx.dir.path(), "other-file", "hi\n");
+    ctx.config().general.clipboard.backend = ClipboardBackend::Command;
+    let copied = ctx.dir.child("copied");
+    ctx.config().general.clipboard.command = vec![
+        "sh".into(),
+        "-c".into(),
+        format!("cat > {}", copied.display()),
+    ];
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("llyd")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert!(fs::read_to_string(copied).unwrap().starts_with("v1.0-1-g"));
+}
+
+#[test]
+fn copy_describe_without_tag() {
+    snapshot!(setup(), "llyd");
+}
+
+#[test]
+fn copy_without_command() {
+    let mut ctx = setup();
+    ctx.config().general.clipboard.backend = ClipboardBackend::Command;
+    snapshot!(ctx, "jjjjyf");
+}
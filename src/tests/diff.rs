@@ -0,0 +1,99 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hello\n");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "second-file", "world\n");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "first-file", "hello again\n");
+    ctx
+}
+
+#[test]
+fn diff_menu() {
+    snapshot!(setup(), "d");
+}
+
+#[test]
+fn diff_range_prompt() {
+    snapshot!(setup(), "dd");
+}
+
+#[test]
+fn diff_two_dots() {
+    snapshot!(setup(), "ddmain..feature<enter>");
+}
+
+#[test]
+fn diff_three_dots() {
+    snapshot!(setup(), "ddmain...feature<enter>");
+}
+
+#[test]
+fn diffstat_jumps_to_file() {
+    snapshot!(setup(), "ddmain..feature<enter>kk<enter>");
+}
+
+#[test]
+fn diff_single_rev() {
+    let ctx = setup();
+    fs::write(ctx.dir.child("first-file"), "changed\n").unwrap();
+    snapshot!(ctx, "ddHEAD<enter>");
+}
+
+#[test]
+fn diff_invalid_range() {
+    snapshot!(setup(), "ddnonexistent..main<enter>");
+}
+
+#[test]
+fn diff_marked_commits() {
+    snapshot!(setup(), "llvjjdd<enter>");
+}
+
+fn setup_difftool() -> TestContext {
+    let mut ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "difftool.noop.cmd", "true"],
+    );
+    ctx.config().general.difftool.tool = "noop".into();
+    ctx
+}
+
+#[test]
+fn difftool_unstaged_file() {
+    let ctx = setup_difftool();
+    fs::write(ctx.dir.child("first-file"), "changed\n").unwrap();
+    snapshot!(ctx, "jjjjdt");
+}
+
+#[test]
+fn difftool_staged_file() {
+    let ctx = setup_difftool();
+    fs::write(ctx.dir.child("first-file"), "changed\n").unwrap();
+    snapshot!(ctx, "jjjjsdt");
+}
+
+#[test]
+fn difftool_commit_dir_diff() {
+    let mut ctx = setup_difftool();
+    ctx.config().general.difftool.dir_diff = true;
+    snapshot!(ctx, "lldt");
+}
+
+#[test]
+fn diff_pager_unstaged_file() {
+    let mut ctx = setup();
+    ctx.config().general.difftool.pager = "cat >/dev/null".into();
+    fs::write(ctx.dir.child("first-file"), "changed\n").unwrap();
+    snapshot!(ctx, "jjjjdp");
+}
+
+#[test]
+fn diff_pager_commit() {
+    let mut ctx = setup();
+    ctx.config().general.difftool.pager = "cat >/dev/null".into();
+    snapshot!(ctx, "lldp");
+}
@@ -0,0 +1,85 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(
+        ctx.dir.path(),
+        "file",
+        "one\ntwo\nthree\nfour\nfive\nsix\nseven\n",
+    );
+    fs::write(
+        ctx.dir.child("file"),
+        "one\n  two\nthree\nfour\nfive\nsix\nseven!\n",
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+fn diff_options_menu() {
+    snapshot!(setup(), "D");
+}
+
+#[test]
+fn ignore_whitespace() {
+    snapshot!(setup(), "jjjj<tab>D-w");
+}
+
+#[test]
+fn ignore_blank_lines() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\ntwo\n");
+    fs::write(ctx.dir.child("file"), "one\n\ntwo\n").unwrap();
+    snapshot!(ctx, "jjjj<tab>D-b");
+}
+
+#[test]
+fn context_lines() {
+    snapshot!(setup(), "jjjj<tab>D-U1<enter>");
+}
+
+#[test]
+fn context_lines_invalid() {
+    snapshot!(setup(), "jjjj<tab>D-U0<enter>");
+}
+
+#[test]
+fn options_kept_when_reopened() {
+    snapshot!(setup(), "D-w-p<esc>D");
+}
+
+#[test]
+fn ignore_whitespace_in_show() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "commit", "-am", "whitespace"]);
+    snapshot!(ctx, "D-wqll<enter>");
+}
+
+#[test]
+fn side_by_side() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\ntwo\nthree\nfour\nfive\n");
+    fs::write(
+        ctx.dir.child("file"),
+        "one\n2\nthree and a line long enough that it wraps in its column of the screen\nfive\nsix\n",
+    )
+    .unwrap();
+    snapshot!(ctx, "jjjj<tab>D-s");
+}
+
+#[test]
+fn side_by_side_line_numbers() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.diff_line_numbers.enabled = true;
+    commit(ctx.dir.path(), "file", "one\ntwo\nthree\n");
+    fs::write(ctx.dir.child("file"), "one\n2\n3\nthree\n").unwrap();
+    snapshot!(ctx, "jjjj<tab>D-sq");
+}
+
+#[test]
+fn side_by_side_search_right_column() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\ntwo\nthree\n");
+    fs::write(ctx.dir.child("file"), "one\nsix\nthree\n").unwrap();
+    snapshot!(ctx, "jjjj<tab>D-sq/six<enter>");
+}
@@ -25,7 +25,7 @@ pub(crate) fn discard_branch_no() {
 pub(crate) fn discard_untracked_file() {
     let ctx = TestContext::setup_clone();
     run(ctx.dir.path(), &["touch", "some-file"]);
-    snapshot!(ctx, "jjKy");
+    snapshot!(ctx, "jjjjKy");
 }
 
 #[test]
@@ -33,7 +33,7 @@ pub(crate) fn discard_untracked_staged_file() {
     let ctx = TestContext::setup_clone();
     run(ctx.dir.path(), &["touch", "some-file"]);
     run(ctx.dir.path(), &["git", "add", "some-file"]);
-    snapshot!(ctx, "jsjKy");
+    snapshot!(ctx, "jjjsjKy");
 }
 
 #[test]
@@ -42,7 +42,16 @@ pub(crate) fn discard_file_move() {
     commit(ctx.dir.path(), "new-file", "hello");
     run(ctx.dir.path(), &["git", "mv", "new-file", "moved-file"]);
 
-    snapshot!(ctx, "jjKy");
+    snapshot!(ctx, "jjjjKy");
+}
+
+#[test]
+pub(crate) fn discard_unstaged_file_move() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello\nworld\n");
+    fs::rename(ctx.dir.child("new-file"), ctx.dir.child("moved-file")).unwrap();
+
+    snapshot!(ctx, "jjjjKy");
 }
 
 #[test]
@@ -50,7 +59,7 @@ pub(crate) fn discard_unstaged_delta() {
     let ctx = TestContext::setup_clone();
     commit(ctx.dir.path(), "file-one", "FOO\nBAR\n");
     fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
-    snapshot!(ctx, "jjKy");
+    snapshot!(ctx, "jjjjKy");
 }
 
 #[test]
@@ -58,7 +67,7 @@ pub(crate) fn discard_unstaged_hunk() {
     let ctx = TestContext::setup_clone();
     commit(ctx.dir.path(), "file-one", "FOO\nBAR\n");
     fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
-    snapshot!(ctx, "jj<tab>jKy");
+    snapshot!(ctx, "jjjj<tab>jKy");
 }
 
 #[test]
@@ -67,7 +76,67 @@ pub(crate) fn discard_staged_file() {
     commit(ctx.dir.path(), "file-one", "FOO\nBAR\n");
     fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
     run(ctx.dir.path(), &["git", "add", "."]);
-    snapshot!(ctx, "jjKy");
+    snapshot!(ctx, "jjjjKy");
+}
+
+#[test]
+pub(crate) fn discard_untracked_file_prompt() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "some-file"]);
+    snapshot!(ctx, "jjjjK");
+}
+
+#[test]
+pub(crate) fn discard_without_confirm() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm_discard.enabled = false;
+    commit(ctx.dir.path(), "file-one", "FOO\nBAR\n");
+    fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
+    snapshot!(ctx, "jjjjK");
+}
+
+#[test]
+pub(crate) fn undo_discard_unstaged_delta() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-one", "FOO\nBAR\n");
+    fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjKy")).unwrap();
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("file-one")).unwrap(),
+        "FOO\nBAR\n"
+    );
+
+    state.update(&mut ctx.term, &keys("<ctrl+x>u")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("file-one")).unwrap(),
+        "blahonga\n"
+    );
+}
+
+#[test]
+pub(crate) fn undo_discard_untracked_file() {
+    let mut ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("some-file"), "keep me\n").unwrap();
+
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jjjjKy<ctrl+x>u"))
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("some-file")).unwrap(),
+        "keep me\n"
+    );
+}
+
+#[test]
+pub(crate) fn undo_discard_twice() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-one", "FOO\nBAR\n");
+    fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
+    snapshot!(ctx, "jjjjKy<ctrl+x>u<ctrl+x>u");
 }
 
 // FIXME Deleting branches doesn't work with the test-setup
@@ -80,3 +149,26 @@ pub(crate) fn discard_staged_file() {
 //         .unwrap();
 //     insta::assert_snapshot!(ctx.redact_buffer());
 // }
+
+#[test]
+pub(crate) fn discard_unstaged_removed_line() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+    snapshot!(ctx, "jjj<tab><ctrl+j><ctrl+j>Ky");
+}
+
+#[test]
+pub(crate) fn discard_unstaged_added_line() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+    snapshot!(ctx, "jjj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>Ky");
+}
+
+#[test]
+pub(crate) fn discard_region_of_files() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "file-a", "file-b", "file-c"]);
+    snapshot!(ctx, "jjjjvjKy");
+}
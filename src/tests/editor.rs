@@ -1,7 +1,14 @@
 use super::*;
 
 fn setup_scroll() -> (TestContext, crate::state::State) {
+    setup_scroll_with(|_| {})
+}
+
+fn setup_scroll_with(
+    configure: impl FnOnce(&mut TestContext),
+) -> (TestContext, crate::state::State) {
     let mut ctx = TestContext::setup_init();
+    configure(&mut ctx);
     for file in ["file-1", "file-2", "file-3"] {
         commit(ctx.dir.path(), file, "");
         fs::write(
@@ -18,7 +25,7 @@ fn setup_scroll() -> (TestContext, crate::state::State) {
 
     let mut state = ctx.init_state();
     state
-        .update(&mut ctx.term, &keys("jjjj<tab>k<tab>k<tab>"))
+        .update(&mut ctx.term, &keys("jjjjj<tab>k<tab>k<tab>"))
         .unwrap();
     (ctx, state)
 }
@@ -32,6 +39,16 @@ fn scroll_down() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn scroll_down_without_scrollbar() {
+    let (mut ctx, mut state) =
+        setup_scroll_with(|ctx| ctx.config().general.scrollbar.enabled = false);
+
+    state.update(&mut ctx.term, &keys("<ctrl+d>")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
 #[test]
 fn scroll_past_selection() {
     let (mut ctx, mut state) = setup_scroll();
@@ -77,3 +94,48 @@ fn exit_from_prompt_shows_menu() {
 fn re_enter_prompt_from_menu() {
     snapshot!(TestContext::setup_init(), "bb<esc>b");
 }
+
+#[test]
+fn page_down() {
+    let (mut ctx, mut state) = setup_scroll();
+
+    state.update(&mut ctx.term, &keys("<pagedown>")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn move_to_bottom() {
+    let (mut ctx, mut state) = setup_scroll();
+
+    state.update(&mut ctx.term, &keys("G")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn move_to_top() {
+    let (mut ctx, mut state) = setup_scroll();
+
+    state.update(&mut ctx.term, &keys("G<home>")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn move_next_hunk_expands_file() {
+    let (mut ctx, mut state) = setup_scroll();
+
+    state.update(&mut ctx.term, &keys("]]")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn move_next_and_prev_file() {
+    let (mut ctx, mut state) = setup_scroll();
+
+    state.update(&mut ctx.term, &keys("}}{")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
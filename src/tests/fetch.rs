@@ -1,11 +1,90 @@
-use super::*;
-
-#[test]
-fn fetch_from_elsewhere_prompt() {
-    snapshot!(TestContext::setup_clone(), "fe");
-}
-
-#[test]
-fn fetch_from_elsewhere() {
-    snapshot!(TestContext::setup_clone(), "feorigin<enter>");
-}
+use super::*;
+use crate::auto_fetch::{AutoFetch, FetchStatus};
+use crossterm::event::Event;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn fetch_from_elsewhere_prompt() {
+    snapshot!(TestContext::setup_clone(), "fe");
+}
+
+#[test]
+fn fetch_from_elsewhere() {
+    snapshot!(TestContext::setup_clone(), "feorigin<enter>");
+}
+
+#[test]
+fn fetch_menu() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "branch.main.pushRemote", "origin"],
+    );
+    snapshot!(ctx, "f");
+}
+
+#[test]
+fn fetch_from_upstream() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    snapshot!(ctx, "f-pu");
+}
+
+fn wait_for_fetch(fetcher: &AutoFetch) -> FetchStatus {
+    let started = Instant::now();
+    loop {
+        if let Some(fetched) = fetcher.poll() {
+            return fetched;
+        }
+
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "Fetch timed out"
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn auto_fetch() {
+    let mut ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+
+    let fetcher = AutoFetch::spawn(
+        ctx.dir.path().to_path_buf(),
+        Duration::from_secs(3600),
+        Default::default(),
+    );
+    let fetched = wait_for_fetch(&fetcher);
+    assert!(fetched.error.is_none());
+
+    let mut state = ctx.init_state();
+    state.last_fetch = Some(fetched);
+    state.update(&mut ctx.term, &[Event::FocusGained]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn auto_fetch_failed() {
+    let mut ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "remote", "set-url", "origin", "/nonexistent"],
+    );
+
+    let fetcher = AutoFetch::spawn(
+        ctx.dir.path().to_path_buf(),
+        Duration::from_secs(3600),
+        Default::default(),
+    );
+    let fetched = wait_for_fetch(&fetcher);
+    assert!(fetched.error.is_some());
+
+    let mut state = ctx.init_state();
+    state.last_fetch = Some(fetched);
+    state.update(&mut ctx.term, &[Event::FocusGained]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -0,0 +1,259 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "origin",
+            "git@github.com:altsem/gitu.git",
+        ],
+    );
+    ctx.config().general.forge.browser = "echo".into();
+    ctx
+}
+
+#[test]
+fn open_commit_in_forge() {
+    snapshot!(setup(), "jjjjjO");
+}
+
+#[test]
+fn open_hunk_in_forge() {
+    let ctx = setup();
+    fs::write(ctx.dir.child("initial-file"), "changed\n").unwrap();
+    snapshot!(ctx, "jjjj<tab>jO");
+}
+
+#[test]
+fn open_file_named_like_url_syntax_in_forge() {
+    let ctx = setup();
+    fs::write(ctx.dir.child("a&b #1%.txt"), "hello\n").unwrap();
+    snapshot!(ctx, "jjjjO");
+}
+
+#[test]
+fn open_branch_in_forge() {
+    snapshot!(setup(), "YjO");
+}
+
+#[test]
+fn open_in_forge_unknown_host() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "origin",
+            "git@git.example.com:altsem/gitu.git",
+        ],
+    );
+    snapshot!(ctx, "jjjjjO");
+}
+
+#[test]
+fn open_in_self_hosted_forge() {
+    let mut ctx = setup();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "origin",
+            "git@git.example.com:altsem/gitu.git",
+        ],
+    );
+    ctx.config()
+        .general
+        .forge
+        .hosts
+        .insert("git.example.com".into(), "gitlab".into());
+    snapshot!(ctx, "jjjjjO");
+}
+
+/// Pushes go to the remote repository, while URLs are made from `url`.
+fn setup_forge_remote(ctx: &TestContext, url: &str) {
+    let remote_dir = ctx.remote_dir.path().to_str().unwrap();
+    run(ctx.dir.path(), &["git", "remote", "set-url", "origin", url]);
+    run(
+        ctx.dir.path(),
+        &["git", "remote", "set-url", "--push", "origin", remote_dir],
+    );
+}
+
+#[test]
+fn create_pull_request() {
+    let ctx = setup();
+    setup_forge_remote(&ctx, "git@gitlab.com:altsem/gitu.git");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-file", "hello\n");
+    snapshot!(ctx, "@c");
+}
+
+#[test]
+fn create_pull_request_detached() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "--detach"]);
+    snapshot!(ctx, "@c");
+}
+
+#[cfg(feature = "forge")]
+mod pull_requests {
+    use super::*;
+    use crate::{
+        github::{CiStatus, PullRequest},
+        state::State,
+    };
+    use crossterm::event::Event;
+
+    fn pull_request(number: u64, title: &str, ci: Option<CiStatus>) -> PullRequest {
+        PullRequest {
+            number,
+            title: title.into(),
+            author: "octocat".into(),
+            head_ref: format!("feature-{}", number),
+            draft: false,
+            ci,
+        }
+    }
+
+    fn show_pull_requests(ctx: &mut TestContext, keys_after: &str) -> State {
+        let mut state = ctx.init_state();
+        state.screens.push(
+            screen::pull_requests::create(
+                Rc::clone(&state.config),
+                ctx.size,
+                "origin".into(),
+                false,
+                || {
+                    Ok(vec![
+                        pull_request(3, "Add a thing", Some(CiStatus::Success)),
+                        pull_request(2, "Fix the thing", Some(CiStatus::Failure)),
+                        PullRequest {
+                            draft: true,
+                            ..pull_request(1, "Try something", Some(CiStatus::Pending))
+                        },
+                    ])
+                },
+            )
+            .unwrap(),
+        );
+        state.update(&mut ctx.term, &[Event::FocusGained]).unwrap();
+        state.update(&mut ctx.term, &keys(keys_after)).unwrap();
+        state
+    }
+
+    #[test]
+    fn pull_requests_screen() {
+        let mut ctx = setup();
+        show_pull_requests(&mut ctx, "");
+        insta::assert_snapshot!(ctx.redact_buffer());
+    }
+
+    #[test]
+    fn open_pull_request_in_forge() {
+        let mut ctx = setup();
+        show_pull_requests(&mut ctx, "jjO");
+        insta::assert_snapshot!(ctx.redact_buffer());
+    }
+
+    #[test]
+    fn checkout_pull_request() {
+        let mut ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "-b", "feature-1"]);
+        commit(ctx.dir.path(), "pull-request-file", "hello\n");
+        run(
+            ctx.dir.path(),
+            &["git", "push", "origin", "feature-1:refs/pull/1/head"],
+        );
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        run(ctx.dir.path(), &["git", "branch", "-D", "feature-1"]);
+
+        show_pull_requests(&mut ctx, "jjjby");
+        insta::assert_snapshot!(ctx.redact_buffer());
+    }
+
+    #[test]
+    fn create_pull_request_prompts_on_github() {
+        let ctx = setup();
+        setup_forge_remote(&ctx, "git@github.com:altsem/gitu.git");
+        run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+        commit(ctx.dir.path(), "feature-file", "hello\n");
+        snapshot!(ctx, "@c");
+    }
+
+    #[test]
+    fn show_pull_requests_not_on_github() {
+        snapshot!(TestContext::setup_clone(), "@l");
+    }
+}
+
+#[cfg(feature = "forge")]
+mod ci_status {
+    use super::*;
+    use crate::{
+        ci::CiStatuses,
+        github::{Checks, CiStatus},
+        state::State,
+    };
+    use crossterm::event::Event;
+
+    fn show_ci_statuses(mut ctx: TestContext, keys_after: &str) -> TestContext {
+        commit(ctx.dir.path(), "new-file", "hello\n");
+        let mut state = ctx.init_state();
+
+        let oid = |state: &State, rev: &str| state.repo.revparse_single(rev).unwrap().id();
+        let (head, parent) = (oid(&state, "HEAD"), oid(&state, "HEAD~1"));
+        state.ci_statuses = Some(CiStatuses::spawn(Default::default(), move |oid| {
+            Ok(if oid == head {
+                Checks {
+                    status: Some(CiStatus::Failure),
+                    failed_url: Some("https://github.com/altsem/gitu/runs/1".into()),
+                }
+            } else if oid == parent {
+                Checks {
+                    status: Some(CiStatus::Success),
+                    failed_url: None,
+                }
+            } else {
+                Checks::default()
+            })
+        }));
+
+        // Drawn once to request the statuses, and then again once they're in
+        state.update(&mut ctx.term, &[Event::FocusGained]).unwrap();
+        let ci_statuses = state.ci_statuses.as_ref().unwrap();
+        while ci_statuses.get(head).is_none() || ci_statuses.get(parent).is_none() {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut events = vec![Event::FocusGained];
+        events.extend(keys(keys_after));
+        state.update(&mut ctx.term, &events).unwrap();
+        ctx
+    }
+
+    #[test]
+    fn ci_statuses_of_commits() {
+        let ctx = show_ci_statuses(setup(), "");
+        insta::assert_snapshot!(ctx.redact_buffer());
+    }
+
+    #[test]
+    fn ci_statuses_of_branches() {
+        let ctx = show_ci_statuses(setup(), "Y");
+        insta::assert_snapshot!(ctx.redact_buffer());
+    }
+
+    #[test]
+    fn open_failed_check() {
+        let ctx = show_ci_statuses(setup(), "jjjj@f");
+        insta::assert_snapshot!(ctx.redact_buffer());
+    }
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    for file in ["first-file", "second-file", "third-file"] {
+        commit(ctx.dir.path(), file, "");
+        fs::write(ctx.dir.child(file), "changed\n").unwrap();
+    }
+    ctx
+}
+
+#[test]
+fn goto_prompt() {
+    snapshot!(setup(), "<ctrl+g>");
+}
+
+#[test]
+fn goto_lists_matches() {
+    snapshot!(setup(), "<ctrl+g>file");
+}
+
+#[test]
+fn goto_file() {
+    snapshot!(setup(), "<ctrl+g>mthrd<enter>");
+}
+
+#[test]
+fn goto_picked() {
+    snapshot!(setup(), "<ctrl+g>recent<tab><enter>");
+}
+
+#[test]
+fn goto_no_match() {
+    snapshot!(setup(), "<ctrl+g>zzz<enter>");
+}
+
+#[test]
+fn goto_picked_among_same_text() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "add", "first-file"]);
+    fs::write(ctx.dir.child("first-file"), "changed again\n").unwrap();
+    snapshot!(ctx, "<ctrl+g>modified first<tab><tab><enter>");
+}
@@ -68,6 +68,10 @@ pub fn set_env_vars() {
     env::set_var("GIT_COMMITTER_EMAIL", "committer@email.com");
     env::set_var("GIT_COMMITTER_DATE", "Sun Feb 18 14:00 2024 +0100");
     env::set_var("LC_ALL", "C");
+    // Submodules are cloned from local paths
+    env::set_var("GIT_CONFIG_COUNT", "1");
+    env::set_var("GIT_CONFIG_KEY_0", "protocol.file.allow");
+    env::set_var("GIT_CONFIG_VALUE_0", "always");
 }
 
 pub fn run(dir: &Path, cmd: &[&str]) -> String {
@@ -5,6 +5,7 @@ use crate::{
     state::State,
     term::{Term, TermBackend},
     tests::helpers::RepoTestContext,
+    welcome::Welcome,
 };
 use crossterm::event::{Event, KeyEvent};
 use git2::Repository;
@@ -73,10 +74,14 @@ impl TestContext {
     }
 
     pub fn init_state_at_path(&mut self, path: PathBuf) -> State {
+        self.init_state_with_args(path, &Args::default())
+    }
+
+    pub fn init_state_with_args(&mut self, path: PathBuf, args: &Args) -> State {
         let mut state = State::create(
             Rc::new(Repository::open(path).unwrap()),
             self.size,
-            &Args::default(),
+            args,
             Rc::clone(&self.config),
             false,
         )
@@ -87,6 +92,14 @@ impl TestContext {
         state
     }
 
+    pub fn init_welcome(&mut self, dir: PathBuf) -> Welcome {
+        let mut welcome = Welcome::new(Rc::clone(&self.config), dir, false);
+        welcome
+            .update(&mut self.term, &[Event::FocusGained])
+            .unwrap();
+        welcome
+    }
+
     pub fn redact_buffer(&self) -> String {
         let TermBackend::Test(test_backend) = self.term.backend() else {
             unreachable!();
@@ -0,0 +1,41 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "untracked-file"]);
+    ctx
+}
+
+#[test]
+fn ignore_menu() {
+    snapshot!(setup(), "jjjji");
+}
+
+#[test]
+fn ignore_toplevel_prompt() {
+    snapshot!(setup(), "jjjjit");
+}
+
+#[test]
+fn ignore_toplevel() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjit<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child(".gitignore")).unwrap(),
+        "/untracked-file\n"
+    );
+}
+
+#[test]
+fn ignore_privately() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jjjjip*.tmp<enter>"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    let exclude = fs::read_to_string(ctx.dir.child(".git/info/exclude")).unwrap();
+    assert!(exclude.ends_with("\n*.tmp\n"));
+}
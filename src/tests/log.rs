@@ -77,3 +77,202 @@ fn log_other_input() {
 fn log_other_invalid() {
     snapshot!(setup(), "lo <enter>");
 }
+
+#[test]
+fn reflog() {
+    snapshot!(setup(), "lr");
+}
+
+#[test]
+fn reflog_show_entry() {
+    snapshot!(setup(), "lrj<enter>");
+}
+
+#[test]
+fn reflog_recover_lost_commits() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "reset", "--hard", "HEAD~2"]);
+    snapshot!(ctx, "lrjXh<enter>y");
+}
+
+fn setup_filters() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "needle.txt", "needle\n");
+    commit(ctx.dir.path(), "hay.txt", "hay\n");
+    fs::write(ctx.dir.child("hay.txt"), "more hay\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "commit",
+            "-m",
+            "other author",
+            "--author=Other Person <other@email.com>",
+        ],
+    );
+    ctx
+}
+
+#[test]
+fn filter_menu() {
+    snapshot!(setup_filters(), "l");
+}
+
+#[test]
+fn author() {
+    snapshot!(setup_filters(), "l-AOther<enter>l");
+}
+
+#[test]
+fn since_excludes_older_commits() {
+    snapshot!(setup_filters(), "l=s2024-03-01<enter>l");
+}
+
+#[test]
+fn until_keeps_older_commits() {
+    snapshot!(setup_filters(), "l=u2024-03-01<enter>l");
+}
+
+#[test]
+fn since_invalid() {
+    snapshot!(setup_filters(), "l=ssomeday<enter>");
+}
+
+#[test]
+fn pickaxe() {
+    snapshot!(setup_filters(), "l-Sneedle<enter>l");
+}
+
+#[test]
+fn paths() {
+    snapshot!(setup_filters(), "l--hay.txt<enter>l");
+}
+
+#[test]
+fn edit_filters_from_log() {
+    snapshot!(setup_filters(), "l--hay.txt<enter>ll-AOther<enter>l");
+}
+
+fn setup_merge() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "other-file", "other\n");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "main-file", "main\n");
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "merge",
+            "--no-ff",
+            "other-branch",
+            "-m",
+            "merge other-branch",
+        ],
+    );
+    ctx
+}
+
+#[test]
+fn graph() {
+    snapshot!(setup_merge(), "ll");
+}
+
+#[test]
+fn graph_off() {
+    snapshot!(setup_merge(), "l-gl");
+}
+
+fn setup_file_history() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "old-name", "hello\n");
+    commit(ctx.dir.path(), "unrelated", "unrelated\n");
+    run(ctx.dir.path(), &["git", "mv", "old-name", "new-name"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "rename old-name"]);
+    commit(ctx.dir.path(), "new-name", "hello\nworld\n");
+    fs::write(ctx.dir.child("new-name"), "hello\nworld\n!\n").unwrap();
+    ctx
+}
+
+#[test]
+fn file_history() {
+    snapshot!(setup_file_history(), "jjjjlf");
+}
+
+#[test]
+fn file_history_show_commit() {
+    snapshot!(setup_file_history(), "jjjjlfjj<enter>");
+}
+
+fn setup_shortlog() -> TestContext {
+    let ctx = setup();
+    for message in ["fix typo", "add docs"] {
+        run(
+            ctx.dir.path(),
+            &[
+                "git",
+                "commit",
+                "--allow-empty",
+                "--author",
+                "Other Author <other@example.com>",
+                "-m",
+                message,
+            ],
+        );
+    }
+    ctx
+}
+
+#[test]
+fn shortlog() {
+    snapshot!(setup_shortlog(), "ls<enter>");
+}
+
+#[test]
+fn shortlog_expand_author() {
+    snapshot!(setup_shortlog(), "ls<enter><tab>");
+}
+
+#[test]
+fn shortlog_of_marked_commits() {
+    snapshot!(setup_shortlog(), "llvjjjls<enter>");
+}
+
+#[test]
+fn shortlog_of_marked_commits_from_root() {
+    snapshot!(setup_shortlog(), "llvGls<enter>");
+}
+
+#[test]
+fn shortlog_invalid_range() {
+    snapshot!(setup_shortlog(), "lsnonexistent<enter>");
+}
+
+fn setup_pages() -> TestContext {
+    let mut ctx = TestContext::setup_init();
+    ctx.config().general.log_page_size = 5;
+    for i in 1..=60 {
+        let message = format!("commit {}", i);
+        run(
+            ctx.dir.path(),
+            &["git", "commit", "--allow-empty", "-m", &message],
+        );
+    }
+    ctx
+}
+
+#[test]
+fn log_loads_first_pages() {
+    snapshot!(setup_pages(), "ll");
+}
+
+#[test]
+fn log_loads_more_when_scrolling() {
+    snapshot!(setup_pages(), "ll<pagedown><pagedown>");
+}
+
+#[test]
+fn log_loads_up_to_limit() {
+    snapshot!(setup_pages(), "l-n-n32<enter>l<pagedown>");
+}
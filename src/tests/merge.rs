@@ -0,0 +1,56 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "hey");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    ctx
+}
+
+fn setup_conflict() -> TestContext {
+    let ctx = setup();
+    commit(ctx.dir.path(), "new-file", "hi");
+    run(ctx.dir.path(), &["git", "merge", "other-branch"]);
+    ctx
+}
+
+#[test]
+fn merge_menu() {
+    snapshot!(setup(), "m");
+}
+
+#[test]
+fn merge_prompt() {
+    snapshot!(setup(), "mm");
+}
+
+#[test]
+fn merge() {
+    snapshot!(setup(), "mmother-branch<enter>");
+}
+
+#[test]
+fn merge_no_ff() {
+    snapshot!(setup(), "m-n-emother-branch<enter>");
+}
+
+#[test]
+fn merge_squash() {
+    snapshot!(setup(), "msother-branch<enter>");
+}
+
+#[test]
+fn merge_conflict_menu() {
+    snapshot!(setup_conflict(), "m");
+}
+
+#[test]
+fn merge_abort() {
+    snapshot!(setup_conflict(), "ma");
+}
+
+#[test]
+fn show_merge_head() {
+    snapshot!(setup_conflict(), "<enter>");
+}
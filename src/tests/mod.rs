@@ -1,23 +1,54 @@
-use std::fs;
+use std::{fs, rc::Rc, thread, time::Duration};
 
 #[macro_use]
 mod helpers;
 mod arg;
+mod bisect;
+mod blob;
+mod branch;
+mod cherry_pick;
+mod clean;
+mod cmd_history;
+mod command_palette;
 mod commit;
+mod copy;
+mod diff;
+mod diff_options;
 mod discard;
 mod editor;
 mod fetch;
+mod forge;
+mod goto;
+mod ignore;
 mod log;
+mod merge;
+#[cfg(unix)]
+mod mode;
+#[cfg(unix)]
+mod non_utf8;
+mod patch;
+mod porcelain;
 mod pull;
 mod push;
 mod quit;
 mod rebase;
 mod remote;
+mod repositories;
 mod reset;
+mod resolve;
+mod rev_prompt;
+mod search;
+mod shell;
+mod signing;
 mod stage;
 mod stash;
+mod submodule;
+mod tag;
+mod tree;
 mod unstage;
+mod welcome;
 
+use crate::{git::log_filter::LogFilter, image, items::LogOptions, menu::Menu, ops::Op, screen};
 use helpers::{clone_and_commit, commit, keys, run, TestContext};
 
 #[test]
@@ -28,6 +59,44 @@ fn no_repo() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn open_repo_from_subdirectory() {
+    let ctx = TestContext::setup_clone();
+    let subdir = ctx.dir.child("subdir");
+    fs::create_dir(&subdir).unwrap();
+
+    let repo = crate::open_repo(Some(&subdir)).unwrap();
+    assert_eq!(
+        repo.workdir().unwrap().canonicalize().unwrap(),
+        ctx.dir.path().canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn open_repo_outside_repo_lists_repos_below() {
+    let parent = temp_dir::TempDir::new().unwrap();
+    fs::create_dir_all(parent.child("some-repo").join(".git")).unwrap();
+    fs::create_dir(parent.child("not-a-repo")).unwrap();
+
+    let Err(err) = crate::open_repo(Some(parent.path())) else {
+        panic!("Opened a repo");
+    };
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "No .git found in {}, open one of its repositories with `gitu -C <path>`: some-repo",
+            parent.path().display()
+        )
+    );
+}
+
+#[test]
+fn bare_repo() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.init_state_at_path(ctx.remote_dir.path().to_path_buf());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
 #[test]
 fn help_menu() {
     let mut ctx = TestContext::setup_init();
@@ -37,6 +106,35 @@ fn help_menu() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn key_hints_for_args() {
+    snapshot!(TestContext::setup_init(), "l-");
+}
+
+#[test]
+fn suspend_redraws_on_resume() {
+    snapshot!(TestContext::setup_clone(), "<ctrl+z>");
+}
+
+#[test]
+fn help_screen() {
+    snapshot!(TestContext::setup_init(), "hH");
+}
+
+#[test]
+fn help_screen_search() {
+    snapshot!(TestContext::setup_init(), "H/Stash<enter>");
+}
+
+#[test]
+fn help_screen_changed_bindings() {
+    let mut ctx = TestContext::setup_init();
+    let root = ctx.config().bindings.get_mut(&Menu::Root).unwrap();
+    root.insert(Op::Refresh, vec!["<ctrl+r>".into()]);
+    root.insert(Op::MoveToTop, vec!["<home>".into(), "gg".into()]);
+    snapshot!(ctx, "H/default<enter>");
+}
+
 #[test]
 fn fresh_init() {
     let mut ctx = TestContext::setup_init();
@@ -54,12 +152,147 @@ fn new_file() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn background_refresh() {
+    let mut ctx = TestContext::setup_init();
+    let mut state = ctx.init_state();
+    state.screens = vec![screen::status::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        ctx.size,
+        true,
+    )
+    .unwrap()];
+
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    state.screen_mut().update().unwrap();
+    assert!(state.screen().refreshing_since().is_some());
+
+    while state.screen().refreshing_since().is_some() {
+        thread::sleep(Duration::from_millis(10));
+        state.update(&mut ctx.term, &[]).unwrap();
+    }
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn background_first_load() {
+    let mut ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "testing\n");
+    fs::write(ctx.dir.child("testfile"), "test\n").unwrap();
+    let mut state = ctx.init_state();
+
+    state.screens = vec![screen::status::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        ctx.size,
+        true,
+    )
+    .unwrap()];
+    assert!(state.screen().refreshing_since().is_some());
+
+    state.screen_mut().await_refresh().unwrap();
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn background_log() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "testing\n");
+    commit(ctx.dir.path(), "secondfile", "testing\n");
+    let mut state = ctx.init_state();
+
+    state.screens = vec![screen::log::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        ctx.size,
+        true,
+        usize::MAX,
+        None,
+        LogFilter::default(),
+        LogOptions::default(),
+    )
+    .unwrap()];
+    assert!(state.screen().refreshing_since().is_some());
+
+    while state.screen().refreshing_since().is_some() {
+        thread::sleep(Duration::from_millis(10));
+        state.update(&mut ctx.term, &[]).unwrap();
+    }
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn background_log_pages() {
+    let mut ctx = TestContext::setup_init();
+    ctx.config().general.log_page_size = 5;
+    for i in 1..=30 {
+        let message = format!("commit {}", i);
+        run(
+            ctx.dir.path(),
+            &["git", "commit", "--allow-empty", "-m", &message],
+        );
+    }
+    let mut state = ctx.init_state();
+
+    state.screens = vec![screen::log::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        ctx.size,
+        true,
+        usize::MAX,
+        None,
+        LogFilter::default(),
+        LogOptions::default(),
+    )
+    .unwrap()];
+
+    // Each page is asked for once the one before it arrived
+    for _ in 0..500 {
+        if state.screen().scroll_position().2 == 30 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+        state.update(&mut ctx.term, &[]).unwrap();
+    }
+
+    let mut revwalk = state.repo.revwalk().unwrap();
+    revwalk.push_head().unwrap();
+    let expected = revwalk
+        .map(|oid| oid.unwrap().to_string())
+        .collect::<Vec<_>>();
+    let loaded = state
+        .screen()
+        .items()
+        .iter()
+        .map(|item| item.id.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(loaded, expected);
+}
+
+#[test]
+fn key_release_isnt_drawn() {
+    let mut ctx = TestContext::setup_init();
+    let mut state = ctx.init_state();
+    state.display_info("Not drawn yet".into());
+
+    let release = crossterm::event::KeyEvent {
+        kind: crossterm::event::KeyEventKind::Release,
+        ..crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Char('j'))
+    };
+    state
+        .update(&mut ctx.term, &[crossterm::event::Event::Key(release)])
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
 #[test]
 fn unstaged_changes() {
     let ctx = TestContext::setup_init();
     commit(ctx.dir.path(), "testfile", "testing\ntesttest\n");
     fs::write(ctx.dir.child("testfile"), "test\ntesttest\n").expect("error writing to file");
-    snapshot!(ctx, "jj<tab>");
+    snapshot!(ctx, "jjj<tab>");
 }
 
 #[test]
@@ -70,6 +303,95 @@ fn binary_file() {
     snapshot!(ctx, "jj<tab>");
 }
 
+#[test]
+fn wide_file_names_diffstat() {
+    let ctx = TestContext::setup_init();
+    for name in ["日本語.txt", "emoji-🎉.txt", "cafe\u{301}.txt", "plain.txt"] {
+        fs::write(ctx.dir.child(name), "content\n").expect("error writing to file");
+    }
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add files"]);
+    snapshot!(ctx, "ll<enter>");
+}
+
+#[test]
+fn wide_commit_message_truncated() {
+    let ctx = TestContext::setup_init();
+    fs::write(ctx.dir.child("file"), "content\n").expect("error writing to file");
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", &"漢字".repeat(20)]);
+    snapshot!(ctx, "");
+}
+
+fn setup_binary_change() -> TestContext {
+    let ctx = TestContext::setup_init();
+    fs::write(ctx.dir.child("image.png"), [0; 1000]).expect("error writing to file");
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add image"]);
+    fs::write(ctx.dir.child("image.png"), [0; 2500]).expect("error writing to file");
+    ctx
+}
+
+#[test]
+fn binary_file_changed() {
+    snapshot!(setup_binary_change(), "jjj<tab>");
+}
+
+#[test]
+fn binary_file_diffstat() {
+    let ctx = setup_binary_change();
+    run(ctx.dir.path(), &["git", "commit", "-am", "grow image"]);
+    snapshot!(ctx, "ll<enter>");
+}
+
+fn setup_image_change(ctx: &TestContext) {
+    let png = |height| image::test_png(64, height);
+    fs::write(ctx.dir.child("image.png"), png(64)).expect("error writing to file");
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add image"]);
+    fs::write(ctx.dir.child("image.png"), png(48)).expect("error writing to file");
+}
+
+#[test]
+fn image_file_changed() {
+    let ctx = TestContext::setup_init();
+    setup_image_change(&ctx);
+    let state = snapshot!(ctx, "jjj<tab>");
+
+    // Drawn by the terminal over the blank lines kept free for it
+    let [(area, image)] = &state.images[..] else {
+        panic!("expected one image, got {:?}", state.images);
+    };
+    assert_eq!((area.x, area.y, area.height), (1, 6, 12));
+    assert_eq!((image.width, image.height), (64, 48));
+}
+
+#[test]
+fn image_preview_disabled() {
+    let mut ctx = TestContext::setup_init();
+    ctx.config().general.image_preview.enabled = false;
+    setup_image_change(&ctx);
+    snapshot!(ctx, "jjj<tab>");
+}
+
+fn setup_large_diff() -> TestContext {
+    let mut ctx = TestContext::setup_init();
+    ctx.config().general.large_diff_lines = 4;
+    commit(ctx.dir.path(), "large-file", "one\ntwo\nthree\n");
+    fs::write(ctx.dir.child("large-file"), "one\n2\nthree\n").expect("error writing to file");
+    ctx
+}
+
+#[test]
+fn large_diff_hidden() {
+    snapshot!(setup_large_diff(), "jjj<tab>");
+}
+
+#[test]
+fn large_diff_show_anyway() {
+    snapshot!(setup_large_diff(), "jjj<tab>j<enter>");
+}
+
 #[test]
 fn collapsed_sections_config() {
     let mut ctx = TestContext::setup_clone();
@@ -84,6 +406,52 @@ fn collapsed_sections_config() {
     snapshot!(ctx, "");
 }
 
+fn setup_levels() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-one", "one\n");
+    commit(ctx.dir.path(), "file-two", "two\n");
+    fs::write(ctx.dir.child("file-one"), "changed one\n").unwrap();
+    fs::write(ctx.dir.child("file-two"), "changed two\n").unwrap();
+    ctx
+}
+
+#[test]
+fn show_level_1() {
+    snapshot!(setup_levels(), "jjjjj1");
+}
+
+#[test]
+fn show_level_2() {
+    snapshot!(setup_levels(), "2");
+}
+
+#[test]
+fn show_level_3() {
+    snapshot!(setup_levels(), "3");
+}
+
+#[test]
+fn show_level_4() {
+    snapshot!(setup_levels(), "14");
+}
+
+#[test]
+fn collapse_all() {
+    snapshot!(setup_levels(), "<alt+c>jj<tab>");
+}
+
+#[test]
+fn expand_all() {
+    snapshot!(setup_levels(), "1<alt+e>");
+}
+
+#[test]
+fn section_level_config() {
+    let mut ctx = setup_levels();
+    ctx.config().general.section_level = 2;
+    snapshot!(ctx, "");
+}
+
 #[test]
 fn log() {
     let ctx = TestContext::setup_clone();
@@ -101,6 +469,28 @@ fn show() {
     snapshot!(ctx, "ll<enter>");
 }
 
+fn setup_tags() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-a", "a");
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+    commit(ctx.dir.path(), "file-b", "b");
+    commit(ctx.dir.path(), "file-c", "c");
+    run(ctx.dir.path(), &["git", "tag", "v2.0"]);
+    commit(ctx.dir.path(), "file-d", "d");
+    run(ctx.dir.path(), &["git", "tag", "v2.1"]);
+    ctx
+}
+
+#[test]
+fn show_describe() {
+    snapshot!(setup_tags(), "lljj<enter>");
+}
+
+#[test]
+fn show_tags_containing() {
+    snapshot!(setup_tags(), "lljj<enter>kk<tab>");
+}
+
 #[test]
 fn rebase_conflict() {
     let mut ctx = TestContext::setup_clone();
@@ -159,6 +549,23 @@ fn revert_abort() {
     snapshot!(ctx, "Va");
 }
 
+#[test]
+fn revert_marked_commits() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hey");
+    commit(ctx.dir.path(), "other-file", "hi");
+
+    snapshot!(ctx, "llvjV-EV<enter>");
+}
+
+#[test]
+fn revert_marked_commits_from_root() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hey");
+
+    snapshot!(ctx, "llvjV-EV<enter>");
+}
+
 #[test]
 fn revert_menu() {
     let ctx = TestContext::setup_clone();
@@ -177,6 +584,44 @@ fn revert_commit() {
     snapshot!(ctx, "llV-eV<enter>");
 }
 
+#[test]
+fn revert_conflict_menu() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hey");
+    commit(ctx.dir.path(), "new-file", "hi");
+
+    run(ctx.dir.path(), &["git", "revert", "HEAD~1"]);
+
+    snapshot!(ctx, "V");
+}
+
+#[test]
+fn revert_no_edit_menu() {
+    snapshot!(TestContext::setup_clone(), "V-E");
+}
+
+#[test]
+fn revert_no_edit() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "llV-EV<enter>");
+}
+
+#[test]
+fn revert_merge_commit_mainline() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "other-file", "hey");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "new-file", "hi");
+    run(
+        ctx.dir.path(),
+        &["git", "merge", "--no-ff", "-m", "merge", "other-branch"],
+    );
+
+    snapshot!(ctx, "llV-e-m1<enter>V<enter>");
+}
+
 #[test]
 fn moved_file() {
     let mut ctx = TestContext::setup_clone();
@@ -232,6 +677,19 @@ mod show_refs {
         snapshot!(ctx, "Yjjjjbb<enter>Y");
     }
 
+    #[test]
+    fn show_refs_upstream_ahead_behind() {
+        let ctx = TestContext::setup_clone();
+        clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+        run(ctx.dir.path(), &["git", "fetch"]);
+        commit(ctx.dir.path(), "local-file", "hello");
+        run(
+            ctx.dir.path(),
+            &["git", "branch", "feature-without-upstream"],
+        );
+        snapshot!(ctx, "Y");
+    }
+
     #[test]
     fn show_refs_at_tag() {
         let ctx = TestContext::setup_clone();
@@ -292,7 +750,7 @@ fn stage_last_hunk_of_first_delta() {
     fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
     fs::write(ctx.dir.child("file-two"), "blahonga\n").unwrap();
 
-    snapshot!(ctx, "jj<tab>js");
+    snapshot!(ctx, "jjjj<tab>js");
 }
 
 #[test]
@@ -303,7 +761,7 @@ fn go_down_past_collapsed() {
     fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
     fs::write(ctx.dir.child("file-two"), "blahonga\n").unwrap();
 
-    snapshot!(ctx, "jjj");
+    snapshot!(ctx, "jjjj");
 }
 
 #[test]
@@ -326,6 +784,31 @@ fn inside_submodule() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn unpulled_and_unpushed_commits() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    commit(ctx.dir.path(), "local-file", "hello");
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn recent_commits_limit() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.recent_commits_limit = 1;
+    commit(ctx.dir.path(), "first-file", "");
+    commit(ctx.dir.path(), "second-file", "");
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn recent_commits_hidden() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.recent_commits_limit = 0;
+    snapshot!(ctx, "");
+}
+
 #[test]
 fn syntax_highlighted() {
     let ctx = TestContext::setup_init();
@@ -340,7 +823,43 @@ fn syntax_highlighted() {
     )
     .unwrap();
 
-    snapshot!(ctx, "jj<tab>");
+    snapshot!(ctx, "jjj<tab>");
+}
+
+#[test]
+fn syntax_highlight_skipped_for_large_files() {
+    let mut ctx = TestContext::setup_init();
+    ctx.config().style.syntax_highlight.max_file_size = 10;
+    commit(
+        ctx.dir.path(),
+        "syntax-highlighted.rs",
+        "fn main() {\n    println!(\"Hey\");\n}\n",
+    );
+    fs::write(
+        ctx.dir.child("syntax-highlighted.rs"),
+        "fn main() {\n    println!(\"Bye\");\n}\n",
+    )
+    .unwrap();
+
+    snapshot!(ctx, "jjj<tab>");
+}
+
+#[test]
+fn diff_line_numbers() {
+    let mut ctx = TestContext::setup_init();
+    ctx.config().general.diff_line_numbers.enabled = true;
+    commit(
+        ctx.dir.path(),
+        "file",
+        "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n",
+    );
+    fs::write(
+        ctx.dir.child("file"),
+        "1\n2\n3\n4\n5\n6\n7\n8\nnine\n10\n11\n12\n",
+    )
+    .unwrap();
+
+    snapshot!(ctx, "jjj<tab>");
 }
 
 #[test]
@@ -372,3 +891,136 @@ fn tab_diff() {
 
     insta::assert_snapshot!(ctx.redact_buffer());
 }
+
+#[test]
+fn interactive_cmd_not_found() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+
+    let result = state.run_cmd_interactive(
+        &mut ctx.term,
+        std::process::Command::new("gitu-nonexistent-editor"),
+    );
+    assert!(result.is_err());
+
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn running_cmd_progress_and_kill() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state.enable_async_cmds = true;
+
+    let mut cmd = std::process::Command::new("sh");
+    cmd.args([
+        "-c",
+        "printf 'Counting: %s\\r' 1 2 >&2; echo >&2; exec sleep 10",
+    ]);
+    state.run_cmd_async(&mut ctx.term, &[], cmd).unwrap();
+
+    while !ctx.redact_buffer().contains("Counting: 2") {
+        state.update(&mut ctx.term, &[]).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    state.update(&mut ctx.term, &keys("<ctrl+c>")).unwrap();
+    while state.pending_cmd.is_some() {
+        state.update(&mut ctx.term, &[]).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[cfg(unix)]
+#[test]
+fn kill_cmd_along_with_what_it_started() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state.enable_async_cmds = true;
+
+    // The inner shell is started by a child of the command, and doesn't exec its sleep
+    fs::write(
+        ctx.dir.child("inner.sh"),
+        "echo $$ > inner.pid; sleep 10; true",
+    )
+    .unwrap();
+    let mut cmd = std::process::Command::new("sh");
+    cmd.args(["-c", "sh -c 'sh inner.sh; true'; true"]);
+    state.run_cmd_async(&mut ctx.term, &[], cmd).unwrap();
+
+    let pid_file = ctx.dir.child("inner.pid");
+    while fs::read_to_string(&pid_file).map_or(true, |pid| !pid.ends_with('\n')) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    let pid: libc::pid_t = fs::read_to_string(&pid_file)
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap();
+
+    state.update(&mut ctx.term, &keys("<ctrl+c>")).unwrap();
+    let killed = (0..500).any(|_| {
+        thread::sleep(Duration::from_millis(10));
+        unsafe { libc::kill(pid, 0) != 0 }
+    });
+    assert!(killed, "the inner shell is still running");
+}
+
+#[test]
+fn kill_cmd_when_idle() {
+    snapshot!(TestContext::setup_clone(), "<ctrl+c>");
+}
+
+#[test]
+fn queued_cmd_runs_in_its_repo() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state.enable_async_cmds = true;
+
+    let mut running = std::process::Command::new("sleep");
+    running.arg("10");
+    state.run_cmd_async(&mut ctx.term, &[], running).unwrap();
+
+    let mut queued = std::process::Command::new("touch");
+    queued.arg("queued-file");
+    state.run_cmd_async(&mut ctx.term, &[], queued).unwrap();
+
+    // Switched to another repository meanwhile
+    state.repo = Rc::new(git2::Repository::open(ctx.remote_dir.path()).unwrap());
+
+    state.update(&mut ctx.term, &keys("<ctrl+c>")).unwrap();
+    while state.pending_cmd.is_some() {
+        state.update(&mut ctx.term, &[]).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    assert!(ctx.dir.child("queued-file").exists());
+    assert!(!ctx.remote_dir.child("queued-file").exists());
+}
+
+#[test]
+fn queued_cmd() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state.enable_async_cmds = true;
+
+    let mut running = std::process::Command::new("sleep");
+    running.arg("10");
+    state.run_cmd_async(&mut ctx.term, &[], running).unwrap();
+
+    let mut queued = std::process::Command::new("echo");
+    queued.arg("queued");
+    state.run_cmd_async(&mut ctx.term, &[], queued).unwrap();
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    state.update(&mut ctx.term, &keys("<ctrl+c>")).unwrap();
+    while state.pending_cmd.is_some() {
+        state.update(&mut ctx.term, &[]).unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
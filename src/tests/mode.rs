@@ -0,0 +1,76 @@
+use super::*;
+use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+
+fn setup_mode_change() -> TestContext {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "script.sh", "echo hi\n");
+    fs::set_permissions(ctx.dir.child("script.sh"), Permissions::from_mode(0o755)).unwrap();
+    ctx
+}
+
+#[test]
+fn mode_change() {
+    snapshot!(setup_mode_change(), "jjj<tab>");
+}
+
+fn setup_symlink_change() -> TestContext {
+    let ctx = TestContext::setup_init();
+    std::os::unix::fs::symlink("old-target", ctx.dir.child("link")).unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add link"]);
+    fs::remove_file(ctx.dir.child("link")).unwrap();
+    std::os::unix::fs::symlink("new-target", ctx.dir.child("link")).unwrap();
+    ctx
+}
+
+#[test]
+fn symlink_change() {
+    snapshot!(setup_symlink_change(), "jjj<tab>");
+}
+
+fn setup_type_change() -> TestContext {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "file", "content\n");
+    fs::remove_file(ctx.dir.child("file")).unwrap();
+    std::os::unix::fs::symlink("target", ctx.dir.child("file")).unwrap();
+    ctx
+}
+
+#[test]
+fn type_change() {
+    snapshot!(setup_type_change(), "jjj<tab>");
+}
+
+#[test]
+fn mode_change_stage() {
+    snapshot!(setup_mode_change(), "jjj<tab>js");
+}
+
+#[test]
+fn mode_change_unstage() {
+    let ctx = setup_mode_change();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "jjj<tab>ju");
+}
+
+#[test]
+fn mode_change_discard() {
+    snapshot!(setup_mode_change(), "jjj<tab>jKy");
+}
+
+#[test]
+fn mode_change_apart_from_hunk() {
+    let ctx = setup_mode_change();
+    fs::write(ctx.dir.child("script.sh"), "echo hello\n").unwrap();
+    snapshot!(ctx, "jjj<tab>jjs");
+}
+
+#[test]
+fn symlink_change_stage() {
+    snapshot!(setup_symlink_change(), "jjj<tab>js");
+}
+
+#[test]
+fn type_change_stage() {
+    snapshot!(setup_type_change(), "jjj<tab>js");
+}
@@ -0,0 +1,36 @@
+use super::*;
+use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+fn latin1_name() -> &'static OsStr {
+    OsStr::from_bytes(b"caf\xe9.txt")
+}
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_init();
+    fs::write(ctx.dir.path().join(latin1_name()), b"caf\xe9\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add latin-1 file"]);
+    fs::write(ctx.dir.path().join(latin1_name()), b"caf\xe9 au lait\n").unwrap();
+    fs::write(ctx.dir.path().join(OsStr::from_bytes(b"na\xefve.txt")), "").unwrap();
+    ctx
+}
+
+#[test]
+fn non_utf8_status() {
+    snapshot!(setup(), "jjjjj<tab>");
+}
+
+#[test]
+fn non_utf8_stage_file() {
+    snapshot!(setup(), "jjjjjs");
+}
+
+#[test]
+fn non_utf8_stage_hunk() {
+    snapshot!(setup(), "jjjjj<tab>js");
+}
+
+#[test]
+fn non_utf8_stage_line() {
+    snapshot!(setup(), "jjjjj<tab><ctrl+j><ctrl+j>s");
+}
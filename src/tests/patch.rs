@@ -0,0 +1,117 @@
+use super::*;
+
+/// Leaves patches of two commits on `other-branch` in `patches/`, which `main` doesn't have
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "first-file", "hey");
+    commit(ctx.dir.path(), "second-file", "hi");
+    run(
+        ctx.dir.path(),
+        &["git", "format-patch", "-o", "patches", "main"],
+    );
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    ctx
+}
+
+fn setup_conflict() -> TestContext {
+    let ctx = setup();
+    commit(ctx.dir.path(), "first-file", "hello");
+    run(ctx.dir.path(), &["sh", "-c", "git am patches/*.patch"]);
+    ctx
+}
+
+#[test]
+fn patch_menu() {
+    snapshot!(setup(), "W");
+}
+
+#[test]
+fn format_patch_prompt() {
+    snapshot!(setup(), "llWc");
+}
+
+#[test]
+fn format_patch_of_marked_commits() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hey");
+    commit(ctx.dir.path(), "second-file", "hi");
+    snapshot!(ctx, "llvjWc");
+}
+
+#[test]
+fn format_patch_of_marked_commits_from_root() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hey");
+    snapshot!(ctx, "llvjWc<enter>out<enter>");
+}
+
+#[test]
+fn format_patch_of_root_commit() {
+    snapshot!(TestContext::setup_clone(), "llWc<enter>out<enter>");
+}
+
+#[test]
+fn format_patch() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hey");
+    snapshot!(ctx, "llWc<enter>out<enter>");
+}
+
+#[test]
+fn apply_patches() {
+    snapshot!(setup(), "Wwpatches<enter>");
+}
+
+#[test]
+fn apply_patches_with_signoff() {
+    snapshot!(
+        setup(),
+        "W-swpatches/0001-add-first-file.patch<enter>ll<enter>"
+    );
+}
+
+#[test]
+fn apply_patches_conflict() {
+    snapshot!(setup_conflict(), "");
+}
+
+#[test]
+fn am_menu() {
+    snapshot!(setup_conflict(), "W");
+}
+
+#[test]
+fn am_skip() {
+    snapshot!(setup_conflict(), "Ws");
+}
+
+#[test]
+fn am_abort() {
+    snapshot!(setup_conflict(), "Wa");
+}
+
+#[test]
+fn apply_patch() {
+    snapshot!(setup(), "Wfpatches/0001-add-first-file.patch<enter>");
+}
+
+#[test]
+fn apply_patch_to_index() {
+    snapshot!(setup(), "Wipatches/0001-add-first-file.patch<enter>");
+}
+
+#[test]
+fn reverse_apply_commit() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hey");
+    snapshot!(ctx, "llWvq");
+}
+
+#[test]
+fn reverse_apply_hunk() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hey\nhi\n");
+    commit(ctx.dir.path(), "new-file", "hey\nhello\n");
+    snapshot!(ctx, "ll<enter>Wvqq");
+}
@@ -0,0 +1,86 @@
+use super::*;
+use crate::{
+    cli::{Args, Commands},
+    porcelain,
+};
+use git2::Repository;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "committed-file", "hello\n");
+    fs::write(ctx.dir.path().join("committed-file"), "hello\nworld\n").unwrap();
+    fs::write(ctx.dir.path().join("staged-file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged-file"]);
+    fs::write(ctx.dir.path().join("untracked-file"), "untracked\n").unwrap();
+    ctx
+}
+
+fn json(value: impl serde::Serialize) -> String {
+    serde_json::to_string_pretty(&value).unwrap()
+}
+
+#[test]
+fn status_porcelain() {
+    let mut ctx = setup();
+    let repo = Repository::open(ctx.dir.path()).unwrap();
+    insta::assert_snapshot!(json(porcelain::status(ctx.config(), &repo).unwrap()));
+}
+
+#[test]
+fn status_porcelain_in_bare_repo() {
+    let mut ctx = setup();
+    let repo = Repository::open(ctx.remote_dir.path()).unwrap();
+    insta::assert_snapshot!(json(porcelain::status(ctx.config(), &repo).unwrap()));
+}
+
+#[test]
+fn log_json() {
+    let ctx = setup();
+    let repo = Repository::open(ctx.dir.path()).unwrap();
+    insta::assert_snapshot!(json(porcelain::log(&repo, None, 1).unwrap()));
+}
+
+#[test]
+fn log_json_of_rev() {
+    let ctx = setup();
+    let repo = Repository::open(ctx.dir.path()).unwrap();
+    let log = porcelain::log(&repo, Some("origin/main"), usize::MAX).unwrap();
+    insta::assert_snapshot!(json(log));
+}
+
+#[test]
+fn diff_json_of_range() {
+    let mut ctx = setup();
+    let repo = Repository::open(ctx.dir.path()).unwrap();
+    let diff = porcelain::diff(ctx.config(), &repo, Some("HEAD~1..HEAD")).unwrap();
+    insta::assert_snapshot!(json(diff));
+}
+
+#[test]
+fn diff_subcommand_opens_unstaged_diff() {
+    let mut ctx = setup();
+    let args = Args {
+        command: Some(Commands::Diff {
+            json: false,
+            range: None,
+        }),
+        ..Default::default()
+    };
+    ctx.init_state_with_args(ctx.dir.path().to_path_buf(), &args);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn log_subcommand_opens_log() {
+    let mut ctx = setup();
+    let args = Args {
+        command: Some(Commands::Log {
+            json: false,
+            max_count: Some(1),
+            rev: None,
+        }),
+        ..Default::default()
+    };
+    ctx.init_state_with_args(ctx.dir.path().to_path_buf(), &args);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
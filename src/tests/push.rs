@@ -102,3 +102,23 @@ fn push_elsewhere_prompt() {
 fn push_elsewhere() {
     snapshot!(TestContext::setup_clone(), "Peorigin<enter>");
 }
+
+#[test]
+fn push_elsewhere_branch() {
+    snapshot!(TestContext::setup_clone(), "Peorigin/other-branch<enter>");
+}
+
+#[test]
+fn push_set_upstream() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "new-branch"]);
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "P-ueorigin/new-branch<enter>");
+}
+
+#[test]
+fn push_tags() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+    snapshot!(ctx, "Pt<enter>");
+}
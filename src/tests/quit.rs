@@ -29,3 +29,45 @@ pub(crate) fn confirm_quit() {
     let state = snapshot!(ctx, "qy");
     assert!(state.quit);
 }
+
+fn setup_watched_log() -> (TestContext, crate::state::State) {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state.files_watched = true;
+    state.update(&mut ctx.term, &[]).unwrap();
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+    (ctx, state)
+}
+
+#[test]
+fn quit_screen_keeps_cached_items() {
+    let (mut ctx, mut state) = setup_watched_log();
+
+    // Unseen without a file watcher running
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    state.update(&mut ctx.term, &keys("q")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn quit_screen_refreshes_after_head_moved() {
+    let (mut ctx, mut state) = setup_watched_log();
+
+    commit(ctx.dir.path(), "new-file", "");
+    state.update(&mut ctx.term, &keys("q")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn quit_screen_refreshes_after_cmd() {
+    let (mut ctx, mut state) = setup_watched_log();
+
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    state
+        .update(&mut ctx.term, &keys("!!true<enter>q"))
+        .unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
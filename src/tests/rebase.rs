@@ -23,3 +23,119 @@ fn rebase_elsewhere_prompt() {
 fn rebase_elsewhere() {
     snapshot!(setup(), "remain<enter>");
 }
+
+fn setup_todo() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-a", "a");
+    commit(ctx.dir.path(), "file-b", "b");
+    commit(ctx.dir.path(), "file-c", "c");
+    ctx
+}
+
+#[test]
+fn rebase_todo() {
+    snapshot!(setup_todo(), "lljjri");
+}
+
+#[test]
+fn rebase_todo_across_merge() {
+    let ctx = setup_todo();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "other-branch", "HEAD~2"],
+    );
+    commit(ctx.dir.path(), "other-file", "other");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(
+        ctx.dir.path(),
+        &["git", "merge", "--no-ff", "-m", "merge", "other-branch"],
+    );
+    snapshot!(ctx, "lljjjjri");
+}
+
+#[test]
+fn rebase_todo_key_hints() {
+    snapshot!(setup_todo(), "lljjri<ctrl+c>");
+}
+
+#[test]
+fn rebase_todo_set_actions() {
+    snapshot!(setup_todo(), "lljjrijfd");
+}
+
+#[test]
+fn rebase_todo_move_down() {
+    snapshot!(setup_todo(), "lljjri<alt+j>");
+}
+
+#[test]
+fn rebase_todo_move_up() {
+    snapshot!(setup_todo(), "lljjrijj<alt+k>");
+}
+
+#[test]
+fn rebase_todo_abort() {
+    snapshot!(setup_todo(), "lljjriq");
+}
+
+#[test]
+fn rebase_todo_fixup_and_drop() {
+    snapshot!(setup_todo(), "lljjrijfd<ctrl+c><ctrl+c>");
+}
+
+#[test]
+fn rebase_todo_reorder() {
+    snapshot!(setup_todo(), "lljjri<alt+j><ctrl+c><ctrl+c>");
+}
+
+#[test]
+fn rebase_reword() {
+    let ctx = setup_todo();
+    // Stands in for the editor, which may be a no-op in tests
+    let hook = ctx.dir.child(".git/hooks/commit-msg");
+    fs::write(&hook, "#!/bin/sh\nsed -i '1s/$/ (reworded)/' \"$1\"\n").unwrap();
+    run(ctx.dir.path(), &["chmod", "+x", hook.to_str().unwrap()]);
+    snapshot!(ctx, "lljrw");
+}
+
+#[test]
+fn rebase_drop_prompt() {
+    snapshot!(setup_todo(), "lljrk");
+}
+
+#[test]
+fn rebase_drop() {
+    snapshot!(setup_todo(), "lljrky");
+}
+
+#[test]
+fn rebase_squash_into_parent() {
+    let ctx = setup_todo();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "lljrs");
+}
+
+#[test]
+fn rebase_squash_into_root_commit() {
+    snapshot!(setup_todo(), "lljjrs");
+}
+
+#[test]
+fn rebase_edit() {
+    snapshot!(setup_todo(), "lljrmq");
+}
+
+#[test]
+fn rebase_edit_split() {
+    snapshot!(setup_todo(), "lljrmqrS");
+}
+
+#[test]
+fn rebase_subset_of_marked_commits() {
+    snapshot!(setup_todo(), "llvjroHEAD~3<enter>");
+}
+
+#[test]
+fn rebase_subset_from_commit() {
+    snapshot!(setup_todo(), "llroHEAD~3<enter>");
+}
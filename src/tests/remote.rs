@@ -1,7 +1,7 @@
 use git2::{Buf, Error, Repository};
 
 use crate::git::remote::*;
-use crate::tests::helpers::{run, RepoTestContext};
+use crate::tests::helpers::{keys, run, RepoTestContext, TestContext};
 
 fn get_head_name(repo: &Repository) -> String {
     repo.head().unwrap().name().unwrap().into()
@@ -71,3 +71,82 @@ fn get_upstream_components_of_feature_branch() {
     assert_eq!(remote, ".");
     assert_eq!(branch, "main");
 }
+
+#[test]
+fn remote_menu() {
+    snapshot!(TestContext::setup_clone(), "M");
+}
+
+#[test]
+fn add_remote_prompt() {
+    snapshot!(TestContext::setup_clone(), "Maupstream<enter>");
+}
+
+#[test]
+fn add_remote() {
+    snapshot!(
+        TestContext::setup_clone(),
+        "YMaupstream<enter>upstream-url<enter>"
+    );
+}
+
+#[test]
+fn add_remote_and_fetch() {
+    let ctx = TestContext::setup_clone();
+    let url = ctx.remote_dir.path().to_str().unwrap();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            &format!("url.{url}.insteadOf"),
+            "upstream-url",
+        ],
+    );
+    snapshot!(ctx, "YM-faupstream<enter>upstream-url<enter>");
+}
+
+#[test]
+fn rename_remote_prompt() {
+    snapshot!(TestContext::setup_clone(), "YjjMr");
+}
+
+#[test]
+fn rename_remote() {
+    snapshot!(TestContext::setup_clone(), "YjjMr<enter>upstream<enter>");
+}
+
+#[test]
+fn remove_remote_prompt() {
+    snapshot!(TestContext::setup_clone(), "YjjMk<enter>");
+}
+
+#[test]
+fn remove_remote() {
+    snapshot!(TestContext::setup_clone(), "YjjMk<enter>y");
+}
+
+#[test]
+fn prune_remote() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "stale"]);
+    run(ctx.dir.path(), &["git", "push", "-u", "origin", "stale"]);
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(ctx.remote_dir.path(), &["git", "branch", "-D", "stale"]);
+    snapshot!(ctx, "YjjjMp<enter>");
+}
+
+#[test]
+fn set_remote_url_prompt() {
+    snapshot!(TestContext::setup_clone(), "YjjMu<enter>");
+}
+
+#[test]
+fn fetch_remote_at_point_prompt() {
+    snapshot!(TestContext::setup_clone(), "Yjjfe");
+}
+
+#[test]
+fn fetch_remote_at_point() {
+    snapshot!(TestContext::setup_clone(), "Yjjfe<enter>");
+}
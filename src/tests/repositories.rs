@@ -0,0 +1,80 @@
+use super::*;
+use crate::screen::repositories::load_recent;
+use temp_dir::TempDir;
+
+/// A root of two repositories, one of them nested in a plain directory.
+fn setup() -> (TestContext, TempDir) {
+    let ctx = TestContext::setup_clone();
+    let root = TempDir::new().unwrap();
+
+    for repo in ["project", "group/nested-project"] {
+        let dir = root.child(repo);
+        fs::create_dir_all(&dir).unwrap();
+        run(&dir, &["git", "init", "--initial-branch=main"]);
+        commit(&dir, &format!("{}-file", repo.replace('/', "-")), "hello\n");
+    }
+    fs::create_dir(root.child("not-a-repo")).unwrap();
+
+    (ctx, root)
+}
+
+/// The root's path varies in length, so the padding of lines is left out as well.
+fn redact(ctx: &TestContext, dir: &TempDir) -> String {
+    let path = dir.path().canonicalize().unwrap();
+    ctx.redact_buffer()
+        .replace(path.to_str().unwrap(), "<root>")
+        .lines()
+        .map(|line| match line.strip_suffix('|') {
+            Some(line) => format!("{}|\n", line.trim_end()),
+            None => format!("{}\n", line),
+        })
+        .collect()
+}
+
+#[test]
+fn no_repositories() {
+    snapshot!(TestContext::setup_clone(), "<ctrl+o>");
+}
+
+#[test]
+fn repositories_in_roots() {
+    let (mut ctx, root) = setup();
+    ctx.config().general.repositories.roots = vec![root.path().to_str().unwrap().into()];
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("<ctrl+o>")).unwrap();
+    insta::assert_snapshot!(redact(&ctx, &root));
+}
+
+#[test]
+fn switch_repository() {
+    let (mut ctx, root) = setup();
+    let data = TempDir::new().unwrap();
+    ctx.config().general.repositories.roots = vec![root.path().to_str().unwrap().into()];
+
+    let mut state = ctx.init_state();
+    state.recent_repos_file = Some(data.child("recent_repositories.toml"));
+    state
+        .update(&mut ctx.term, &keys("<ctrl+o>jj<enter>"))
+        .unwrap();
+    insta::assert_snapshot!(redact(&ctx, &root));
+
+    assert_eq!(
+        load_recent(&data.child("recent_repositories.toml")),
+        vec![root.child("project").canonicalize().unwrap()]
+    );
+}
+
+#[test]
+fn recent_repositories() {
+    let (mut ctx, root) = setup();
+    let data = TempDir::new().unwrap();
+    ctx.config().general.repositories.roots = vec![root.path().to_str().unwrap().into()];
+
+    let mut state = ctx.init_state();
+    state.recent_repos_file = Some(data.child("recent_repositories.toml"));
+    state
+        .update(&mut ctx.term, &keys("<ctrl+o>jj<enter><ctrl+o>"))
+        .unwrap();
+    insta::assert_snapshot!(redact(&ctx, &root));
+}
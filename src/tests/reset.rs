@@ -28,5 +28,36 @@ pub(crate) fn reset_mixed() {
 
 #[test]
 fn reset_hard() {
-    snapshot!(setup(), "lljXh<enter>q");
+    snapshot!(setup(), "lljXh<enter>yq");
+}
+
+#[test]
+fn reset_hard_prompt() {
+    snapshot!(setup(), "lljXh<enter>");
+}
+
+#[test]
+fn reset_hard_without_confirm() {
+    let mut ctx = setup();
+    ctx.config().general.confirm_reset_hard.enabled = false;
+    snapshot!(ctx, "lljXh<enter>q");
+}
+
+#[test]
+fn reset_keep() {
+    snapshot!(setup(), "lljXk<enter>q");
+}
+
+#[test]
+fn reset_file() {
+    let ctx = setup();
+    commit(ctx.dir.path(), "initial-file", "changed\n");
+    snapshot!(ctx, "XfHEAD~2<enter>initial-file<enter>");
+}
+
+#[test]
+fn reset_file_prompt_defaults_to_renamed_file() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "mv", "unwanted-file", "new-name"]);
+    snapshot!(ctx, "jjjjXfHEAD<enter>");
 }
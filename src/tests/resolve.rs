@@ -0,0 +1,54 @@
+use super::*;
+
+fn setup_conflict() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello\n");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "hey\n");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "new-file", "hi\n");
+    run(ctx.dir.path(), &["git", "merge", "other-branch"]);
+    ctx
+}
+
+fn read_new_file(ctx: &TestContext) -> String {
+    fs::read_to_string(ctx.dir.child("new-file")).unwrap()
+}
+
+#[test]
+fn conflict_status() {
+    let mut ctx = setup_conflict();
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn resolve_menu() {
+    snapshot!(setup_conflict(), "jjjR");
+}
+
+#[test]
+fn keep_ours_file() {
+    let mut ctx = setup_conflict();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjRo")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(read_new_file(&ctx), "hi\n");
+}
+
+#[test]
+fn keep_theirs_conflict() {
+    let mut ctx = setup_conflict();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjRt")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(read_new_file(&ctx), "hey\n");
+}
+
+#[test]
+fn keep_both_file() {
+    let mut ctx = setup_conflict();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjRb")).unwrap();
+    assert_eq!(read_new_file(&ctx), "hi\nhey\n");
+}
@@ -0,0 +1,61 @@
+use super::*;
+use crossterm::event::Event;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "branch", "feature"]);
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+    commit(ctx.dir.path(), "second-file", "");
+    ctx
+}
+
+#[test]
+fn rev_prompt_filters_as_typed() {
+    snapshot!(setup(), "loman");
+}
+
+#[test]
+fn rev_prompt_fuzzy_match() {
+    snapshot!(setup(), "loftr");
+}
+
+#[test]
+fn rev_prompt_matches_commit_summary() {
+    snapshot!(setup(), "losecond");
+}
+
+#[test]
+fn rev_prompt_tab_completes() {
+    snapshot!(setup(), "loma<tab><tab>");
+}
+
+#[test]
+fn rev_prompt_tab_completes_and_submits() {
+    snapshot!(setup(), "lofe<tab><enter>");
+}
+
+#[test]
+fn rev_prompt_history() {
+    snapshot!(setup(), "lov1.0<enter>qlofeature<enter>qlo<up><up>");
+}
+
+#[test]
+fn rev_prompt_history_newer() {
+    snapshot!(setup(), "lov1.0<enter>qlofeature<enter>qlo<up><up><down>");
+}
+
+#[test]
+fn rev_prompt_arrows_move_through_matches() {
+    snapshot!(setup(), "loma<down><down><down><up>");
+}
+
+#[test]
+fn rev_prompt_paste() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("lo")).unwrap();
+    state
+        .update(&mut ctx.term, &[Event::Paste("feat\n".into())])
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
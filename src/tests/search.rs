@@ -0,0 +1,40 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hello\n");
+    commit(ctx.dir.path(), "second-file", "hello\nworld\n");
+    fs::write(ctx.dir.child("first-file"), "hello\nthere\n").unwrap();
+    fs::write(ctx.dir.child("second-file"), "hello\nworld!\n").unwrap();
+    ctx
+}
+
+#[test]
+fn search_incremental() {
+    snapshot!(setup(), "/seco");
+}
+
+#[test]
+fn search_submit() {
+    snapshot!(setup(), "/file<enter>");
+}
+
+#[test]
+fn search_next() {
+    snapshot!(setup(), "/file<enter>nn");
+}
+
+#[test]
+fn search_previous_wraps_around() {
+    snapshot!(setup(), "/file<enter>N");
+}
+
+#[test]
+fn search_expands_collapsed_section() {
+    snapshot!(setup(), "/world!<enter>");
+}
+
+#[test]
+fn search_no_match() {
+    snapshot!(setup(), "/nothing<enter>");
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn shell_menu() {
+    snapshot!(TestContext::setup_clone(), "!");
+}
+
+#[test]
+fn shell_command_prompt() {
+    snapshot!(TestContext::setup_clone(), "!!");
+}
+
+#[test]
+fn shell_command() {
+    snapshot!(TestContext::setup_clone(), "!!touch new-file<enter>");
+}
+
+#[test]
+fn shell_command_empty() {
+    snapshot!(TestContext::setup_clone(), "!!<enter>");
+}
+
+#[test]
+fn shell_command_large_output() {
+    snapshot!(
+        TestContext::setup_clone(),
+        "!!seq 1 100000 | tr -d '\\n'; echo<enter>"
+    );
+}
+
+#[test]
+fn shell_command_without_input() {
+    snapshot!(TestContext::setup_clone(), "!!cat; echo read<enter>");
+}
+
+#[test]
+fn shell_command_non_utf8_output() {
+    snapshot!(TestContext::setup_clone(), "!!printf 'a\\377b'<enter>");
+}
@@ -0,0 +1,92 @@
+use super::*;
+use crate::{
+    git::log_filter::LogFilter,
+    items::{self, LogOptions},
+};
+use git2::Repository;
+use ratatui::style::Style;
+use temp_dir::TempDir;
+
+/// Signs commits with a new ssh key, which is trusted for verifying them.
+fn setup_signing(ctx: &TestContext) -> TempDir {
+    let key_dir = TempDir::new().unwrap();
+    let key = key_dir.path().join("key");
+    let allowed_signers = key_dir.path().join("allowed_signers");
+
+    run(
+        key_dir.path(),
+        &["ssh-keygen", "-q", "-t", "ed25519", "-N", "", "-f", "key"],
+    );
+    let public_key = fs::read_to_string(key.with_extension("pub")).unwrap();
+    fs::write(
+        &allowed_signers,
+        format!("ci@example.com namespaces=\"git\" {}", public_key),
+    )
+    .unwrap();
+
+    for (name, value) in [
+        ("gpg.format", "ssh"),
+        ("user.signingkey", key.to_str().unwrap()),
+        (
+            "gpg.ssh.allowedSignersFile",
+            allowed_signers.to_str().unwrap(),
+        ),
+    ] {
+        run(ctx.dir.path(), &["git", "config", name, value]);
+    }
+
+    key_dir
+}
+
+#[test]
+fn commit_menu_gpg_sign() {
+    snapshot!(TestContext::setup_clone(), "c-S");
+}
+
+#[test]
+fn tag_menu_sign() {
+    snapshot!(TestContext::setup_clone(), "t-s");
+}
+
+#[test]
+fn show_signed_commit() {
+    let mut ctx = TestContext::setup_clone();
+    let _key_dir = setup_signing(&ctx);
+    run(
+        ctx.dir.path(),
+        &["git", "commit", "--allow-empty", "-S", "-m", "signed"],
+    );
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("ll<enter>")).unwrap();
+    assert!(ctx
+        .redact_buffer()
+        .contains("Signature: good, by ci@example.com"));
+}
+
+#[test]
+fn log_signatures() {
+    let mut ctx = TestContext::setup_clone();
+    let _key_dir = setup_signing(&ctx);
+    run(
+        ctx.dir.path(),
+        &["git", "commit", "--allow-empty", "-S", "-m", "signed"],
+    );
+
+    let config = ctx.config().clone();
+    let repo = Repository::open(ctx.dir.path()).unwrap();
+    let options = LogOptions {
+        graph: false,
+        signatures: true,
+    };
+    let log = items::log_items(
+        items::log_lines(&config, &repo, 10, None, &LogFilter::default(), options).unwrap(),
+    );
+
+    let hash_style = |item: &items::Item| item.display.spans[0].style;
+    assert_eq!(
+        hash_style(&log[0]),
+        Style::from(&config.style.signature_good)
+    );
+    assert_eq!(hash_style(&log[1]), Style::from(&config.style.hash));
+}
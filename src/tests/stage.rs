@@ -18,7 +18,29 @@ fn stage_all_unstaged() {
 
     fs::write(ctx.dir.child("firstfile"), "blahonga\n").unwrap();
     fs::write(ctx.dir.child("secondfile"), "blahonga\n").unwrap();
-    snapshot!(ctx, "js");
+    snapshot!(ctx, "jjs");
+}
+
+#[test]
+fn stage_file_keeps_fold_and_selects_next() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    commit(ctx.dir.path(), "secondfile", "testing\ntesttest\n");
+
+    fs::write(ctx.dir.child("firstfile"), "blahonga\n").unwrap();
+    fs::write(ctx.dir.child("secondfile"), "blahonga\n").unwrap();
+    snapshot!(ctx, "jjjs");
+}
+
+#[test]
+fn partially_staged_file_folds_apart() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "a\n");
+    fs::write(ctx.dir.child("file"), "b\n").expect("error writing to file");
+    run(ctx.dir.path(), &["git", "add", "file"]);
+    fs::write(ctx.dir.child("file"), "c\n").expect("error writing to file");
+
+    snapshot!(ctx, "jjjj<tab>");
 }
 
 #[test]
@@ -34,7 +56,7 @@ fn stage_removed_line() {
     let ctx = TestContext::setup_init();
     commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
     fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
-    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j>s");
+    snapshot!(ctx, "jjj<tab><ctrl+j><ctrl+j>s");
 }
 
 #[test]
@@ -43,7 +65,7 @@ fn stage_added_line() {
     commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
     fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
 
-    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>s");
+    snapshot!(ctx, "jjj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>s");
 }
 
 #[test]
@@ -52,5 +74,84 @@ fn stage_changes_crlf() {
     commit(ctx.dir.path(), "testfile", "testing\r\ntesttest\r\n");
     fs::write(ctx.dir.child("testfile"), "test\r\ntesttest\r\n").expect("error writing to file");
 
-    snapshot!(ctx, "jj<tab>");
+    snapshot!(ctx, "jjj<tab>");
+}
+
+#[test]
+fn mark_region() {
+    let ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["touch", "file-a", "file-b", "file-c"]);
+    snapshot!(ctx, "jjvj");
+}
+
+#[test]
+fn stage_region_of_files() {
+    let ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["touch", "file-a", "file-b", "file-c"]);
+    snapshot!(ctx, "jjvjs");
+}
+
+#[test]
+fn stage_region_of_lines() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+    snapshot!(ctx, "jjj<tab><ctrl+j><ctrl+j><ctrl+j>v<ctrl+j>s");
+}
+
+fn setup_tracked_and_untracked() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked", "testing\n");
+    fs::write(ctx.dir.child("tracked"), "changed\n").unwrap();
+    run(ctx.dir.path(), &["touch", "untracked"]);
+    ctx
+}
+
+#[test]
+fn stage_all_tracked() {
+    snapshot!(setup_tracked_and_untracked(), "S");
+}
+
+#[test]
+fn stage_all_prompt() {
+    snapshot!(setup_tracked_and_untracked(), "<alt+s>");
+}
+
+#[test]
+fn stage_all_including_untracked() {
+    snapshot!(setup_tracked_and_untracked(), "<alt+s>y");
+}
+
+#[test]
+fn stage_all_without_untracked() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked", "testing\n");
+    fs::write(ctx.dir.child("tracked"), "changed\n").unwrap();
+    snapshot!(ctx, "<alt+s>");
+}
+
+#[test]
+fn intent_to_add() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    snapshot!(ctx, "jjjjI");
+}
+
+fn setup_moved_file() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "old-name", "hello\nworld\n");
+    fs::rename(ctx.dir.child("old-name"), ctx.dir.child("new-name")).unwrap();
+    ctx
+}
+
+#[test]
+fn moved_file() {
+    let mut ctx = setup_moved_file();
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn stage_moved_file() {
+    snapshot!(setup_moved_file(), "jjjjs");
 }
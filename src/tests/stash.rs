@@ -45,7 +45,7 @@ pub(crate) fn stash_working_tree() {
 
 #[test]
 pub(crate) fn stash_working_tree_when_everything_is_staged() {
-    snapshot!(setup(), "jszw");
+    snapshot!(setup(), "jjjszw");
 }
 
 #[test]
@@ -130,3 +130,18 @@ pub(crate) fn stash_drop() {
 pub(crate) fn stash_drop_default() {
     snapshot!(setup_two_stashes(), "zk<enter>");
 }
+
+#[test]
+pub(crate) fn discard_stash_at_point_prompt() {
+    snapshot!(setup_two_stashes(), "jjjjK");
+}
+
+#[test]
+pub(crate) fn discard_stash_at_point() {
+    snapshot!(setup_two_stashes(), "jjjjKy");
+}
+
+#[test]
+fn stash_pop_picker() {
+    snapshot!(setup_two_stashes(), "zpfile-two");
+}
@@ -0,0 +1,99 @@
+use super::*;
+use std::process::Command;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    // Relative to the remote of the superproject, keeps .gitmodules the same across runs
+    run(ctx.dir.path(), &["git", "submodule", "add", "./", "sub"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add submodule"]);
+    ctx
+}
+
+#[test]
+fn submodule_status() {
+    snapshot!(setup(), "");
+}
+
+#[test]
+fn submodule_ahead_and_modified() {
+    let ctx = setup();
+    let sub = ctx.dir.path().join("sub");
+    commit(&sub, "new-file", "hello");
+    fs::write(sub.join("untracked-file"), "").unwrap();
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn submodule_not_checked_out() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "submodule", "deinit", "sub"]);
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn submodule_menu() {
+    snapshot!(setup(), "jjjjo");
+}
+
+#[test]
+fn submodule_init() {
+    let mut ctx = setup();
+    run(ctx.dir.path(), &["git", "submodule", "deinit", "sub"]);
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjoi")).unwrap();
+
+    let url = Command::new("git")
+        .args(["config", "submodule.sub.url"])
+        .current_dir(ctx.dir.path())
+        .output()
+        .unwrap()
+        .stdout;
+    assert!(!url.is_empty());
+}
+
+#[test]
+fn submodule_update_init() {
+    let mut ctx = setup();
+    run(ctx.dir.path(), &["git", "submodule", "deinit", "sub"]);
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjo-iu")).unwrap();
+    // Clears the command output, it contains the temp dir path
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn submodule_sync() {
+    snapshot!(setup(), "os");
+}
+
+#[test]
+fn enter_submodule() {
+    let ctx = setup();
+    commit(&ctx.dir.path().join("sub"), "new-file", "hello");
+    snapshot!(ctx, "jjjjoo");
+}
+
+#[test]
+fn enter_submodule_with_show() {
+    snapshot!(setup(), "jjjj<enter>");
+}
+
+#[test]
+fn enter_submodule_not_checked_out() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "submodule", "deinit", "sub"]);
+    snapshot!(ctx, "jjjjoo");
+}
+
+#[test]
+fn leave_submodule() {
+    snapshot!(setup(), "jjjjooqos");
+}
+
+#[test]
+fn show_refs_stays_in_submodule() {
+    snapshot!(setup(), "jjjjooYq");
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+    ctx
+}
+
+#[test]
+fn tag_menu() {
+    snapshot!(TestContext::setup_clone(), "t");
+}
+
+#[test]
+fn create_tag_prompt() {
+    snapshot!(TestContext::setup_clone(), "tt");
+}
+
+#[test]
+fn create_tag_at_prompt() {
+    snapshot!(TestContext::setup_clone(), "llttv2.0<enter>");
+}
+
+#[test]
+fn create_tag() {
+    snapshot!(TestContext::setup_clone(), "ttv2.0<enter><enter>");
+}
+
+#[test]
+fn create_tag_at_selected_commit() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "lljttv2.0<enter><enter>");
+}
+
+#[test]
+fn delete_tag_prompt() {
+    snapshot!(setup(), "Yjjjjjjtk");
+}
+
+#[test]
+fn delete_tag() {
+    snapshot!(setup(), "Yjjjjjjtk<enter>");
+}
+
+#[test]
+fn discard_tag() {
+    snapshot!(setup(), "YjjjjjjKy");
+}
+
+#[test]
+fn push_tag() {
+    snapshot!(setup(), "tpv1.0<enter>");
+}
+
+#[test]
+fn rebase_interactive_from_tag() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "");
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+    commit(ctx.dir.path(), "second-file", "");
+    snapshot!(ctx, "Yjjjjjjri");
+}
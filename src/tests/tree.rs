@@ -0,0 +1,40 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    fs::create_dir(ctx.dir.path().join("src")).unwrap();
+    commit(ctx.dir.path(), "src/main.rs", "fn main() {}\n");
+    commit(ctx.dir.path(), "README", "Hello\n");
+    fs::write(ctx.dir.path().join("README"), "Hello, world\n").unwrap();
+    ctx
+}
+
+#[test]
+fn browse_tree() {
+    snapshot!(setup(), "llT");
+}
+
+#[test]
+fn browse_tree_expand_dir() {
+    snapshot!(setup(), "llTj<tab>");
+}
+
+#[test]
+fn browse_tree_show_file() {
+    snapshot!(setup(), "llTjj<enter>");
+}
+
+#[test]
+fn browse_tree_diff_worktree() {
+    snapshot!(setup(), "llTjjdw");
+}
+
+#[test]
+fn browse_tree_checkout_file_prompt() {
+    snapshot!(setup(), "llTjjbf");
+}
+
+#[test]
+fn browse_tree_checkout_file() {
+    snapshot!(setup(), "llTjjbfyqq");
+}
@@ -14,7 +14,7 @@ fn unstage_removed_line() {
     commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
     fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
     run(ctx.dir.path(), &["git", "add", "."]);
-    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j>u");
+    snapshot!(ctx, "jjj<tab><ctrl+j><ctrl+j>u");
 }
 
 #[test]
@@ -23,5 +23,32 @@ fn unstage_added_line() {
     commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
     fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
     run(ctx.dir.path(), &["git", "add", "."]);
-    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>u");
+    snapshot!(ctx, "jjj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>u");
+}
+
+#[test]
+fn unstage_region_of_lines() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "jjj<tab><ctrl+j><ctrl+j>v<ctrl+j>u");
+}
+
+#[test]
+fn unstage_all() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked", "testing\n");
+    fs::write(ctx.dir.child("tracked"), "changed\n").unwrap();
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "U");
+}
+
+#[test]
+fn unstage_renamed_file() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "old-name", "hello\nworld\n");
+    run(ctx.dir.path(), &["git", "mv", "old-name", "new-name"]);
+    snapshot!(ctx, "jjjju");
 }
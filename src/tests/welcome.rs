@@ -0,0 +1,85 @@
+use super::*;
+use crossterm::event::Event;
+use temp_dir::TempDir;
+
+/// The directory's path varies in length, so the padding of lines is left out as well.
+fn redact(ctx: &TestContext, dir: &TempDir) -> String {
+    ctx.redact_buffer()
+        .replace(dir.path().to_str().unwrap(), "<dir>")
+        .lines()
+        .map(|line| match line.strip_suffix('|') {
+            Some(line) => format!("{}|\n", line.trim_end()),
+            None => format!("{}\n", line),
+        })
+        .collect()
+}
+
+#[test]
+fn welcome_screen() {
+    let mut ctx = TestContext::setup_clone();
+    let dir = TempDir::new().unwrap();
+    ctx.init_welcome(dir.path().to_path_buf());
+    insta::assert_snapshot!(redact(&ctx, &dir));
+}
+
+#[test]
+fn init_here() {
+    let mut ctx = TestContext::setup_clone();
+    let dir = TempDir::new().unwrap();
+    let mut welcome = ctx.init_welcome(dir.path().to_path_buf());
+
+    let repo = welcome.update(&mut ctx.term, &keys("i")).unwrap();
+    assert_eq!(repo.as_deref(), Some(dir.path()));
+    assert!(dir.child(".git").exists());
+}
+
+#[test]
+fn clone_prompt() {
+    let mut ctx = TestContext::setup_clone();
+    let dir = TempDir::new().unwrap();
+    let mut welcome = ctx.init_welcome(dir.path().to_path_buf());
+
+    welcome.update(&mut ctx.term, &keys("c")).unwrap();
+    insta::assert_snapshot!(redact(&ctx, &dir));
+}
+
+#[test]
+fn clone_into_default_dir() {
+    let mut ctx = TestContext::setup_clone();
+    let dir = TempDir::new().unwrap();
+    let mut welcome = ctx.init_welcome(dir.path().to_path_buf());
+
+    let url = ctx.remote_dir.path().to_str().unwrap().to_string();
+    welcome.update(&mut ctx.term, &keys("c")).unwrap();
+    welcome.update(&mut ctx.term, &[Event::Paste(url)]).unwrap();
+    let repo = welcome
+        .update(&mut ctx.term, &keys("<enter><enter>"))
+        .unwrap();
+
+    let into = dir.path().join(ctx.remote_dir.path().file_name().unwrap());
+    assert_eq!(repo, Some(into.clone()));
+    assert!(into.join(".git").exists());
+}
+
+#[test]
+fn clone_fails() {
+    let mut ctx = TestContext::setup_clone();
+    let dir = TempDir::new().unwrap();
+    let mut welcome = ctx.init_welcome(dir.path().to_path_buf());
+
+    let repo = welcome
+        .update(&mut ctx.term, &keys("c/nonexistent<enter><enter>"))
+        .unwrap();
+    assert_eq!(repo, None);
+    insta::assert_snapshot!(redact(&ctx, &dir));
+}
+
+#[test]
+fn quit() {
+    let mut ctx = TestContext::setup_clone();
+    let dir = TempDir::new().unwrap();
+    let mut welcome = ctx.init_welcome(dir.path().to_path_buf());
+
+    welcome.update(&mut ctx.term, &keys("q")).unwrap();
+    assert!(welcome.quit);
+}
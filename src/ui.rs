@@ -1,13 +1,25 @@
+#[cfg(feature = "forge")]
+use crate::ci::{self, CiStatuses};
+use crate::picker::CandidateKind;
+use crate::screen::commit_msg;
 use crate::state::State;
+use itertools::Itertools;
 use ratatui::prelude::*;
 use ratatui::style::Stylize;
 use ratatui::widgets::*;
 use ratatui::Frame;
+use std::time::Instant;
 use tui_prompts::State as _;
 use tui_prompts::TextPrompt;
+use unicode_width::UnicodeWidthStr;
 
 mod menu;
 
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const SPINNER_FRAME_MILLIS: u128 = 100;
+/// How much of the log is shown while a command is running, unless expanded.
+const RUNNING_CMD_OUTPUT_LINES: usize = 5;
+
 pub(crate) struct SizedWidget<W> {
     height: u16,
     widget: W,
@@ -28,8 +40,24 @@ impl<W: StatefulWidget> StatefulWidget for SizedWidget<W> {
 }
 
 pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
-    let maybe_log = if !state.current_cmd_log.is_empty() {
-        let text: Text = state.current_cmd_log.format_log(&state.config);
+    // In place of the log it comes from, until dismissed
+    let maybe_log = if let Some(hook_output) = &state.hook_output {
+        let text = Text::raw(hook_output.output.clone());
+        let title = Line::styled(
+            format!("Failed in the {}", hook_output.title),
+            Style::new().red().bold(),
+        );
+
+        Some(SizedWidget {
+            height: 1 + text.lines.len() as u16,
+            widget: Paragraph::new(text).block(popup_block().title(title)),
+        })
+    } else if !state.current_cmd_log.is_empty() {
+        let mut text: Text = state.current_cmd_log.format_log(&state.config);
+        if state.pending_cmd.is_some() && !state.cmd_output_expanded {
+            let hidden = text.lines.len().saturating_sub(RUNNING_CMD_OUTPUT_LINES);
+            text.lines.drain(..hidden);
+        }
 
         Some(SizedWidget {
             widget: Paragraph::new(text.clone()).block(popup_block()),
@@ -39,13 +67,17 @@ pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
         None
     };
 
+    let picker_matches = picker_matches(state);
     let maybe_prompt = state.prompt.data.as_ref().map(|prompt_data| SizedWidget {
-        height: 2,
+        height: 2 + picker_matches.lines.len() as u16,
         widget: TextPrompt::new(prompt_data.prompt_text.clone()).with_block(popup_block()),
     });
 
+    let maybe_hints = menu::key_hints(state);
+
+    // The hints take the place of the menu until the keys are complete
     let maybe_menu = state.pending_menu.as_ref().and_then(|menu| {
-        if menu.is_hidden {
+        if menu.is_hidden || maybe_hints.is_some() {
             None
         } else {
             Some(menu::MenuWidget::new(
@@ -63,27 +95,272 @@ pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
         [
             Constraint::Min(1),
             widget_height(&maybe_prompt),
+            widget_height(&maybe_hints),
             widget_height(&maybe_menu),
             widget_height(&maybe_log),
         ],
     )
     .split(frame.area());
 
-    frame.render_widget(state.screens.last().unwrap(), layout[0]);
+    maybe_render(maybe_hints, frame, layout[2]);
+    maybe_render(maybe_menu, frame, layout[3]);
+    maybe_render(maybe_log, frame, layout[4]);
 
-    maybe_render(maybe_menu, frame, layout[2]);
-    maybe_render(maybe_log, frame, layout[3]);
+    let screen_area = match &state.commit_msg {
+        Some(commit_msg) if state.screen().name() == Some(commit_msg::NAME) => {
+            let [editor_area, screen_area] = Layout::vertical([
+                Constraint::Length(commit_msg.height(layout[0].height)),
+                Constraint::Min(1),
+            ])
+            .areas(layout[0]);
+
+            frame.render_widget(commit_msg, editor_area);
+            if !state.prompt.state.is_focused() {
+                frame.set_cursor_position(commit_msg.cursor(editor_area));
+            }
+            screen_area
+        }
+        _ => layout[0],
+    };
+
+    let (_, _, lines) = state.screen().scroll_position();
+    let scrollable = state.config.general.scrollbar.enabled
+        && screen_area.width >= 2
+        && screen_area.height >= 2
+        && lines > screen_area.height as usize;
+
+    // Leaving the last column to the scrollbar, and the last row to the position of the cursor
+    let (scroll_area, position_area) = if scrollable {
+        let [scroll_area, position_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(screen_area);
+        (scroll_area, Some(position_area))
+    } else {
+        (screen_area, None)
+    };
+    let content_area = if scrollable {
+        Rect {
+            width: scroll_area.width - 1,
+            ..scroll_area
+        }
+    } else {
+        scroll_area
+    };
+
+    // Before it's drawn, as a row taken for the position could leave the cursor below it
+    state
+        .screens
+        .last_mut()
+        .unwrap()
+        .resize(scroll_area.as_size());
+    frame.render_widget(state.screens.last().unwrap(), content_area);
+
+    if let Some(started) = state.screens.last().unwrap().refreshing_since() {
+        render_spinner(frame, screen_area, started);
+    }
+
+    let status_area = match position_area {
+        Some(position_area) => render_scroll_position(frame, scroll_area, position_area, state),
+        None => screen_area,
+    };
+
+    #[cfg(feature = "forge")]
+    if let Some(ci_statuses) = &state.ci_statuses {
+        render_ci_statuses(frame, content_area, state, ci_statuses);
+    }
+
+    if let Some(fetched) = &state.last_fetch {
+        render_fetch_status(frame, status_area, &fetched.describe());
+    }
 
     if let Some(prompt) = maybe_prompt {
-        frame.render_stateful_widget(prompt, layout[1], &mut state.prompt.state);
+        let [prompt_area, matches_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(layout[1]);
+        frame.render_stateful_widget(prompt, prompt_area, &mut state.prompt.state);
+        frame.render_widget(picker_matches, matches_area);
         let (cx, cy) = state.prompt.state.cursor();
         frame.set_cursor_position((cx, cy));
     }
 
-    state.screens.last_mut().unwrap().size = layout[0].as_size();
+    state.images = state.screens.last().unwrap().images(content_area);
+}
+
+/// The candidates matching what's been typed into a prompt, with the matched characters highlighted.
+fn picker_matches(state: &State) -> Text<'static> {
+    let Some(picker) = &state.prompt.picker else {
+        return Text::default();
+    };
+
+    let style = &state.config.style;
+    picker
+        .shown_matches()
+        .map(|(candidate, matched, selected)| {
+            let value_style = match candidate.kind {
+                CandidateKind::Branch => (&style.branch).into(),
+                CandidateKind::Remote => (&style.remote).into(),
+                CandidateKind::Tag => (&style.tag).into(),
+                CandidateKind::Commit | CandidateKind::Stash => (&style.hash).into(),
+                CandidateKind::Item | CandidateKind::Author => Style::new(),
+                CandidateKind::Command => (&style.command).into(),
+            };
+
+            let no_positions = &[][..];
+            let (value_positions, description_positions) = if matched.in_description {
+                (no_positions, &matched.positions[..])
+            } else {
+                (&matched.positions[..], no_positions)
+            };
+
+            let mut spans = highlight_matched(
+                &candidate.value,
+                value_positions,
+                value_style,
+                (&style.search_match).into(),
+            );
+            if !candidate.description.is_empty() {
+                spans.push(Span::raw(" "));
+                spans.extend(highlight_matched(
+                    &candidate.description,
+                    description_positions,
+                    Style::new(),
+                    (&style.search_match).into(),
+                ));
+            }
+
+            let line = Line::from(spans);
+            if selected {
+                line.patch_style(&style.selection_line)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn highlight_matched(
+    text: &str,
+    positions: &[usize],
+    style: Style,
+    matched_style: Style,
+) -> Vec<Span<'static>> {
+    text.chars()
+        .enumerate()
+        .chunk_by(|(i, _)| positions.contains(i))
+        .into_iter()
+        .map(|(matched, chars)| {
+            let text = chars.map(|(_, c)| c).collect::<String>();
+            if matched {
+                Span::styled(text, style.patch(matched_style))
+            } else {
+                Span::styled(text, style)
+            }
+        })
+        .collect()
+}
+
+/// Shown in the top right corner while the screen is refreshing in the background.
+fn render_spinner(frame: &mut Frame, area: Rect, started: Instant) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+
+    let frame_index = started.elapsed().as_millis() / SPINNER_FRAME_MILLIS;
+    let symbol = SPINNER_FRAMES[frame_index as usize % SPINNER_FRAMES.len()];
+    frame
+        .buffer_mut()
+        .set_string(area.right() - 1, area.top(), symbol, Style::new().dim());
+}
+
+/// A scrollbar along the right edge of `area` and the line of the cursor at the right of the row
+/// below it. Returns what's left of that row for the fetch status.
+fn render_scroll_position(
+    frame: &mut Frame,
+    area: Rect,
+    position_area: Rect,
+    state: &State,
+) -> Rect {
+    let (scroll, cursor, lines) = state.screen().scroll_position();
+    let height = area.height as usize;
+
+    // One position per line the screen can be scrolled to
+    let mut scrollbar_state = ScrollbarState::new(lines - height + 1)
+        .position(scroll)
+        .viewport_content_length(height);
+    frame.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_symbol(Some(symbols::line::VERTICAL))
+            .style(Style::new().dim()),
+        area,
+        &mut scrollbar_state,
+    );
+
+    let position = format!(
+        "line {} of {} / {}%",
+        cursor + 1,
+        lines,
+        (cursor + 1) * 100 / lines
+    );
+    let width = (position.width() as u16).min(position_area.width);
+    frame.buffer_mut().set_stringn(
+        position_area.right() - width,
+        position_area.y,
+        &position,
+        width as usize,
+        Style::new().dim(),
+    );
+
+    Rect {
+        width: position_area.width.saturating_sub(width + 1),
+        ..position_area
+    }
+}
+
+/// Marks the commits and branches on screen by their CI status, after the rest of their line.
+#[cfg(feature = "forge")]
+fn render_ci_statuses(frame: &mut Frame, area: Rect, state: &State, ci_statuses: &CiStatuses) {
+    if area.width < 2 {
+        return;
+    }
+
+    let items = state
+        .screen()
+        .visible_items(area.as_size())
+        .collect::<Vec<_>>();
+    for (row, item) in items.into_iter().enumerate() {
+        let Some(oid) = ci::target_commit(&state.repo, item.target_data.as_ref()) else {
+            continue;
+        };
+        let Some(status) = ci_statuses.get(oid).and_then(|checks| checks.status) else {
+            continue;
+        };
+
+        let (glyph, style) = status.glyph(&state.config.style);
+        let x = (area.x + item.display.width() as u16 + 2).min(area.right() - 1);
+        frame.buffer_mut()[(x, area.y + row as u16)]
+            .set_symbol(glyph)
+            .set_style(style);
+    }
+}
+
+/// Shown in the bottom right corner when fetching in the background.
+fn render_fetch_status(frame: &mut Frame, area: Rect, status: &str) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+
+    let width = (status.width() as u16).min(area.width);
+    frame.buffer_mut().set_stringn(
+        area.right() - width,
+        area.bottom() - 1,
+        status,
+        width as usize,
+        Style::new().dim(),
+    );
 }
 
-fn popup_block() -> Block<'static> {
+pub(crate) fn popup_block() -> Block<'static> {
     Block::new()
         .borders(Borders::TOP)
         .border_style(Style::new().dim())
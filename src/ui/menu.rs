@@ -1,6 +1,7 @@
 use super::SizedWidget;
 use crate::{
-    bindings::Bindings, config::Config, items::Item, menu::PendingMenu, ops::Op, state::State,
+    bindings::Bindings, config::Config, items::Item, key_parser, menu::PendingMenu, ops::Op,
+    state::State,
 };
 use itertools::Itertools;
 use ratatui::{
@@ -8,8 +9,9 @@ use ratatui::{
     layout::{Constraint, Rect},
     style::Style,
     text::{Line, Span},
-    widgets::{Row, Table, Widget},
+    widgets::{Paragraph, Row, Table, Widget},
 };
+use std::iter;
 
 pub(crate) struct MenuWidget<'a> {
     table: Table<'a>,
@@ -30,6 +32,7 @@ impl<'a> MenuWidget<'a> {
         let non_target_binds = bindings
             .list(&pending.menu)
             .filter(|keybind| !keybind.op.clone().implementation().is_target_op())
+            .filter(|keybind| keybind.op.clone().implementation().is_available(state))
             .collect::<Vec<_>>();
 
         let mut pending_binds_column = vec![];
@@ -161,6 +164,71 @@ impl<'a> MenuWidget<'a> {
     }
 }
 
+/// Lists how the keys pressed so far of a binding can be continued, and what each would do.
+/// Only shown while such keys are pending.
+pub(crate) fn key_hints(state: &State) -> Option<SizedWidget<Paragraph<'static>>> {
+    let pending_keys = state.pending_keys();
+    if pending_keys.is_empty() {
+        return None;
+    }
+
+    let style = &state.config.style;
+    let menu = state.key_menu();
+    let target = state.screen().get_selected_item().target_data.as_ref();
+
+    let binds = state
+        .bindings
+        .match_bindings(&menu, pending_keys)
+        .collect::<Vec<_>>();
+
+    // What's been typed, as it's written in the config
+    let typed = key_parser::split_keys(&binds.first()?.raw)[..pending_keys.len()].concat();
+
+    let hints = binds
+        .iter()
+        .filter_map(|bind| {
+            let description = match &bind.op {
+                Op::ToggleArg(name) => {
+                    let arg = state.pending_menu.as_ref()?.args.get(name.as_str())?;
+                    format!("{} ({})", arg.display, arg.arg)
+                }
+                Op::OpenMenu(menu) => format!("{}", menu),
+                op => {
+                    let implementation = op.clone().implementation();
+                    let valid = if implementation.is_target_op() {
+                        implementation.get_action(target).is_some()
+                    } else {
+                        implementation.is_available(state)
+                    };
+                    if !valid {
+                        return None;
+                    }
+
+                    implementation.display(state)
+                }
+            };
+
+            let remaining = key_parser::split_keys(&bind.raw)
+                .split_off(pending_keys.len())
+                .concat();
+
+            Some(Line::from(vec![
+                Span::styled(remaining, &style.hotkey),
+                Span::raw(format!(" {}", description)),
+            ]))
+        })
+        .collect::<Vec<_>>();
+
+    let lines = iter::once(Line::styled(typed, &style.command))
+        .chain(hints)
+        .collect::<Vec<_>>();
+
+    Some(SizedWidget {
+        height: 1 + lines.len() as u16,
+        widget: Paragraph::new(lines).block(super::popup_block()),
+    })
+}
+
 fn col_width(column: &[Line<'_>]) -> Constraint {
     Constraint::Length(column.iter().map(|line| line.width()).max().unwrap_or(0) as u16)
 }
@@ -0,0 +1,323 @@
+use crate::{events::Waker, git, git2_opts, screen};
+use git2::Repository;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Files of the git dir that what's shown is read from. Objects and logs change along with them.
+const WATCHED_GIT_FILES: [&str; 7] = [
+    "HEAD",
+    "index",
+    "packed-refs",
+    "MERGE_HEAD",
+    "REVERT_HEAD",
+    "CHERRY_PICK_HEAD",
+    "BISECT_START",
+];
+
+/// Directories of the git dir whose files are watched, refs and the state of a rebase,
+/// a `git am` or a series of cherry-picks or reverts.
+const WATCHED_GIT_DIRS: [&str; 4] = ["refs", "rebase-merge", "rebase-apply", "sequencer"];
+
+/// How long changes have to stop for before they're looked at, e.g. while git writes several files.
+const SETTLE: Duration = Duration::from_millis(100);
+
+/// Notices changes made outside of gitu, e.g. by running git in another terminal.
+/// The work tree and git dir are watched for changes on a background thread, which once things
+/// have settled checks whether what's shown could have changed, going by a `fingerprint`.
+/// Should the file system not be watchable, they're polled every `interval` instead,
+/// and a change is only reported once things have settled for an interval.
+/// `waker` is woken on each change reported.
+pub(crate) struct Watcher {
+    changed: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Watcher {
+    pub(crate) fn spawn(
+        git_dir: PathBuf,
+        workdir: Option<PathBuf>,
+        interval: Duration,
+        waker: Waker,
+    ) -> Self {
+        let changed = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let watcher = Self {
+            changed: Arc::clone(&changed),
+            stop: Arc::clone(&stop),
+        };
+
+        thread::spawn(move || {
+            let Ok(repo) = screen::open_repo(&git_dir, workdir.as_deref()) else {
+                return;
+            };
+
+            let report = |reported: &mut u64, current: u64| {
+                if current != *reported {
+                    *reported = current;
+                    changed.store(true, Ordering::Relaxed);
+                    waker.wake();
+                }
+            };
+
+            let mut reported = fingerprint(&repo);
+            let (sender, events) = mpsc::channel();
+            let notifier = watch(&repo, sender).inspect_err(|err| {
+                log::warn!("Couldn't watch the repository, polling it: {}", err)
+            });
+
+            if notifier.is_ok() {
+                while !stop.load(Ordering::Relaxed) {
+                    // Wakes up every interval to see whether to stop
+                    match events.recv_timeout(interval) {
+                        Ok(event) if is_relevant(&event, repo.path()) => (),
+                        Ok(_) | Err(RecvTimeoutError::Timeout) => continue,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+
+                    // While changes keep coming, like during a build, they're looked at every interval
+                    let first = Instant::now();
+                    while first.elapsed() < interval {
+                        match events.recv_timeout(SETTLE) {
+                            Ok(_) => continue,
+                            Err(RecvTimeoutError::Timeout) => break,
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+
+                    report(&mut reported, fingerprint(&repo));
+                }
+                return;
+            }
+
+            let mut previous = reported;
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(interval);
+
+                let current = fingerprint(&repo);
+                if current == previous {
+                    report(&mut reported, current);
+                }
+                previous = current;
+            }
+        });
+
+        watcher
+    }
+
+    /// Whether anything changed since the last call.
+    pub(crate) fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Watches the work tree, and the git dir unless it's within it, sending their changes to `sender`.
+/// Watching stops once the watcher returned is dropped.
+fn watch(
+    repo: &Repository,
+    sender: Sender<notify::Result<notify::Event>>,
+) -> notify::Result<RecommendedWatcher> {
+    let mut notifier = notify::recommended_watcher(sender)?;
+    let git_dir = repo.path();
+
+    match repo.workdir() {
+        Some(workdir) => {
+            notifier.watch(workdir, RecursiveMode::Recursive)?;
+            if !git_dir.starts_with(workdir) {
+                notifier.watch(git_dir, RecursiveMode::Recursive)?;
+            }
+        }
+        None => notifier.watch(git_dir, RecursiveMode::Recursive)?,
+    }
+
+    Ok(notifier)
+}
+
+/// Whether `event` could change what's shown. Files are only read, or written within the git dir
+/// other than to its refs and state files, like objects and logs, don't.
+fn is_relevant(event: &notify::Result<notify::Event>, git_dir: &Path) -> bool {
+    let Ok(event) = event else {
+        return true;
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+
+    event.paths.iter().any(|path| {
+        let Ok(in_git_dir) = path.strip_prefix(git_dir) else {
+            return true;
+        };
+        in_git_dir.components().next().is_some_and(|first| {
+            let first = first.as_os_str();
+            WATCHED_GIT_FILES.iter().any(|file| first == *file)
+                || WATCHED_GIT_DIRS.iter().any(|dir| first == *dir)
+        })
+    })
+}
+
+/// A hash of what could change what's shown: the sizes and modification times of the refs and
+/// state files of the git dir, and the status of each changed file of the work tree along with
+/// its size and modification time. Rather than walking the work tree, its status is left to git,
+/// which only looks closer at files the index doesn't have cached as unchanged.
+fn fingerprint(repo: &Repository) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let git_dir = repo.path();
+
+    for file in WATCHED_GIT_FILES {
+        hash_file(&git_dir.join(file), &mut hasher);
+    }
+    for dir in WATCHED_GIT_DIRS {
+        hash_dir(&git_dir.join(dir), &mut hasher);
+    }
+
+    let Some(workdir) = repo.workdir() else {
+        return hasher.finish();
+    };
+
+    let statuses = git2_opts::status(repo)
+        .and_then(|mut opts| Ok(repo.statuses(Some(&mut opts))?))
+        .inspect_err(|err| log::warn!("Couldn't get the status to watch: {}", err));
+
+    for entry in statuses.iter().flat_map(|statuses| statuses.iter()) {
+        entry.status().bits().hash(&mut hasher);
+        hash_file(
+            &workdir.join(git::path_from_bytes(entry.path_bytes())),
+            &mut hasher,
+        );
+    }
+
+    hasher.finish()
+}
+
+fn hash_dir(dir: &Path, hasher: &mut DefaultHasher) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            hash_dir(&path, hasher);
+        } else {
+            hash_file(&path, hasher);
+        }
+    }
+}
+
+/// Missing files are hashed too, by their path alone.
+fn hash_file(path: &Path, hasher: &mut DefaultHasher) {
+    path.hash(hasher);
+    let metadata = fs::symlink_metadata(path).ok();
+    metadata
+        .as_ref()
+        .map(|metadata| metadata.len())
+        .hash(hasher);
+    metadata
+        .and_then(|metadata| metadata.modified().ok())
+        .hash(hasher);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use temp_dir::TempDir;
+
+    #[test]
+    fn fingerprint_changes_with_files() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let workdir = dir.path();
+
+        let before = fingerprint(&repo);
+        assert_eq!(before, fingerprint(&repo));
+
+        fs::write(workdir.join("file"), "content").unwrap();
+        let after = fingerprint(&repo);
+        assert_ne!(before, after);
+
+        fs::write(workdir.join("file"), "changed content").unwrap();
+        assert_ne!(after, fingerprint(&repo));
+
+        fs::write(workdir.join(".gitignore"), "ignored/\n").unwrap();
+        let with_gitignore = fingerprint(&repo);
+        fs::create_dir(workdir.join("ignored")).unwrap();
+        fs::write(workdir.join("ignored").join("file"), "content").unwrap();
+        assert_eq!(with_gitignore, fingerprint(&repo));
+    }
+
+    #[test]
+    fn fingerprint_changes_with_refs() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let before = fingerprint(&repo);
+
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let signature = git2::Signature::now("Author", "author@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "commit", &tree, &[])
+            .unwrap();
+
+        assert_ne!(before, fingerprint(&repo));
+    }
+
+    #[test]
+    fn watcher_notices_changes_before_interval() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let watcher = Watcher::spawn(
+            repo.path().into(),
+            repo.workdir().map(Into::into),
+            Duration::from_secs(600),
+            Waker::default(),
+        );
+
+        // The watch may only start after the first writes
+        let changed = (0..50).any(|i| {
+            fs::write(dir.path().join("file"), i.to_string()).unwrap();
+            thread::sleep(Duration::from_millis(100));
+            watcher.take_changed()
+        });
+        assert!(changed);
+    }
+
+    #[test]
+    fn is_relevant_leaves_out_git_objects() {
+        let git_dir = Path::new("/repo/.git/");
+        let event = |path: &str| -> notify::Result<notify::Event> {
+            Ok(
+                notify::Event::new(EventKind::Modify(notify::event::ModifyKind::Any))
+                    .add_path(PathBuf::from(path)),
+            )
+        };
+
+        assert!(is_relevant(&event("/repo/src/main.rs"), git_dir));
+        assert!(is_relevant(&event("/repo/.git/index"), git_dir));
+        assert!(is_relevant(&event("/repo/.git/refs/heads/main"), git_dir));
+        assert!(!is_relevant(&event("/repo/.git/objects/ab/cdef"), git_dir));
+        assert!(!is_relevant(&event("/repo/.git/index.lock"), git_dir));
+    }
+}
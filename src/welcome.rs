@@ -0,0 +1,381 @@
+//! What gitu shows when started outside of a repository: a way to make one there, by init or clone.
+
+use crate::{
+    cmd_log::{CmdLog, CmdLogEntry},
+    config::Config,
+    events::{Events, Waker},
+    state::stream_output,
+    term::Term,
+    ui, Res,
+};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Layout},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::{
+    io,
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    rc::Rc,
+    sync::{Arc, RwLock},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use tui_prompts::{State as _, Status, TextPrompt, TextState};
+
+pub(crate) struct Welcome {
+    config: Rc<Config>,
+    dir: PathBuf,
+    enable_async_cmds: bool,
+    waker: Waker,
+    step: Step,
+    input: TextState<'static>,
+    cmd_log: CmdLog,
+    pub(crate) quit: bool,
+}
+
+enum Step {
+    Choose,
+    CloneFrom,
+    CloneInto { url: String },
+    Cloning(PendingClone),
+}
+
+struct PendingClone {
+    child: Child,
+    log_entry: Arc<RwLock<CmdLogEntry>>,
+    stderr: JoinHandle<io::Result<Vec<u8>>>,
+    into: PathBuf,
+}
+
+/// Shows the welcome screen until a repository was made, which is returned, or until quit.
+pub(crate) fn run(
+    config: Rc<Config>,
+    dir: PathBuf,
+    term: &mut Term,
+    events: &Events,
+) -> Res<Option<PathBuf>> {
+    let mut welcome = Welcome::new(config, dir, true);
+    welcome.waker = events.waker();
+    welcome.update(term, &[Event::FocusGained])?;
+
+    while !welcome.quit {
+        let busy = matches!(welcome.step, Step::Cloning(_));
+        let events = events.wait(crate::poll_timeout(&welcome.config, busy))?;
+
+        if let Some(repo) = welcome.update(term, &events)? {
+            return Ok(Some(repo));
+        }
+    }
+
+    Ok(None)
+}
+
+impl Welcome {
+    pub(crate) fn new(config: Rc<Config>, dir: PathBuf, enable_async_cmds: bool) -> Self {
+        Self {
+            config,
+            dir,
+            enable_async_cmds,
+            waker: Waker::default(),
+            step: Step::Choose,
+            input: TextState::new(),
+            cmd_log: CmdLog::new(),
+            quit: false,
+        }
+    }
+
+    /// Handles events and redraws, returns the new repository once there is one.
+    pub(crate) fn update(&mut self, term: &mut Term, events: &[Event]) -> Res<Option<PathBuf>> {
+        for event in events {
+            let result = match event {
+                Event::Key(key) => self.handle_key(*key),
+                Event::Paste(text) => {
+                    self.paste(text);
+                    Ok(None)
+                }
+                _ => Ok(None),
+            };
+
+            if let Some(repo) = self.handle_result(result) {
+                return Ok(Some(repo));
+            }
+        }
+
+        let result = self.poll_clone();
+        if let Some(repo) = self.handle_result(result) {
+            return Ok(Some(repo));
+        }
+
+        self.draw(term)?;
+        Ok(None)
+    }
+
+    fn handle_result(&mut self, result: Res<Option<PathBuf>>) -> Option<PathBuf> {
+        match result {
+            Ok(repo) => repo,
+            Err(error) => {
+                self.step = Step::Choose;
+                self.cmd_log.push(CmdLogEntry::Error(error.to_string()));
+                None
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Res<Option<PathBuf>> {
+        match &self.step {
+            Step::Choose if key.kind == KeyEventKind::Press => {
+                self.cmd_log.clear();
+                match key.code {
+                    KeyCode::Char('i') => return self.init(),
+                    KeyCode::Char('c') => self.prompt(Step::CloneFrom, String::new()),
+                    KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+                    _ => (),
+                }
+            }
+            Step::CloneFrom | Step::CloneInto { .. } => {
+                self.input.handle_key_event(key);
+                match self.input.status() {
+                    Status::Pending => (),
+                    Status::Aborted => self.step = Step::Choose,
+                    Status::Done => self.submit()?,
+                }
+            }
+            Step::Choose | Step::Cloning(_) => (),
+        }
+
+        Ok(None)
+    }
+
+    /// Types pasted text into the prompt, if there's one, as a single line.
+    fn paste(&mut self, text: &str) {
+        if !matches!(self.step, Step::CloneFrom | Step::CloneInto { .. }) {
+            return;
+        }
+
+        for c in text.trim_end_matches(['\r', '\n']).chars() {
+            let c = if c == '\r' || c == '\n' { ' ' } else { c };
+            self.input
+                .handle_key_event(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    fn prompt(&mut self, step: Step, value: String) {
+        self.step = step;
+        self.input = TextState::new().with_value(value);
+        self.input.move_end();
+        self.input.focus();
+    }
+
+    fn submit(&mut self) -> Res<()> {
+        let value = self.input.value().trim().to_string();
+
+        match std::mem::replace(&mut self.step, Step::Choose) {
+            Step::CloneFrom => {
+                if value.is_empty() {
+                    return Err("No repository to clone given".into());
+                }
+                let into = default_clone_dir(&value);
+                self.prompt(Step::CloneInto { url: value }, into);
+            }
+            Step::CloneInto { url } => {
+                let into = match value.is_empty() {
+                    true => default_clone_dir(&url),
+                    false => value,
+                };
+                self.clone(url, into)?;
+            }
+            step => self.step = step,
+        }
+
+        Ok(())
+    }
+
+    fn init(&mut self) -> Res<Option<PathBuf>> {
+        let mut cmd = Command::new("git");
+        cmd.arg("init").current_dir(&self.dir);
+
+        let output = cmd.output()?;
+        let out = [output.stdout, output.stderr].concat();
+        self.cmd_log.push_cmd_with_output(
+            &cmd,
+            String::from_utf8_lossy(&out).into_owned().into(),
+            output.status.code(),
+        );
+
+        if !output.status.success() {
+            return Err(exited("git init", output.status.code()).into());
+        }
+
+        Ok(Some(self.dir.clone()))
+    }
+
+    fn clone(&mut self, url: String, into: String) -> Res<()> {
+        let mut cmd = Command::new("git");
+        cmd.args(["clone", "--progress", &url, &into])
+            .current_dir(&self.dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        let log_entry = self.cmd_log.push_cmd(&cmd);
+        let mut child = cmd.spawn()?;
+        let stderr = stream_output(
+            child.stderr.take().unwrap(),
+            Arc::clone(&log_entry),
+            self.waker.clone(),
+        );
+
+        self.step = Step::Cloning(PendingClone {
+            child,
+            log_entry,
+            stderr,
+            into: self.dir.join(into),
+        });
+
+        if !self.enable_async_cmds {
+            if let Step::Cloning(PendingClone { child, stderr, .. }) = &mut self.step {
+                child.wait()?;
+
+                // The rest of the output might still be on its way
+                while !stderr.is_finished() {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the cloned repository once the clone is done.
+    fn poll_clone(&mut self) -> Res<Option<PathBuf>> {
+        let Step::Cloning(pending) = &mut self.step else {
+            return Ok(None);
+        };
+
+        let Some(status) = pending.child.try_wait()? else {
+            return Ok(None);
+        };
+        if !pending.stderr.is_finished() {
+            return Ok(None);
+        }
+
+        let Step::Cloning(PendingClone {
+            log_entry,
+            stderr,
+            into,
+            ..
+        }) = std::mem::replace(&mut self.step, Step::Choose)
+        else {
+            unreachable!();
+        };
+
+        let out = stderr
+            .join()
+            .map_err(|_| "Couldn't read cmd output")?
+            .map_err(|e| format!("Couldn't read cmd output: {}", e))?;
+
+        if let CmdLogEntry::Cmd {
+            args,
+            out: out_log,
+            exit_code,
+            ..
+        } = &mut *log_entry.write().unwrap()
+        {
+            *out_log = Some(String::from_utf8_lossy(&out).into_owned().into());
+            *exit_code = status.code();
+
+            if !status.success() {
+                return Err(exited(args, status.code()).into());
+            }
+        }
+
+        Ok(Some(into))
+    }
+
+    fn draw(&mut self, term: &mut Term) -> Res<()> {
+        let style = &self.config.style;
+        let prompt_text = match self.step {
+            Step::CloneFrom => Some("Clone repository from"),
+            Step::CloneInto { .. } => Some("Clone into"),
+            Step::Choose | Step::Cloning(_) => None,
+        };
+
+        let hotkey = |key, text| {
+            Line::from(vec![
+                Span::styled(key, &style.hotkey),
+                Span::raw(" "),
+                Span::raw(text),
+            ])
+        };
+        let choices = Paragraph::new(vec![
+            Line::styled("No repository here", &style.section_header),
+            Line::raw(self.dir.display().to_string()),
+            Line::raw(""),
+            hotkey("i", "Init a repository here"),
+            hotkey("c", "Clone a repository"),
+            hotkey("q", "Quit"),
+        ]);
+
+        let log = self.cmd_log.format_log(&self.config);
+        let log_height = match log.lines.is_empty() {
+            true => 0,
+            false => 1 + log.lines.len() as u16,
+        };
+
+        let input = &mut self.input;
+        term.draw(|frame| {
+            let [choices_area, prompt_area, log_area] = Layout::vertical([
+                Constraint::Min(1),
+                Constraint::Length(if prompt_text.is_some() { 2 } else { 0 }),
+                Constraint::Length(log_height),
+            ])
+            .areas(frame.area());
+
+            frame.render_widget(choices, choices_area);
+            if log_height > 0 {
+                frame.render_widget(Paragraph::new(log).block(ui::popup_block()), log_area);
+            }
+
+            if let Some(text) = prompt_text {
+                let prompt = TextPrompt::new(text.into()).with_block(ui::popup_block());
+                frame.render_stateful_widget(prompt, prompt_area, input);
+                frame.set_cursor_position(input.cursor());
+            }
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Where git would clone into: the last part of the url, without `.git`.
+fn default_clone_dir(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+    let name = url.rsplit(['/', ':']).next().unwrap_or(url);
+    name.strip_suffix(".git").unwrap_or(name).to_string()
+}
+
+fn exited(args: &str, code: Option<i32>) -> String {
+    format!(
+        "'{}' exited with code: {}",
+        args,
+        code.map(|c| c.to_string()).unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::default_clone_dir;
+
+    #[test]
+    fn default_clone_dir_of_urls() {
+        assert_eq!(
+            default_clone_dir("https://github.com/altsem/gitu.git"),
+            "gitu"
+        );
+        assert_eq!(default_clone_dir("git@github.com:altsem/gitu"), "gitu");
+        assert_eq!(default_clone_dir("../some/repo/"), "repo");
+    }
+}